@@ -0,0 +1,688 @@
+//! # RustyRoad derive
+//! The `#[derive(Model)]` macro behind `rustyroad::model::Model`. Use it through the
+//! `rustyroad` crate, which re-exports it next to the trait.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Expr, Fields, LitInt, LitStr, Path, Token};
+
+/// # Name: Model
+/// ## Description
+/// Implements `rustyroad::model::Model` and `sqlx::FromRow` for a struct with named fields.
+/// Every field is a column of the same name unless it says otherwise.
+/// ## Attributes
+/// * `#[model(table = "people")]` - The table; defaults to the plural snake_case struct name.
+/// * `#[model(primary_key = "person_id")]` - The primary key column; defaults to `id`.
+/// * `#[model(primary_key)]` on a field - Marks that field's column as the primary key.
+/// * `#[model(column = "name")]` on a field - Reads and writes a differently named column.
+/// * `#[model(skip)]` on a field - Not a column; it is filled with `Default::default()`.
+/// * `#[model(timestamps)]`, `#[model(soft_delete)]` and `#[model(lock_version)]` - Turn on the
+///   `Model` behaviours of the same names, which need `created_at` and `updated_at`,
+///   `deleted_at` and `lock_version` columns respectively.
+/// * `#[model(callbacks)]` - The struct implements `rustyroad::model::Callbacks` itself, to
+///   hook into its writes. Otherwise the derive implements it without any hooks.
+///
+/// It also implements `rustyroad::testing::Factory`, which fills each column with the
+/// `FactoryValue` of its type, or its `Default`.
+///
+/// Associations add a loader method and an `include_<name>` function that loads the
+/// association for many records with one query. Associated models must be `Clone`.
+/// * `#[model(belongs_to(User))]` on a foreign key field `author_id` - `author(&conn)` returns
+///   the `User` it points to (an `Option` when the field is), and `include_author(&conn, records)`
+///   pairs records with theirs. `name = ".."` renames the association.
+/// * `#[model(has_many(Post))]` on the struct - `posts(&conn)` returns the `Post`s whose
+///   `<struct>_id` column holds this record's primary key. `foreign_key = ".."` and
+///   `name = ".."` override the defaults.
+/// * `#[model(has_many(Tag, through = "post_tags"))]` on the struct - `tags(&conn)` returns the
+///   `Tag`s linked by the join table's `<struct>_id` and `tag_id` columns, which
+///   `foreign_key = ".."` and `target_key = ".."` override.
+#[proc_macro_derive(Model, attributes(model, validate))]
+pub fn derive_model(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// An opt-in `Model` behaviour: its struct attribute, the `Model` constant it sets and the
+/// columns it needs.
+struct Behaviour {
+    attribute: &'static str,
+    flag: &'static str,
+    columns: &'static [&'static str],
+}
+
+const BEHAVIOURS: &[Behaviour] = &[
+    Behaviour {
+        attribute: "timestamps",
+        flag: "TIMESTAMPS",
+        columns: &["created_at", "updated_at"],
+    },
+    Behaviour {
+        attribute: "soft_delete",
+        flag: "SOFT_DELETE",
+        columns: &["deleted_at"],
+    },
+    Behaviour {
+        attribute: "lock_version",
+        flag: "LOCK_VERSION",
+        columns: &["lock_version"],
+    },
+];
+
+struct Column {
+    field: syn::Ident,
+    ty: syn::Type,
+    name: String,
+}
+
+/// The arguments of `belongs_to(..)` and `has_many(..)`: a target model, then `key = "value"`s.
+struct Association {
+    target: Path,
+    options: Vec<(String, LitStr)>,
+}
+
+impl Association {
+    fn parse(meta: &syn::meta::ParseNestedMeta, allowed: &[&str]) -> Result<Self, Error> {
+        let mut target = None;
+        let mut options = Vec::new();
+        meta.parse_nested_meta(|inner| {
+            if inner.input.peek(Token![=]) {
+                let key = inner.path.get_ident().map(|i| i.to_string());
+                match key {
+                    Some(key) if allowed.contains(&key.as_str()) => {
+                        options.push((key, inner.value()?.parse::<LitStr>()?));
+                        Ok(())
+                    }
+                    _ => Err(inner.error(format!("expected one of: {}", allowed.join(", ")))),
+                }
+            } else if target.is_none() {
+                target = Some(inner.path.clone());
+                Ok(())
+            } else {
+                Err(inner.error("the target model is already given"))
+            }
+        })?;
+        let target = target.ok_or_else(|| meta.error("name the associated model first"))?;
+        Ok(Association { target, options })
+    }
+
+    fn option(&self, key: &str) -> Option<String> {
+        self.options
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.value())
+    }
+
+    fn target_name(&self) -> String {
+        let last = self.target.segments.last().expect("a path has a segment");
+        to_snake_case(&last.ident.to_string())
+    }
+
+    /// The method name, checked to be an identifier.
+    fn method(&self, default: String) -> Result<syn::Ident, Error> {
+        let name = self.option("name").unwrap_or(default);
+        syn::parse_str::<syn::Ident>(&name).map_err(|_| {
+            Error::new_spanned(
+                &self.target,
+                format!("`{}` is not a valid association name", name),
+            )
+        })
+    }
+}
+
+/// The field a `#[validate(..)]` rule is on and its column, which skipped fields lack.
+/// `None` for the struct.
+type RuleTarget<'a> = Option<(&'a syn::Ident, Option<&'a str>)>;
+
+/// One `#[validate(..)]` rule as a `rustyroad::model::Validation` expression.
+fn parse_rule(
+    meta: &syn::meta::ParseNestedMeta,
+    target: RuleTarget,
+) -> Result<proc_macro2::TokenStream, Error> {
+    let rule_path = quote!(::rustyroad::model::Rule);
+    let Some((field, column)) = target else {
+        if !meta.path.is_ident("custom") {
+            return Err(meta.error("only `custom` rules apply to the whole struct"));
+        }
+        let check = meta.value()?.parse::<LitStr>()?.parse::<Path>()?;
+        return Ok(quote! {
+            ::rustyroad::model::Validation {
+                field: ::rustyroad::model::BASE,
+                column: "",
+                rule: #rule_path::Custom(#check),
+            }
+        });
+    };
+    let field_name = field.to_string().trim_start_matches("r#").to_string();
+    if meta.path.is_ident("custom") {
+        let check = meta.value()?.parse::<LitStr>()?.parse::<Path>()?;
+        let column = column.unwrap_or_default();
+        return Ok(quote! {
+            ::rustyroad::model::Validation {
+                field: #field_name,
+                column: #column,
+                rule: #rule_path::Custom(|record: &Self| #check(&record.#field)),
+            }
+        });
+    }
+    let Some(column) = column else {
+        return Err(meta.error("a skipped field has no column; only `custom` rules apply"));
+    };
+    let rule = if meta.path.is_ident("presence") {
+        quote!(#rule_path::Presence)
+    } else if meta.path.is_ident("unique") {
+        quote!(#rule_path::Uniqueness)
+    } else if meta.path.is_ident("format") {
+        let pattern = meta.value()?.parse::<LitStr>()?;
+        if let Err(e) = regex::Regex::new(&pattern.value()) {
+            return Err(Error::new(
+                pattern.span(),
+                format!("invalid format pattern: {}", e),
+            ));
+        }
+        quote!({
+            static FORMAT: ::std::sync::OnceLock<::rustyroad::model::Regex> =
+                ::std::sync::OnceLock::new();
+            #rule_path::format_cached(&FORMAT, #pattern)
+        })
+    } else if meta.path.is_ident("inclusion") {
+        let content;
+        syn::parenthesized!(content in meta.input);
+        let values = Punctuated::<Expr, Token![,]>::parse_terminated(&content)?;
+        let values = values.iter();
+        quote!(#rule_path::Inclusion(::std::vec![
+            #(::rustyroad::database::SqlValue::from(#values),)*
+        ]))
+    } else if meta.path.is_ident("length") {
+        let (mut min, mut max) = (quote!(None), quote!(None));
+        meta.parse_nested_meta(|bound| {
+            let value = bound.value()?.parse::<LitInt>()?;
+            if bound.path.is_ident("min") {
+                min = quote!(Some(#value));
+            } else if bound.path.is_ident("max") {
+                max = quote!(Some(#value));
+            } else {
+                return Err(bound.error("expected `min` or `max`"));
+            }
+            Ok(())
+        })?;
+        quote!(#rule_path::Length { min: #min, max: #max })
+    } else if meta.path.is_ident("range") {
+        let (mut min, mut max) = (quote!(None), quote!(None));
+        meta.parse_nested_meta(|bound| {
+            let value = bound.value()?.parse::<Expr>()?;
+            if bound.path.is_ident("min") {
+                min = quote!(Some((#value) as f64));
+            } else if bound.path.is_ident("max") {
+                max = quote!(Some((#value) as f64));
+            } else {
+                return Err(bound.error("expected `min` or `max`"));
+            }
+            Ok(())
+        })?;
+        quote!(#rule_path::Range { min: #min, max: #max })
+    } else {
+        return Err(meta.error(
+            "expected `presence`, `length`, `format`, `inclusion`, `range`, `unique` or `custom`",
+        ));
+    };
+    Ok(quote! {
+        ::rustyroad::model::Validation {
+            field: #field_name,
+            column: #column,
+            rule: #rule,
+        }
+    })
+}
+
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+fn expand(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "#[derive(Model)] does not support generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    ident,
+                    "#[derive(Model)] needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                ident,
+                "#[derive(Model)] can only be used on structs",
+            ))
+        }
+    };
+
+    let mut table = pluralize(&to_snake_case(&ident.to_string()));
+    let mut primary_key = None;
+    let mut has_many = Vec::new();
+    let mut behaviours = Vec::new();
+    let mut callbacks = false;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("model")) {
+        attr.parse_nested_meta(|meta| {
+            if let Some(behaviour) = BEHAVIOURS.iter().find(|b| meta.path.is_ident(b.attribute)) {
+                if !behaviours.iter().any(|b: &&Behaviour| b.flag == behaviour.flag) {
+                    behaviours.push(behaviour);
+                }
+            } else if meta.path.is_ident("callbacks") {
+                callbacks = true;
+            } else if meta.path.is_ident("table") {
+                table = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("primary_key") {
+                primary_key = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("has_many") {
+                has_many.push(Association::parse(
+                    &meta,
+                    &["name", "foreign_key", "through", "target_key"],
+                )?);
+            } else {
+                return Err(meta.error(
+                    "expected `table`, `primary_key`, `has_many`, `timestamps`, `soft_delete`, `lock_version` or `callbacks`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+
+    let mut validations = Vec::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("validate")) {
+        attr.parse_nested_meta(|meta| {
+            validations.push(parse_rule(&meta, None)?);
+            Ok(())
+        })?;
+    }
+
+    let mut columns = Vec::new();
+    let mut skipped = Vec::new();
+    let mut belongs_to = Vec::new();
+    for field in fields {
+        let name = field.ident.clone().expect("named field");
+        let mut column = name.to_string().trim_start_matches("r#").to_string();
+        let mut skip = false;
+        let mut is_primary_key = false;
+        let mut association = None;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("model")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("belongs_to") {
+                    association = Some(Association::parse(&meta, &["name"])?);
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                } else if meta.path.is_ident("column") {
+                    column = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("primary_key") {
+                    is_primary_key = true;
+                } else {
+                    return Err(
+                        meta.error("expected `skip`, `column`, `primary_key` or `belongs_to`")
+                    );
+                }
+                Ok(())
+            })?;
+        }
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("validate")) {
+            let target = (&name, (!skip).then_some(column.as_str()));
+            attr.parse_nested_meta(|meta| {
+                validations.push(parse_rule(&meta, Some(target))?);
+                Ok(())
+            })?;
+        }
+        if is_primary_key {
+            if primary_key.is_some() {
+                return Err(Error::new_spanned(
+                    &name,
+                    "the primary key is already set by another attribute",
+                ));
+            }
+            primary_key = Some(column.clone());
+        }
+        if let Some(association) = association {
+            if skip {
+                return Err(Error::new_spanned(
+                    &name,
+                    "a skipped field cannot hold a belongs_to foreign key",
+                ));
+            }
+            belongs_to.push((name.clone(), field.ty.clone(), column.clone(), association));
+        }
+        if skip {
+            skipped.push(name);
+        } else {
+            columns.push(Column {
+                field: name,
+                ty: field.ty.clone(),
+                name: column,
+            });
+        }
+    }
+
+    let primary_key = primary_key.unwrap_or_else(|| "id".to_string());
+    if !columns.iter().any(|c| c.name == primary_key) {
+        return Err(Error::new(
+            Span::call_site(),
+            format!(
+                "`{}` has no `{}` column to use as its primary key; add #[model(primary_key)] to a field",
+                ident, primary_key
+            ),
+        ));
+    }
+
+    let mut behaviour_consts = Vec::new();
+    for behaviour in behaviours {
+        if let Some(missing) = behaviour
+            .columns
+            .iter()
+            .find(|name| !columns.iter().any(|c| c.name == **name))
+        {
+            return Err(Error::new(
+                Span::call_site(),
+                format!(
+                    "#[model({})] needs a `{}` column on `{}`",
+                    behaviour.attribute, missing, ident
+                ),
+            ));
+        }
+        let flag = format_ident!("{}", behaviour.flag);
+        behaviour_consts.push(quote!(const #flag: bool = true;));
+    }
+
+    let primary_key_type = &columns
+        .iter()
+        .find(|c| c.name == primary_key)
+        .expect("checked above")
+        .ty;
+    let struct_name = to_snake_case(&ident.to_string());
+    let mut associations = Vec::new();
+    for (field, ty, column, association) in &belongs_to {
+        let target = &association.target;
+        let default = column
+            .strip_suffix("_id")
+            .map(str::to_string)
+            .unwrap_or_else(|| association.target_name());
+        let method = association.method(default)?;
+        let include = format_ident!("include_{}", method);
+        let doc = format!(
+            " The `{}` this record's `{}` column points to.",
+            quote!(#target).to_string().replace(' ', ""),
+            column
+        );
+        let include_doc = format!(
+            " Pairs each record with its `{}`, loaded with one query.",
+            method
+        );
+        associations.push(if is_option(ty) {
+            quote! {
+                #[doc = #doc]
+                pub async fn #method(
+                    &self,
+                    conn: &impl ::rustyroad::database::Executor,
+                ) -> ::std::result::Result<::std::option::Option<#target>, ::rustyroad::model::sqlx::Error> {
+                    ::rustyroad::model::belongs_to::<#target>(
+                        conn,
+                        ::rustyroad::database::SqlValue::from(::std::clone::Clone::clone(&self.#field)),
+                    )
+                    .await
+                }
+
+                #[doc = #include_doc]
+                pub async fn #include(
+                    conn: &impl ::rustyroad::database::Executor,
+                    records: ::std::vec::Vec<Self>,
+                ) -> ::std::result::Result<
+                    ::std::vec::Vec<(Self, ::std::option::Option<#target>)>,
+                    ::rustyroad::model::sqlx::Error,
+                > {
+                    ::rustyroad::model::preload_belongs_to::<Self, #target>(conn, records, #column).await
+                }
+            }
+        } else {
+            quote! {
+                #[doc = #doc]
+                pub async fn #method(
+                    &self,
+                    conn: &impl ::rustyroad::database::Executor,
+                ) -> ::std::result::Result<#target, ::rustyroad::model::sqlx::Error> {
+                    ::rustyroad::model::belongs_to::<#target>(
+                        conn,
+                        ::rustyroad::database::SqlValue::from(::std::clone::Clone::clone(&self.#field)),
+                    )
+                    .await?
+                    .ok_or(::rustyroad::model::sqlx::Error::RowNotFound)
+                }
+
+                #[doc = #include_doc]
+                pub async fn #include(
+                    conn: &impl ::rustyroad::database::Executor,
+                    records: ::std::vec::Vec<Self>,
+                ) -> ::std::result::Result<::std::vec::Vec<(Self, #target)>, ::rustyroad::model::sqlx::Error> {
+                    ::rustyroad::model::preload_belongs_to::<Self, #target>(conn, records, #column)
+                        .await?
+                        .into_iter()
+                        .map(|(record, parent)| {
+                            parent
+                                .map(|parent| (record, parent))
+                                .ok_or(::rustyroad::model::sqlx::Error::RowNotFound)
+                        })
+                        .collect()
+                }
+            }
+        });
+    }
+    for association in &has_many {
+        let target = &association.target;
+        let method = association.method(pluralize(&association.target_name()))?;
+        let include = format_ident!("include_{}", method);
+        let foreign_key = association
+            .option("foreign_key")
+            .unwrap_or_else(|| format!("{}_id", struct_name));
+        let target_name = quote!(#target).to_string().replace(' ', "");
+        let include_doc = format!(
+            " Pairs each record with its `{}`, loaded with one query.",
+            method
+        );
+        associations.push(match association.option("through") {
+            Some(through) => {
+                let target_key = association
+                    .option("target_key")
+                    .unwrap_or_else(|| format!("{}_id", association.target_name()));
+                let doc = format!(
+                    " The `{}`s linked to this record through `{}`.",
+                    target_name, through
+                );
+                quote! {
+                    #[doc = #doc]
+                    pub async fn #method(
+                        &self,
+                        conn: &impl ::rustyroad::database::Executor,
+                    ) -> ::std::result::Result<::std::vec::Vec<#target>, ::rustyroad::model::sqlx::Error> {
+                        ::rustyroad::model::has_many_through::<#target>(
+                            conn,
+                            #through,
+                            #foreign_key,
+                            #target_key,
+                            ::rustyroad::model::Model::primary_key(self),
+                        )
+                        .await
+                    }
+
+                    #[doc = #include_doc]
+                    pub async fn #include(
+                        conn: &impl ::rustyroad::database::Executor,
+                        records: ::std::vec::Vec<Self>,
+                    ) -> ::std::result::Result<
+                        ::std::vec::Vec<(Self, ::std::vec::Vec<#target>)>,
+                        ::rustyroad::model::sqlx::Error,
+                    > {
+                        ::rustyroad::model::preload_has_many_through::<Self, #target, #primary_key_type>(
+                            conn,
+                            records,
+                            #through,
+                            #foreign_key,
+                            #target_key,
+                        )
+                        .await
+                    }
+                }
+            }
+            None => {
+                let doc = format!(
+                    " The `{}`s whose `{}` column holds this record's primary key.",
+                    target_name, foreign_key
+                );
+                quote! {
+                    #[doc = #doc]
+                    pub async fn #method(
+                        &self,
+                        conn: &impl ::rustyroad::database::Executor,
+                    ) -> ::std::result::Result<::std::vec::Vec<#target>, ::rustyroad::model::sqlx::Error> {
+                        ::rustyroad::model::has_many::<#target>(
+                            conn,
+                            #foreign_key,
+                            ::rustyroad::model::Model::primary_key(self),
+                        )
+                        .await
+                    }
+
+                    #[doc = #include_doc]
+                    pub async fn #include(
+                        conn: &impl ::rustyroad::database::Executor,
+                        records: ::std::vec::Vec<Self>,
+                    ) -> ::std::result::Result<
+                        ::std::vec::Vec<(Self, ::std::vec::Vec<#target>)>,
+                        ::rustyroad::model::sqlx::Error,
+                    > {
+                        ::rustyroad::model::preload_has_many::<Self, #target>(conn, records, #foreign_key).await
+                    }
+                }
+            }
+        });
+    }
+    let associations = if associations.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            impl #ident {
+                #(#associations)*
+            }
+        }
+    };
+
+    let callbacks = (!callbacks).then(|| {
+        quote! {
+            impl ::rustyroad::model::Callbacks for #ident {}
+        }
+    });
+
+    let column_names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+    let column_fields: Vec<&syn::Ident> = columns.iter().map(|c| &c.field).collect();
+    let column_types: Vec<&syn::Type> = columns.iter().map(|c| &c.ty).collect();
+
+    Ok(quote! {
+        impl<'r, R> ::rustyroad::model::sqlx::FromRow<'r, R> for #ident
+        where
+            R: ::rustyroad::model::sqlx::Row,
+            &'r str: ::rustyroad::model::sqlx::ColumnIndex<R>,
+            #(#column_types: ::rustyroad::model::sqlx::Decode<'r, R::Database>
+                + ::rustyroad::model::sqlx::Type<R::Database>,)*
+        {
+            fn from_row(row: &'r R) -> ::std::result::Result<Self, ::rustyroad::model::sqlx::Error> {
+                Ok(Self {
+                    #(#column_fields: ::rustyroad::model::sqlx::Row::try_get(row, #column_names)?,)*
+                    #(#skipped: ::std::default::Default::default(),)*
+                })
+            }
+        }
+
+        impl ::rustyroad::model::Model for #ident {
+            const TABLE: &'static str = #table;
+            const PRIMARY_KEY: &'static str = #primary_key;
+            const COLUMNS: &'static [&'static str] = &[#(#column_names),*];
+            #(#behaviour_consts)*
+
+            fn values(&self) -> ::std::vec::Vec<(&'static str, ::rustyroad::database::SqlValue)> {
+                ::std::vec![
+                    #((#column_names, ::rustyroad::database::SqlValue::from(
+                        ::std::clone::Clone::clone(&self.#column_fields),
+                    )),)*
+                ]
+            }
+        }
+
+        impl ::rustyroad::model::Validate for #ident {
+            fn validations() -> ::std::vec::Vec<::rustyroad::model::Validation<Self>> {
+                ::std::vec![#(#validations),*]
+            }
+        }
+
+        impl ::rustyroad::testing::Factory for #ident {
+            fn build(sequence: u64) -> Self {
+                #[allow(unused_imports)]
+                use ::rustyroad::testing::{ViaDefault as _, ViaFactoryValue as _, ViaNothing as _};
+                Self {
+                    #(#column_fields: (&&&::rustyroad::testing::FactoryProbe::<#column_types>::new())
+                        .factory_default(#column_names, sequence),)*
+                    #(#skipped: ::std::default::Default::default(),)*
+                }
+            }
+        }
+
+        #callbacks
+
+        #associations
+    })
+}
+
+/// `BlogPost` becomes `blog_post`.
+fn to_snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// The same pluralization the generators use for table names.
+fn pluralize(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix('y') {
+        if !stem.ends_with(['a', 'e', 'i', 'o', 'u']) {
+            return format!("{}ies", stem);
+        }
+    }
+    if name.ends_with('s')
+        || name.ends_with('x')
+        || name.ends_with('z')
+        || name.ends_with("ch")
+        || name.ends_with("sh")
+    {
+        return format!("{}es", name);
+    }
+    format!("{}s", name)
+}
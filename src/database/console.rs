@@ -0,0 +1,668 @@
+use crate::database::migrations::CustomMigrationError;
+use crate::database::{
+    display_value, introspect_schema, run_statement, split_sql_statements, Database,
+    DatabaseConnection, QueryResult, SchemaInfo,
+};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::io;
+use std::path::PathBuf;
+use std::time::Instant;
+
+const HISTORY_FILE: &str = ".rustyroad_console_history";
+
+const META_COMMANDS: &[(&str, &str)] = &[
+    ("\\dt", "List tables"),
+    ("\\d", "Describe a table: \\d <table>"),
+    ("\\x", "Toggle expanded (one column per line) output"),
+    ("\\timing", "Toggle printing of statement execution time"),
+    ("\\i", "Execute statements from a file: \\i <file.sql>"),
+    ("\\?", "Show this help"),
+    ("\\q", "Quit the console"),
+];
+
+const SQL_KEYWORDS: &[&str] = &[
+    "SELECT",
+    "FROM",
+    "WHERE",
+    "INSERT",
+    "INTO",
+    "VALUES",
+    "UPDATE",
+    "SET",
+    "DELETE",
+    "CREATE",
+    "TABLE",
+    "ALTER",
+    "DROP",
+    "INDEX",
+    "JOIN",
+    "LEFT",
+    "RIGHT",
+    "INNER",
+    "OUTER",
+    "ON",
+    "AND",
+    "OR",
+    "NOT",
+    "NULL",
+    "IS",
+    "IN",
+    "LIKE",
+    "ORDER",
+    "BY",
+    "GROUP",
+    "HAVING",
+    "LIMIT",
+    "OFFSET",
+    "DISTINCT",
+    "COUNT",
+    "AS",
+    "ASC",
+    "DESC",
+    "RETURNING",
+    "PRIMARY",
+    "KEY",
+    "REFERENCES",
+    "DEFAULT",
+    "BEGIN",
+    "COMMIT",
+    "ROLLBACK",
+    "EXPLAIN",
+    "WITH",
+    "UNION",
+];
+
+/// # Name: ConsoleHelper
+/// ## Description
+/// rustyline helper that completes meta-commands, SQL keywords and the table and column names
+/// found by introspection.
+#[derive(Debug, Default)]
+pub struct ConsoleHelper {
+    tables: BTreeMap<String, Vec<String>>,
+}
+
+impl ConsoleHelper {
+    pub fn new(schema: &SchemaInfo) -> Self {
+        let mut helper = ConsoleHelper::default();
+        helper.refresh(schema);
+        helper
+    }
+
+    /// Replaces the completion candidates with the tables and columns of `schema`.
+    pub fn refresh(&mut self, schema: &SchemaInfo) {
+        self.tables = schema
+            .tables
+            .iter()
+            .map(|table| {
+                (
+                    table.name.clone(),
+                    table.columns.iter().map(|c| c.name.clone()).collect(),
+                )
+            })
+            .collect();
+    }
+
+    /// Returns the completions for `word`, the partial token under the cursor.
+    pub fn candidates(&self, word: &str) -> Vec<String> {
+        let lower = word.to_ascii_lowercase();
+
+        if word.starts_with('\\') {
+            return META_COMMANDS
+                .iter()
+                .map(|(command, _)| command.to_string())
+                .filter(|command| command.starts_with(word))
+                .collect();
+        }
+
+        if let Some((table, column)) = word.split_once('.') {
+            return self
+                .tables
+                .get(table)
+                .into_iter()
+                .flatten()
+                .filter(|c| {
+                    c.to_ascii_lowercase()
+                        .starts_with(&column.to_ascii_lowercase())
+                })
+                .map(|c| format!("{}.{}", table, c))
+                .collect();
+        }
+
+        let mut candidates: Vec<String> = self
+            .tables
+            .keys()
+            .chain(self.tables.values().flatten())
+            .filter(|name| name.to_ascii_lowercase().starts_with(&lower))
+            .cloned()
+            .collect();
+
+        let lowercase_keywords = word.chars().next().is_some_and(|c| c.is_lowercase());
+        candidates.extend(
+            SQL_KEYWORDS
+                .iter()
+                .filter(|keyword| keyword.to_ascii_lowercase().starts_with(&lower))
+                .map(|keyword| {
+                    if lowercase_keywords {
+                        keyword.to_ascii_lowercase()
+                    } else {
+                        keyword.to_string()
+                    }
+                }),
+        );
+
+        candidates.sort();
+        candidates.dedup();
+        candidates
+    }
+}
+
+impl Completer for ConsoleHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos]
+            .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '\\'))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let word = &line[start..pos];
+        if word.is_empty() {
+            return Ok((pos, Vec::new()));
+        }
+
+        let pairs = self
+            .candidates(word)
+            .into_iter()
+            .map(|candidate| Pair {
+                display: candidate.clone(),
+                replacement: candidate,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ConsoleHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ConsoleHelper {}
+
+impl Validator for ConsoleHelper {}
+
+impl Helper for ConsoleHelper {}
+
+/// # Name: format_table
+/// ## Description
+/// Formats a result set as an aligned psql-style table followed by a row count.
+pub fn format_table(result: &QueryResult) -> String {
+    let mut widths: Vec<usize> = result.columns.iter().map(|c| c.chars().count()).collect();
+    let cells: Vec<Vec<String>> = result
+        .rows
+        .iter()
+        .map(|row| row.iter().map(display_value).collect())
+        .collect();
+    for row in &cells {
+        for (i, cell) in row.iter().enumerate() {
+            if let Some(width) = widths.get_mut(i) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+    }
+
+    let mut out = String::new();
+    let header: Vec<String> = result
+        .columns
+        .iter()
+        .zip(&widths)
+        .map(|(name, width)| format!(" {:^width$} ", name, width = width))
+        .collect();
+    let _ = writeln!(out, "{}", header.join("|"));
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(w + 2)).collect();
+    let _ = writeln!(out, "{}", rule.join("+"));
+
+    for (row, values) in cells.iter().zip(&result.rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(values)
+            .zip(&widths)
+            .map(|((cell, value), width)| {
+                if value.is_number() {
+                    format!(" {:>width$} ", cell, width = width)
+                } else {
+                    format!(" {:<width$} ", cell, width = width)
+                }
+            })
+            .collect();
+        let _ = writeln!(out, "{}", line.join("|").trim_end());
+    }
+
+    let count = result.rows.len();
+    let _ = writeln!(
+        out,
+        "({} {})",
+        count,
+        if count == 1 { "row" } else { "rows" }
+    );
+    out
+}
+
+/// # Name: format_expanded
+/// ## Description
+/// Formats a result set with one `column | value` line per field, as psql does with `\x`.
+pub fn format_expanded(result: &QueryResult) -> String {
+    let width = result
+        .columns
+        .iter()
+        .map(|c| c.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    if result.rows.is_empty() {
+        out.push_str("(0 rows)\n");
+        return out;
+    }
+    for (n, row) in result.rows.iter().enumerate() {
+        let _ = writeln!(out, "-[ RECORD {} ]-", n + 1);
+        for (column, value) in result.columns.iter().zip(row) {
+            let _ = writeln!(
+                out,
+                "{:<width$} | {}",
+                column,
+                display_value(value),
+                width = width
+            );
+        }
+    }
+    out
+}
+
+/// Whether a statement may change the schema, so completion data should be reloaded.
+fn changes_schema(sql: &str) -> bool {
+    // Skip leading `-- ...` and `/* ... */` comments
+    let mut rest = sql.trim_start();
+    loop {
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, after)| after);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, after)| after);
+        } else {
+            break;
+        }
+        rest = rest.trim_start();
+    }
+    let first = rest
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    matches!(first.as_str(), "create" | "alter" | "drop" | "rename")
+}
+
+struct Console {
+    connection: DatabaseConnection,
+    schema: SchemaInfo,
+    expanded: bool,
+    timing: bool,
+}
+
+impl Console {
+    fn print_result(&self, result: &QueryResult) {
+        match result.rows_affected {
+            Some(affected) => println!("OK, {} row(s) affected", affected),
+            None if self.expanded => print!("{}", format_expanded(result)),
+            None => print!("{}", format_table(result)),
+        }
+    }
+
+    async fn refresh_schema(&mut self) {
+        match introspect_schema(&self.connection).await {
+            Ok(schema) => self.schema = schema,
+            Err(e) => eprintln!("WARNING: could not reload schema: {}", e),
+        }
+    }
+
+    /// Runs one SQL statement and prints its result. Returns true when the schema may have changed.
+    async fn execute(&mut self, sql: &str) -> bool {
+        let started = Instant::now();
+        match run_statement(&self.connection, sql).await {
+            Ok(result) => self.print_result(&result),
+            Err(e) => eprintln!("ERROR: {}", e),
+        }
+        if self.timing {
+            println!("Time: {:.3} ms", started.elapsed().as_secs_f64() * 1000.0);
+        }
+        changes_schema(sql)
+    }
+
+    fn list_tables(&self) {
+        let result = QueryResult {
+            columns: vec!["Name".to_string(), "Columns".to_string()],
+            rows: self
+                .schema
+                .tables
+                .iter()
+                .map(|t| {
+                    vec![
+                        Value::String(t.name.clone()),
+                        Value::from(t.columns.len() as u64),
+                    ]
+                })
+                .collect(),
+            rows_affected: None,
+        };
+        print!("{}", format_table(&result));
+    }
+
+    fn describe_table(&self, name: &str) {
+        let Some(table) = self.schema.table(name) else {
+            eprintln!("Did not find any relation named \"{}\".", name);
+            return;
+        };
+
+        println!("Table \"{}\"", table.name);
+        let result = QueryResult {
+            columns: ["Column", "Type", "Nullable", "Default", "Key"]
+                .iter()
+                .map(|c| c.to_string())
+                .collect(),
+            rows: table
+                .columns
+                .iter()
+                .map(|c| {
+                    vec![
+                        Value::String(c.name.clone()),
+                        Value::String(c.data_type.clone()),
+                        Value::String(if c.nullable { "" } else { "not null" }.to_string()),
+                        Value::String(c.default.clone().unwrap_or_default()),
+                        Value::String(if c.primary_key { "PK" } else { "" }.to_string()),
+                    ]
+                })
+                .collect(),
+            rows_affected: None,
+        };
+        print!("{}", format_table(&result));
+
+        let outgoing = self.schema.foreign_keys_from(&table.name);
+        if !outgoing.is_empty() {
+            println!("Foreign-key constraints:");
+            for fk in outgoing {
+                println!(
+                    "    ({}) REFERENCES {}({})",
+                    fk.column, fk.referenced_table, fk.referenced_column
+                );
+            }
+        }
+        let incoming = self.schema.foreign_keys_to(&table.name);
+        if !incoming.is_empty() {
+            println!("Referenced by:");
+            for fk in incoming {
+                println!(
+                    "    {}({}) REFERENCES {}({})",
+                    fk.table, fk.column, fk.referenced_table, fk.referenced_column
+                );
+            }
+        }
+    }
+
+    async fn include_file(&mut self, path: &str) -> bool {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                return false;
+            }
+        };
+        let (mut statements, rest) = split_sql_statements(&contents);
+        if !rest.is_empty() {
+            statements.push(rest);
+        }
+
+        let mut schema_changed = false;
+        for statement in statements {
+            schema_changed |= self.execute(&statement).await;
+        }
+        schema_changed
+    }
+
+    /// Handles a backslash command. Returns false when the console should exit.
+    async fn meta_command(&mut self, line: &str, helper: Option<&mut ConsoleHelper>) -> bool {
+        let mut parts = line.split_whitespace();
+        let command = parts.next().unwrap_or("");
+        let argument = parts.collect::<Vec<_>>().join(" ");
+
+        match command {
+            "\\q" | "\\quit" => return false,
+            "\\?" | "\\help" => {
+                for (command, description) in META_COMMANDS {
+                    println!("  {:<10} {}", command, description);
+                }
+                println!("  Statements end with ';' and may span several lines.");
+            }
+            "\\dt" => self.list_tables(),
+            "\\d" if argument.is_empty() => self.list_tables(),
+            "\\d" => self.describe_table(&argument),
+            "\\x" => {
+                self.expanded = !self.expanded;
+                println!(
+                    "Expanded display is {}.",
+                    if self.expanded { "on" } else { "off" }
+                );
+            }
+            "\\timing" => {
+                self.timing = !self.timing;
+                println!("Timing is {}.", if self.timing { "on" } else { "off" });
+            }
+            "\\i" if argument.is_empty() => eprintln!("\\i: missing required argument"),
+            "\\i" => {
+                if self.include_file(&argument).await {
+                    self.refresh_schema().await;
+                    if let Some(helper) = helper {
+                        helper.refresh(&self.schema);
+                    }
+                }
+            }
+            other => eprintln!("Invalid command {}. Try \\? for help.", other),
+        }
+        true
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(HISTORY_FILE))
+}
+
+fn readline_error(e: ReadlineError) -> CustomMigrationError {
+    CustomMigrationError::IoError(io::Error::other(e.to_string()))
+}
+
+/// # Name: run_console
+/// ## Description
+/// Starts an interactive SQL console against the database configured in rustyroad.toml.
+/// Works the same for PostgreSQL, MySQL and SQLite, so no native client needs to be installed.
+/// History is kept in `~/.rustyroad_console_history`.
+pub async fn run_console() -> Result<(), CustomMigrationError> {
+    let database = Database::get_database_from_rustyroad_toml()?;
+
+    let connection = Database::create_database_connection(&database)
+        .await
+        .map_err(CustomMigrationError::SendError)?;
+
+    let schema = introspect_schema(&connection).await?;
+    let mut console = Console {
+        connection,
+        schema,
+        expanded: false,
+        timing: false,
+    };
+
+    let mut editor: Editor<ConsoleHelper, DefaultHistory> =
+        Editor::new().map_err(readline_error)?;
+    editor.set_helper(Some(ConsoleHelper::new(&console.schema)));
+    let history = history_path();
+    if let Some(path) = &history {
+        // A missing history file just means this is the first session
+        let _ = editor.load_history(path);
+    }
+
+    println!(
+        "rustyroad console ({} database \"{}\")",
+        database.database_type, database.name
+    );
+    println!("Type \\? for help, \\q to quit.");
+
+    let mut buffer = String::new();
+    loop {
+        let prompt = if buffer.is_empty() {
+            "rustyroad=> "
+        } else {
+            "rustyroad-> "
+        };
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                buffer.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(readline_error(e)),
+        };
+
+        if buffer.is_empty() && line.trim_start().starts_with('\\') {
+            let _ = editor.add_history_entry(line.trim());
+            if !console.meta_command(line.trim(), editor.helper_mut()).await {
+                break;
+            }
+            continue;
+        }
+
+        buffer.push_str(&line);
+        buffer.push('\n');
+        let (statements, rest) = split_sql_statements(&buffer);
+        if statements.is_empty() {
+            if rest.is_empty() {
+                buffer.clear();
+            }
+            continue;
+        }
+
+        let _ = editor.add_history_entry(buffer.trim());
+        buffer = rest;
+
+        let mut schema_changed = false;
+        for statement in statements {
+            schema_changed |= console.execute(&statement).await;
+        }
+        if schema_changed {
+            console.refresh_schema().await;
+            if let Some(helper) = editor.helper_mut() {
+                helper.refresh(&console.schema);
+            }
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Err(e) = editor.save_history(path) {
+            eprintln!("WARNING: could not save history: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{ColumnInfo, TableInfo};
+
+    fn users_schema() -> SchemaInfo {
+        let column = |name: &str| ColumnInfo {
+            name: name.to_string(),
+            data_type: "text".to_string(),
+            nullable: true,
+            default: None,
+            primary_key: false,
+        };
+        SchemaInfo {
+            tables: vec![TableInfo {
+                name: "users".to_string(),
+                columns: vec![column("id"), column("email"), column("username")],
+            }],
+            foreign_keys: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_candidates() {
+        let helper = ConsoleHelper::new(&users_schema());
+
+        assert_eq!(helper.candidates("us"), vec!["username", "users"]);
+        assert_eq!(helper.candidates("users.e"), vec!["users.email"]);
+        assert_eq!(helper.candidates("SEL"), vec!["SELECT"]);
+        assert_eq!(helper.candidates("sel"), vec!["select"]);
+        assert_eq!(helper.candidates("\\d"), vec!["\\dt", "\\d"]);
+    }
+
+    #[test]
+    fn test_format_table() {
+        let result = QueryResult {
+            columns: vec!["id".to_string(), "email".to_string()],
+            rows: vec![
+                vec![Value::from(1), Value::String("a@example.com".to_string())],
+                vec![Value::from(10), Value::Null],
+            ],
+            rows_affected: None,
+        };
+
+        assert_eq!(
+            format_table(&result),
+            " id |     email     \n\
+             ----+---------------\n  \
+             1 | a@example.com\n \
+             10 | NULL\n\
+             (2 rows)\n"
+        );
+    }
+
+    #[test]
+    fn test_format_expanded() {
+        let result = QueryResult {
+            columns: vec!["id".to_string(), "email".to_string()],
+            rows: vec![vec![Value::from(1), Value::String("a".to_string())]],
+            rows_affected: None,
+        };
+
+        assert_eq!(
+            format_expanded(&result),
+            "-[ RECORD 1 ]-\nid    | 1\nemail | a\n"
+        );
+    }
+
+    #[test]
+    fn test_changes_schema() {
+        assert!(changes_schema("CREATE TABLE posts (id int)"));
+        assert!(changes_schema(
+            "-- add a column\nalter table posts add title text"
+        ));
+        assert!(changes_schema("/* cleanup */ DROP TABLE posts;"));
+        assert!(changes_schema("/* a\nb */\n-- c\n  rename table a to b"));
+        assert!(!changes_schema("-- drop table posts\nSELECT 1"));
+        assert!(!changes_schema("/* create */ select * from posts"));
+        assert!(!changes_schema("-- only a comment"));
+        assert!(!changes_schema(""));
+    }
+}
@@ -41,6 +41,40 @@ pub enum DatabaseConnection {
     Sqlite(Arc<SqlitePool>),
 }
 
+impl DatabaseConnection {
+    /// The backend this connection talks to, which decides the SQL dialect.
+    pub fn database_type(&self) -> DatabaseType {
+        match self {
+            DatabaseConnection::Pg(_) => DatabaseType::Postgres,
+            DatabaseConnection::MySql(_) => DatabaseType::Mysql,
+            DatabaseConnection::Sqlite(_) => DatabaseType::Sqlite,
+        }
+    }
+}
+
+/// Generated models open their pool with `Database::get_db_pool`, and hand it to the model
+/// and association functions as a `DatabaseConnection`.
+impl From<PoolConnection> for DatabaseConnection {
+    fn from(pool: PoolConnection) -> Self {
+        match pool {
+            PoolConnection::Pg(pool) => DatabaseConnection::Pg(Arc::new(pool)),
+            PoolConnection::MySql(pool) => DatabaseConnection::MySql(Arc::new(pool)),
+            PoolConnection::Sqlite(pool) => DatabaseConnection::Sqlite(Arc::new(pool)),
+        }
+    }
+}
+
+/// The pool of a `DatabaseConnection`, for `get_db_pool` to hand out a test database.
+impl From<DatabaseConnection> for PoolConnection {
+    fn from(connection: DatabaseConnection) -> Self {
+        match connection {
+            DatabaseConnection::Pg(pool) => PoolConnection::Pg((*pool).clone()),
+            DatabaseConnection::MySql(pool) => PoolConnection::MySql((*pool).clone()),
+            DatabaseConnection::Sqlite(pool) => PoolConnection::Sqlite((*pool).clone()),
+        }
+    }
+}
+
 /// # Name: Database
 /// ## Description
 /// Struct representing a database connection configuration.
@@ -303,7 +337,8 @@ impl Database {
     /// * `database` - Database struct
     ///
     /// # Returns
-    /// * `DatabaseConnection` - Database connection pool
+    /// * `PoolConnection` - Database connection pool, or the error connecting to it. MongoDB has
+    ///   no pool, so it is an error too.
     ///
     /// # Example
     /// ```
@@ -312,24 +347,28 @@ impl Database {
     /// let db_pool = database.get_db_pool(database).unwrap();
     /// ```
     pub async fn get_db_pool(database: Database) -> Result<PoolConnection, Box<dyn Error + Send>> {
+        // Tests running in `TestDatabase::scope` use the test database
+        if let Some(connection) = crate::testing::scoped_connection() {
+            return Ok(PoolConnection::from(connection));
+        }
+        let boxed = |e: sqlx::Error| Box::new(e) as Box<dyn Error + Send>;
         match database.database_type {
             DatabaseType::Mysql => {
-                let pool = get_mysql_pool(&database)
-                    .await
-                    .expect("Error getting mysql pool");
+                let pool = get_mysql_pool(&database).await.map_err(boxed)?;
                 Ok(PoolConnection::MySql(pool))
             }
             DatabaseType::Sqlite => {
-                let pool = get_sqlite_pool(&database)
-                    .await
-                    .expect("Error getting sqlite pool");
+                let pool = get_sqlite_pool(&database).await.map_err(boxed)?;
                 Ok(PoolConnection::Sqlite(pool))
             }
             DatabaseType::Postgres => {
-                let pool = get_pg_pool(&database).await.expect("Error getting pg pool");
+                let pool = get_pg_pool(&database).await.map_err(boxed)?;
                 Ok(PoolConnection::Pg(pool))
             }
-            DatabaseType::Mongo => todo!(),
+            DatabaseType::Mongo => Err(Box::new(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "MongoDB has no sqlx connection pool",
+            ))),
         }
     }
 }
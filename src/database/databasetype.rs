@@ -23,6 +23,25 @@ impl Display for DatabaseType {
     }
 }
 
+impl DatabaseType {
+    /// `name` quoted as an identifier: backticks on MySQL, double quotes elsewhere.
+    pub fn quote_identifier(&self, name: &str) -> String {
+        match self {
+            DatabaseType::Mysql => format!("`{}`", name.replace('`', "``")),
+            _ => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+
+    /// The bind placeholder of the `index`th parameter, counting from 1: `$1` on PostgreSQL,
+    /// `?` elsewhere.
+    pub fn placeholder(&self, index: usize) -> String {
+        match self {
+            DatabaseType::Postgres => format!("${}", index),
+            _ => "?".to_string(),
+        }
+    }
+}
+
 pub trait DatabaseTypeTrait {
     type DatabaseType;
     type DataType: Clone;
@@ -0,0 +1,401 @@
+use crate::database::migrations::CustomMigrationError;
+use crate::database::{introspect_schema, Database, SchemaInfo};
+use std::fmt::Write;
+use std::io;
+use std::str::FromStr;
+
+/// # Name: DiagramFormat
+/// ## Description
+/// Text formats an entity-relationship diagram can be rendered to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramFormat {
+    Mermaid,
+    Dot,
+    PlantUml,
+}
+
+impl FromStr for DiagramFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "mermaid" => Ok(DiagramFormat::Mermaid),
+            "dot" | "graphviz" => Ok(DiagramFormat::Dot),
+            "plantuml" | "puml" => Ok(DiagramFormat::PlantUml),
+            other => Err(format!(
+                "Unknown diagram format '{other}'. Supported: mermaid, dot, plantuml"
+            )),
+        }
+    }
+}
+
+/// # Name: render_er_diagram
+/// ## Description
+/// Renders the tables and foreign keys of `schema` as an entity-relationship diagram.
+/// ## Example
+/// ```
+/// use rustyroad::database::{render_er_diagram, DiagramFormat, SchemaInfo};
+///
+/// let diagram = render_er_diagram(&SchemaInfo::default(), DiagramFormat::Mermaid);
+/// assert!(diagram.starts_with("erDiagram"));
+/// ```
+pub fn render_er_diagram(schema: &SchemaInfo, format: DiagramFormat) -> String {
+    match format {
+        DiagramFormat::Mermaid => render_mermaid(schema),
+        DiagramFormat::Dot => render_dot(schema),
+        DiagramFormat::PlantUml => render_plantuml(schema),
+    }
+}
+
+/// Whether `table.column` is the source of a foreign key.
+fn is_foreign_key(schema: &SchemaInfo, table: &str, column: &str) -> bool {
+    schema
+        .foreign_keys
+        .iter()
+        .any(|fk| fk.table == table && fk.column == column)
+}
+
+/// Whether the foreign key column allows NULL, i.e. the parent is optional.
+fn is_optional_reference(schema: &SchemaInfo, table: &str, column: &str) -> bool {
+    schema
+        .table(table)
+        .and_then(|t| t.column(column))
+        .map(|c| c.nullable)
+        .unwrap_or(true)
+}
+
+/// Reduces a name or type to characters every diagram syntax accepts as a bare token.
+fn identifier(value: &str) -> String {
+    let cleaned: String = value
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let cleaned = cleaned.trim_matches('_').to_string();
+    if cleaned.is_empty() {
+        "unknown".to_string()
+    } else {
+        cleaned
+    }
+}
+
+fn render_mermaid(schema: &SchemaInfo) -> String {
+    let mut out = String::from("erDiagram\n");
+
+    for table in &schema.tables {
+        let _ = writeln!(out, "    {} {{", identifier(&table.name));
+        for column in &table.columns {
+            let mut keys = Vec::new();
+            if column.primary_key {
+                keys.push("PK");
+            }
+            if is_foreign_key(schema, &table.name, &column.name) {
+                keys.push("FK");
+            }
+            let _ = write!(
+                out,
+                "        {} {}",
+                identifier(&column.data_type),
+                identifier(&column.name)
+            );
+            if !keys.is_empty() {
+                let _ = write!(out, " {}", keys.join(", "));
+            }
+            out.push('\n');
+        }
+        out.push_str("    }\n");
+    }
+
+    for fk in &schema.foreign_keys {
+        let parent = if is_optional_reference(schema, &fk.table, &fk.column) {
+            "o|"
+        } else {
+            "||"
+        };
+        let _ = writeln!(
+            out,
+            "    {} }}o--{} {} : \"{}\"",
+            identifier(&fk.table),
+            parent,
+            identifier(&fk.referenced_table),
+            fk.column
+        );
+    }
+
+    out
+}
+
+/// Escapes text for use inside a Graphviz HTML-like label.
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escapes text for use inside a double-quoted DOT string.
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn render_dot(schema: &SchemaInfo) -> String {
+    let mut out = String::from("digraph schema {\n");
+    out.push_str("    rankdir=LR;\n");
+    out.push_str("    node [shape=plaintext, fontname=\"Helvetica\"];\n");
+    out.push_str("    edge [arrowhead=crow, arrowtail=none, fontname=\"Helvetica\"];\n\n");
+
+    for table in &schema.tables {
+        let _ = write!(
+            out,
+            "    \"{}\" [label=<<TABLE BORDER=\"0\" CELLBORDER=\"1\" CELLSPACING=\"0\" CELLPADDING=\"4\">\
+<TR><TD BGCOLOR=\"lightgrey\"><B>{}</B></TD></TR>",
+            dot_escape(&table.name),
+            html_escape(&table.name)
+        );
+        for column in &table.columns {
+            let mut label = format!(
+                "{} : {}",
+                html_escape(&column.name),
+                html_escape(&column.data_type)
+            );
+            if column.primary_key {
+                label = format!("<U>{}</U> (PK)", label);
+            } else if is_foreign_key(schema, &table.name, &column.name) {
+                label.push_str(" (FK)");
+            }
+            if !column.nullable && !column.primary_key {
+                label.push_str(" NOT NULL");
+            }
+            let _ = write!(
+                out,
+                "<TR><TD PORT=\"{}\" ALIGN=\"LEFT\">{}</TD></TR>",
+                identifier(&column.name),
+                label
+            );
+        }
+        out.push_str("</TABLE>>];\n");
+    }
+
+    if !schema.foreign_keys.is_empty() {
+        out.push('\n');
+    }
+    for fk in &schema.foreign_keys {
+        let style = if is_optional_reference(schema, &fk.table, &fk.column) {
+            ", style=dashed"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            "    \"{}\":\"{}\" -> \"{}\":\"{}\" [label=\"{}\"{}];",
+            dot_escape(&fk.table),
+            identifier(&fk.column),
+            dot_escape(&fk.referenced_table),
+            identifier(&fk.referenced_column),
+            dot_escape(&fk.column),
+            style
+        );
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+fn render_plantuml(schema: &SchemaInfo) -> String {
+    let mut out = String::from("@startuml\n");
+    out.push_str("hide circle\n");
+    out.push_str("skinparam linetype ortho\n\n");
+
+    for table in &schema.tables {
+        let _ = writeln!(
+            out,
+            "entity \"{}\" as {} {{",
+            table.name,
+            identifier(&table.name)
+        );
+        let (keys, others): (Vec<_>, Vec<_>) = table.columns.iter().partition(|c| c.primary_key);
+        for column in &keys {
+            let _ = writeln!(out, "  * {} : {} <<PK>>", column.name, column.data_type);
+        }
+        out.push_str("  --\n");
+        for column in &others {
+            let marker = if column.nullable { "" } else { "* " };
+            let fk = if is_foreign_key(schema, &table.name, &column.name) {
+                " <<FK>>"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                out,
+                "  {}{} : {}{}",
+                marker, column.name, column.data_type, fk
+            );
+        }
+        out.push_str("}\n\n");
+    }
+
+    for fk in &schema.foreign_keys {
+        let parent = if is_optional_reference(schema, &fk.table, &fk.column) {
+            "o|"
+        } else {
+            "||"
+        };
+        let _ = writeln!(
+            out,
+            "{} }}o--{} {} : {}",
+            identifier(&fk.table),
+            parent,
+            identifier(&fk.referenced_table),
+            fk.column
+        );
+    }
+
+    out.push_str("@enduml\n");
+    out
+}
+
+/// # Name: print_er_diagram
+/// ## Description
+/// Introspects the database configured in rustyroad.toml and prints an entity-relationship diagram.
+/// RustyRoad's own bookkeeping tables are left out unless they are named in `tables`.
+/// ## Arguments
+/// * `format` - The diagram syntax to emit.
+/// * `tables` - Optional list of tables to include. An empty list includes every table.
+pub async fn print_er_diagram(
+    format: DiagramFormat,
+    tables: &[String],
+) -> Result<(), CustomMigrationError> {
+    let database = Database::get_database_from_rustyroad_toml()?;
+
+    let connection = Database::create_database_connection(&database)
+        .await
+        .map_err(CustomMigrationError::SendError)?;
+
+    let mut schema = introspect_schema(&connection).await?;
+    if tables.is_empty() {
+        schema = schema.without_internal_tables();
+    }
+    schema.retain_tables(tables);
+
+    let missing: Vec<&String> = tables
+        .iter()
+        .filter(|name| schema.table(name).is_none())
+        .collect();
+    if !missing.is_empty() {
+        return Err(CustomMigrationError::IoError(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "Table(s) not found: {}",
+                missing
+                    .iter()
+                    .map(|s| s.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        )));
+    }
+
+    print!("{}", render_er_diagram(&schema, format));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{ColumnInfo, ForeignKeyInfo, TableInfo};
+
+    fn blog_schema() -> SchemaInfo {
+        let column = |name: &str, data_type: &str, nullable: bool, primary_key: bool| ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable,
+            default: None,
+            primary_key,
+        };
+        SchemaInfo {
+            tables: vec![
+                TableInfo {
+                    name: "posts".to_string(),
+                    columns: vec![
+                        column("id", "integer", false, true),
+                        column("title", "character varying", false, false),
+                        column("user_id", "integer", false, false),
+                    ],
+                },
+                TableInfo {
+                    name: "users".to_string(),
+                    columns: vec![
+                        column("id", "integer", false, true),
+                        column("email", "text", true, false),
+                    ],
+                },
+            ],
+            foreign_keys: vec![ForeignKeyInfo {
+                table: "posts".to_string(),
+                column: "user_id".to_string(),
+                referenced_table: "users".to_string(),
+                referenced_column: "id".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_render_mermaid() {
+        let diagram = render_er_diagram(&blog_schema(), DiagramFormat::Mermaid);
+
+        assert!(diagram.starts_with("erDiagram\n"));
+        assert!(diagram.contains("    posts {\n        integer id PK\n"));
+        assert!(diagram.contains("        character_varying title\n"));
+        assert!(diagram.contains("        integer user_id FK\n"));
+        assert!(diagram.contains("    posts }o--|| users : \"user_id\"\n"));
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let diagram = render_er_diagram(&blog_schema(), DiagramFormat::Dot);
+
+        assert!(diagram.starts_with("digraph schema {\n"));
+        assert!(diagram
+            .contains("<TD PORT=\"user_id\" ALIGN=\"LEFT\">user_id : integer (FK) NOT NULL</TD>"));
+        assert!(diagram.contains("\"posts\":\"user_id\" -> \"users\":\"id\" [label=\"user_id\"];"));
+        assert!(diagram.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn test_render_dot_escapes_names() {
+        let mut schema = blog_schema();
+        schema.tables[0].name = "a\"b<&>".to_string();
+        schema.tables[0].columns[1].name = "x<y".to_string();
+        schema.foreign_keys[0].table = "a\"b<&>".to_string();
+
+        let diagram = render_er_diagram(&schema, DiagramFormat::Dot);
+
+        assert!(diagram.contains("    \"a\\\"b<&>\" [label=<"));
+        assert!(diagram.contains("<B>a&quot;b&lt;&amp;&gt;</B>"));
+        assert!(diagram.contains(">x&lt;y : character varying NOT NULL</TD>"));
+        assert!(diagram.contains("\"a\\\"b<&>\":\"user_id\" -> \"users\":\"id\""));
+    }
+
+    #[test]
+    fn test_render_plantuml() {
+        let diagram = render_er_diagram(&blog_schema(), DiagramFormat::PlantUml);
+
+        assert!(diagram.starts_with("@startuml\n"));
+        assert!(diagram.contains(
+            "entity \"users\" as users {\n  * id : integer <<PK>>\n  --\n  email : text\n}"
+        ));
+        assert!(diagram.contains("  * user_id : integer <<FK>>\n"));
+        assert!(diagram.contains("posts }o--|| users : user_id\n"));
+        assert!(diagram.ends_with("@enduml\n"));
+    }
+
+    #[test]
+    fn test_diagram_format_from_str() {
+        assert_eq!("Mermaid".parse(), Ok(DiagramFormat::Mermaid));
+        assert_eq!("graphviz".parse(), Ok(DiagramFormat::Dot));
+        assert_eq!("plantuml".parse(), Ok(DiagramFormat::PlantUml));
+        assert!("svg".parse::<DiagramFormat>().is_err());
+    }
+}
@@ -0,0 +1,738 @@
+use crate::database::migrations::CustomMigrationError;
+use crate::database::{run_statement, Database, DatabaseConnection};
+use color_eyre::owo_colors::{OwoColorize, Style};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::io::IsTerminal;
+use std::time::Instant;
+
+/// # Name: ExplainMode
+/// ## Description
+/// Whether to only plan the query or also execute it and collect actual timings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplainMode {
+    Plan,
+    Analyze,
+}
+
+/// # Name: PlanNode
+/// ## Description
+/// One step of a query plan, normalized across PostgreSQL, MySQL and SQLite.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PlanNode {
+    pub operation: String,
+    pub relation: Option<String>,
+    pub index: Option<String>,
+    pub estimated_cost: Option<f64>,
+    pub estimated_rows: Option<f64>,
+    pub actual_time_ms: Option<f64>,
+    pub actual_rows: Option<f64>,
+    pub sequential_scan: bool,
+    pub detail: Option<String>,
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    fn walk<'a>(&'a self, nodes: &mut Vec<&'a PlanNode>) {
+        nodes.push(self);
+        for child in &self.children {
+            child.walk(nodes);
+        }
+    }
+}
+
+/// # Name: QueryPlan
+/// ## Description
+/// A normalized query plan plus the findings worth a second look (sequential scans,
+/// unused indexes, temporary sorts).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct QueryPlan {
+    pub backend: String,
+    pub analyzed: bool,
+    pub nodes: Vec<PlanNode>,
+    pub estimated_cost: Option<f64>,
+    pub execution_time_ms: Option<f64>,
+    pub warnings: Vec<String>,
+}
+
+impl QueryPlan {
+    fn new(backend: &str, analyzed: bool, nodes: Vec<PlanNode>) -> Self {
+        let mut plan = QueryPlan {
+            backend: backend.to_string(),
+            analyzed,
+            estimated_cost: nodes.first().and_then(|n| n.estimated_cost),
+            nodes,
+            ..Default::default()
+        };
+        plan.warnings = plan_warnings(&plan.nodes);
+        plan
+    }
+
+    /// Every node of the plan, depth first.
+    pub fn all_nodes(&self) -> Vec<&PlanNode> {
+        let mut nodes = Vec::new();
+        for node in &self.nodes {
+            node.walk(&mut nodes);
+        }
+        nodes
+    }
+}
+
+fn plan_warnings(nodes: &[PlanNode]) -> Vec<String> {
+    let mut all = Vec::new();
+    for node in nodes {
+        node.walk(&mut all);
+    }
+
+    let mut warnings = Vec::new();
+    for node in all {
+        if node.sequential_scan {
+            let relation = node.relation.as_deref().unwrap_or("?");
+            match &node.detail {
+                Some(detail) => warnings.push(format!(
+                    "Sequential scan on {} filtered by {}: an index on the filtered column(s) may help",
+                    relation, detail
+                )),
+                None => warnings.push(format!("Sequential scan on {}", relation)),
+            }
+        }
+        let operation = node.operation.to_ascii_lowercase();
+        if operation.contains("temp b-tree") || operation.contains("filesort") {
+            warnings.push(format!(
+                "{}: consider an index matching the ORDER BY / GROUP BY",
+                node.operation
+            ));
+        }
+    }
+    warnings
+}
+
+fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn text(value: Option<&Value>) -> Option<String> {
+    value.and_then(Value::as_str).map(str::to_string)
+}
+
+/// Normalizes the output of `EXPLAIN (FORMAT JSON)` on PostgreSQL.
+fn postgres_node(plan: &Value) -> PlanNode {
+    let operation = text(plan.get("Node Type")).unwrap_or_else(|| "Unknown".to_string());
+    let detail = [
+        "Filter",
+        "Index Cond",
+        "Hash Cond",
+        "Join Filter",
+        "Merge Cond",
+        "Sort Key",
+    ]
+    .iter()
+    .find_map(|key| match plan.get(*key)? {
+        Value::Array(items) => Some(
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        other => other.as_str().map(str::to_string),
+    });
+
+    PlanNode {
+        sequential_scan: operation == "Seq Scan",
+        operation,
+        relation: text(plan.get("Relation Name")),
+        index: text(plan.get("Index Name")),
+        estimated_cost: number(plan.get("Total Cost")),
+        estimated_rows: number(plan.get("Plan Rows")),
+        actual_time_ms: number(plan.get("Actual Total Time")),
+        actual_rows: number(plan.get("Actual Rows")),
+        detail,
+        children: plan
+            .get("Plans")
+            .and_then(Value::as_array)
+            .map(|plans| plans.iter().map(postgres_node).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// # Name: parse_postgres_plan
+/// ## Description
+/// Builds a `QueryPlan` from the JSON document returned by `EXPLAIN (FORMAT JSON)`.
+pub fn parse_postgres_plan(document: &Value, analyzed: bool) -> QueryPlan {
+    let top = match document {
+        Value::Array(items) => items.first().cloned().unwrap_or(Value::Null),
+        other => other.clone(),
+    };
+    let nodes = top.get("Plan").map(postgres_node).into_iter().collect();
+
+    let mut plan = QueryPlan::new("postgres", analyzed, nodes);
+    plan.execution_time_ms = number(top.get("Execution Time"));
+    plan
+}
+
+fn mysql_table_node(table: &Value, warnings: &mut Vec<String>) -> PlanNode {
+    let name = text(table.get("table_name"));
+    let access_type = text(table.get("access_type")).unwrap_or_default();
+    let key = text(table.get("key"));
+    let possible_keys = table.get("possible_keys").and_then(Value::as_array);
+
+    let operation = match access_type.as_str() {
+        "ALL" => "Full table scan".to_string(),
+        "index" => "Full index scan".to_string(),
+        "" => "Table access".to_string(),
+        other => format!("Index lookup ({})", other),
+    };
+    if access_type == "ALL" && key.is_none() {
+        if let Some(keys) = possible_keys {
+            let keys: Vec<&str> = keys.iter().filter_map(Value::as_str).collect();
+            warnings.push(format!(
+                "{}: possible index(es) {} not used",
+                name.as_deref().unwrap_or("?"),
+                keys.join(", ")
+            ));
+        }
+    }
+
+    let mut children = Vec::new();
+    if let Some(subquery) = table.get("materialized_from_subquery") {
+        children.extend(mysql_nodes(subquery, warnings));
+    }
+
+    PlanNode {
+        operation,
+        relation: name,
+        index: key,
+        estimated_cost: number(table.get("cost_info").and_then(|c| c.get("prefix_cost"))),
+        estimated_rows: number(table.get("rows_examined_per_scan")),
+        sequential_scan: access_type == "ALL",
+        detail: text(table.get("attached_condition")),
+        children,
+        ..Default::default()
+    }
+}
+
+fn mysql_nodes(value: &Value, warnings: &mut Vec<String>) -> Vec<PlanNode> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .flat_map(|item| mysql_nodes(item, warnings))
+            .collect(),
+        Value::Object(map) if map.contains_key("table_name") => {
+            vec![mysql_table_node(value, warnings)]
+        }
+        Value::Object(map) => {
+            let mut nodes = Vec::new();
+            for (key, child) in map {
+                if !(child.is_object() || child.is_array()) || key == "cost_info" {
+                    continue;
+                }
+                if key == "table" {
+                    nodes.extend(mysql_nodes(child, warnings));
+                    continue;
+                }
+                let mut operation = key.replace('_', " ");
+                if child.get("using_filesort").and_then(Value::as_bool) == Some(true) {
+                    operation.push_str(" (filesort)");
+                }
+                if child.get("using_temporary_table").and_then(Value::as_bool) == Some(true) {
+                    operation.push_str(" (temporary table)");
+                }
+                nodes.push(PlanNode {
+                    operation,
+                    estimated_cost: number(
+                        child
+                            .get("cost_info")
+                            .and_then(|c| c.get("query_cost").or_else(|| c.get("sort_cost"))),
+                    ),
+                    children: mysql_nodes(child, warnings),
+                    ..Default::default()
+                });
+            }
+            nodes
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// # Name: parse_mysql_plan
+/// ## Description
+/// Builds a `QueryPlan` from the JSON document returned by `EXPLAIN FORMAT=JSON`.
+pub fn parse_mysql_plan(document: &Value) -> QueryPlan {
+    let mut warnings = Vec::new();
+    let nodes = mysql_nodes(document, &mut warnings);
+    let mut plan = QueryPlan::new("mysql", false, nodes);
+    plan.warnings.extend(warnings);
+    plan
+}
+
+/// # Name: parse_mysql_tree
+/// ## Description
+/// Builds a `QueryPlan` from the indented `-> ...` text of MySQL's `EXPLAIN ANALYZE`.
+pub fn parse_mysql_tree(output: &str) -> QueryPlan {
+    let estimate = Regex::new(r"\(cost=([\d.]+)(?:\.\.[\d.]+)? rows=([\d.]+)\)").unwrap();
+    let actual = Regex::new(r"\(actual time=[\d.]+\.\.([\d.]+) rows=([\d.]+) loops=\d+\)").unwrap();
+    let table = Regex::new(r" on (\w+)").unwrap();
+    let index = Regex::new(r" using (\w+)").unwrap();
+
+    // (depth, node) pairs; a node is attached to its parent once all its children are read
+    let mut stack: Vec<(usize, PlanNode)> = Vec::new();
+    let mut roots = Vec::new();
+
+    for line in output.lines() {
+        let Some(arrow) = line.find("-> ") else {
+            continue;
+        };
+        let depth = arrow / 4;
+        let body = &line[arrow + 3..];
+        let operation = body.split(" (").next().unwrap_or(body).trim().to_string();
+
+        let mut node = PlanNode {
+            sequential_scan: operation.starts_with("Table scan"),
+            relation: table.captures(&operation).map(|c| c[1].to_string()),
+            index: index.captures(&operation).map(|c| c[1].to_string()),
+            operation,
+            ..Default::default()
+        };
+        if let Some(c) = estimate.captures(body) {
+            node.estimated_cost = c[1].parse().ok();
+            node.estimated_rows = c[2].parse().ok();
+        }
+        if let Some(c) = actual.captures(body) {
+            node.actual_time_ms = c[1].parse().ok();
+            node.actual_rows = c[2].parse().ok();
+        }
+
+        while stack.last().is_some_and(|(d, _)| *d >= depth) {
+            let (_, finished) = stack.pop().unwrap();
+            match stack.last_mut() {
+                Some((_, parent)) => parent.children.push(finished),
+                None => roots.push(finished),
+            }
+        }
+        stack.push((depth, node));
+    }
+    while let Some((_, finished)) = stack.pop() {
+        match stack.last_mut() {
+            Some((_, parent)) => parent.children.push(finished),
+            None => roots.push(finished),
+        }
+    }
+
+    let mut plan = QueryPlan::new("mysql", true, roots);
+    plan.execution_time_ms = plan.nodes.first().and_then(|n| n.actual_time_ms);
+    plan
+}
+
+/// # Name: parse_sqlite_plan
+/// ## Description
+/// Builds a `QueryPlan` from the `(id, parent, detail)` rows of `EXPLAIN QUERY PLAN`.
+pub fn parse_sqlite_plan(rows: &[(i64, i64, String)]) -> QueryPlan {
+    let scan = Regex::new(r"^(SCAN|SEARCH)(?: TABLE)? (\w+)(?: AS \w+)?(.*)$").unwrap();
+    let index = Regex::new(r"USING (?:COVERING )?INDEX (\w+)").unwrap();
+
+    let mut by_parent: BTreeMap<i64, Vec<(i64, PlanNode)>> = BTreeMap::new();
+    for (id, parent, detail) in rows {
+        let mut node = PlanNode {
+            operation: detail.clone(),
+            ..Default::default()
+        };
+        if let Some(c) = scan.captures(detail) {
+            let uses_index = c[3].contains("INDEX") || c[3].contains("PRIMARY KEY");
+            node.operation = match (&c[1], uses_index) {
+                ("SCAN", false) => "Table scan".to_string(),
+                ("SCAN", true) => "Index scan".to_string(),
+                _ => "Index search".to_string(),
+            };
+            node.relation = Some(c[2].to_string());
+            node.index = index.captures(&c[3]).map(|i| i[1].to_string()).or_else(|| {
+                c[3].contains("PRIMARY KEY")
+                    .then(|| "PRIMARY KEY".to_string())
+            });
+            node.sequential_scan = &c[1] == "SCAN" && !uses_index;
+            let rest = c[3].trim();
+            if !rest.is_empty() {
+                node.detail = Some(rest.to_string());
+            }
+        }
+        by_parent.entry(*parent).or_default().push((*id, node));
+    }
+
+    fn attach(id: i64, by_parent: &mut BTreeMap<i64, Vec<(i64, PlanNode)>>) -> Vec<PlanNode> {
+        by_parent
+            .remove(&id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(child_id, mut node)| {
+                node.children = attach(child_id, by_parent);
+                node
+            })
+            .collect()
+    }
+
+    let nodes = attach(0, &mut by_parent);
+    QueryPlan::new("sqlite", false, nodes)
+}
+
+fn first_cell(result: &crate::database::QueryResult) -> Value {
+    result
+        .rows
+        .first()
+        .and_then(|row| row.first())
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+/// # Name: explain_statement
+/// ## Description
+/// Runs the backend's EXPLAIN for `sql` and normalizes the result:
+/// `EXPLAIN (FORMAT JSON)` on PostgreSQL, `EXPLAIN FORMAT=JSON` on MySQL
+/// (`EXPLAIN ANALYZE` when analyzing) and `EXPLAIN QUERY PLAN` on SQLite.
+/// SQLite has no EXPLAIN ANALYZE, so in that mode the query is executed and timed as a whole.
+/// ## Warning
+/// `ExplainMode::Analyze` executes the statement, including any writes it makes.
+pub async fn explain_statement(
+    connection: &DatabaseConnection,
+    sql: &str,
+    mode: ExplainMode,
+) -> Result<QueryPlan, sqlx::Error> {
+    let sql = sql.trim().trim_end_matches(';');
+    let analyze = mode == ExplainMode::Analyze;
+
+    match connection {
+        DatabaseConnection::Pg(_) => {
+            let explain = if analyze {
+                format!("EXPLAIN (ANALYZE, FORMAT JSON) {}", sql)
+            } else {
+                format!("EXPLAIN (FORMAT JSON) {}", sql)
+            };
+            let result = run_statement(connection, &explain).await?;
+            let document = match first_cell(&result) {
+                Value::String(s) => serde_json::from_str(&s).unwrap_or(Value::Null),
+                other => other,
+            };
+            Ok(parse_postgres_plan(&document, analyze))
+        }
+        DatabaseConnection::MySql(_) => {
+            if analyze {
+                let result = run_statement(connection, &format!("EXPLAIN ANALYZE {}", sql)).await?;
+                let output = match first_cell(&result) {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                Ok(parse_mysql_tree(&output))
+            } else {
+                let result =
+                    run_statement(connection, &format!("EXPLAIN FORMAT=JSON {}", sql)).await?;
+                let document = match first_cell(&result) {
+                    Value::String(s) => serde_json::from_str(&s).unwrap_or(Value::Null),
+                    other => other,
+                };
+                Ok(parse_mysql_plan(&document))
+            }
+        }
+        DatabaseConnection::Sqlite(_) => {
+            let result = run_statement(connection, &format!("EXPLAIN QUERY PLAN {}", sql)).await?;
+            let rows: Vec<(i64, i64, String)> = result
+                .rows
+                .iter()
+                .map(|row| {
+                    (
+                        row.first().and_then(Value::as_i64).unwrap_or_default(),
+                        row.get(1).and_then(Value::as_i64).unwrap_or_default(),
+                        row.get(3)
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string(),
+                    )
+                })
+                .collect();
+            let mut plan = parse_sqlite_plan(&rows);
+
+            if analyze {
+                let started = Instant::now();
+                run_statement(connection, sql).await?;
+                plan.analyzed = true;
+                plan.execution_time_ms = Some(started.elapsed().as_secs_f64() * 1000.0);
+            }
+            Ok(plan)
+        }
+    }
+}
+
+/// `style` when the plan is coloured, otherwise a style that writes no escape codes.
+fn paint(color: bool, style: Style) -> Style {
+    if color {
+        style
+    } else {
+        Style::new()
+    }
+}
+
+fn render_node(out: &mut String, node: &PlanNode, prefix: &str, last: bool, color: bool) {
+    let branch = if last { "└─ " } else { "├─ " };
+    let mut line = node.operation.clone();
+    if let Some(relation) = &node.relation {
+        if !line.contains(relation.as_str()) {
+            let _ = write!(line, " on {}", relation);
+        }
+    }
+    if let Some(index) = &node.index {
+        if !line.contains(index.as_str()) {
+            let _ = write!(line, " using {}", index);
+        }
+    }
+
+    let mut stats = Vec::new();
+    if let Some(cost) = node.estimated_cost {
+        stats.push(format!("cost={:.2}", cost));
+    }
+    if let Some(rows) = node.estimated_rows {
+        stats.push(format!("rows={}", rows));
+    }
+    if let Some(time) = node.actual_time_ms {
+        stats.push(format!("actual={:.3}ms", time));
+    }
+    if let Some(rows) = node.actual_rows {
+        stats.push(format!("actual rows={}", rows));
+    }
+
+    let _ = write!(out, "{}{}", prefix, branch);
+    if node.sequential_scan {
+        let _ = write!(
+            out,
+            "{} {}",
+            line.style(paint(color, Style::new().red().bold())),
+            "[SEQ SCAN]".style(paint(color, Style::new().red()))
+        );
+    } else if node.index.is_some() {
+        let _ = write!(out, "{}", line.style(paint(color, Style::new().green())));
+    } else {
+        let _ = write!(out, "{}", line);
+    }
+    if !stats.is_empty() {
+        let _ = write!(
+            out,
+            "  ({})",
+            stats.join(" ").style(paint(color, Style::new().dimmed()))
+        );
+    }
+    out.push('\n');
+
+    let child_prefix = format!("{}{}", prefix, if last { "   " } else { "│  " });
+    if let Some(detail) = &node.detail {
+        let _ = writeln!(
+            out,
+            "{}   {}",
+            child_prefix,
+            detail.style(paint(color, Style::new().dimmed()))
+        );
+    }
+    for (i, child) in node.children.iter().enumerate() {
+        render_node(
+            out,
+            child,
+            &child_prefix,
+            i + 1 == node.children.len(),
+            color,
+        );
+    }
+}
+
+/// # Name: render_query_plan
+/// ## Description
+/// Renders a plan as an indented tree with sequential scans highlighted, followed by
+/// the estimated cost, execution time and warnings. Without `color` it is plain text, for
+/// output piped to a file or pager.
+pub fn render_query_plan(plan: &QueryPlan, color: bool) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "Query plan ({}{})",
+        plan.backend,
+        if plan.analyzed { ", analyzed" } else { "" }
+    );
+    for (i, node) in plan.nodes.iter().enumerate() {
+        render_node(&mut out, node, "", i + 1 == plan.nodes.len(), color);
+    }
+    if plan.nodes.is_empty() {
+        out.push_str("(empty plan)\n");
+    }
+    out.push('\n');
+
+    if let Some(cost) = plan.estimated_cost {
+        let _ = writeln!(
+            out,
+            "Estimated total cost: {}",
+            format!("{:.2}", cost).style(paint(color, Style::new().yellow()))
+        );
+    }
+    if let Some(time) = plan.execution_time_ms {
+        let _ = writeln!(out, "Execution time: {:.3} ms", time);
+    }
+    if !plan.warnings.is_empty() {
+        out.push_str("\nWarnings:\n");
+        for warning in &plan.warnings {
+            let _ = writeln!(
+                out,
+                "  {} {}",
+                "!".style(paint(color, Style::new().yellow().bold())),
+                warning
+            );
+        }
+    }
+    out
+}
+
+/// # Name: explain_query
+/// ## Description
+/// Explains `query` against the database configured in rustyroad.toml and prints the plan
+/// as a tree (`format == "text"`) or as JSON.
+pub async fn explain_query(
+    query: &str,
+    mode: ExplainMode,
+    format: &str,
+) -> Result<(), CustomMigrationError> {
+    let database = Database::get_database_from_rustyroad_toml()?;
+    let connection = Database::create_database_connection(&database)
+        .await
+        .map_err(CustomMigrationError::SendError)?;
+
+    let plan = explain_statement(&connection, query, mode).await?;
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+    } else {
+        print!(
+            "{}",
+            render_query_plan(&plan, std::io::stdout().is_terminal())
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_postgres_plan() {
+        let document = json!([{
+            "Plan": {
+                "Node Type": "Hash Join",
+                "Total Cost": 35.5,
+                "Plan Rows": 10,
+                "Actual Total Time": 0.25,
+                "Actual Rows": 2,
+                "Hash Cond": "(posts.user_id = users.id)",
+                "Plans": [
+                    {"Node Type": "Seq Scan", "Relation Name": "posts", "Total Cost": 20.0,
+                     "Plan Rows": 1000, "Filter": "(title = 'x'::text)"},
+                    {"Node Type": "Index Scan", "Relation Name": "users",
+                     "Index Name": "users_pkey", "Total Cost": 8.3, "Plan Rows": 1}
+                ]
+            },
+            "Execution Time": 0.4
+        }]);
+
+        let plan = parse_postgres_plan(&document, true);
+
+        assert_eq!(plan.estimated_cost, Some(35.5));
+        assert_eq!(plan.execution_time_ms, Some(0.4));
+        assert_eq!(plan.nodes[0].children.len(), 2);
+        assert!(plan.nodes[0].children[0].sequential_scan);
+        assert_eq!(
+            plan.nodes[0].children[1].index.as_deref(),
+            Some("users_pkey")
+        );
+        assert_eq!(plan.warnings.len(), 1);
+        assert!(plan.warnings[0].contains("posts filtered by (title = 'x'::text)"));
+    }
+
+    #[test]
+    fn test_render_query_plan_color() {
+        let document = json!([{
+            "Plan": {"Node Type": "Seq Scan", "Relation Name": "posts", "Total Cost": 20.0,
+                     "Plan Rows": 1000, "Filter": "(title = 'x'::text)"}
+        }]);
+        let plan = parse_postgres_plan(&document, false);
+
+        let plain = render_query_plan(&plan, false);
+        assert!(!plain.contains('\x1b'));
+        assert!(plain.contains("[SEQ SCAN]"));
+        assert!(render_query_plan(&plan, true).contains('\x1b'));
+    }
+
+    #[test]
+    fn test_parse_mysql_plan() {
+        let document = json!({
+            "query_block": {
+                "select_id": 1,
+                "cost_info": {"query_cost": "1.20"},
+                "table": {
+                    "table_name": "users",
+                    "access_type": "ALL",
+                    "possible_keys": ["idx_email"],
+                    "rows_examined_per_scan": 10,
+                    "cost_info": {"prefix_cost": "1.20"},
+                    "attached_condition": "(`users`.`email` like '%x')"
+                }
+            }
+        });
+
+        let plan = parse_mysql_plan(&document);
+        let scan = &plan.nodes[0].children[0];
+
+        assert_eq!(plan.estimated_cost, Some(1.2));
+        assert_eq!(scan.relation.as_deref(), Some("users"));
+        assert!(scan.sequential_scan);
+        assert!(plan
+            .warnings
+            .iter()
+            .any(|w| w.contains("idx_email not used")));
+    }
+
+    #[test]
+    fn test_parse_mysql_tree() {
+        let output = "-> Nested loop inner join  (cost=2.2 rows=3) (actual time=0.05..0.08 rows=3 loops=1)\n    \
+            -> Table scan on posts  (cost=0.55 rows=3) (actual time=0.02..0.03 rows=3 loops=1)\n    \
+            -> Single-row index lookup on users using PRIMARY (id=posts.user_id)  (cost=0.28 rows=1) (actual time=0.01..0.01 rows=1 loops=3)\n";
+
+        let plan = parse_mysql_tree(output);
+
+        assert_eq!(plan.nodes.len(), 1);
+        assert_eq!(plan.execution_time_ms, Some(0.08));
+        let children = &plan.nodes[0].children;
+        assert_eq!(children.len(), 2);
+        assert!(children[0].sequential_scan);
+        assert_eq!(children[1].index.as_deref(), Some("PRIMARY"));
+    }
+
+    #[test]
+    fn test_parse_sqlite_plan() {
+        let rows = vec![
+            (2, 0, "SCAN posts".to_string()),
+            (
+                5,
+                0,
+                "SEARCH users USING INTEGER PRIMARY KEY (rowid=?)".to_string(),
+            ),
+            (9, 0, "USE TEMP B-TREE FOR ORDER BY".to_string()),
+        ];
+
+        let plan = parse_sqlite_plan(&rows);
+
+        assert_eq!(plan.nodes.len(), 3);
+        assert!(plan.nodes[0].sequential_scan);
+        assert_eq!(plan.nodes[1].operation, "Index search");
+        assert_eq!(plan.nodes[1].index.as_deref(), Some("PRIMARY KEY"));
+        assert_eq!(plan.warnings.len(), 2);
+    }
+}
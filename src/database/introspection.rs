@@ -0,0 +1,448 @@
+use crate::database::migrations::CustomMigrationError;
+use crate::database::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+/// # Name: ColumnInfo
+/// ## Description
+/// A single column as reported by the live database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnInfo {
+    pub name: String,
+    /// The type as the database names it; on MySQL with its width, e.g. `tinyint(1)`.
+    pub data_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+    pub primary_key: bool,
+}
+
+/// # Name: TableInfo
+/// ## Description
+/// A table and its columns, in ordinal order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableInfo {
+    pub name: String,
+    pub columns: Vec<ColumnInfo>,
+}
+
+impl TableInfo {
+    /// Returns the column with the given name, if any.
+    pub fn column(&self, name: &str) -> Option<&ColumnInfo> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// Returns the names of the primary key columns.
+    pub fn primary_key(&self) -> Vec<&str> {
+        self.columns
+            .iter()
+            .filter(|c| c.primary_key)
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+}
+
+/// # Name: ForeignKeyInfo
+/// ## Description
+/// A foreign key from `table.column` to `referenced_table.referenced_column`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignKeyInfo {
+    pub table: String,
+    pub column: String,
+    pub referenced_table: String,
+    pub referenced_column: String,
+}
+
+/// # Name: SchemaInfo
+/// ## Description
+/// The introspected tables, columns and foreign keys of a database.
+/// ## Example
+/// ```no_run
+/// use rustyroad::database::{introspect_schema, Database};
+///
+/// # async fn run() {
+/// let database = Database::get_database_from_rustyroad_toml().unwrap();
+/// let connection = database.create_database_connection().await.unwrap();
+/// let schema = introspect_schema(&connection).await.unwrap();
+/// for table in &schema.tables {
+///     println!("{}", table.name);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaInfo {
+    pub tables: Vec<TableInfo>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+}
+
+impl SchemaInfo {
+    /// Returns the table with the given name, if any.
+    pub fn table(&self, name: &str) -> Option<&TableInfo> {
+        self.tables.iter().find(|t| t.name == name)
+    }
+
+    /// Returns the foreign keys declared on `table`.
+    pub fn foreign_keys_from(&self, table: &str) -> Vec<&ForeignKeyInfo> {
+        self.foreign_keys
+            .iter()
+            .filter(|fk| fk.table == table)
+            .collect()
+    }
+
+    /// Returns the foreign keys pointing at `table`.
+    pub fn foreign_keys_to(&self, table: &str) -> Vec<&ForeignKeyInfo> {
+        self.foreign_keys
+            .iter()
+            .filter(|fk| fk.referenced_table == table)
+            .collect()
+    }
+
+    /// Keeps only the named tables, and the foreign keys whose both ends are kept.
+    /// An empty list keeps everything.
+    pub fn retain_tables(&mut self, names: &[String]) {
+        if names.is_empty() {
+            return;
+        }
+        self.tables.retain(|t| names.contains(&t.name));
+        self.foreign_keys
+            .retain(|fk| names.contains(&fk.table) && names.contains(&fk.referenced_table));
+    }
+
+    /// Table names ordered so that every table comes after the tables it references.
+    /// Self-references are ignored; tables caught in a reference cycle are appended last.
+    pub fn dependency_order(&self) -> Vec<String> {
+        let mut ordered: Vec<String> = Vec::new();
+        let mut remaining: Vec<&TableInfo> = self.tables.iter().collect();
+
+        while !remaining.is_empty() {
+            let (ready, blocked): (Vec<&TableInfo>, Vec<&TableInfo>) =
+                remaining.into_iter().partition(|table| {
+                    self.foreign_keys_from(&table.name).iter().all(|fk| {
+                        fk.referenced_table == table.name
+                            || ordered.contains(&fk.referenced_table)
+                            || self.table(&fk.referenced_table).is_none()
+                    })
+                });
+            if ready.is_empty() {
+                ordered.extend(blocked.iter().map(|t| t.name.clone()));
+                break;
+            }
+            ordered.extend(ready.iter().map(|t| t.name.clone()));
+            remaining = blocked;
+        }
+
+        ordered
+    }
+
+    /// Drops RustyRoad's own bookkeeping tables (e.g. `_rustyroad_migrations`).
+    pub fn without_internal_tables(mut self) -> Self {
+        self.tables.retain(|t| !t.name.starts_with("_rustyroad"));
+        self.foreign_keys.retain(|fk| {
+            !fk.table.starts_with("_rustyroad") && !fk.referenced_table.starts_with("_rustyroad")
+        });
+        self
+    }
+}
+
+/// # Name: introspect_schema
+/// ## Description
+/// Reads tables, columns, primary keys and foreign keys from the connected database.
+/// Tables are returned sorted by name; columns keep their ordinal order.
+/// ## Returns
+/// * `Result<SchemaInfo, CustomMigrationError>` - The introspected schema or the database error.
+pub async fn introspect_schema(
+    connection: &DatabaseConnection,
+) -> Result<SchemaInfo, CustomMigrationError> {
+    let mut schema = match connection {
+        DatabaseConnection::Pg(pool) => {
+            let table_rows = sqlx::query(
+                "SELECT table_name::text AS table_name FROM information_schema.tables
+                 WHERE table_schema = 'public' AND table_type = 'BASE TABLE'",
+            )
+            .fetch_all(&**pool)
+            .await?;
+
+            let pk_rows = sqlx::query(
+                "SELECT kcu.table_name::text AS table_name, kcu.column_name::text AS column_name
+                 FROM information_schema.table_constraints tc
+                 JOIN information_schema.key_column_usage kcu
+                   ON tc.constraint_name = kcu.constraint_name
+                  AND tc.table_schema = kcu.table_schema
+                 WHERE tc.constraint_type = 'PRIMARY KEY' AND tc.table_schema = 'public'",
+            )
+            .fetch_all(&**pool)
+            .await?;
+            let primary_keys: Vec<(String, String)> = pk_rows
+                .iter()
+                .map(|r| (r.get("table_name"), r.get("column_name")))
+                .collect();
+
+            let mut tables = Vec::new();
+            for row in table_rows {
+                let table_name: String = row.get("table_name");
+                let column_rows = sqlx::query(
+                    "SELECT column_name::text AS column_name, data_type::text AS data_type,
+                            is_nullable::text AS is_nullable, column_default::text AS column_default
+                     FROM information_schema.columns
+                     WHERE table_schema = 'public' AND table_name = $1
+                     ORDER BY ordinal_position",
+                )
+                .bind(&table_name)
+                .fetch_all(&**pool)
+                .await?;
+
+                let columns = column_rows
+                    .iter()
+                    .map(|c| {
+                        let name: String = c.get("column_name");
+                        let nullable: String = c.get("is_nullable");
+                        ColumnInfo {
+                            primary_key: primary_keys
+                                .iter()
+                                .any(|(t, col)| t == &table_name && col == &name),
+                            name,
+                            data_type: c.get("data_type"),
+                            nullable: nullable == "YES",
+                            default: c.get("column_default"),
+                        }
+                    })
+                    .collect();
+                tables.push(TableInfo {
+                    name: table_name,
+                    columns,
+                });
+            }
+
+            let fk_rows = sqlx::query(
+                "SELECT kcu.table_name::text AS table_name, kcu.column_name::text AS column_name,
+                        ccu.table_name::text AS referenced_table, ccu.column_name::text AS referenced_column
+                 FROM information_schema.table_constraints tc
+                 JOIN information_schema.key_column_usage kcu
+                   ON tc.constraint_name = kcu.constraint_name
+                  AND tc.table_schema = kcu.table_schema
+                 JOIN information_schema.constraint_column_usage ccu
+                   ON ccu.constraint_name = tc.constraint_name
+                  AND ccu.table_schema = tc.table_schema
+                 WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_schema = 'public'",
+            )
+            .fetch_all(&**pool)
+            .await?;
+            let foreign_keys = fk_rows
+                .iter()
+                .map(|r| ForeignKeyInfo {
+                    table: r.get("table_name"),
+                    column: r.get("column_name"),
+                    referenced_table: r.get("referenced_table"),
+                    referenced_column: r.get("referenced_column"),
+                })
+                .collect();
+
+            SchemaInfo {
+                tables,
+                foreign_keys,
+            }
+        }
+        DatabaseConnection::MySql(pool) => {
+            let table_rows = sqlx::query(
+                "SELECT TABLE_NAME AS table_name FROM information_schema.tables
+                 WHERE TABLE_SCHEMA = DATABASE() AND TABLE_TYPE = 'BASE TABLE'",
+            )
+            .fetch_all(&**pool)
+            .await?;
+
+            let mut tables = Vec::new();
+            for row in table_rows {
+                let table_name: String = row.get("table_name");
+                let column_rows = sqlx::query(
+                    "SELECT COLUMN_NAME AS column_name, COLUMN_TYPE AS data_type,
+                            IS_NULLABLE AS is_nullable, COLUMN_DEFAULT AS column_default,
+                            COLUMN_KEY AS column_key
+                     FROM information_schema.columns
+                     WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?
+                     ORDER BY ORDINAL_POSITION",
+                )
+                .bind(&table_name)
+                .fetch_all(&**pool)
+                .await?;
+
+                let columns = column_rows
+                    .iter()
+                    .map(|c| {
+                        let nullable: String = c.get("is_nullable");
+                        let key: String = c.get("column_key");
+                        ColumnInfo {
+                            name: c.get("column_name"),
+                            data_type: c.get("data_type"),
+                            nullable: nullable == "YES",
+                            default: c.get("column_default"),
+                            primary_key: key == "PRI",
+                        }
+                    })
+                    .collect();
+                tables.push(TableInfo {
+                    name: table_name,
+                    columns,
+                });
+            }
+
+            let fk_rows = sqlx::query(
+                "SELECT TABLE_NAME AS table_name, COLUMN_NAME AS column_name,
+                        REFERENCED_TABLE_NAME AS referenced_table,
+                        REFERENCED_COLUMN_NAME AS referenced_column
+                 FROM information_schema.key_column_usage
+                 WHERE TABLE_SCHEMA = DATABASE() AND REFERENCED_TABLE_NAME IS NOT NULL",
+            )
+            .fetch_all(&**pool)
+            .await?;
+            let foreign_keys = fk_rows
+                .iter()
+                .map(|r| ForeignKeyInfo {
+                    table: r.get("table_name"),
+                    column: r.get("column_name"),
+                    referenced_table: r.get("referenced_table"),
+                    referenced_column: r.get("referenced_column"),
+                })
+                .collect();
+
+            SchemaInfo {
+                tables,
+                foreign_keys,
+            }
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            let table_rows = sqlx::query(
+                "SELECT name FROM sqlite_master
+                 WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+            )
+            .fetch_all(&**pool)
+            .await?;
+
+            let mut tables = Vec::new();
+            let mut foreign_keys = Vec::new();
+            for row in table_rows {
+                let table_name: String = row.get("name");
+                let column_rows = sqlx::query(&format!("PRAGMA table_info(\"{}\")", table_name))
+                    .fetch_all(&**pool)
+                    .await?;
+
+                let columns: Vec<ColumnInfo> = column_rows
+                    .iter()
+                    .map(|c| {
+                        let not_null: i32 = c.get("notnull");
+                        let pk: i32 = c.get("pk");
+                        ColumnInfo {
+                            name: c.get("name"),
+                            data_type: c.get("type"),
+                            nullable: not_null == 0 && pk == 0,
+                            default: c.get("dflt_value"),
+                            primary_key: pk > 0,
+                        }
+                    })
+                    .collect();
+
+                let fk_rows = sqlx::query(&format!("PRAGMA foreign_key_list(\"{}\")", table_name))
+                    .fetch_all(&**pool)
+                    .await?;
+                for fk in fk_rows {
+                    let referenced_table: String = fk.get("table");
+                    // `to` is NULL when the reference targets the parent's primary key implicitly
+                    let referenced_column: Option<String> = fk.get("to");
+                    foreign_keys.push(ForeignKeyInfo {
+                        table: table_name.clone(),
+                        column: fk.get("from"),
+                        referenced_table,
+                        referenced_column: referenced_column.unwrap_or_else(|| "id".to_string()),
+                    });
+                }
+
+                tables.push(TableInfo {
+                    name: table_name,
+                    columns,
+                });
+            }
+
+            SchemaInfo {
+                tables,
+                foreign_keys,
+            }
+        }
+    };
+
+    schema.tables.sort_by(|a, b| a.name.cmp(&b.name));
+    schema.foreign_keys.sort_by(|a, b| {
+        (a.table.as_str(), a.column.as_str()).cmp(&(b.table.as_str(), b.column.as_str()))
+    });
+    schema.foreign_keys.dedup();
+
+    Ok(schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: "integer".to_string(),
+            nullable: false,
+            default: None,
+            primary_key: name == "id",
+        }
+    }
+
+    fn sample_schema() -> SchemaInfo {
+        SchemaInfo {
+            tables: vec![
+                TableInfo {
+                    name: "_rustyroad_migrations".to_string(),
+                    columns: vec![column("id")],
+                },
+                TableInfo {
+                    name: "posts".to_string(),
+                    columns: vec![column("id"), column("user_id")],
+                },
+                TableInfo {
+                    name: "users".to_string(),
+                    columns: vec![column("id")],
+                },
+            ],
+            foreign_keys: vec![ForeignKeyInfo {
+                table: "posts".to_string(),
+                column: "user_id".to_string(),
+                referenced_table: "users".to_string(),
+                referenced_column: "id".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_retain_tables_drops_dangling_foreign_keys() {
+        let mut schema = sample_schema();
+        schema.retain_tables(&["posts".to_string()]);
+
+        assert_eq!(schema.tables.len(), 1);
+        assert!(schema.foreign_keys.is_empty());
+    }
+
+    #[test]
+    fn test_without_internal_tables() {
+        let schema = sample_schema().without_internal_tables();
+
+        assert!(schema.table("_rustyroad_migrations").is_none());
+        assert_eq!(schema.foreign_keys_to("users").len(), 1);
+        assert_eq!(schema.table("posts").unwrap().primary_key(), vec!["id"]);
+    }
+
+    #[test]
+    fn test_dependency_order_puts_referenced_tables_first() {
+        let mut schema = sample_schema().without_internal_tables();
+        schema.foreign_keys.push(ForeignKeyInfo {
+            table: "users".to_string(),
+            column: "invited_by_id".to_string(),
+            referenced_table: "users".to_string(),
+            referenced_column: "id".to_string(),
+        });
+
+        assert_eq!(schema.dependency_order(), vec!["users", "posts"]);
+    }
+}
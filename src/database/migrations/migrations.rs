@@ -1,7 +1,7 @@
 use chrono::Local;
 use regex::Regex;
 use sqlx::Executor;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::fs::{create_dir_all, DirEntry};
@@ -12,13 +12,13 @@ use std::{
     io::{self, ErrorKind},
 };
 
-use crate::database::{Database, DatabaseConnection};
+use crate::database::{Database, DatabaseConnection, DatabaseType};
 use rustyline::DefaultEditor;
 use serde::de::StdError;
 use serde_derive::{Deserialize, Serialize};
 use strum_macros::Display;
 
-use crate::generators::create_file;
+use crate::generators::{create_file, Generator};
 use crate::writers::write_to_file;
 use crate::Project;
 
@@ -38,7 +38,8 @@ struct MigrationsOutput {
 }
 
 const CONSTRAINTS: &[&str] = &["PRIMARY KEY", "NOT NULL", "FOREIGN KEY"];
-const MIGRATIONS_DIR: &str = "./config/database/migrations";
+/// Where every migration lives, as `<timestamp>-<name>/{up.sql,down.sql}`.
+pub const MIGRATIONS_DIR: &str = "./config/database/migrations";
 
 /// Returns the RustyRoad config filename for the active environment.
 ///
@@ -72,22 +73,53 @@ pub fn get_config_file_name() -> String {
 /// Parsed column with constraints
 struct ParsedColumn {
     name: String,
+    data_type: String,
     sql_type: String,
     constraints: Vec<String>,
     foreign_key: Option<(String, String, String)>, // (constraint_name, ref_table, ref_column)
 }
 
+/// The columns of a shortcut that stands for the columns a model behaviour needs:
+/// `timestamps` (created_at and updated_at), `soft_delete` (deleted_at) and `lock_version`.
+fn column_shortcut(col_def: &str) -> Option<Vec<ParsedColumn>> {
+    let column = |name: &str, data_type: &str, constraints: &[&str]| ParsedColumn {
+        name: name.to_string(),
+        data_type: data_type.to_string(),
+        sql_type: map_common_type_to_sql(data_type),
+        constraints: constraints.iter().map(|c| c.to_string()).collect(),
+        foreign_key: None,
+    };
+    let current_time = ["NOT NULL", "DEFAULT CURRENT_TIMESTAMP"];
+    match col_def.trim().to_lowercase().as_str() {
+        "timestamps" => Some(vec![
+            column("created_at", "timestamp", &current_time),
+            column("updated_at", "timestamp", &current_time),
+        ]),
+        "soft_delete" => Some(vec![column("deleted_at", "timestamp", &[])]),
+        "lock_version" => Some(vec![column(
+            "lock_version",
+            "integer",
+            &["NOT NULL", "DEFAULT 0"],
+        )]),
+        _ => None,
+    }
+}
+
 /// Parse a column definition string into structured components
-/// Format: name:type[:constraints]
+/// Format: name:type[:constraints], or one of the shortcuts `timestamps`, `soft_delete` and
+/// `lock_version`, which add the columns of the model behaviours of the same names
 /// Constraints can be: nullable, not_null, primary_key, unique, default=value, references=table(column)
-fn parse_column_definition(col_def: &str) -> Option<ParsedColumn> {
+fn parse_column_definition(col_def: &str) -> Vec<ParsedColumn> {
+    if let Some(columns) = column_shortcut(col_def) {
+        return columns;
+    }
     let parts: Vec<&str> = col_def.split(':').collect();
     if parts.len() < 2 {
         eprintln!(
-            "Skipping invalid column definition: '{}'. Format is name:type[:constraints]",
+            "Skipping invalid column definition: '{}'. Format is name:type[:constraints], timestamps, soft_delete or lock_version",
             col_def
         );
-        return None;
+        return Vec::new();
     }
 
     let col_name = parts[0].to_string();
@@ -141,12 +173,13 @@ fn parse_column_definition(col_def: &str) -> Option<ParsedColumn> {
         }
     }
 
-    Some(ParsedColumn {
+    vec![ParsedColumn {
         name: col_name,
+        data_type: col_type.to_string(),
         sql_type,
         constraints,
         foreign_key,
-    })
+    }]
 }
 
 /// Represents the type of migration operation
@@ -288,7 +321,7 @@ pub async fn create_migration(name: &str, columns: Vec<String>) -> Result<(), io
     }
 
     // Ensure the migrations directory exists (this is the only supported location)
-    create_dir_all(MIGRATIONS_DIR)?;
+    Generator::create_dir_all(MIGRATIONS_DIR)?;
 
     // Helpful hint for beginners/LLMs that often create the wrong folder name.
     if Path::new("./migrations").is_dir() {
@@ -311,7 +344,7 @@ pub async fn create_migration(name: &str, columns: Vec<String>) -> Result<(), io
                 column_definitions_sql.push("id SERIAL PRIMARY KEY".to_string());
             } else {
                 for col_def in &columns {
-                    if let Some(parsed) = parse_column_definition(col_def) {
+                    for parsed in parse_column_definition(col_def) {
                         let constraints_str = parsed.constraints.join(" ");
                         let column_sql =
                             format!("{} {} {}", parsed.name, parsed.sql_type, constraints_str)
@@ -360,7 +393,7 @@ pub async fn create_migration(name: &str, columns: Vec<String>) -> Result<(), io
             if !columns.is_empty() {
                 // Use columns from CLI with full constraint support
                 for col_def in &columns {
-                    if let Some(parsed) = parse_column_definition(col_def) {
+                    for parsed in parse_column_definition(col_def) {
                         let constraints_str = parsed.constraints.join(" ");
                         let column_sql = if constraints_str.is_empty() {
                             format!("ADD COLUMN {} {}", parsed.name, parsed.sql_type)
@@ -394,6 +427,23 @@ pub async fn create_migration(name: &str, columns: Vec<String>) -> Result<(), io
             } else {
                 // Use column names derived from migration name with default type
                 for col_name in &cols_to_add {
+                    // add_timestamps_to_posts and the like add the shortcut's columns
+                    if let Some(shortcut) = column_shortcut(col_name) {
+                        for parsed in shortcut {
+                            alter_statements.push(
+                                format!(
+                                    "ADD COLUMN {} {} {}",
+                                    parsed.name,
+                                    parsed.sql_type,
+                                    parsed.constraints.join(" ")
+                                )
+                                .trim_end()
+                                .to_string(),
+                            );
+                            column_names_for_down.push(parsed.name);
+                        }
+                        continue;
+                    }
                     // Default to INTEGER type, but this should ideally be specified in the command
                     alter_statements.push(format!("ADD COLUMN {} INTEGER", col_name));
                     column_names_for_down.push(col_name.clone());
@@ -518,6 +568,63 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_create_table_migration_sql_per_dialect() {
+        let columns = vec![
+            "title:string:not_null".to_string(),
+            "published:boolean:default=false".to_string(),
+            "rating:float".to_string(),
+            "user_id:integer:references=users".to_string(),
+        ];
+
+        let (up, down) = create_table_migration_sql("posts", &columns, &DatabaseType::Postgres);
+        assert_eq!(
+            up,
+            "CREATE TABLE IF NOT EXISTS posts (\n    id SERIAL PRIMARY KEY,\n    title VARCHAR(255) NOT NULL,\n    published BOOLEAN DEFAULT false,\n    rating DOUBLE PRECISION,\n    user_id INTEGER,\n    CONSTRAINT fk_user_id_users FOREIGN KEY (user_id) REFERENCES users(id)\n);"
+        );
+        assert_eq!(down, "DROP TABLE IF EXISTS posts;");
+
+        let (up, _) = create_table_migration_sql("posts", &columns, &DatabaseType::Mysql);
+        assert!(up.contains("id INT AUTO_INCREMENT PRIMARY KEY"));
+        assert!(up.contains("rating DOUBLE,"));
+
+        let (up, _) = create_table_migration_sql("posts", &columns, &DatabaseType::Sqlite);
+        assert!(up.contains("id INTEGER PRIMARY KEY AUTOINCREMENT"));
+        assert!(up.contains("rating REAL,"));
+
+        let (up, _) = create_table_migration_sql(
+            "tags",
+            &["slug:string:primary_key".to_string()],
+            &DatabaseType::Sqlite,
+        );
+        assert!(!up.contains("AUTOINCREMENT"));
+    }
+
+    #[test]
+    fn test_column_shortcuts() {
+        let columns = vec![
+            "title:string:not_null".to_string(),
+            "timestamps".to_string(),
+            "soft_delete".to_string(),
+            "lock_version".to_string(),
+        ];
+        let (up, _) = create_table_migration_sql("posts", &columns, &DatabaseType::Postgres);
+        assert!(up.ends_with(
+            "    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,\n    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,\n    deleted_at TIMESTAMP,\n    lock_version INTEGER NOT NULL DEFAULT 0\n);"
+        ));
+        let (up, _) = create_table_migration_sql("posts", &columns, &DatabaseType::Mysql);
+        assert!(up.contains("created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,"));
+        assert!(up.contains("deleted_at DATETIME,"));
+
+        let names: Vec<String> = parse_column_definition("Timestamps")
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(names, ["created_at", "updated_at"]);
+        assert!(parse_column_definition("timestamps:integer").len() == 1);
+        assert!(parse_column_definition("title").is_empty());
+    }
+
     #[test]
     fn test_map_common_type_to_sql() {
         assert_eq!(map_common_type_to_sql("integer"), "INTEGER");
@@ -550,6 +657,142 @@ fn map_common_type_to_sql(common_type: &str) -> String {
     }
 }
 
+/// Maps a `name:type` column type to the SQL type of a specific backend.
+/// Types that are spelled the same everywhere fall back to [`map_common_type_to_sql`].
+pub fn map_common_type_for_dialect(common_type: &str, database_type: &DatabaseType) -> String {
+    let sql_type = match (common_type.to_lowercase().as_str(), database_type) {
+        ("text", _) => "TEXT",
+        ("float" | "double", DatabaseType::Mysql) => "DOUBLE",
+        ("float" | "double", DatabaseType::Sqlite) => "REAL",
+        ("float" | "double", _) => "DOUBLE PRECISION",
+        ("datetime" | "timestamp", DatabaseType::Mysql) => "DATETIME",
+        ("binary" | "blob", DatabaseType::Mysql | DatabaseType::Sqlite) => "BLOB",
+        ("json", DatabaseType::Mysql) => "JSON",
+        ("json", DatabaseType::Sqlite) => "TEXT",
+        ("uuid", DatabaseType::Mysql) => "CHAR(36)",
+        ("uuid", DatabaseType::Sqlite) => "TEXT",
+        ("serial", DatabaseType::Mysql) => "INT AUTO_INCREMENT",
+        ("bigserial", DatabaseType::Mysql) => "BIGINT AUTO_INCREMENT",
+        ("serial" | "bigserial", DatabaseType::Sqlite) => "INTEGER",
+        _ => return map_common_type_to_sql(common_type),
+    };
+    sql_type.to_string()
+}
+
+/// The auto-incrementing `id` primary key column for a backend.
+fn primary_key_column_sql(database_type: &DatabaseType) -> &'static str {
+    match database_type {
+        DatabaseType::Mysql => "id INT AUTO_INCREMENT PRIMARY KEY",
+        DatabaseType::Sqlite => "id INTEGER PRIMARY KEY AUTOINCREMENT",
+        _ => "id SERIAL PRIMARY KEY",
+    }
+}
+
+/// # Name: create_table_migration_sql
+/// ### Description: Builds the up/down SQL for a CREATE TABLE migration in the given dialect.
+/// Columns use the `name:type[:constraints]` syntax of `rustyroad migration generate`.
+/// An auto-incrementing `id` primary key is added unless a column is already declared `primary_key`.
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::create_table_migration_sql;
+/// use rustyroad::database::DatabaseType;
+///
+/// let (up, down) = create_table_migration_sql(
+///     "posts",
+///     &["title:string:not_null".to_string()],
+///     &DatabaseType::Sqlite,
+/// );
+/// assert!(up.contains("id INTEGER PRIMARY KEY AUTOINCREMENT"));
+/// assert_eq!(down, "DROP TABLE IF EXISTS posts;");
+/// ```
+pub fn create_table_migration_sql(
+    table_name: &str,
+    columns: &[String],
+    database_type: &DatabaseType,
+) -> (String, String) {
+    let mut column_definitions_sql = Vec::new();
+    let mut foreign_key_constraints = Vec::new();
+    let mut has_primary_key = false;
+
+    for col_def in columns {
+        for parsed in parse_column_definition(col_def) {
+            has_primary_key |= parsed.constraints.iter().any(|c| c == "PRIMARY KEY");
+            let column_sql = format!(
+                "{} {} {}",
+                parsed.name,
+                map_common_type_for_dialect(&parsed.data_type, database_type),
+                parsed.constraints.join(" ")
+            )
+            .trim()
+            .to_string();
+            column_definitions_sql.push(column_sql);
+
+            if let Some((constraint_name, ref_table, ref_column)) = parsed.foreign_key {
+                foreign_key_constraints.push(format!(
+                    "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {}({})",
+                    constraint_name, parsed.name, ref_table, ref_column
+                ));
+            }
+        }
+    }
+
+    if !has_primary_key {
+        column_definitions_sql.insert(0, primary_key_column_sql(database_type).to_string());
+    }
+    column_definitions_sql.extend(foreign_key_constraints);
+
+    let up_sql = format!(
+        "CREATE TABLE IF NOT EXISTS {} (\n    {}\n);",
+        table_name,
+        column_definitions_sql.join(",\n    ")
+    );
+    let down_sql = format!("DROP TABLE IF EXISTS {};", table_name);
+
+    (up_sql, down_sql)
+}
+
+/// # Name: write_create_table_migration
+/// ### Description: Writes a CREATE TABLE migration for `table_name` without any prompts.
+/// Returns the migration folder that was created.
+pub fn write_create_table_migration(
+    table_name: &str,
+    columns: &[String],
+    database_type: &DatabaseType,
+) -> Result<String, io::Error> {
+    Generator::create_dir_all(MIGRATIONS_DIR)?;
+
+    let (up_sql_contents, down_sql_contents) =
+        create_table_migration_sql(table_name, columns, database_type);
+    let folder_name = format!(
+        "config/database/migrations/{}-create_{}",
+        Local::now().format("%Y%m%d%H%M%S"),
+        table_name
+    );
+
+    create_migration_files(&folder_name, &up_sql_contents, &down_sql_contents)?;
+
+    Ok(folder_name)
+}
+
+/// # Name: find_create_table_migration
+/// ### Description: Returns the folder of an existing migration that creates `table_name`,
+/// named either `<timestamp>-create_<table>` or `<timestamp>-<table>` (the `migration generate` form).
+pub fn find_create_table_migration(table_name: &str) -> Option<String> {
+    let create_suffix = format!("-create_{}", table_name);
+    let plain_suffix = format!("-{}", table_name);
+    let mut folders: Vec<String> = std::fs::read_dir(MIGRATIONS_DIR)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.ends_with(&create_suffix) || name.ends_with(&plain_suffix))
+        .collect();
+    folders.sort();
+    folders
+        .pop()
+        .map(|name| format!("config/database/migrations/{}", name))
+}
+
 pub fn create_migration_files(
     folder_name: &str,
     up_sql_contents: &str,
@@ -558,13 +801,11 @@ pub fn create_migration_files(
     let up_file = format!("{}/up.sql", folder_name);
     let down_file = format!("{}/down.sql", folder_name);
 
-    match std::fs::create_dir(folder_name) {
-        Ok(_) => {}
-        Err(_) => {
-            println!("Migration already exists");
-            return Err(io::Error::other("Migration already exists"));
-        }
+    if Generator::exists(folder_name) {
+        println!("Migration already exists");
+        return Err(io::Error::other("Migration already exists"));
     }
+    Generator::create_dir_all(folder_name)?;
 
     create_file(&up_file)?;
     create_file(&down_file)?;
@@ -1208,44 +1449,7 @@ pub async fn list_migrations(format: &str) -> Result<(), CustomMigrationError> {
         .unwrap_or_else(|why| panic!("Couldn't create database connection: {}", why));
 
     // Ensure the migrations tracking table exists (list/status should work even before first run)
-    match connection.clone() {
-        DatabaseConnection::Pg(conn) => {
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS _rustyroad_migrations (
-                    id SERIAL PRIMARY KEY,
-                    name VARCHAR(255) NOT NULL,
-                    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                    direction VARCHAR(10) NOT NULL
-                )",
-            )
-            .await
-            .map_err(CustomMigrationError::from)?;
-        }
-        DatabaseConnection::MySql(conn) => {
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS _rustyroad_migrations (
-                    id INT AUTO_INCREMENT PRIMARY KEY,
-                    name VARCHAR(255) NOT NULL,
-                    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                    direction VARCHAR(10) NOT NULL
-                )",
-            )
-            .await
-            .map_err(CustomMigrationError::from)?;
-        }
-        DatabaseConnection::Sqlite(conn) => {
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS _rustyroad_migrations (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    name TEXT NOT NULL,
-                    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                    direction TEXT NOT NULL
-                )",
-            )
-            .await
-            .map_err(CustomMigrationError::from)?;
-        }
-    }
+    ensure_migrations_table(&connection).await?;
 
     // Get all migration files from the migrations directory
     let migrations_dir = Path::new(MIGRATIONS_DIR);
@@ -1273,37 +1477,7 @@ pub async fn list_migrations(format: &str) -> Result<(), CustomMigrationError> {
     }
 
     // Get applied migrations from the database
-    let applied_migrations = match connection {
-        DatabaseConnection::Pg(conn) => {
-            match sqlx::query_as::<_, (String, String, String)>(
-                "SELECT name, applied_at::text, direction FROM _rustyroad_migrations ORDER BY applied_at",
-            )
-            .fetch_all(&*conn)
-            .await
-            {
-                Ok(rows) => rows,
-                Err(e) => return Err(CustomMigrationError::SqlxError(e)),
-            }
-        }
-        DatabaseConnection::MySql(conn) => match sqlx::query_as::<_, (String, String, String)>(
-            "SELECT name, CAST(applied_at AS CHAR), direction FROM _rustyroad_migrations ORDER BY applied_at",
-        )
-        .fetch_all(&*conn)
-        .await
-        {
-            Ok(rows) => rows,
-            Err(e) => return Err(CustomMigrationError::SqlxError(e)),
-        },
-        DatabaseConnection::Sqlite(conn) => match sqlx::query_as::<_, (String, String, String)>(
-            "SELECT name, CAST(applied_at AS TEXT), direction FROM _rustyroad_migrations ORDER BY applied_at",
-        )
-        .fetch_all(&*conn)
-        .await
-        {
-            Ok(rows) => rows,
-            Err(e) => return Err(CustomMigrationError::SqlxError(e)),
-        },
-    };
+    let applied_migrations = migration_history(&connection).await?;
 
     // Build a map of latest status per migration name (ordered by applied_at, so later wins)
     let mut latest_by_name: HashMap<String, (String, String)> = HashMap::new();
@@ -1371,3 +1545,104 @@ pub async fn list_migrations(format: &str) -> Result<(), CustomMigrationError> {
 
     Ok(())
 }
+
+/// Creates the `_rustyroad_migrations` tracking table if it doesn't exist yet.
+async fn ensure_migrations_table(
+    connection: &DatabaseConnection,
+) -> Result<(), CustomMigrationError> {
+    match connection.clone() {
+        DatabaseConnection::Pg(conn) => {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS _rustyroad_migrations (
+                    id SERIAL PRIMARY KEY,
+                    name VARCHAR(255) NOT NULL,
+                    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    direction VARCHAR(10) NOT NULL
+                )",
+            )
+            .await
+            .map_err(CustomMigrationError::from)?;
+        }
+        DatabaseConnection::MySql(conn) => {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS _rustyroad_migrations (
+                    id INT AUTO_INCREMENT PRIMARY KEY,
+                    name VARCHAR(255) NOT NULL,
+                    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    direction VARCHAR(10) NOT NULL
+                )",
+            )
+            .await
+            .map_err(CustomMigrationError::from)?;
+        }
+        DatabaseConnection::Sqlite(conn) => {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS _rustyroad_migrations (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL,
+                    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    direction TEXT NOT NULL
+                )",
+            )
+            .await
+            .map_err(CustomMigrationError::from)?;
+        }
+    }
+    Ok(())
+}
+
+/// Every `(name, applied_at, direction)` row of the tracking table, oldest first.
+async fn migration_history(
+    connection: &DatabaseConnection,
+) -> Result<Vec<(String, String, String)>, CustomMigrationError> {
+    let rows = match connection.clone() {
+        DatabaseConnection::Pg(conn) => {
+            sqlx::query_as::<_, (String, String, String)>(
+                "SELECT name, applied_at::text, direction FROM _rustyroad_migrations ORDER BY applied_at",
+            )
+            .fetch_all(&*conn)
+            .await?
+        }
+        DatabaseConnection::MySql(conn) => {
+            sqlx::query_as::<_, (String, String, String)>(
+                "SELECT name, CAST(applied_at AS CHAR), direction FROM _rustyroad_migrations ORDER BY applied_at",
+            )
+            .fetch_all(&*conn)
+            .await?
+        }
+        DatabaseConnection::Sqlite(conn) => {
+            sqlx::query_as::<_, (String, String, String)>(
+                "SELECT name, CAST(applied_at AS TEXT), direction FROM _rustyroad_migrations ORDER BY applied_at",
+            )
+            .fetch_all(&*conn)
+            .await?
+        }
+    };
+    Ok(rows)
+}
+
+/// # Name: applied_migrations
+/// ### Description: The names of the migrations whose latest run in `_rustyroad_migrations`
+/// was `up`, i.e. that are applied and have not been rolled back.
+pub async fn applied_migrations() -> Result<HashSet<String>, CustomMigrationError> {
+    let database = Database::get_database_from_rustyroad_toml().map_err(|e| {
+        CustomMigrationError::IoError(io::Error::other(format!(
+            "Couldn't parse the rustyroad.toml file: {}",
+            e
+        )))
+    })?;
+    let connection = Database::create_database_connection(&database)
+        .await
+        .map_err(CustomMigrationError::SendError)?;
+    ensure_migrations_table(&connection).await?;
+
+    let mut latest: HashMap<String, String> = HashMap::new();
+    for (name, _, direction) in migration_history(&connection).await? {
+        latest.insert(name, direction);
+    }
+    Ok(latest
+        .into_iter()
+        .filter(|(_, direction)| direction == "up")
+        .map(|(name, _)| name)
+        .collect())
+}
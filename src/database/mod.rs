@@ -1,13 +1,33 @@
 pub mod connect;
+pub mod console;
 pub mod database;
 pub mod databasetype;
 pub mod datatype;
+pub mod diagram;
+pub mod explain;
+pub mod introspection;
 pub mod migrations;
+pub mod query_builder;
+pub mod query_result;
 pub mod schema;
+pub mod stats;
+pub mod transaction;
+pub mod transfer;
+pub mod value;
 
 pub use connect::*;
+pub use console::*;
 pub use database::*;
 pub use databasetype::*;
 pub use datatype::*;
+pub use diagram::*;
+pub use explain::*;
+pub use introspection::*;
 pub use migrations::*;
+pub use query_builder::*;
+pub use query_result::*;
 pub use schema::*;
+pub use stats::*;
+pub use transaction::*;
+pub use transfer::*;
+pub use value::*;
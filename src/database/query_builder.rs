@@ -0,0 +1,755 @@
+use crate::database::{
+    BindValues, DatabaseConnection, DatabaseType, Executor, ExecutorRef, SqlValue,
+    TransactionConnection,
+};
+use sqlx::mysql::{MySqlQueryResult, MySqlRow};
+use sqlx::postgres::{PgQueryResult, PgRow};
+use sqlx::sqlite::{SqliteQueryResult, SqliteRow};
+use sqlx::{FromRow, MySql, Postgres, Sqlite};
+
+/// A type that decodes from a row of every backend `DatabaseConnection` supports, such as a
+/// struct with `#[derive(sqlx::FromRow)]` or `#[derive(Model)]`.
+pub trait FromDatabaseRow:
+    for<'r> FromRow<'r, PgRow>
+    + for<'r> FromRow<'r, MySqlRow>
+    + for<'r> FromRow<'r, SqliteRow>
+    + Send
+    + Unpin
+{
+}
+
+impl<T> FromDatabaseRow for T where
+    T: for<'r> FromRow<'r, PgRow>
+        + for<'r> FromRow<'r, MySqlRow>
+        + for<'r> FromRow<'r, SqliteRow>
+        + Send
+        + Unpin
+{
+}
+
+/// Runs `$body` with `$executor` bound to the pool, or the open transaction, that `$conn` runs
+/// its queries on, and with `$db` naming the sqlx database of the backend.
+macro_rules! on_executor {
+    ($conn:expr, |$db:ident, $executor:ident| $body:expr) => {
+        match $conn.executor() {
+            ExecutorRef::Connection(DatabaseConnection::Pg(pool)) => {
+                type $db = Postgres;
+                let $executor = pool.as_ref();
+                $body
+            }
+            ExecutorRef::Connection(DatabaseConnection::MySql(pool)) => {
+                type $db = MySql;
+                let $executor = pool.as_ref();
+                $body
+            }
+            ExecutorRef::Connection(DatabaseConnection::Sqlite(pool)) => {
+                type $db = Sqlite;
+                let $executor = pool.as_ref();
+                $body
+            }
+            ExecutorRef::Transaction(tx) => match &mut *tx.connection().await? {
+                TransactionConnection::Pg(tx) => {
+                    type $db = Postgres;
+                    let $executor = &mut **tx;
+                    $body
+                }
+                TransactionConnection::MySql(tx) => {
+                    type $db = MySql;
+                    let $executor = &mut **tx;
+                    $body
+                }
+                TransactionConnection::Sqlite(tx) => {
+                    type $db = Sqlite;
+                    let $executor = &mut **tx;
+                    $body
+                }
+            },
+        }
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+/// A comparison in a `WHERE` clause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Like,
+}
+
+impl Op {
+    fn as_sql(&self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::Ne => "<>",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Like => "LIKE",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Statement {
+    Select,
+    Insert,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Compare(String, Op, SqlValue),
+    IsNull(String),
+    IsNotNull(String),
+    In(String, Vec<SqlValue>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Join {
+    left: bool,
+    table: String,
+    on: (String, String),
+}
+
+/// # Name: QueryBuilder
+/// ## Description
+/// Builds a SELECT, INSERT, UPDATE or DELETE whose placeholders (`$1` or `?`) and identifier
+/// quoting (`"name"` or `` `name` ``) follow the `DatabaseType` it is rendered for, so the same
+/// code runs on PostgreSQL, MySQL and SQLite. Values are bound as typed `SqlValue`s, and rows
+/// decode into any `FromDatabaseRow` type.
+///
+/// Column names may be qualified (`posts.user_id`); `*` and `table.*` are left unquoted.
+/// Conditions are joined with `AND`. Joins, ordering, limits and offsets apply to SELECT;
+/// `returning` applies to INSERT, UPDATE and DELETE, which MySQL cannot return rows from.
+/// Building a statement that does not fit those rules is `sqlx::Error::InvalidArgument`.
+/// ## Example
+/// ```
+/// use rustyroad::database::{DatabaseType, Op, Order, QueryBuilder};
+///
+/// let query = QueryBuilder::select("posts")
+///     .columns(["posts.*"])
+///     .join("users", "users.id", "posts.user_id")
+///     .where_eq("users.email", "ann@example.com")
+///     .where_("posts.views", Op::Gt, 10)
+///     .order_by("posts.created_at", Order::Desc)
+///     .limit(20);
+/// let (sql, values) = query.build(&DatabaseType::Postgres).unwrap();
+/// assert_eq!(
+///     sql,
+///     r#"SELECT "posts".* FROM "posts" INNER JOIN "users" ON "users"."id" = "posts"."user_id" WHERE "users"."email" = $1 AND "posts"."views" > $2 ORDER BY "posts"."created_at" DESC LIMIT 20"#
+/// );
+/// assert_eq!(values.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryBuilder {
+    statement: Statement,
+    table: String,
+    columns: Vec<String>,
+    assignments: Vec<(String, SqlValue)>,
+    joins: Vec<Join>,
+    conditions: Vec<Condition>,
+    order: Vec<(String, Order)>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    returning: Vec<String>,
+}
+
+impl QueryBuilder {
+    fn new(statement: Statement, table: &str) -> Self {
+        QueryBuilder {
+            statement,
+            table: table.to_string(),
+            columns: Vec::new(),
+            assignments: Vec::new(),
+            joins: Vec::new(),
+            conditions: Vec::new(),
+            order: Vec::new(),
+            limit: None,
+            offset: None,
+            returning: Vec::new(),
+        }
+    }
+
+    /// `SELECT * FROM table`, until `columns` narrows it.
+    pub fn select(table: &str) -> Self {
+        Self::new(Statement::Select, table)
+    }
+
+    /// `INSERT INTO table`, with the columns given by `value` or `values`. Without any it
+    /// inserts defaults.
+    pub fn insert(table: &str) -> Self {
+        Self::new(Statement::Insert, table)
+    }
+
+    /// `UPDATE table SET ..`, with the columns given by `value` or `values`.
+    pub fn update(table: &str) -> Self {
+        Self::new(Statement::Update, table)
+    }
+
+    /// `DELETE FROM table`. Without conditions it deletes every row.
+    pub fn delete(table: &str) -> Self {
+        Self::new(Statement::Delete, table)
+    }
+
+    /// The columns a SELECT returns.
+    pub fn columns<'a>(mut self, columns: impl IntoIterator<Item = &'a str>) -> Self {
+        self.columns.extend(columns.into_iter().map(str::to_string));
+        self
+    }
+
+    /// A column an INSERT writes or an UPDATE sets.
+    pub fn value(mut self, column: &str, value: impl Into<SqlValue>) -> Self {
+        self.assignments.push((column.to_string(), value.into()));
+        self
+    }
+
+    pub fn values<'a>(mut self, values: impl IntoIterator<Item = (&'a str, SqlValue)>) -> Self {
+        self.assignments
+            .extend(values.into_iter().map(|(c, v)| (c.to_string(), v)));
+        self
+    }
+
+    /// `INNER JOIN table ON left = right`
+    pub fn join(mut self, table: &str, left: &str, right: &str) -> Self {
+        self.joins.push(Join {
+            left: false,
+            table: table.to_string(),
+            on: (left.to_string(), right.to_string()),
+        });
+        self
+    }
+
+    /// `LEFT JOIN table ON left = right`
+    pub fn left_join(mut self, table: &str, left: &str, right: &str) -> Self {
+        self.joins.push(Join {
+            left: true,
+            table: table.to_string(),
+            on: (left.to_string(), right.to_string()),
+        });
+        self
+    }
+
+    /// `column <op> value`. A `None` compared with `Op::Eq` or `Op::Ne` becomes `IS NULL` or
+    /// `IS NOT NULL`, since `= NULL` never matches.
+    pub fn where_(mut self, column: &str, op: Op, value: impl Into<SqlValue>) -> Self {
+        let value = value.into();
+        let condition = match op {
+            Op::Eq if value.is_null() => Condition::IsNull(column.to_string()),
+            Op::Ne if value.is_null() => Condition::IsNotNull(column.to_string()),
+            _ => Condition::Compare(column.to_string(), op, value),
+        };
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn where_eq(self, column: &str, value: impl Into<SqlValue>) -> Self {
+        self.where_(column, Op::Eq, value)
+    }
+
+    pub fn where_null(mut self, column: &str) -> Self {
+        self.conditions.push(Condition::IsNull(column.to_string()));
+        self
+    }
+
+    pub fn where_not_null(mut self, column: &str) -> Self {
+        self.conditions
+            .push(Condition::IsNotNull(column.to_string()));
+        self
+    }
+
+    /// `column IN (..)`. An empty list matches no rows.
+    pub fn where_in<V: Into<SqlValue>>(
+        mut self,
+        column: &str,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        self.conditions.push(Condition::In(
+            column.to_string(),
+            values.into_iter().map(Into::into).collect(),
+        ));
+        self
+    }
+
+    pub fn order_by(mut self, column: &str, order: Order) -> Self {
+        self.order.push((column.to_string(), order));
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// The columns an INSERT, UPDATE or DELETE returns, e.g. `["*"]`.
+    pub fn returning<'a>(mut self, columns: impl IntoIterator<Item = &'a str>) -> Self {
+        self.returning
+            .extend(columns.into_iter().map(str::to_string));
+        self
+    }
+
+    /// # Name: build
+    /// ## Description
+    /// Renders the statement for `dialect` and returns it with the values to bind, in
+    /// placeholder order.
+    pub fn build(&self, dialect: &DatabaseType) -> Result<(String, Vec<SqlValue>), sqlx::Error> {
+        self.check(dialect)?;
+        let mut values = Vec::new();
+        let table = quote_column(dialect, &self.table);
+        let mut sql = match self.statement {
+            Statement::Select => {
+                let columns = if self.columns.is_empty() {
+                    "*".to_string()
+                } else {
+                    quote_list(dialect, &self.columns)
+                };
+                let mut sql = format!("SELECT {} FROM {}", columns, table);
+                for join in &self.joins {
+                    sql.push_str(&format!(
+                        " {} {} ON {} = {}",
+                        if join.left { "LEFT JOIN" } else { "INNER JOIN" },
+                        quote_column(dialect, &join.table),
+                        quote_column(dialect, &join.on.0),
+                        quote_column(dialect, &join.on.1)
+                    ));
+                }
+                sql
+            }
+            Statement::Insert if self.assignments.is_empty() => match dialect {
+                DatabaseType::Mysql => format!("INSERT INTO {} () VALUES ()", table),
+                _ => format!("INSERT INTO {} DEFAULT VALUES", table),
+            },
+            Statement::Insert => {
+                let mut columns = Vec::new();
+                let mut placeholders = Vec::new();
+                for (column, value) in &self.assignments {
+                    values.push(value.clone());
+                    columns.push(quote_column(dialect, column));
+                    placeholders.push(dialect.placeholder(values.len()));
+                }
+                format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    table,
+                    columns.join(", "),
+                    placeholders.join(", ")
+                )
+            }
+            Statement::Update => {
+                let mut assignments = Vec::new();
+                for (column, value) in &self.assignments {
+                    values.push(value.clone());
+                    assignments.push(format!(
+                        "{} = {}",
+                        quote_column(dialect, column),
+                        dialect.placeholder(values.len())
+                    ));
+                }
+                format!("UPDATE {} SET {}", table, assignments.join(", "))
+            }
+            Statement::Delete => format!("DELETE FROM {}", table),
+        };
+
+        if !self.conditions.is_empty() {
+            let clauses: Vec<String> = self
+                .conditions
+                .iter()
+                .map(|condition| render_condition(dialect, condition, &mut values))
+                .collect();
+            sql.push_str(&format!(" WHERE {}", clauses.join(" AND ")));
+        }
+        if !self.order.is_empty() {
+            let order: Vec<String> = self
+                .order
+                .iter()
+                .map(|(column, order)| {
+                    let direction = match order {
+                        Order::Asc => "ASC",
+                        Order::Desc => "DESC",
+                    };
+                    format!("{} {}", quote_column(dialect, column), direction)
+                })
+                .collect();
+            sql.push_str(&format!(" ORDER BY {}", order.join(", ")));
+        }
+        match (self.limit, self.offset, dialect) {
+            (Some(limit), _, _) => sql.push_str(&format!(" LIMIT {}", limit)),
+            // MySQL and SQLite only take an OFFSET after a LIMIT
+            (None, Some(_), DatabaseType::Mysql) => sql.push_str(" LIMIT 18446744073709551615"),
+            (None, Some(_), DatabaseType::Sqlite) => sql.push_str(" LIMIT -1"),
+            _ => {}
+        }
+        if let Some(offset) = self.offset {
+            sql.push_str(&format!(" OFFSET {}", offset));
+        }
+        if !self.returning.is_empty() {
+            sql.push_str(&format!(
+                " RETURNING {}",
+                quote_list(dialect, &self.returning)
+            ));
+        }
+        Ok((sql, values))
+    }
+
+    fn check(&self, dialect: &DatabaseType) -> Result<(), sqlx::Error> {
+        let invalid = |message: &str| Err(sqlx::Error::InvalidArgument(message.to_string()));
+        if self.statement != Statement::Select
+            && (!self.joins.is_empty()
+                || !self.order.is_empty()
+                || self.limit.is_some()
+                || self.offset.is_some())
+        {
+            return invalid("joins, order_by, limit and offset only apply to SELECT");
+        }
+        if self.statement == Statement::Select && !self.assignments.is_empty() {
+            return invalid("values only apply to INSERT and UPDATE");
+        }
+        if self.statement == Statement::Update && self.assignments.is_empty() {
+            return invalid("an UPDATE needs at least one value");
+        }
+        if self.statement == Statement::Insert && !self.conditions.is_empty() {
+            return invalid("an INSERT takes no conditions");
+        }
+        if !self.returning.is_empty() {
+            if self.statement == Statement::Select {
+                return invalid("returning only applies to INSERT, UPDATE and DELETE");
+            }
+            if *dialect == DatabaseType::Mysql {
+                return invalid("MySQL does not support RETURNING; read the rows back instead");
+            }
+        }
+        if matches!(dialect, DatabaseType::Mongo) {
+            return invalid("MongoDB is not a SQL database");
+        }
+        Ok(())
+    }
+
+    /// Runs the statement and returns every row.
+    pub async fn fetch_all<T: FromDatabaseRow>(
+        &self,
+        conn: &impl Executor,
+    ) -> Result<Vec<T>, sqlx::Error> {
+        let (sql, values) = self.build(&conn.database_type())?;
+        on_executor!(conn, |Db, executor| {
+            sqlx::query_as_with(&sql, Db::arguments(values)?)
+                .fetch_all(executor)
+                .await
+        })
+    }
+
+    /// Runs the statement and returns the first row, if there is one.
+    pub async fn fetch_optional<T: FromDatabaseRow>(
+        &self,
+        conn: &impl Executor,
+    ) -> Result<Option<T>, sqlx::Error> {
+        let (sql, values) = self.build(&conn.database_type())?;
+        on_executor!(conn, |Db, executor| {
+            sqlx::query_as_with(&sql, Db::arguments(values)?)
+                .fetch_optional(executor)
+                .await
+        })
+    }
+
+    /// Runs the statement and returns the first row, or `sqlx::Error::RowNotFound`.
+    pub async fn fetch_one<T: FromDatabaseRow>(
+        &self,
+        conn: &impl Executor,
+    ) -> Result<T, sqlx::Error> {
+        self.fetch_optional(conn)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Counts the rows a SELECT matches, ignoring its columns, ordering, limit and offset.
+    pub async fn count(&self, conn: &impl Executor) -> Result<i64, sqlx::Error> {
+        let mut query = self.clone();
+        query.columns.clear();
+        query.order.clear();
+        query.limit = None;
+        query.offset = None;
+        let (sql, values) = query.build(&conn.database_type())?;
+        let sql = sql.replacen("SELECT *", "SELECT COUNT(*)", 1);
+        on_executor!(conn, |Db, executor| {
+            sqlx::query_scalar_with(&sql, Db::arguments(values)?)
+                .fetch_one(executor)
+                .await
+        })
+    }
+
+    /// Runs the statement and returns the number of affected rows and, on MySQL, the id of
+    /// the last inserted row (0 elsewhere).
+    pub async fn execute(&self, conn: &impl Executor) -> Result<(u64, u64), sqlx::Error> {
+        let (sql, values) = self.build(&conn.database_type())?;
+        on_executor!(conn, |Db, executor| {
+            let result = sqlx::query_with(&sql, Db::arguments(values)?)
+                .execute(executor)
+                .await?;
+            Ok((result.rows_affected(), result.inserted_id()))
+        })
+    }
+}
+
+/// The id `QueryBuilder::execute` reports for the last inserted row.
+trait InsertedId {
+    fn inserted_id(&self) -> u64;
+}
+
+impl InsertedId for PgQueryResult {
+    fn inserted_id(&self) -> u64 {
+        0
+    }
+}
+
+impl InsertedId for MySqlQueryResult {
+    fn inserted_id(&self) -> u64 {
+        self.last_insert_id()
+    }
+}
+
+impl InsertedId for SqliteQueryResult {
+    fn inserted_id(&self) -> u64 {
+        0
+    }
+}
+
+/// Quotes each part of a possibly qualified column, leaving `*` as it is.
+fn quote_column(dialect: &DatabaseType, column: &str) -> String {
+    column
+        .split('.')
+        .map(|part| {
+            if part == "*" {
+                part.to_string()
+            } else {
+                dialect.quote_identifier(part)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn quote_list(dialect: &DatabaseType, columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|column| quote_column(dialect, column))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn render_condition(
+    dialect: &DatabaseType,
+    condition: &Condition,
+    values: &mut Vec<SqlValue>,
+) -> String {
+    match condition {
+        Condition::Compare(column, op, value) => {
+            values.push(value.clone());
+            format!(
+                "{} {} {}",
+                quote_column(dialect, column),
+                op.as_sql(),
+                dialect.placeholder(values.len())
+            )
+        }
+        Condition::IsNull(column) => format!("{} IS NULL", quote_column(dialect, column)),
+        Condition::IsNotNull(column) => format!("{} IS NOT NULL", quote_column(dialect, column)),
+        Condition::In(_, list) if list.is_empty() => "1 = 0".to_string(),
+        Condition::In(column, list) => {
+            let placeholders: Vec<String> = list
+                .iter()
+                .map(|value| {
+                    values.push(value.clone());
+                    dialect.placeholder(values.len())
+                })
+                .collect();
+            format!(
+                "{} IN ({})",
+                quote_column(dialect, column),
+                placeholders.join(", ")
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    const DIALECTS: [DatabaseType; 3] = [
+        DatabaseType::Postgres,
+        DatabaseType::Mysql,
+        DatabaseType::Sqlite,
+    ];
+
+    /// Renders `query` for PostgreSQL, MySQL and SQLite.
+    fn render(query: &QueryBuilder) -> Vec<String> {
+        DIALECTS
+            .iter()
+            .map(|dialect| query.build(dialect).unwrap().0)
+            .collect()
+    }
+
+    #[test]
+    fn test_select_snapshots() {
+        let query = QueryBuilder::select("posts")
+            .columns(["posts.*", "users.name"])
+            .left_join("users", "users.id", "posts.user_id")
+            .where_eq("posts.published", true)
+            .where_in("posts.id", [1, 2])
+            .where_null("posts.deleted_at")
+            .order_by("posts.id", Order::Desc)
+            .limit(10)
+            .offset(20);
+        assert_eq!(
+            render(&query),
+            [
+                r#"SELECT "posts".*, "users"."name" FROM "posts" LEFT JOIN "users" ON "users"."id" = "posts"."user_id" WHERE "posts"."published" = $1 AND "posts"."id" IN ($2, $3) AND "posts"."deleted_at" IS NULL ORDER BY "posts"."id" DESC LIMIT 10 OFFSET 20"#,
+                "SELECT `posts`.*, `users`.`name` FROM `posts` LEFT JOIN `users` ON `users`.`id` = `posts`.`user_id` WHERE `posts`.`published` = ? AND `posts`.`id` IN (?, ?) AND `posts`.`deleted_at` IS NULL ORDER BY `posts`.`id` DESC LIMIT 10 OFFSET 20",
+                r#"SELECT "posts".*, "users"."name" FROM "posts" LEFT JOIN "users" ON "users"."id" = "posts"."user_id" WHERE "posts"."published" = ? AND "posts"."id" IN (?, ?) AND "posts"."deleted_at" IS NULL ORDER BY "posts"."id" DESC LIMIT 10 OFFSET 20"#,
+            ]
+        );
+        assert_eq!(
+            query.build(&DatabaseType::Mysql).unwrap().1,
+            [
+                SqlValue::Bool(Some(true)),
+                SqlValue::Int(Some(1)),
+                SqlValue::Int(Some(2))
+            ]
+        );
+
+        let query = QueryBuilder::select("users")
+            .where_("name", Op::Like, "A%")
+            .where_("email", Op::Ne, None::<String>)
+            .where_in("id", Vec::<i32>::new())
+            .offset(5);
+        assert_eq!(
+            render(&query),
+            [
+                r#"SELECT * FROM "users" WHERE "name" LIKE $1 AND "email" IS NOT NULL AND 1 = 0 OFFSET 5"#,
+                "SELECT * FROM `users` WHERE `name` LIKE ? AND `email` IS NOT NULL AND 1 = 0 LIMIT 18446744073709551615 OFFSET 5",
+                r#"SELECT * FROM "users" WHERE "name" LIKE ? AND "email" IS NOT NULL AND 1 = 0 LIMIT -1 OFFSET 5"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_snapshots() {
+        let insert = QueryBuilder::insert("users")
+            .value("name", "Ann")
+            .value("age", None::<i32>);
+        assert_eq!(
+            render(&insert),
+            [
+                r#"INSERT INTO "users" ("name", "age") VALUES ($1, $2)"#,
+                "INSERT INTO `users` (`name`, `age`) VALUES (?, ?)",
+                r#"INSERT INTO "users" ("name", "age") VALUES (?, ?)"#,
+            ]
+        );
+        assert_eq!(
+            render(&QueryBuilder::insert("users")),
+            [
+                r#"INSERT INTO "users" DEFAULT VALUES"#,
+                "INSERT INTO `users` () VALUES ()",
+                r#"INSERT INTO "users" DEFAULT VALUES"#,
+            ]
+        );
+
+        let update = QueryBuilder::update("users")
+            .value("name", "Bo")
+            .where_("id", Op::Ge, 3);
+        assert_eq!(
+            render(&update),
+            [
+                r#"UPDATE "users" SET "name" = $1 WHERE "id" >= $2"#,
+                "UPDATE `users` SET `name` = ? WHERE `id` >= ?",
+                r#"UPDATE "users" SET "name" = ? WHERE "id" >= ?"#,
+            ]
+        );
+
+        let delete = QueryBuilder::delete("users").where_eq("id", 3);
+        assert_eq!(
+            render(&delete),
+            [
+                r#"DELETE FROM "users" WHERE "id" = $1"#,
+                "DELETE FROM `users` WHERE `id` = ?",
+                r#"DELETE FROM "users" WHERE "id" = ?"#,
+            ]
+        );
+
+        let returning = delete.returning(["*"]);
+        assert_eq!(
+            returning.build(&DatabaseType::Sqlite).unwrap().0,
+            r#"DELETE FROM "users" WHERE "id" = ? RETURNING *"#
+        );
+        assert!(returning.build(&DatabaseType::Mysql).is_err());
+        assert!(QueryBuilder::update("users")
+            .build(&DatabaseType::Postgres)
+            .is_err());
+        assert!(QueryBuilder::delete("users")
+            .limit(1)
+            .build(&DatabaseType::Postgres)
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_runs_on_sqlite() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let conn = DatabaseConnection::Sqlite(Arc::new(pool));
+        if let DatabaseConnection::Sqlite(pool) = &conn {
+            sqlx::query("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+                .execute(pool.as_ref())
+                .await
+                .unwrap();
+        }
+
+        let (id, name): (i64, String) = QueryBuilder::insert("users")
+            .value("name", "Ann")
+            .returning(["id", "name"])
+            .fetch_one(&conn)
+            .await
+            .unwrap();
+        assert_eq!((id, name.as_str()), (1, "Ann"));
+        QueryBuilder::insert("users")
+            .value("name", "Bo")
+            .execute(&conn)
+            .await
+            .unwrap();
+
+        let names: Vec<(String,)> = QueryBuilder::select("users")
+            .columns(["name"])
+            .order_by("name", Order::Desc)
+            .fetch_all(&conn)
+            .await
+            .unwrap();
+        assert_eq!(names, [("Bo".to_string(),), ("Ann".to_string(),)]);
+
+        let query = QueryBuilder::select("users").where_("id", Op::Gt, 1);
+        assert_eq!(query.count(&conn).await.unwrap(), 1);
+        let (deleted, _) = QueryBuilder::delete("users")
+            .where_eq("name", "Ann")
+            .execute(&conn)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 1);
+    }
+}
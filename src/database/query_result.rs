@@ -0,0 +1,437 @@
+use crate::database::DatabaseConnection;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde_json::Value;
+use sqlx::mysql::MySqlRow;
+use sqlx::postgres::{PgRow, PgValueFormat};
+use sqlx::types::{Json, Uuid};
+use sqlx::{Column, ColumnIndex, Decode, Row, Type, TypeInfo, ValueRef};
+
+/// # Name: QueryResult
+/// ## Description
+/// The outcome of a single statement, independent of the database backend.
+/// Statements that return rows fill `columns` and `rows`; other statements report `rows_affected`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub rows_affected: Option<u64>,
+}
+
+/// Returns true when `sql` is expected to produce a result set rather than a row count.
+pub fn returns_rows(sql: &str) -> bool {
+    let first_word = sql
+        .trim_start()
+        .split(|c: char| c.is_whitespace() || c == '(')
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+
+    matches!(
+        first_word.as_str(),
+        "select"
+            | "with"
+            | "pragma"
+            | "show"
+            | "explain"
+            | "values"
+            | "describe"
+            | "desc"
+            | "table"
+    ) || sql.to_ascii_lowercase().contains(" returning ")
+}
+
+/// Reads a NUMERIC or DECIMAL cell as its exact digits, which neither `f64` nor
+/// `rust_decimal` can hold for wide columns.
+type DecimalDecoder<R> = fn(&R, usize) -> Option<String>;
+
+fn pg_decimal(row: &PgRow, index: usize) -> Option<String> {
+    let raw = row.try_get_raw(index).ok()?;
+    if raw.type_info().name() != "NUMERIC" {
+        return None;
+    }
+    match raw.format() {
+        PgValueFormat::Binary => pg_numeric_text(raw.as_bytes().ok()?),
+        PgValueFormat::Text => raw.as_str().ok().map(String::from),
+    }
+}
+
+/// Formats Postgres' binary NUMERIC: digit count, weight, sign and display scale, then the
+/// base-10000 digits, the first of which is multiplied by 10000^weight.
+fn pg_numeric_text(bytes: &[u8]) -> Option<String> {
+    let word = |i: usize| {
+        bytes
+            .get(i * 2..i * 2 + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+    };
+    let digits = word(0)? as i64;
+    let weight = word(1)? as i16 as i64;
+    let scale = word(3)? as usize;
+    let digit = |i: i64| {
+        if (0..digits).contains(&i) {
+            word(4 + i as usize)
+        } else {
+            Some(0)
+        }
+    };
+
+    let mut text = match word(2)? {
+        0xC000 => return Some("NaN".to_string()),
+        0xD000 => return Some("Infinity".to_string()),
+        0xF000 => return Some("-Infinity".to_string()),
+        0x4000 => "-".to_string(),
+        _ => String::new(),
+    };
+    if weight < 0 {
+        text.push('0');
+    }
+    for i in 0..=weight {
+        let group = digit(i)?;
+        if i == 0 {
+            text.push_str(&group.to_string());
+        } else {
+            text.push_str(&format!("{:04}", group));
+        }
+    }
+    if scale > 0 {
+        let mut fraction = String::new();
+        let mut i = weight + 1;
+        while fraction.len() < scale {
+            fraction.push_str(&format!("{:04}", digit(i)?));
+            i += 1;
+        }
+        fraction.truncate(scale);
+        text.push('.');
+        text.push_str(&fraction);
+    }
+    Some(text)
+}
+
+/// MySQL sends DECIMAL values as text, which sqlx only decodes into `String` unchecked.
+fn mysql_decimal(row: &MySqlRow, index: usize) -> Option<String> {
+    let raw = row.try_get_raw(index).ok()?;
+    if raw.type_info().name() != "DECIMAL" {
+        return None;
+    }
+    row.try_get_unchecked::<String, _>(index).ok()
+}
+
+/// Decodes one cell into JSON, trying the Rust types every backend supports in turn.
+/// Decimals become strings, so no digits are lost.
+fn decode_cell<R>(row: &R, index: usize, decimal: DecimalDecoder<R>) -> Value
+where
+    R: Row,
+    usize: ColumnIndex<R>,
+    for<'r> String: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> i64: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> i32: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> i16: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> f64: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> f32: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> bool: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> NaiveDateTime: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> DateTime<Utc>: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> NaiveDate: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> NaiveTime: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> Uuid: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> Json<Value>: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> Vec<u8>: Decode<'r, R::Database> + Type<R::Database>,
+{
+    match row.try_get_raw(index) {
+        Ok(raw) if raw.is_null() => return Value::Null,
+        Ok(_) => {}
+        Err(_) => return Value::String("<error>".to_string()),
+    }
+
+    if let Some(v) = decimal(row, index) {
+        Value::String(v)
+    } else if let Ok(v) = row.try_get::<String, _>(index) {
+        Value::String(v)
+    } else if let Ok(v) = row.try_get::<i64, _>(index) {
+        Value::from(v)
+    } else if let Ok(v) = row.try_get::<i32, _>(index) {
+        Value::from(v)
+    } else if let Ok(v) = row.try_get::<i16, _>(index) {
+        Value::from(v)
+    } else if let Ok(v) = row.try_get::<f64, _>(index) {
+        Value::from(v)
+    } else if let Ok(v) = row.try_get::<f32, _>(index) {
+        Value::from(v as f64)
+    } else if let Ok(v) = row.try_get::<bool, _>(index) {
+        Value::Bool(v)
+    } else if let Ok(v) = row.try_get::<NaiveDateTime, _>(index) {
+        Value::String(v.to_string())
+    } else if let Ok(v) = row.try_get::<DateTime<Utc>, _>(index) {
+        Value::String(v.to_rfc3339())
+    } else if let Ok(v) = row.try_get::<NaiveDate, _>(index) {
+        Value::String(v.to_string())
+    } else if let Ok(v) = row.try_get::<NaiveTime, _>(index) {
+        Value::String(v.to_string())
+    } else if let Ok(v) = row.try_get::<Uuid, _>(index) {
+        Value::String(v.to_string())
+    } else if let Ok(Json(v)) = row.try_get::<Json<Value>, _>(index) {
+        v
+    } else if let Ok(v) = row.try_get::<Vec<u8>, _>(index) {
+        // Hex-encoded like psql's bytea output, so exports can round-trip binary data
+        Value::String(format!(
+            "\\x{}",
+            v.iter().map(|b| format!("{:02x}", b)).collect::<String>()
+        ))
+    } else {
+        Value::String("<unprintable>".to_string())
+    }
+}
+
+fn collect_rows<R>(rows: &[R], decimal: DecimalDecoder<R>) -> (Vec<String>, Vec<Vec<Value>>)
+where
+    R: Row,
+    usize: ColumnIndex<R>,
+    for<'r> String: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> i64: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> i32: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> i16: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> f64: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> f32: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> bool: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> NaiveDateTime: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> DateTime<Utc>: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> NaiveDate: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> NaiveTime: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> Uuid: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> Json<Value>: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> Vec<u8>: Decode<'r, R::Database> + Type<R::Database>,
+{
+    let columns = rows
+        .first()
+        .map(|row| row.columns().iter().map(|c| c.name().to_string()).collect())
+        .unwrap_or_default();
+
+    let values = rows
+        .iter()
+        .map(|row| {
+            (0..row.len())
+                .map(|i| decode_cell(row, i, decimal))
+                .collect()
+        })
+        .collect();
+
+    (columns, values)
+}
+
+/// # Name: run_statement
+/// ## Description
+/// Executes one SQL statement on any backend and returns its rows as JSON values.
+/// ## Example
+/// ```no_run
+/// use rustyroad::database::{run_statement, Database};
+///
+/// # async fn run() {
+/// let database = Database::get_database_from_rustyroad_toml().unwrap();
+/// let connection = database.create_database_connection().await.unwrap();
+/// let result = run_statement(&connection, "SELECT 1 AS one").await.unwrap();
+/// assert_eq!(result.columns, vec!["one"]);
+/// # }
+/// ```
+pub async fn run_statement(
+    connection: &DatabaseConnection,
+    sql: &str,
+) -> Result<QueryResult, sqlx::Error> {
+    let fetch = returns_rows(sql);
+
+    let (columns, rows, rows_affected) = match connection {
+        DatabaseConnection::Pg(pool) => {
+            if fetch {
+                let rows = sqlx::query(sql).fetch_all(&**pool).await?;
+                let (columns, rows) = collect_rows(&rows, pg_decimal);
+                (columns, rows, None)
+            } else {
+                let done = sqlx::query(sql).execute(&**pool).await?;
+                (Vec::new(), Vec::new(), Some(done.rows_affected()))
+            }
+        }
+        DatabaseConnection::MySql(pool) => {
+            if fetch {
+                let rows = sqlx::query(sql).fetch_all(&**pool).await?;
+                let (columns, rows) = collect_rows(&rows, mysql_decimal);
+                (columns, rows, None)
+            } else {
+                let done = sqlx::query(sql).execute(&**pool).await?;
+                (Vec::new(), Vec::new(), Some(done.rows_affected()))
+            }
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            if fetch {
+                let rows = sqlx::query(sql).fetch_all(&**pool).await?;
+                // SQLite stores NUMERIC values as integers or reals, which decode as numbers
+                let (columns, rows) = collect_rows(&rows, |_, _| None);
+                (columns, rows, None)
+            } else {
+                let done = sqlx::query(sql).execute(&**pool).await?;
+                (Vec::new(), Vec::new(), Some(done.rows_affected()))
+            }
+        }
+    };
+
+    Ok(QueryResult {
+        columns,
+        rows,
+        rows_affected,
+    })
+}
+
+/// Renders a cell for terminal output: strings without quotes, `NULL` for nulls, JSON otherwise.
+pub fn display_value(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// # Name: split_sql_statements
+/// ## Description
+/// Splits `input` on `;` terminators that are outside quotes and comments.
+/// Returns the complete statements (without the terminator) and the unterminated remainder.
+/// ## Example
+/// ```
+/// use rustyroad::database::split_sql_statements;
+///
+/// let (statements, rest) = split_sql_statements("SELECT ';'; SELECT 2;\nSELECT");
+/// assert_eq!(statements, vec!["SELECT ';'", "SELECT 2"]);
+/// assert_eq!(rest, "SELECT");
+/// ```
+pub fn split_sql_statements(input: &str) -> (Vec<String>, String) {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut chars = input.chars().peekable();
+    let mut quote: Option<char> = None;
+    let mut line_comment = false;
+    let mut block_comment = false;
+
+    while let Some(c) = chars.next() {
+        if line_comment {
+            current.push(c);
+            if c == '\n' {
+                line_comment = false;
+            }
+            continue;
+        }
+        if block_comment {
+            current.push(c);
+            if c == '*' && chars.peek() == Some(&'/') {
+                current.push(chars.next().unwrap());
+                block_comment = false;
+            }
+            continue;
+        }
+        if let Some(q) = quote {
+            current.push(c);
+            if c == q {
+                // A doubled quote is an escaped quote, not the end of the literal
+                if chars.peek() == Some(&q) {
+                    current.push(chars.next().unwrap());
+                } else {
+                    quote = None;
+                }
+            }
+            continue;
+        }
+
+        match c {
+            '\'' | '"' | '`' => {
+                quote = Some(c);
+                current.push(c);
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                line_comment = true;
+                current.push(c);
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                block_comment = true;
+                current.push(c);
+            }
+            ';' => {
+                let statement = current.trim();
+                if !statement.is_empty() {
+                    statements.push(statement.to_string());
+                }
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+
+    (statements, current.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_returns_rows() {
+        assert!(returns_rows("SELECT * FROM users"));
+        assert!(returns_rows("  with t as (select 1) select * from t"));
+        assert!(returns_rows("PRAGMA table_info(users)"));
+        assert!(returns_rows(
+            "INSERT INTO users (email) VALUES ('a') RETURNING id"
+        ));
+        assert!(!returns_rows("INSERT INTO users (email) VALUES ('a')"));
+        assert!(!returns_rows("CREATE TABLE t (id INTEGER)"));
+    }
+
+    #[test]
+    fn test_pg_numeric_text() {
+        let numeric =
+            |words: &[u16]| -> Vec<u8> { words.iter().flat_map(|w| w.to_be_bytes()).collect() };
+        // 12345678901234567890.1234 as NUMERIC(30,10), more digits than rust_decimal holds
+        assert_eq!(
+            pg_numeric_text(&numeric(&[6, 4, 0, 10, 1234, 5678, 9012, 3456, 7890, 1234]))
+                .as_deref(),
+            Some("12345678901234567890.1234000000")
+        );
+        assert_eq!(
+            pg_numeric_text(&numeric(&[2, 0, 0x4000, 2, 12, 3000])).as_deref(),
+            Some("-12.30")
+        );
+        assert_eq!(
+            pg_numeric_text(&numeric(&[1, 0xFFFE, 0, 6, 1200])).as_deref(),
+            Some("0.000012")
+        );
+        assert_eq!(
+            pg_numeric_text(&numeric(&[1, 2, 0, 0, 1])).as_deref(),
+            Some("100000000")
+        );
+        assert_eq!(
+            pg_numeric_text(&numeric(&[0, 0, 0, 0])).as_deref(),
+            Some("0")
+        );
+        assert_eq!(
+            pg_numeric_text(&numeric(&[0, 0, 0xC000, 0])).as_deref(),
+            Some("NaN")
+        );
+        assert_eq!(pg_numeric_text(&[0, 1]), None);
+    }
+
+    #[test]
+    fn test_split_sql_statements_respects_quotes_and_comments() {
+        let input = "INSERT INTO t VALUES ('a;b', 'it''s');\n-- comment; here\nSELECT \"x;y\" FROM t; /* ; */ SELECT 1";
+        let (statements, rest) = split_sql_statements(input);
+
+        assert_eq!(
+            statements,
+            vec![
+                "INSERT INTO t VALUES ('a;b', 'it''s')",
+                "-- comment; here\nSELECT \"x;y\" FROM t",
+            ]
+        );
+        assert_eq!(rest, "/* ; */ SELECT 1");
+    }
+
+    #[test]
+    fn test_display_value() {
+        assert_eq!(display_value(&Value::Null), "NULL");
+        assert_eq!(display_value(&Value::String("hi".to_string())), "hi");
+        assert_eq!(display_value(&serde_json::json!(42)), "42");
+        assert_eq!(display_value(&serde_json::json!({"a": 1})), "{\"a\":1}");
+    }
+}
@@ -23,8 +23,8 @@ struct SchemaOutput {
     tables: Vec<SchemaTable>,
 }
 
-/// Inspects and prints the database schema
-pub async fn inspect_schema(format: &str) -> Result<(), CustomMigrationError> {
+/// Inspects and prints the database schema, limited to `tables` unless it is empty
+pub async fn inspect_schema(format: &str, tables: &[String]) -> Result<(), CustomMigrationError> {
     let database = Database::get_database_from_rustyroad_toml()
         .expect("Couldn't parse the rustyroad.toml file");
 
@@ -151,6 +151,21 @@ pub async fn inspect_schema(format: &str) -> Result<(), CustomMigrationError> {
         }
     }
 
+    if !tables.is_empty() {
+        all_tables.retain(|table| tables.contains(&table.name));
+        let missing: Vec<&str> = tables
+            .iter()
+            .filter(|name| !all_tables.iter().any(|table| &table.name == *name))
+            .map(|name| name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(CustomMigrationError::IoError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Table(s) not found: {}", missing.join(", ")),
+            )));
+        }
+    }
+
     if format == "json" {
         let output = SchemaOutput {
             database_type: db_type,
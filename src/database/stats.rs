@@ -0,0 +1,563 @@
+use crate::database::migrations::CustomMigrationError;
+use crate::database::{
+    format_table, introspect_schema, run_statement, Database, DatabaseConnection, QueryResult,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Dead-row ratio above which a Postgres table is flagged for VACUUM.
+const DEAD_ROW_RATIO: f64 = 0.2;
+
+/// Share of free pages (or free bytes) above which a table or SQLite file is flagged as bloated.
+const FREE_SPACE_RATIO: f64 = 0.1;
+
+/// # Name: TableStats
+/// ## Description
+/// Size and row-count figures for one table. Fields a backend cannot report are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TableStats {
+    pub name: String,
+    pub estimated_rows: Option<i64>,
+    pub exact_rows: Option<i64>,
+    pub table_bytes: Option<i64>,
+    pub index_bytes: Option<i64>,
+    /// Postgres: dead tuples waiting for VACUUM.
+    pub dead_rows: Option<i64>,
+    /// MySQL: allocated but unused bytes (`DATA_FREE`).
+    pub free_bytes: Option<i64>,
+    pub last_vacuum: Option<String>,
+    pub last_analyze: Option<String>,
+}
+
+/// # Name: IndexUsage
+/// ## Description
+/// An index that has not been used since statistics were last reset.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IndexUsage {
+    pub table: String,
+    pub index: String,
+    pub size_bytes: Option<i64>,
+}
+
+/// # Name: SqliteFileStats
+/// ## Description
+/// Page-level figures for a SQLite database file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SqliteFileStats {
+    pub page_size: i64,
+    pub page_count: i64,
+    pub freelist_count: i64,
+}
+
+impl SqliteFileStats {
+    pub fn file_bytes(&self) -> i64 {
+        self.page_size * self.page_count
+    }
+
+    pub fn free_bytes(&self) -> i64 {
+        self.page_size * self.freelist_count
+    }
+}
+
+/// # Name: DatabaseStats
+/// ## Description
+/// The health overview printed by `rustyroad db stats`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DatabaseStats {
+    pub backend: String,
+    pub database: String,
+    pub tables: Vec<TableStats>,
+    pub unused_indexes: Vec<IndexUsage>,
+    pub sqlite: Option<SqliteFileStats>,
+    pub hints: Vec<String>,
+}
+
+impl DatabaseStats {
+    fn table_mut(&mut self, name: &str) -> Option<&mut TableStats> {
+        self.tables.iter_mut().find(|t| t.name == name)
+    }
+
+    /// Derives VACUUM / OPTIMIZE suggestions from the collected figures.
+    fn add_bloat_hints(&mut self) {
+        for table in &self.tables {
+            if let (Some(dead), Some(live)) = (table.dead_rows, table.estimated_rows) {
+                if dead > 0 && dead as f64 > (live.max(1) as f64) * DEAD_ROW_RATIO {
+                    self.hints.push(format!(
+                        "{} has {} dead rows ({} live): consider VACUUM (ANALYZE) {}",
+                        table.name, dead, live, table.name
+                    ));
+                }
+            }
+            if let (Some(free), Some(data)) = (table.free_bytes, table.table_bytes) {
+                if free > 0 && free as f64 > (data.max(1) as f64) * FREE_SPACE_RATIO {
+                    self.hints.push(format!(
+                        "{} has {} of free space: consider OPTIMIZE TABLE {}",
+                        table.name,
+                        format_bytes(free),
+                        table.name
+                    ));
+                }
+            }
+        }
+        if let Some(file) = &self.sqlite {
+            if file.freelist_count > 0
+                && file.freelist_count as f64 > file.page_count as f64 * FREE_SPACE_RATIO
+            {
+                self.hints.push(format!(
+                    "{} free pages ({}) in the database file: consider VACUUM",
+                    file.freelist_count,
+                    format_bytes(file.free_bytes())
+                ));
+            }
+        }
+        for index in &self.unused_indexes {
+            self.hints.push(format!(
+                "Index {} on {} has never been used: consider dropping it",
+                index.index, index.table
+            ));
+        }
+    }
+}
+
+/// Formats a byte count with a binary unit, e.g. `8.0 kB`.
+pub fn format_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn cell_i64(row: &[Value], index: usize) -> Option<i64> {
+    match row.get(index)? {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn cell_string(row: &[Value], index: usize) -> Option<String> {
+    match row.get(index)? {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+async fn postgres_stats(
+    connection: &DatabaseConnection,
+    stats: &mut DatabaseStats,
+) -> Result<(), sqlx::Error> {
+    let tables = run_statement(
+        connection,
+        "SELECT c.relname::text AS name, \
+                c.reltuples::bigint AS estimated_rows, \
+                pg_table_size(c.oid) AS table_bytes, \
+                pg_indexes_size(c.oid) AS index_bytes, \
+                s.n_dead_tup AS dead_rows, \
+                GREATEST(s.last_vacuum, s.last_autovacuum)::text AS last_vacuum, \
+                GREATEST(s.last_analyze, s.last_autoanalyze)::text AS last_analyze \
+         FROM pg_class c \
+         JOIN pg_namespace n ON n.oid = c.relnamespace \
+         LEFT JOIN pg_stat_user_tables s ON s.relid = c.oid \
+         WHERE c.relkind IN ('r', 'p') AND n.nspname = current_schema() \
+         ORDER BY c.relname",
+    )
+    .await?;
+    stats.tables = tables
+        .rows
+        .iter()
+        .map(|row| TableStats {
+            name: cell_string(row, 0).unwrap_or_default(),
+            // reltuples is -1 for tables that have never been analyzed
+            estimated_rows: cell_i64(row, 1).filter(|n| *n >= 0),
+            table_bytes: cell_i64(row, 2),
+            index_bytes: cell_i64(row, 3),
+            dead_rows: cell_i64(row, 4),
+            last_vacuum: cell_string(row, 5),
+            last_analyze: cell_string(row, 6),
+            ..Default::default()
+        })
+        .collect();
+
+    let unused = run_statement(
+        connection,
+        "SELECT s.relname::text AS table_name, s.indexrelname::text AS index_name, \
+                pg_relation_size(s.indexrelid) AS size_bytes \
+         FROM pg_stat_user_indexes s \
+         JOIN pg_index i ON i.indexrelid = s.indexrelid \
+         WHERE s.idx_scan = 0 AND NOT i.indisunique AND NOT i.indisprimary \
+         ORDER BY pg_relation_size(s.indexrelid) DESC",
+    )
+    .await?;
+    stats.unused_indexes = unused
+        .rows
+        .iter()
+        .map(|row| IndexUsage {
+            table: cell_string(row, 0).unwrap_or_default(),
+            index: cell_string(row, 1).unwrap_or_default(),
+            size_bytes: cell_i64(row, 2),
+        })
+        .collect();
+    Ok(())
+}
+
+async fn mysql_stats(
+    connection: &DatabaseConnection,
+    stats: &mut DatabaseStats,
+) -> Result<(), sqlx::Error> {
+    // information_schema reports BIGINT UNSIGNED, so cast to SIGNED for decoding
+    let tables = run_statement(
+        connection,
+        "SELECT TABLE_NAME AS name, \
+                CAST(TABLE_ROWS AS SIGNED) AS estimated_rows, \
+                CAST(DATA_LENGTH AS SIGNED) AS table_bytes, \
+                CAST(INDEX_LENGTH AS SIGNED) AS index_bytes, \
+                CAST(DATA_FREE AS SIGNED) AS free_bytes \
+         FROM information_schema.TABLES \
+         WHERE TABLE_SCHEMA = DATABASE() AND TABLE_TYPE = 'BASE TABLE' \
+         ORDER BY TABLE_NAME",
+    )
+    .await?;
+    stats.tables = tables
+        .rows
+        .iter()
+        .map(|row| TableStats {
+            name: cell_string(row, 0).unwrap_or_default(),
+            estimated_rows: cell_i64(row, 1),
+            table_bytes: cell_i64(row, 2),
+            index_bytes: cell_i64(row, 3),
+            free_bytes: cell_i64(row, 4),
+            ..Default::default()
+        })
+        .collect();
+
+    // sys.schema_unused_indexes needs the sys schema and performance_schema enabled
+    match run_statement(
+        connection,
+        "SELECT object_name AS table_name, index_name \
+         FROM sys.schema_unused_indexes \
+         WHERE object_schema = DATABASE() \
+         ORDER BY object_name, index_name",
+    )
+    .await
+    {
+        Ok(unused) => {
+            stats.unused_indexes = unused
+                .rows
+                .iter()
+                .map(|row| IndexUsage {
+                    table: cell_string(row, 0).unwrap_or_default(),
+                    index: cell_string(row, 1).unwrap_or_default(),
+                    size_bytes: None,
+                })
+                .collect();
+        }
+        Err(_) => stats
+            .hints
+            .push("Unused index detection needs the sys schema and performance_schema".to_string()),
+    }
+    Ok(())
+}
+
+async fn pragma_i64(connection: &DatabaseConnection, pragma: &str) -> Result<i64, sqlx::Error> {
+    let result = run_statement(connection, &format!("PRAGMA {}", pragma)).await?;
+    Ok(result
+        .rows
+        .first()
+        .and_then(|row| cell_i64(row, 0))
+        .unwrap_or_default())
+}
+
+async fn sqlite_stats(
+    connection: &DatabaseConnection,
+    stats: &mut DatabaseStats,
+) -> Result<(), CustomMigrationError> {
+    let schema = introspect_schema(connection).await?;
+    stats.tables = schema
+        .tables
+        .iter()
+        .map(|t| TableStats {
+            name: t.name.clone(),
+            ..Default::default()
+        })
+        .collect();
+
+    // Row estimates only exist after ANALYZE has filled sqlite_stat1
+    if let Ok(estimates) = run_statement(
+        connection,
+        "SELECT tbl, MAX(CAST(stat AS INTEGER)) FROM sqlite_stat1 GROUP BY tbl",
+    )
+    .await
+    {
+        for row in &estimates.rows {
+            if let Some(table) = cell_string(row, 0).and_then(|name| stats.table_mut(&name)) {
+                table.estimated_rows = cell_i64(row, 1);
+            }
+        }
+    }
+
+    // The dbstat virtual table is optional in SQLite builds; sizes stay unknown without it
+    if let Ok(sizes) = run_statement(
+        connection,
+        "SELECT m.tbl_name, m.type, SUM(d.pgsize) \
+         FROM dbstat d JOIN sqlite_master m ON m.name = d.name \
+         GROUP BY m.tbl_name, m.type",
+    )
+    .await
+    {
+        for row in &sizes.rows {
+            let kind = cell_string(row, 1).unwrap_or_default();
+            let bytes = cell_i64(row, 2);
+            if let Some(table) = cell_string(row, 0).and_then(|name| stats.table_mut(&name)) {
+                if kind == "index" {
+                    table.index_bytes = bytes;
+                } else {
+                    table.table_bytes = bytes;
+                }
+            }
+        }
+    }
+
+    stats.sqlite = Some(SqliteFileStats {
+        page_size: pragma_i64(connection, "page_size").await?,
+        page_count: pragma_i64(connection, "page_count").await?,
+        freelist_count: pragma_i64(connection, "freelist_count").await?,
+    });
+    Ok(())
+}
+
+/// # Name: collect_database_stats
+/// ## Description
+/// Gathers row counts, table and index sizes, vacuum/analyze times (Postgres), unused
+/// indexes (Postgres, MySQL) and page/freelist figures (SQLite), plus bloat hints.
+/// ## Arguments
+/// * `connection` - The database to inspect.
+/// * `exact` - Also run `COUNT(*)` on every table, which can be slow on large tables.
+pub async fn collect_database_stats(
+    connection: &DatabaseConnection,
+    database_name: &str,
+    exact: bool,
+) -> Result<DatabaseStats, CustomMigrationError> {
+    let mut stats = DatabaseStats {
+        database: database_name.to_string(),
+        ..Default::default()
+    };
+
+    match connection {
+        DatabaseConnection::Pg(_) => {
+            stats.backend = "postgres".to_string();
+            postgres_stats(connection, &mut stats).await?;
+        }
+        DatabaseConnection::MySql(_) => {
+            stats.backend = "mysql".to_string();
+            mysql_stats(connection, &mut stats).await?;
+        }
+        DatabaseConnection::Sqlite(_) => {
+            stats.backend = "sqlite".to_string();
+            sqlite_stats(connection, &mut stats).await?;
+        }
+    }
+
+    if exact {
+        for table in &mut stats.tables {
+            let sql = format!(
+                "SELECT COUNT(*) FROM {}",
+                connection.database_type().quote_identifier(&table.name)
+            );
+            let result = run_statement(connection, &sql).await?;
+            table.exact_rows = result.rows.first().and_then(|row| cell_i64(row, 0));
+        }
+    }
+
+    stats.add_bloat_hints();
+    Ok(stats)
+}
+
+/// # Name: render_database_stats
+/// ## Description
+/// Renders `stats` as text tables for the terminal.
+pub fn render_database_stats(stats: &DatabaseStats) -> String {
+    let optional = |value: Option<i64>, f: fn(i64) -> String| {
+        value.map(|v| Value::String(f(v))).unwrap_or(Value::Null)
+    };
+    let is_postgres = stats.backend == "postgres";
+    let exact = stats.tables.iter().any(|t| t.exact_rows.is_some());
+
+    let mut columns = vec!["Table", "Est. rows"];
+    if exact {
+        columns.push("Rows");
+    }
+    columns.extend(["Table size", "Index size"]);
+    if is_postgres {
+        columns.extend(["Dead rows", "Last vacuum", "Last analyze"]);
+    }
+
+    let rows = stats
+        .tables
+        .iter()
+        .map(|t| {
+            let mut row = vec![
+                Value::String(t.name.clone()),
+                t.estimated_rows.map(Value::from).unwrap_or(Value::Null),
+            ];
+            if exact {
+                row.push(t.exact_rows.map(Value::from).unwrap_or(Value::Null));
+            }
+            row.push(optional(t.table_bytes, format_bytes));
+            row.push(optional(t.index_bytes, format_bytes));
+            if is_postgres {
+                row.push(t.dead_rows.map(Value::from).unwrap_or(Value::Null));
+                row.push(
+                    t.last_vacuum
+                        .clone()
+                        .map(Value::String)
+                        .unwrap_or(Value::Null),
+                );
+                row.push(
+                    t.last_analyze
+                        .clone()
+                        .map(Value::String)
+                        .unwrap_or(Value::Null),
+                );
+            }
+            row
+        })
+        .collect();
+
+    let mut out = format!("Database: {} ({})\n", stats.database, stats.backend);
+    out.push_str(&format_table(&QueryResult {
+        columns: columns.iter().map(|c| c.to_string()).collect(),
+        rows,
+        rows_affected: None,
+    }));
+
+    if let Some(file) = &stats.sqlite {
+        out.push_str(&format!(
+            "\nSQLite file: {} pages x {} = {}; freelist {} pages ({})\n",
+            file.page_count,
+            format_bytes(file.page_size),
+            format_bytes(file.file_bytes()),
+            file.freelist_count,
+            format_bytes(file.free_bytes())
+        ));
+    }
+
+    if stats.backend != "sqlite" {
+        if stats.unused_indexes.is_empty() {
+            out.push_str("\nNo unused indexes found.\n");
+        } else {
+            out.push_str("\nUnused indexes:\n");
+            for index in &stats.unused_indexes {
+                match index.size_bytes {
+                    Some(bytes) => out.push_str(&format!(
+                        "  {}.{} ({})\n",
+                        index.table,
+                        index.index,
+                        format_bytes(bytes)
+                    )),
+                    None => out.push_str(&format!("  {}.{}\n", index.table, index.index)),
+                }
+            }
+        }
+    }
+
+    if !stats.hints.is_empty() {
+        out.push_str("\nHints:\n");
+        for hint in &stats.hints {
+            out.push_str(&format!("  - {}\n", hint));
+        }
+    }
+    out
+}
+
+/// # Name: print_database_stats
+/// ## Description
+/// Prints the stats of the database configured in rustyroad.toml as text or JSON.
+pub async fn print_database_stats(exact: bool, format: &str) -> Result<(), CustomMigrationError> {
+    let database = Database::get_database_from_rustyroad_toml()?;
+    let connection = Database::create_database_connection(&database)
+        .await
+        .map_err(CustomMigrationError::SendError)?;
+
+    let stats = collect_database_stats(&connection, &database.name, exact).await?;
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        print!("{}", render_database_stats(&stats));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(8192), "8.0 kB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 + 512 * 1024), "5.5 MB");
+    }
+
+    #[test]
+    fn test_bloat_hints() {
+        let mut stats = DatabaseStats {
+            backend: "postgres".to_string(),
+            tables: vec![
+                TableStats {
+                    name: "events".to_string(),
+                    estimated_rows: Some(100),
+                    dead_rows: Some(50),
+                    ..Default::default()
+                },
+                TableStats {
+                    name: "users".to_string(),
+                    estimated_rows: Some(100),
+                    dead_rows: Some(5),
+                    ..Default::default()
+                },
+            ],
+            unused_indexes: vec![IndexUsage {
+                table: "users".to_string(),
+                index: "idx_users_name".to_string(),
+                size_bytes: Some(8192),
+            }],
+            sqlite: None,
+            ..Default::default()
+        };
+
+        stats.add_bloat_hints();
+
+        assert_eq!(stats.hints.len(), 2);
+        assert!(stats.hints[0].starts_with("events has 50 dead rows"));
+        assert!(stats.hints[1].contains("idx_users_name"));
+    }
+
+    #[test]
+    fn test_sqlite_freelist_hint() {
+        let mut stats = DatabaseStats {
+            backend: "sqlite".to_string(),
+            sqlite: Some(SqliteFileStats {
+                page_size: 4096,
+                page_count: 100,
+                freelist_count: 40,
+            }),
+            ..Default::default()
+        };
+
+        stats.add_bloat_hints();
+
+        assert_eq!(
+            stats.hints,
+            vec!["40 free pages (160.0 kB) in the database file: consider VACUUM"]
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use crate::database::{DatabaseConnection, DatabaseType};
+use sqlx::{MySql, Postgres, Sqlite};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+
+/// What an `Executor` runs its queries on.
+pub enum ExecutorRef<'a> {
+    Connection(&'a DatabaseConnection),
+    Transaction(&'a Transaction),
+}
+
+/// # Name: Executor
+/// ## Description
+/// Something queries run on: a `DatabaseConnection`, which takes a connection from its pool for
+/// each query, or a `Transaction`, whose queries share one connection and only take effect once
+/// it commits. `QueryBuilder`, `Model` and the association loaders accept either.
+/// ## Example
+/// ```no_run
+/// use rustyroad::database::{DatabaseConnection, Executor, QueryBuilder};
+///
+/// async fn transfer(conn: &DatabaseConnection) -> Result<(), sqlx::Error> {
+///     let tx = conn.begin().await?;
+///     let result = async {
+///         QueryBuilder::update("accounts")
+///             .value("balance", 90)
+///             .where_eq("id", 1)
+///             .execute(&tx)
+///             .await?;
+///         QueryBuilder::update("accounts")
+///             .value("balance", 110)
+///             .where_eq("id", 2)
+///             .execute(&tx)
+///             .await
+///     }
+///     .await;
+///     tx.finish(result).await?;
+///     Ok(())
+/// }
+/// ```
+pub trait Executor: Sync {
+    fn executor(&self) -> ExecutorRef<'_>;
+
+    /// The backend the queries run on, which decides the SQL dialect.
+    fn database_type(&self) -> DatabaseType {
+        match self.executor() {
+            ExecutorRef::Connection(conn) => conn.database_type(),
+            ExecutorRef::Transaction(tx) => tx.database_type.clone(),
+        }
+    }
+
+    /// Starts a transaction. Within a transaction this sets a savepoint instead, which commits
+    /// or rolls back only what ran since.
+    fn begin(&self) -> impl Future<Output = Result<Transaction, sqlx::Error>> + Send {
+        let executor = self.executor();
+        async move {
+            match executor {
+                ExecutorRef::Connection(conn) => Transaction::begin(conn).await,
+                ExecutorRef::Transaction(tx) => tx.savepoint().await,
+            }
+        }
+    }
+}
+
+impl Executor for DatabaseConnection {
+    fn executor(&self) -> ExecutorRef<'_> {
+        ExecutorRef::Connection(self)
+    }
+}
+
+impl Executor for Transaction {
+    fn executor(&self) -> ExecutorRef<'_> {
+        ExecutorRef::Transaction(self)
+    }
+}
+
+/// The open transaction on one connection of the pool.
+pub(crate) enum TransactionConnection {
+    Pg(sqlx::Transaction<'static, Postgres>),
+    MySql(sqlx::Transaction<'static, MySql>),
+    Sqlite(sqlx::Transaction<'static, Sqlite>),
+}
+
+/// # Name: Transaction
+/// ## Description
+/// A database transaction, started with `Executor::begin`. Queries run on it see each other's
+/// writes, which only reach the database with `commit`. Dropping it without committing rolls
+/// it back. A transaction begun on a transaction is a savepoint within it.
+pub struct Transaction {
+    connection: Arc<Mutex<Option<TransactionConnection>>>,
+    database_type: DatabaseType,
+    savepoint: Option<String>,
+    savepoints: Arc<AtomicUsize>,
+}
+
+impl Transaction {
+    async fn begin(conn: &DatabaseConnection) -> Result<Transaction, sqlx::Error> {
+        let connection = match conn {
+            DatabaseConnection::Pg(pool) => TransactionConnection::Pg(pool.begin().await?),
+            DatabaseConnection::MySql(pool) => TransactionConnection::MySql(pool.begin().await?),
+            DatabaseConnection::Sqlite(pool) => TransactionConnection::Sqlite(pool.begin().await?),
+        };
+        Ok(Transaction {
+            connection: Arc::new(Mutex::new(Some(connection))),
+            database_type: conn.database_type(),
+            savepoint: None,
+            savepoints: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    async fn savepoint(&self) -> Result<Transaction, sqlx::Error> {
+        let name = format!(
+            "rustyroad_savepoint_{}",
+            self.savepoints.fetch_add(1, Ordering::Relaxed) + 1
+        );
+        self.run(&format!("SAVEPOINT {}", name)).await?;
+        Ok(Transaction {
+            connection: self.connection.clone(),
+            database_type: self.database_type.clone(),
+            savepoint: Some(name),
+            savepoints: self.savepoints.clone(),
+        })
+    }
+
+    /// The connection of the transaction, held until the guard is dropped.
+    pub(crate) async fn connection(
+        &self,
+    ) -> Result<MappedMutexGuard<'_, TransactionConnection>, sqlx::Error> {
+        MutexGuard::try_map(self.connection.lock().await, |connection| {
+            connection.as_mut()
+        })
+        .map_err(|_| finished())
+    }
+
+    async fn run(&self, sql: &str) -> Result<(), sqlx::Error> {
+        match &mut *self.connection().await? {
+            TransactionConnection::Pg(tx) => {
+                sqlx::query(sql).execute(&mut **tx).await?;
+            }
+            TransactionConnection::MySql(tx) => {
+                sqlx::query(sql).execute(&mut **tx).await?;
+            }
+            TransactionConnection::Sqlite(tx) => {
+                sqlx::query(sql).execute(&mut **tx).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes the writes of the transaction permanent.
+    pub async fn commit(self) -> Result<(), sqlx::Error> {
+        if let Some(name) = &self.savepoint {
+            return self.run(&format!("RELEASE SAVEPOINT {}", name)).await;
+        }
+        match self.connection.lock().await.take().ok_or_else(finished)? {
+            TransactionConnection::Pg(tx) => tx.commit().await,
+            TransactionConnection::MySql(tx) => tx.commit().await,
+            TransactionConnection::Sqlite(tx) => tx.commit().await,
+        }
+    }
+
+    /// Discards the writes of the transaction.
+    pub async fn rollback(self) -> Result<(), sqlx::Error> {
+        if let Some(name) = &self.savepoint {
+            return self.run(&format!("ROLLBACK TO SAVEPOINT {}", name)).await;
+        }
+        match self.connection.lock().await.take().ok_or_else(finished)? {
+            TransactionConnection::Pg(tx) => tx.rollback().await,
+            TransactionConnection::MySql(tx) => tx.rollback().await,
+            TransactionConnection::Sqlite(tx) => tx.rollback().await,
+        }
+    }
+
+    /// Commits when `result` is `Ok`, and otherwise rolls back and returns the error as it is.
+    pub async fn finish<T, E: From<sqlx::Error>>(self, result: Result<T, E>) -> Result<T, E> {
+        match result {
+            Ok(value) => {
+                self.commit().await?;
+                Ok(value)
+            }
+            Err(err) => {
+                // the error that aborted the transaction says more than a failed rollback
+                let _ = self.rollback().await;
+                Err(err)
+            }
+        }
+    }
+}
+
+fn finished() -> sqlx::Error {
+    sqlx::Error::InvalidArgument("the transaction has already finished".to_string())
+}
@@ -0,0 +1,973 @@
+use crate::database::migrations::CustomMigrationError;
+use crate::database::{
+    format_table, introspect_schema, run_statement, ColumnInfo, Database, DatabaseConnection,
+    Executor, ForeignKeyInfo, QueryResult, SchemaInfo, TableInfo, Transaction,
+    TransactionConnection,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Name of the schema manifest written next to the table files.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Rows fetched per page while exporting, and the default number of rows per INSERT on import.
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+
+/// Upper bound on bind parameters per INSERT, below the SQLite, Postgres and MySQL limits.
+const MAX_BIND_PARAMETERS: usize = 30_000;
+
+/// # Name: DataFormat
+/// ## Description
+/// File formats table data can be exported to and imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFormat {
+    Ndjson,
+    Csv,
+}
+
+impl DataFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DataFormat::Ndjson => "ndjson",
+            DataFormat::Csv => "csv",
+        }
+    }
+}
+
+impl fmt::Display for DataFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+impl FromStr for DataFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ndjson" | "jsonl" => Ok(DataFormat::Ndjson),
+            "csv" => Ok(DataFormat::Csv),
+            other => Err(format!(
+                "Unknown data format '{other}'. Supported: ndjson, csv"
+            )),
+        }
+    }
+}
+
+/// # Name: DumpTable
+/// ## Description
+/// One exported table in the manifest: its data file, row count and source columns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DumpTable {
+    pub name: String,
+    pub file: String,
+    pub rows: u64,
+    pub columns: Vec<ColumnInfo>,
+}
+
+/// # Name: DumpManifest
+/// ## Description
+/// Describes an export directory so it can be imported into any backend.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DumpManifest {
+    pub version: u32,
+    pub format: DataFormat,
+    pub source: String,
+    pub exported_at: String,
+    pub tables: Vec<DumpTable>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+}
+
+impl DumpManifest {
+    /// The exported tables and foreign keys as a `SchemaInfo`.
+    pub fn schema(&self) -> SchemaInfo {
+        SchemaInfo {
+            tables: self
+                .tables
+                .iter()
+                .map(|t| TableInfo {
+                    name: t.name.clone(),
+                    columns: t.columns.clone(),
+                })
+                .collect(),
+            foreign_keys: self.foreign_keys.clone(),
+        }
+    }
+
+    pub fn table(&self, name: &str) -> Option<&DumpTable> {
+        self.tables.iter().find(|t| t.name == name)
+    }
+}
+
+/// # Name: CellValue
+/// ## Description
+/// A value coerced to the type family of its target column, ready to be bound.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl CellValue {
+    /// Text form used for Postgres, where every parameter is bound as text and cast in SQL.
+    fn to_text(&self) -> Option<String> {
+        match self {
+            CellValue::Null => None,
+            CellValue::Bool(b) => Some(b.to_string()),
+            CellValue::Int(i) => Some(i.to_string()),
+            CellValue::Float(f) => Some(f.to_string()),
+            CellValue::Text(s) => Some(s.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeFamily {
+    Bool,
+    Integer,
+    Float,
+    /// NUMERIC and DECIMAL, kept as text so no digits are lost on the way to the server.
+    Decimal,
+    Json,
+    Text,
+}
+
+fn type_family(data_type: &str) -> TypeFamily {
+    let t = data_type.trim().to_ascii_lowercase();
+    let integer_prefixes = [
+        "bigint",
+        "smallint",
+        "tinyint",
+        "mediumint",
+        "serial",
+        "bigserial",
+    ];
+
+    if t.starts_with("bool") {
+        TypeFamily::Bool
+    } else if (t.starts_with("int") && !t.starts_with("interval"))
+        || integer_prefixes.iter().any(|p| t.starts_with(p))
+    {
+        TypeFamily::Integer
+    } else if ["real", "double", "float"].iter().any(|p| t.starts_with(p)) {
+        TypeFamily::Float
+    } else if t.starts_with("numeric") || t.starts_with("decimal") {
+        TypeFamily::Decimal
+    } else if t.contains("json") {
+        TypeFamily::Json
+    } else {
+        TypeFamily::Text
+    }
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "t" | "true" | "1" | "y" | "yes" | "on" => Some(true),
+        "f" | "false" | "0" | "n" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// # Name: coerce_value
+/// ## Description
+/// Converts an exported value to the type family of the target column, e.g. SQLite's `0`/`1`
+/// booleans become real booleans for Postgres and numeric strings become numbers.
+/// Values that cannot be converted are passed through as text for the database to judge, and so
+/// are NUMERIC and DECIMAL values, which the server parses without going through `f64`.
+/// ## Example
+/// ```
+/// use rustyroad::database::{coerce_value, CellValue};
+/// use serde_json::json;
+///
+/// assert_eq!(coerce_value(&json!(1), "boolean"), CellValue::Bool(true));
+/// assert_eq!(coerce_value(&json!("42"), "bigint"), CellValue::Int(42));
+/// ```
+pub fn coerce_value(value: &Value, data_type: &str) -> CellValue {
+    if value.is_null() {
+        return CellValue::Null;
+    }
+
+    let text = || match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    match type_family(data_type) {
+        TypeFamily::Bool => match value {
+            Value::Bool(b) => CellValue::Bool(*b),
+            Value::Number(n) => CellValue::Bool(n.as_f64().map(|f| f != 0.0).unwrap_or(true)),
+            Value::String(s) => parse_bool(s)
+                .map(CellValue::Bool)
+                .unwrap_or_else(|| CellValue::Text(s.clone())),
+            _ => CellValue::Text(text()),
+        },
+        TypeFamily::Integer => match value {
+            Value::Bool(b) => CellValue::Int(*b as i64),
+            Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+                (Some(i), _) => CellValue::Int(i),
+                (None, Some(f)) if f.fract() == 0.0 => CellValue::Int(f as i64),
+                _ => CellValue::Text(n.to_string()),
+            },
+            Value::String(s) => match (s.trim().parse::<i64>(), s.trim().parse::<f64>()) {
+                (Ok(i), _) => CellValue::Int(i),
+                (_, Ok(f)) if f.fract() == 0.0 => CellValue::Int(f as i64),
+                _ => parse_bool(s)
+                    .map(|b| CellValue::Int(b as i64))
+                    .unwrap_or_else(|| CellValue::Text(s.clone())),
+            },
+            _ => CellValue::Text(text()),
+        },
+        TypeFamily::Float => match value {
+            Value::Bool(b) => CellValue::Float(*b as i64 as f64),
+            Value::Number(n) => n
+                .as_f64()
+                .map(CellValue::Float)
+                .unwrap_or_else(|| CellValue::Text(n.to_string())),
+            Value::String(s) => s
+                .trim()
+                .parse::<f64>()
+                .map(CellValue::Float)
+                .unwrap_or_else(|_| CellValue::Text(s.clone())),
+            _ => CellValue::Text(text()),
+        },
+        TypeFamily::Decimal => match value {
+            Value::Bool(b) => CellValue::Int(*b as i64),
+            _ => CellValue::Text(text()),
+        },
+        TypeFamily::Json | TypeFamily::Text => CellValue::Text(text()),
+    }
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Column expression used when exporting. Types the generic decoder cannot read
+/// (numeric, enums, arrays, intervals, ...) are cast to text on the server.
+fn select_expression(connection: &DatabaseConnection, column: &ColumnInfo) -> String {
+    let quoted = connection.database_type().quote_identifier(&column.name);
+    let t = column.data_type.to_ascii_lowercase();
+    match connection {
+        DatabaseConnection::Pg(_) => {
+            let decodable = [
+                "smallint",
+                "integer",
+                "bigint",
+                "real",
+                "double precision",
+                "boolean",
+                "text",
+                "character varying",
+                "character",
+                "timestamp without time zone",
+                "timestamp with time zone",
+                "date",
+                "time without time zone",
+                "uuid",
+                "json",
+                "jsonb",
+                "bytea",
+            ];
+            if decodable.contains(&t.as_str()) {
+                quoted
+            } else {
+                format!("{}::text AS {}", quoted, quoted)
+            }
+        }
+        DatabaseConnection::MySql(_) => {
+            let base = t.split('(').next().unwrap_or_default().trim();
+            if ["decimal", "numeric", "enum", "set", "year", "bit"].contains(&base) {
+                format!("CAST({} AS CHAR) AS {}", quoted, quoted)
+            } else {
+                quoted
+            }
+        }
+        DatabaseConnection::Sqlite(_) => quoted,
+    }
+}
+
+fn invalid_data(message: String) -> CustomMigrationError {
+    CustomMigrationError::IoError(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+/// Encodes one CSV field. NULL is an empty unquoted field; an empty string is `""`.
+fn csv_field(value: &Value) -> String {
+    let text = match value {
+        Value::Null => return String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.is_empty() || text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+/// Parses one CSV line into fields, or returns `None` when a quoted field continues
+/// on the next line. Unquoted empty fields are NULL (`None`).
+fn parse_csv_record(line: &str) -> Option<Vec<Option<String>>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push(chars.next().unwrap());
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_quotes = true;
+                quoted = true;
+            }
+            ',' => {
+                fields.push(if field.is_empty() && !quoted {
+                    None
+                } else {
+                    Some(std::mem::take(&mut field))
+                });
+                quoted = false;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return None;
+    }
+    fields.push(if field.is_empty() && !quoted {
+        None
+    } else {
+        Some(field)
+    });
+    Some(fields)
+}
+
+/// Reads the data file of one exported table as JSON objects keyed by column name.
+struct RowReader {
+    format: DataFormat,
+    lines: io::Lines<BufReader<File>>,
+    header: Vec<String>,
+}
+
+impl RowReader {
+    fn open(path: &Path, format: DataFormat) -> Result<Self, CustomMigrationError> {
+        let mut reader = RowReader {
+            format,
+            lines: BufReader::new(File::open(path)?).lines(),
+            header: Vec::new(),
+        };
+        if format == DataFormat::Csv {
+            reader.header = reader
+                .next_csv_record()?
+                .unwrap_or_default()
+                .into_iter()
+                .map(Option::unwrap_or_default)
+                .collect();
+        }
+        Ok(reader)
+    }
+
+    fn next_csv_record(&mut self) -> Result<Option<Vec<Option<String>>>, CustomMigrationError> {
+        let mut buffer = String::new();
+        for line in self.lines.by_ref() {
+            let line = line?;
+            if !buffer.is_empty() {
+                buffer.push('\n');
+            }
+            buffer.push_str(line.strip_suffix('\r').unwrap_or(&line));
+            if let Some(fields) = parse_csv_record(&buffer) {
+                return Ok(Some(fields));
+            }
+        }
+        if buffer.is_empty() {
+            Ok(None)
+        } else {
+            Err(invalid_data("Unterminated quoted CSV field".to_string()))
+        }
+    }
+
+    fn next_row(&mut self) -> Result<Option<Map<String, Value>>, CustomMigrationError> {
+        match self.format {
+            DataFormat::Ndjson => {
+                for line in self.lines.by_ref() {
+                    let line = line?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    return match serde_json::from_str::<Value>(&line)? {
+                        Value::Object(map) => Ok(Some(map)),
+                        other => Err(invalid_data(format!("Expected a JSON object, got {other}"))),
+                    };
+                }
+                Ok(None)
+            }
+            DataFormat::Csv => Ok(self.next_csv_record()?.map(|fields| {
+                self.header
+                    .iter()
+                    .cloned()
+                    .zip(
+                        fields
+                            .into_iter()
+                            .map(|f| f.map(Value::String).unwrap_or(Value::Null)),
+                    )
+                    .collect()
+            })),
+        }
+    }
+}
+
+async fn connect() -> Result<(Database, DatabaseConnection), CustomMigrationError> {
+    let database = Database::get_database_from_rustyroad_toml()?;
+    let connection = Database::create_database_connection(&database)
+        .await
+        .map_err(CustomMigrationError::SendError)?;
+    Ok((database, connection))
+}
+
+/// # Name: export_data
+/// ## Description
+/// Writes every table (or the given `tables`) of the configured database to `dir`,
+/// one `<table>.ndjson` or `<table>.csv` file per table plus a `manifest.json`
+/// describing the columns and foreign keys. RustyRoad's own bookkeeping tables are
+/// skipped unless named explicitly.
+/// ## Arguments
+/// * `dir` - Output directory; created if missing.
+/// * `tables` - Tables to export. An empty list exports all tables.
+/// * `format` - File format of the table data.
+/// * `batch_size` - Rows fetched per query.
+pub async fn export_data(
+    dir: &Path,
+    tables: &[String],
+    format: DataFormat,
+    batch_size: usize,
+) -> Result<DumpManifest, CustomMigrationError> {
+    let (database, connection) = connect().await?;
+
+    let mut schema = introspect_schema(&connection).await?;
+    if tables.is_empty() {
+        schema = schema.without_internal_tables();
+    }
+    schema.retain_tables(tables);
+    let missing: Vec<&str> = tables
+        .iter()
+        .filter(|name| schema.table(name).is_none())
+        .map(|s| s.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(CustomMigrationError::IoError(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Table(s) not found: {}", missing.join(", ")),
+        )));
+    }
+
+    fs::create_dir_all(dir)?;
+    let batch_size = batch_size.max(1);
+    let mut manifest = DumpManifest {
+        version: 1,
+        format,
+        source: database.database_type.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        tables: Vec::new(),
+        foreign_keys: schema.foreign_keys.clone(),
+    };
+
+    for table in &schema.tables {
+        let file_name = format!("{}.{}", table.name, format.extension());
+        let mut writer = BufWriter::new(File::create(dir.join(&file_name))?);
+        if format == DataFormat::Csv {
+            let header: Vec<Value> = table
+                .columns
+                .iter()
+                .map(|c| Value::String(c.name.clone()))
+                .collect();
+            writeln!(
+                writer,
+                "{}",
+                header.iter().map(csv_field).collect::<Vec<_>>().join(",")
+            )?;
+        }
+
+        let columns: Vec<String> = table
+            .columns
+            .iter()
+            .map(|c| select_expression(&connection, c))
+            .collect();
+        let primary_key: Vec<String> = table
+            .primary_key()
+            .iter()
+            .map(|c| connection.database_type().quote_identifier(c))
+            .collect();
+        let base = format!(
+            "SELECT {} FROM {}",
+            columns.join(", "),
+            connection.database_type().quote_identifier(&table.name)
+        );
+
+        let mut rows_written: u64 = 0;
+        loop {
+            // Without a primary key there is no stable order to page by, so read everything at once
+            let sql = if primary_key.is_empty() {
+                base.clone()
+            } else {
+                format!(
+                    "{} ORDER BY {} LIMIT {} OFFSET {}",
+                    base,
+                    primary_key.join(", "),
+                    batch_size,
+                    rows_written
+                )
+            };
+            let result = run_statement(&connection, &sql).await?;
+            let fetched = result.rows.len();
+
+            for row in result.rows {
+                match format {
+                    DataFormat::Ndjson => {
+                        let object: Map<String, Value> = table
+                            .columns
+                            .iter()
+                            .map(|c| c.name.clone())
+                            .zip(row)
+                            .collect();
+                        writeln!(writer, "{}", Value::Object(object))?;
+                    }
+                    DataFormat::Csv => {
+                        writeln!(
+                            writer,
+                            "{}",
+                            row.iter().map(csv_field).collect::<Vec<_>>().join(",")
+                        )?;
+                    }
+                }
+            }
+            rows_written += fetched as u64;
+
+            if primary_key.is_empty() || fetched < batch_size {
+                break;
+            }
+        }
+        writer.flush()?;
+
+        println!(
+            "Exported {} ({} rows) -> {}",
+            table.name,
+            rows_written,
+            dir.join(&file_name).display()
+        );
+        manifest.tables.push(DumpTable {
+            name: table.name.clone(),
+            file: file_name,
+            rows: rows_written,
+            columns: table.columns.clone(),
+        });
+    }
+
+    fs::write(
+        dir.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    println!("Wrote {}", dir.join(MANIFEST_FILE).display());
+
+    Ok(manifest)
+}
+
+/// Builds a multi-row INSERT for `rows` rows of `columns` in the dialect of `connection`.
+/// Postgres parameters are bound as text and cast to the column type in SQL.
+fn insert_sql(
+    connection: &DatabaseConnection,
+    table: &str,
+    columns: &[&ColumnInfo],
+    rows: usize,
+) -> String {
+    let column_list: Vec<String> = columns
+        .iter()
+        .map(|c| connection.database_type().quote_identifier(&c.name))
+        .collect();
+
+    let mut parameter = 0;
+    let values: Vec<String> = (0..rows)
+        .map(|_| {
+            let placeholders: Vec<String> = columns
+                .iter()
+                .map(|c| match connection {
+                    DatabaseConnection::Pg(_) => {
+                        parameter += 1;
+                        let upper = c.data_type.to_ascii_uppercase();
+                        if upper == "USER-DEFINED" || upper == "ARRAY" {
+                            format!("${}", parameter)
+                        } else {
+                            format!("CAST(${} AS {})", parameter, c.data_type)
+                        }
+                    }
+                    _ => "?".to_string(),
+                })
+                .collect();
+            format!("({})", placeholders.join(", "))
+        })
+        .collect();
+
+    format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        connection.database_type().quote_identifier(table),
+        column_list.join(", "),
+        values.join(", ")
+    )
+}
+
+async fn insert_batch(
+    tx: &Transaction,
+    sql: &str,
+    rows: &[Vec<CellValue>],
+) -> Result<u64, sqlx::Error> {
+    let cells = rows.iter().flatten();
+    let done = match &mut *tx.connection().await? {
+        TransactionConnection::Pg(tx) => {
+            let mut query = sqlx::query(sql);
+            for cell in cells {
+                query = query.bind(cell.to_text());
+            }
+            query.execute(&mut **tx).await?.rows_affected()
+        }
+        TransactionConnection::MySql(tx) => {
+            let mut query = sqlx::query(sql);
+            for cell in cells {
+                query = match cell {
+                    CellValue::Null => query.bind(None::<String>),
+                    CellValue::Bool(b) => query.bind(*b),
+                    CellValue::Int(i) => query.bind(*i),
+                    CellValue::Float(f) => query.bind(*f),
+                    CellValue::Text(s) => query.bind(s.clone()),
+                };
+            }
+            query.execute(&mut **tx).await?.rows_affected()
+        }
+        TransactionConnection::Sqlite(tx) => {
+            let mut query = sqlx::query(sql);
+            for cell in cells {
+                query = match cell {
+                    CellValue::Null => query.bind(None::<String>),
+                    CellValue::Bool(b) => query.bind(*b),
+                    CellValue::Int(i) => query.bind(*i),
+                    CellValue::Float(f) => query.bind(*f),
+                    CellValue::Text(s) => query.bind(s.clone()),
+                };
+            }
+            query.execute(&mut **tx).await?.rows_affected()
+        }
+    };
+    Ok(done)
+}
+
+/// Moves the sequence / auto-increment counter of `table` past its largest id, so rows
+/// inserted after the import do not collide with imported ids.
+async fn reset_sequence(
+    connection: &DatabaseConnection,
+    table: &TableInfo,
+) -> Result<(), sqlx::Error> {
+    let primary_key = table.primary_key();
+    let [key] = primary_key.as_slice() else {
+        return Ok(());
+    };
+    let is_integer = table
+        .column(key)
+        .map(|c| type_family(&c.data_type) == TypeFamily::Integer)
+        .unwrap_or(false);
+    if !is_integer {
+        return Ok(());
+    }
+
+    let quoted_table = connection.database_type().quote_identifier(&table.name);
+    let quoted_key = connection.database_type().quote_identifier(key);
+    match connection {
+        DatabaseConnection::Pg(_) => {
+            let sql = format!(
+                "SELECT setval(pg_get_serial_sequence({}, {}), COALESCE((SELECT MAX({}) FROM {}), 0) + 1, false)",
+                quote_literal(&quoted_table),
+                quote_literal(key),
+                quoted_key,
+                quoted_table
+            );
+            run_statement(connection, &sql).await?;
+        }
+        DatabaseConnection::MySql(_) => {
+            let sql = format!(
+                "SELECT CAST(COALESCE(MAX({}), 0) + 1 AS SIGNED) AS next_id FROM {}",
+                quoted_key, quoted_table
+            );
+            let result = run_statement(connection, &sql).await?;
+            let next_id = result
+                .rows
+                .first()
+                .and_then(|row| row.first())
+                .and_then(Value::as_i64)
+                .unwrap_or(1);
+            run_statement(
+                connection,
+                &format!("ALTER TABLE {} AUTO_INCREMENT = {}", quoted_table, next_id),
+            )
+            .await?;
+        }
+        DatabaseConnection::Sqlite(_) => {
+            // INTEGER PRIMARY KEY picks max(rowid) + 1 by itself; only AUTOINCREMENT
+            // tables keep a counter in sqlite_sequence, which may not exist at all.
+            let sql = format!(
+                "UPDATE sqlite_sequence SET seq = (SELECT MAX({}) FROM {}) WHERE name = {}",
+                quoted_key,
+                quoted_table,
+                quote_literal(&table.name)
+            );
+            let _ = run_statement(connection, &sql).await;
+        }
+    }
+    Ok(())
+}
+
+/// # Name: import_data
+/// ## Description
+/// Loads an export written by `export_data` into the configured database, which may be a
+/// different backend than the one exported from. Tables must already exist (run the
+/// migrations first). Tables are loaded in foreign-key dependency order, in batched
+/// multi-row INSERTs, with each value coerced to the target column's type. Afterwards
+/// sequences and auto-increment counters are moved past the imported ids.
+/// ## Arguments
+/// * `dir` - Directory containing `manifest.json` and the table files.
+/// * `tables` - Tables to import. An empty list imports every table in the manifest.
+/// * `batch_size` - Maximum rows per INSERT statement.
+/// ## Returns
+/// * The number of rows written per table, in import order.
+pub async fn import_data(
+    dir: &Path,
+    tables: &[String],
+    batch_size: usize,
+) -> Result<Vec<(String, u64)>, CustomMigrationError> {
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let manifest: DumpManifest =
+        serde_json::from_str(&fs::read_to_string(&manifest_path).map_err(|e| {
+            io::Error::new(e.kind(), format!("{}: {}", manifest_path.display(), e))
+        })?)?;
+
+    let mut dump_schema = manifest.schema();
+    dump_schema.retain_tables(tables);
+    let missing: Vec<&str> = tables
+        .iter()
+        .filter(|name| dump_schema.table(name).is_none())
+        .map(|s| s.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(CustomMigrationError::IoError(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Table(s) not in {}: {}", MANIFEST_FILE, missing.join(", ")),
+        )));
+    }
+
+    let (_, connection) = connect().await?;
+    let target = introspect_schema(&connection).await?;
+    let order = dump_schema.dependency_order();
+
+    let not_migrated: Vec<&str> = order
+        .iter()
+        .filter(|name| target.table(name).is_none())
+        .map(|s| s.as_str())
+        .collect();
+    if !not_migrated.is_empty() {
+        return Err(CustomMigrationError::IoError(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "Table(s) missing in the target database: {}. Run the migrations first.",
+                not_migrated.join(", ")
+            ),
+        )));
+    }
+
+    let mut summary = Vec::new();
+    for name in &order {
+        let dump_table = manifest.table(name).expect("table comes from the manifest");
+        let target_table = target.table(name).expect("checked above");
+
+        // Only columns present on both sides are copied, in the target's column order
+        let columns: Vec<&ColumnInfo> = target_table
+            .columns
+            .iter()
+            .filter(|c| dump_table.columns.iter().any(|d| d.name == c.name))
+            .collect();
+        let skipped: Vec<&str> = dump_table
+            .columns
+            .iter()
+            .filter(|d| target_table.column(&d.name).is_none())
+            .map(|d| d.name.as_str())
+            .collect();
+        if !skipped.is_empty() {
+            println!(
+                "WARNING: {} has no column(s) {}; they are skipped",
+                name,
+                skipped.join(", ")
+            );
+        }
+        if columns.is_empty() {
+            summary.push((name.clone(), 0));
+            continue;
+        }
+
+        let rows_per_batch = batch_size
+            .max(1)
+            .min((MAX_BIND_PARAMETERS / columns.len()).max(1));
+        let mut reader = RowReader::open(&dir.join(&dump_table.file), manifest.format)?;
+
+        // A table is imported completely or not at all
+        let tx = connection.begin().await?;
+        let result: Result<u64, CustomMigrationError> = async {
+            let mut written: u64 = 0;
+            let mut batch: Vec<Vec<CellValue>> = Vec::with_capacity(rows_per_batch);
+            loop {
+                let row = reader.next_row()?;
+                if let Some(row) = &row {
+                    batch.push(
+                        columns
+                            .iter()
+                            .map(|c| {
+                                coerce_value(row.get(&c.name).unwrap_or(&Value::Null), &c.data_type)
+                            })
+                            .collect(),
+                    );
+                }
+                if !batch.is_empty() && (row.is_none() || batch.len() == rows_per_batch) {
+                    let sql = insert_sql(&connection, name, &columns, batch.len());
+                    written += insert_batch(&tx, &sql, &batch)
+                        .await
+                        .map_err(|e| invalid_data(format!("Importing {} failed: {}", name, e)))?;
+                    batch.clear();
+                }
+                if row.is_none() {
+                    return Ok(written);
+                }
+            }
+        }
+        .await;
+        let written = tx.finish(result).await?;
+
+        if let Err(e) = reset_sequence(&connection, target_table).await {
+            println!(
+                "WARNING: could not reset the id sequence of {}: {}",
+                name, e
+            );
+        }
+        summary.push((name.clone(), written));
+    }
+
+    Ok(summary)
+}
+
+/// # Name: print_import_summary
+/// ## Description
+/// Prints the rows written per table by `import_data`.
+pub fn print_import_summary(summary: &[(String, u64)]) {
+    let result = QueryResult {
+        columns: vec!["Table".to_string(), "Rows".to_string()],
+        rows: summary
+            .iter()
+            .map(|(table, rows)| vec![Value::String(table.clone()), Value::from(*rows)])
+            .collect(),
+        rows_affected: None,
+    };
+    print!("{}", format_table(&result));
+    println!(
+        "Imported {} rows into {} tables",
+        summary.iter().map(|(_, rows)| rows).sum::<u64>(),
+        summary.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_coerce_value() {
+        assert_eq!(coerce_value(&json!(0), "boolean"), CellValue::Bool(false));
+        assert_eq!(coerce_value(&json!("t"), "boolean"), CellValue::Bool(true));
+        assert_eq!(coerce_value(&json!(true), "tinyint"), CellValue::Int(1));
+        assert_eq!(coerce_value(&json!("7"), "INTEGER"), CellValue::Int(7));
+        assert_eq!(coerce_value(&json!(3.0), "bigint"), CellValue::Int(3));
+        assert_eq!(coerce_value(&json!("1.5"), "real"), CellValue::Float(1.5));
+        assert_eq!(
+            coerce_value(&json!("12345678901234567890.1234"), "numeric(30,10)"),
+            CellValue::Text("12345678901234567890.1234".to_string())
+        );
+        assert_eq!(
+            coerce_value(&json!(2.5), "DECIMAL(10,2)"),
+            CellValue::Text("2.5".to_string())
+        );
+        assert_eq!(
+            coerce_value(&json!({"a": 1}), "jsonb"),
+            CellValue::Text("{\"a\":1}".to_string())
+        );
+        assert_eq!(
+            coerce_value(&json!(12), "text"),
+            CellValue::Text("12".to_string())
+        );
+        assert_eq!(coerce_value(&Value::Null, "integer"), CellValue::Null);
+        assert_eq!(
+            coerce_value(&json!("1 day"), "interval"),
+            CellValue::Text("1 day".to_string())
+        );
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let row = [
+            json!(1),
+            json!("plain"),
+            json!("with, comma"),
+            json!("say \"hi\"\nbye"),
+            json!(""),
+            Value::Null,
+        ];
+        let line = row.iter().map(csv_field).collect::<Vec<_>>().join(",");
+
+        assert_eq!(
+            line,
+            "1,plain,\"with, comma\",\"say \"\"hi\"\"\nbye\",\"\","
+        );
+        assert_eq!(parse_csv_record("1,\"open"), None);
+        assert_eq!(
+            parse_csv_record(&line).unwrap(),
+            vec![
+                Some("1".to_string()),
+                Some("plain".to_string()),
+                Some("with, comma".to_string()),
+                Some("say \"hi\"\nbye".to_string()),
+                Some(String::new()),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_data_format_from_str() {
+        assert_eq!("NDJSON".parse(), Ok(DataFormat::Ndjson));
+        assert_eq!("csv".parse(), Ok(DataFormat::Csv));
+        assert!("xml".parse::<DataFormat>().is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::types::Uuid;
+use sqlx::{Arguments, MySql, Postgres, Sqlite};
+
+/// # Name: SqlValue
+/// ## Description
+/// A value bound to a query parameter, typed so it binds the same way on PostgreSQL, MySQL
+/// and SQLite. A `None` keeps its type, since PostgreSQL rejects a NULL parameter of the
+/// wrong type. Anything a model field holds converts into one with `SqlValue::from`.
+/// ## Example
+/// ```
+/// use rustyroad::database::SqlValue;
+///
+/// assert_eq!(SqlValue::from(7), SqlValue::Int(Some(7)));
+/// assert!(SqlValue::from(None::<String>).is_null());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Bool(Option<bool>),
+    Int(Option<i32>),
+    BigInt(Option<i64>),
+    Float(Option<f64>),
+    Decimal(Option<Decimal>),
+    Text(Option<String>),
+    Bytes(Option<Vec<u8>>),
+    Date(Option<NaiveDate>),
+    DateTime(Option<NaiveDateTime>),
+    Timestamp(Option<DateTime<Utc>>),
+    Time(Option<NaiveTime>),
+    Json(Option<serde_json::Value>),
+    Uuid(Option<Uuid>),
+}
+
+impl SqlValue {
+    pub fn is_null(&self) -> bool {
+        match self {
+            SqlValue::Bool(v) => v.is_none(),
+            SqlValue::Int(v) => v.is_none(),
+            SqlValue::BigInt(v) => v.is_none(),
+            SqlValue::Float(v) => v.is_none(),
+            SqlValue::Decimal(v) => v.is_none(),
+            SqlValue::Text(v) => v.is_none(),
+            SqlValue::Bytes(v) => v.is_none(),
+            SqlValue::Date(v) => v.is_none(),
+            SqlValue::DateTime(v) => v.is_none(),
+            SqlValue::Timestamp(v) => v.is_none(),
+            SqlValue::Time(v) => v.is_none(),
+            SqlValue::Json(v) => v.is_none(),
+            SqlValue::Uuid(v) => v.is_none(),
+        }
+    }
+}
+
+macro_rules! sql_value_from {
+    ($variant:ident, $target:ty: $($source:ty),+) => {
+        $(
+            impl From<$source> for SqlValue {
+                fn from(value: $source) -> Self {
+                    SqlValue::$variant(Some(<$target>::from(value)))
+                }
+            }
+
+            impl From<Option<$source>> for SqlValue {
+                fn from(value: Option<$source>) -> Self {
+                    SqlValue::$variant(value.map(<$target>::from))
+                }
+            }
+        )+
+    };
+}
+
+sql_value_from!(Bool, bool: bool);
+sql_value_from!(Int, i32: i8, i16, i32, u8, u16);
+sql_value_from!(BigInt, i64: i64, u32);
+sql_value_from!(Float, f64: f32, f64);
+sql_value_from!(Decimal, Decimal: Decimal);
+sql_value_from!(Text, String: String, &str);
+sql_value_from!(Bytes, Vec<u8>: Vec<u8>);
+sql_value_from!(Date, NaiveDate: NaiveDate);
+sql_value_from!(DateTime, NaiveDateTime: NaiveDateTime);
+sql_value_from!(Timestamp, DateTime<Utc>: DateTime<Utc>);
+sql_value_from!(Time, NaiveTime: NaiveTime);
+sql_value_from!(Json, serde_json::Value: serde_json::Value);
+sql_value_from!(Uuid, Uuid: Uuid);
+
+/// # Name: BindValues
+/// ## Description
+/// A database whose query arguments can be built from `SqlValue`s, in parameter order.
+/// Implemented for `Postgres`, `MySql` and `Sqlite`.
+pub trait BindValues: sqlx::Database {
+    fn arguments<'q>(values: Vec<SqlValue>) -> Result<Self::Arguments<'q>, sqlx::Error>;
+}
+
+macro_rules! bind_values {
+    ($($database:ty: $decimal:expr),+) => {
+        $(
+            impl BindValues for $database {
+                fn arguments<'q>(values: Vec<SqlValue>) -> Result<Self::Arguments<'q>, sqlx::Error> {
+                    let mut arguments = Self::Arguments::default();
+                    for value in values {
+                        match value {
+                            SqlValue::Bool(v) => arguments.add(v),
+                            SqlValue::Int(v) => arguments.add(v),
+                            SqlValue::BigInt(v) => arguments.add(v),
+                            SqlValue::Float(v) => arguments.add(v),
+                            SqlValue::Decimal(v) => arguments.add($decimal(v)),
+                            SqlValue::Text(v) => arguments.add(v),
+                            SqlValue::Bytes(v) => arguments.add(v),
+                            SqlValue::Date(v) => arguments.add(v),
+                            SqlValue::DateTime(v) => arguments.add(v),
+                            SqlValue::Timestamp(v) => arguments.add(v),
+                            SqlValue::Time(v) => arguments.add(v),
+                            SqlValue::Json(v) => arguments.add(v),
+                            SqlValue::Uuid(v) => arguments.add(v),
+                        }
+                        .map_err(sqlx::Error::Encode)?;
+                    }
+                    Ok(arguments)
+                }
+            }
+        )+
+    };
+}
+
+// sqlx has no DECIMAL support for SQLite, whose NUMERIC columns convert the text themselves
+bind_values!(
+    Postgres: std::convert::identity,
+    MySql: std::convert::identity,
+    Sqlite: |v: Option<Decimal>| v.map(|v| v.to_string())
+);
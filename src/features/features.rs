@@ -1,14 +1,20 @@
 use crate::features::implementation::GrapesJs;
+use crate::generators::{Generator, GeneratorOptions};
 use color_eyre::eyre::Result;
 use eyre::Error;
 
-pub async fn add_feature(feature_name: String) -> Result<(), Error> {
+/// Adds a feature's files through the generator runtime, so `options` decides whether they are
+/// only previewed and what happens to files that already exist.
+pub async fn add_feature(feature_name: String, options: GeneratorOptions) -> Result<(), Error> {
     match feature_name.as_str() {
         "grapesjs" => {
             let mut grapesjs = GrapesJs::new();
 
-            match grapesjs.add_page().await {
+            match Generator::run_async(options, grapesjs.add_page()).await {
+                Ok(_) if options.pretend => {}
                 Ok(_) => {
+                    // The page table's migration can only run once its files are written
+                    grapesjs.run_page_migration().await?;
                     println!("Successfully added grapesjs");
                 }
                 Err(e) => {
@@ -1,5 +1,5 @@
 use crate::features::write_to_get_page_by_id;
-use crate::generators::create_file;
+use crate::generators::{create_file, Generator};
 use crate::helpers::helpers::{
     add_or_update_import, determine_controller_path, prompt_to_create_controller,
 };
@@ -10,78 +10,49 @@ use crate::writers::{
 };
 use color_eyre::owo_colors::OwoColorize;
 use eyre::Error;
-use std::fs;
-use std::fs::{read_to_string, OpenOptions};
-use std::io::Write;
-use std::path::PathBuf;
 
 pub fn write_to_get_page_details_controller() -> Result<(), Error> {
     let contents = r#"
-    #[get("/page/{id}/details")]
-pub async fn get_page_details(tmpl: Data<Tera>, user: Option<Identity>, id: Path<i32> ) -> HttpResponse {
+#[get("/page/{id}/details")]
+pub async fn get_page_details(
+    tmpl: Data<Tera>,
+    user: Option<Identity>,
+    id: Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    // Signed out visitors are redirected to /login by the ErrorPages middleware
+    let user = user.ok_or(AppError::Unauthorized)?;
+    let username = user
+        .id()
+        .map_err(|e| AppError::Internal(format!("Failed to read the session: {}", e)))?;
+    let page = Page::get_page_by_id(id.into_inner()).await?;
+
     let mut context = Context::new();
     context.insert("title", "page");
     context.insert("controller_name", "page");
+    context.insert("username", &username);
+    context.insert("page", &page);
+    context.insert("html_content", &page.html_content);
+    let rendered = tmpl.render(
+        "layouts/authenticated_page/page/page_details.html.tera",
+        &context,
+    )?;
 
-    let rendered = if let Some(user) = user {
-        context.insert("username", &user.id().unwrap());
-
-        let page = Page::get_page_by_id(id.into_inner()).await;
-
-        match page {
-            Ok(page) => {
-                context.insert("page", &page);
-                context.insert("html_content", &page.html_content);
-                tmpl.render(
-                    "layouts/authenticated_page/page/page_details.html.tera",
-                    &context,
-                )
-                .unwrap()
-            }
-            Err(e) => {
-                let mut context = Context::new();
-                context.insert("error", &e.to_string());
-                let page = Page::new();
-                context.insert("page", &page);
-                context.insert("html_content", &page.html_content);
-                context.insert("title", "Create Page");
-                tmpl.render(
-                    "layouts/authenticated_page/page/page_details.html.tera",
-                    &context,
-                )
-                .unwrap()
-            }
-        }
-    } else {
-        // redirect to login page
-        let mut context = Context::new();
-        context.insert("title", "Login");
-        context.insert("route_name", "login");
-        context.insert("error", "You must be logged in to create a new page.");
-        tmpl.render(
-            "pages/login.html.tera",
-            &context,
-        )
-        .unwrap()
-    };
-
-    HttpResponse::Ok()
+    Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
-        .body(rendered)
-
+        .body(rendered))
 }
 "#.to_string();
     // Determine the controller file path
     let path = determine_controller_path("page");
 
     // Ensure the controller file exists, or create it
-    let file_exists = PathBuf::from(&path).exists();
+    let file_exists = Generator::exists(&path);
     if !file_exists {
         prompt_to_create_controller(&path).expect("Error prompting to create controller");
     }
     // Read and update the file contents
     let mut file_contents =
-        fs::read_to_string(&path).expect("Couldn't read get_page_details controller");
+        Generator::read_to_string(&path).expect("Couldn't read get_page_details controller");
 
     file_contents = add_or_update_import(&file_contents, "actix_web", "get");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Path");
@@ -91,21 +62,14 @@ pub async fn get_page_details(tmpl: Data<Tera>, user: Option<Identity>, id: Path
     file_contents = add_or_update_import(&file_contents, "models", "Page");
     file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Data");
+    file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
     // Add the new controller content to the file
     file_contents.push_str("\n\n");
     file_contents.push_str(&contents);
 
     // Write to file
-    let mut file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open(&path)
-        .unwrap();
-    // Write the updated contents to the file
-    writeln!(file, "{}", file_contents)?;
-
-    file.flush()?;
+    Generator::update_file(&path, &format!("{}\n", file_contents))?;
 
     add_new_controller_to_main_rs(None, Some("page"), "get_page_details")?;
 
@@ -116,35 +80,29 @@ pub fn write_to_get_page_by_slug_controller() -> Result<(), Error> {
     let contents = r#"
 
 #[get("/page/{slug}")]
-pub async fn get_page_by_slug(tmpl: Data<Tera>, slug: Path<String>, user: Option<Identity>) -> HttpResponse {
-    let result = Page::get_page_by_slug(slug.into_inner()).await;
-    match result {
-        Ok(page) => {
-            let mut context = Context::new();
-            if let Some(_user) = user {
-                context.insert("username", &_user.id().unwrap());
-            }
-            context.insert("title", "Create Page");
-            context.insert("route_name", "create_page");
-            context.insert("html_content", &page.html_content);
-            context.insert("page_id", &page.id.unwrap());
-            let s = tmpl.render("pages/page.html.tera", &context).unwrap();
-            HttpResponse::Ok()
-                .content_type("text/html; charset=utf-8")
-                .body(s)
-        }
-        Err(e) => {
-            let mut context = Context::new();
-            context.insert("error", &e.to_string());
-            if let Some(_user) = user {
-                context.insert("username", &_user.id().unwrap());
-            }
-            let s = tmpl.render("pages/404.html.tera", &context).unwrap();
-            HttpResponse::Ok()
-                .content_type("text/html; charset=utf-8")
-                .body(s)
-        }
+pub async fn get_page_by_slug(
+    tmpl: Data<Tera>,
+    slug: Path<String>,
+    user: Option<Identity>,
+) -> Result<HttpResponse, AppError> {
+    let page = Page::get_page_by_slug(slug.into_inner()).await?;
+
+    let mut context = Context::new();
+    if let Some(user) = user {
+        let username = user
+            .id()
+            .map_err(|e| AppError::Internal(format!("Failed to read the session: {}", e)))?;
+        context.insert("username", &username);
     }
+    context.insert("title", "Create Page");
+    context.insert("route_name", "create_page");
+    context.insert("html_content", &page.html_content);
+    context.insert("page_id", &page.id);
+    let rendered = tmpl.render("pages/page.html.tera", &context)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(rendered))
 }
 "#.to_string();
 
@@ -152,14 +110,14 @@ pub async fn get_page_by_slug(tmpl: Data<Tera>, slug: Path<String>, user: Option
     let path = determine_controller_path("page");
 
     // Ensure the controller file exists, or create it
-    let file_exists = PathBuf::from(&path).exists();
+    let file_exists = Generator::exists(&path);
     if !file_exists {
         prompt_to_create_controller(&path).expect("Error prompting to create controller");
     }
 
     // Update imports
     let mut file_contents =
-        read_to_string(&path).expect("Couldn't read get_page_by_slug controller");
+        Generator::read_to_string(&path).expect("Couldn't read get_page_by_slug controller");
     file_contents = add_or_update_import(&file_contents, "actix_web", "get");
     file_contents = add_or_update_import(&file_contents, "actix_web", "get");
     file_contents = add_or_update_import(&file_contents, "tera", "Tera");
@@ -169,20 +127,13 @@ pub async fn get_page_by_slug(tmpl: Data<Tera>, slug: Path<String>, user: Option
     file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Data");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Path");
+    file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
     // Add the new controller content to the file
     file_contents.push_str("\n\n");
     file_contents.push_str(&contents);
 
-    let mut file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open(&path)
-        .unwrap();
-    // Write the updated contents to the file
-    writeln!(file, "{}", file_contents)?;
-
-    file.flush()?;
+    Generator::update_file(&path, &format!("{}\n", file_contents))?;
 
     add_new_controller_to_main_rs(None, Some("page"), "get_page_by_slug")?;
     Ok(())
@@ -191,33 +142,27 @@ pub async fn get_page_by_slug(tmpl: Data<Tera>, slug: Path<String>, user: Option
 pub fn delete_page_controller() -> Result<(), Error> {
     let contents = r#"
 #[get("/page/{id}/delete")]
-pub async fn delete_page(tmpl: Data<Tera>, user: Option<Identity>, id: Path<i32>) -> HttpResponse {
-    let result = Page::delete_page(id.into_inner()).await;
-    match result {
-        Ok(_) => {
-            let mut context = Context::new();
-            if let Some(_user) = user {
-                context.insert("username", &_user.id().unwrap());
-            }
-            context.insert("title", "Create Page");
-            context.insert("route_name", "create_page");
-            let s = tmpl.render("pages/page.html.tera", &context).unwrap();
-            HttpResponse::Ok()
-                .content_type("text/html; charset=utf-8")
-                .body(s)
-        }
-        Err(e) => {
-            let mut context = Context::new();
-            context.insert("error", &e.to_string());
-            if let Some(_user) = user {
-                context.insert("username", &_user.id().unwrap());
-            }
-            let s = tmpl.render("pages/404.html.tera", &context).unwrap();
-            HttpResponse::Ok()
-                .content_type("text/html; charset=utf-8")
-                .body(s)
-        }
+pub async fn delete_page(
+    tmpl: Data<Tera>,
+    user: Option<Identity>,
+    id: Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    Page::delete_page(id.into_inner()).await?;
+
+    let mut context = Context::new();
+    if let Some(user) = user {
+        let username = user
+            .id()
+            .map_err(|e| AppError::Internal(format!("Failed to read the session: {}", e)))?;
+        context.insert("username", &username);
     }
+    context.insert("title", "Create Page");
+    context.insert("route_name", "create_page");
+    let rendered = tmpl.render("pages/page.html.tera", &context)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(rendered))
 }
 
 "#
@@ -226,7 +171,7 @@ pub async fn delete_page(tmpl: Data<Tera>, user: Option<Identity>, id: Path<i32>
     let path = determine_controller_path("page");
 
     let mut file_contents =
-        fs::read_to_string(&path).expect("Couldn't read delete_page controller");
+        Generator::read_to_string(&path).expect("Couldn't read delete_page controller");
 
     // Update imports
     file_contents = add_or_update_import(&file_contents, "actix_web", "get");
@@ -237,6 +182,7 @@ pub async fn delete_page(tmpl: Data<Tera>, user: Option<Identity>, id: Path<i32>
     file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Data");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Path");
+    file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
     println!("import contents {}", file_contents.red());
 
@@ -245,15 +191,7 @@ pub async fn delete_page(tmpl: Data<Tera>, user: Option<Identity>, id: Path<i32>
     file_contents.push_str(&contents);
 
     // Write to file
-    let mut file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open(&path)
-        .unwrap();
-    // Write the updated contents to the file
-    writeln!(file, "{}", file_contents)?;
-
-    file.flush()?;
+    Generator::update_file(&path, &format!("{}\n", file_contents))?;
 
     Ok(())
 }
@@ -271,36 +209,32 @@ use std::{fs, io::Write, path::PathBuf};
 use actix_multipart::Multipart;
 use image::imageops::FilterType;
 use mime::{Mime, IMAGE_GIF, IMAGE_JPEG, IMAGE_PNG};
+use rustyroad::model::ValidationErrors;
+use rustyroad::web::AppError;
+
+/// A 422 with `message` for the `file` field.
+fn invalid_file(message: &str) -> AppError {
+    let mut errors = ValidationErrors::new();
+    errors.add("file", message);
+    AppError::Validation(errors)
+}
+
 #[post("/image")]
-async fn upload_image(mut payload: Multipart, req: HttpRequest) -> HttpResponse {
+async fn upload_image(mut payload: Multipart, req: HttpRequest) -> Result<HttpResponse, AppError> {
     let legal_filetypes: [Mime; 3] = [IMAGE_PNG, IMAGE_JPEG, IMAGE_GIF];
     let image_directory = PathBuf::from("static/images");
 
     // ensure image directory exists
-    if !image_directory.exists() {
-        if let Err(_) = fs::create_dir_all(&image_directory) {
-            return HttpResponse::InternalServerError().json({
-                json!({
-                    "error": "Failed to create image directory.",
-                    "status": 500
-                })
-            });
-        }
-    }
+    fs::create_dir_all(&image_directory)
+        .map_err(|e| AppError::Internal(format!("Failed to create image directory: {}", e)))?;
 
     let mut files_info = Vec::new();
 
     while let Ok(Some(mut field)) = payload.try_next().await {
-        let content_type = field.content_type().clone();
-
         // ensure file is an image
-        if content_type.is_none() || !legal_filetypes.contains(&content_type.unwrap()) {
-            return HttpResponse::BadRequest().json({
-                json!({
-                    "error": "File must be an image.",
-                    "status": 400
-                })
-            });
+        match field.content_type() {
+            Some(content_type) if legal_filetypes.contains(content_type) => {}
+            _ => return Err(invalid_file("must be an image")),
         }
 
         let filename = field
@@ -315,48 +249,22 @@ async fn upload_image(mut payload: Multipart, req: HttpRequest) -> HttpResponse
         let filepath = image_directory.join(&sanitized_filename);
 
         // create a path to save the file
-        let mut file = match fs::File::create(&filepath) {
-            Ok(file) => file,
-            Err(_) => return HttpResponse::InternalServerError().json({
-                json!({
-                    "error": "Failed to create file.",
-                    "status": 500
-                })
-            })
-        };
+        let mut file = fs::File::create(&filepath)
+            .map_err(|e| AppError::Internal(format!("Failed to create file: {}", e)))?;
 
         // copy the content of file into the file variable
         while let Ok(Some(chunk)) = field.try_next().await {
-            if let Err(_) = file.write_all(&chunk) {
-                return HttpResponse::InternalServerError().json({
-                    json!({
-                        "error": "Failed to write file.",
-                        "status": 500
-                    })
-                });
-            }
+            file.write_all(&chunk)
+                .map_err(|e| AppError::Internal(format!("Failed to write file: {}", e)))?;
         }
 
         // create a thumbnail of the image
-        match image::open(&filepath) {
-            Ok(image) => {
-                let thumbnail = image.thumbnail(100, 100);
-                if let Err(_) = thumbnail.save(filepath.with_extension("thumb.jpg")) {
-                    return HttpResponse::InternalServerError().json({
-                        json!({
-                            "error": "Failed to save thumbnail.",
-                            "status": 500
-                        })
-                    });
-                }
-            }
-            Err(_) => return HttpResponse::InternalServerError().json({
-                json!({
-                    "error": "Failed to open image.",
-                    "status": 500
-                })
-            }),
-        };
+        let image = image::open(&filepath)
+            .map_err(|e| AppError::Internal(format!("Failed to open image: {}", e)))?;
+        image
+            .thumbnail(100, 100)
+            .save(filepath.with_extension("thumb.jpg"))
+            .map_err(|e| AppError::Internal(format!("Failed to save thumbnail: {}", e)))?;
 
         files_info.push(json!({
             "filename": sanitized_filename.as_str(),
@@ -375,22 +283,17 @@ async fn upload_image(mut payload: Multipart, req: HttpRequest) -> HttpResponse
 
     // Check if any files were uploaded
     if files_info.is_empty() {
-        return HttpResponse::BadRequest().json({
-            json!({
-                "error": "No file uploaded.",
-                "status": 400
-            })
-        });
+        return Err(invalid_file("can't be blank"));
     }
 
     // On successful upload of multiple files, return JSON response
-    HttpResponse::Ok().json({
+    Ok(HttpResponse::Ok().json({
         json!({
             "status": 200,
             "message": "Files uploaded successfully.",
             "files": files_info
         })
-    })
+    }))
 }
 "#
     .to_string();
@@ -402,20 +305,12 @@ async fn upload_image(mut payload: Multipart, req: HttpRequest) -> HttpResponse
     create_file(path).expect("Error creating image controller.");
 
     // Read and update the file contents
-    let mut file_contents = read_to_string(path).expect("Couldn't read image controller");
+    let mut file_contents = Generator::read_to_string(path).expect("Couldn't read image controller");
 
     file_contents.push_str("\n\n");
     file_contents.push_str(&contents);
     // Write the updated contents to the file
-    let mut file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open(path)
-        .unwrap();
-    // Write the updated contents to the file
-    writeln!(file, "{}", file_contents)?;
-
-    file.flush()?;
+    Generator::update_file(path, &format!("{}\n", file_contents))?;
 
     add_module_declaration(
         "image".to_string(),
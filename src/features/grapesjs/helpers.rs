@@ -1,8 +1,7 @@
 use crate::features::Page;
 use crate::helpers::helpers::get_project_name_from_rustyroad_toml;
+use crate::generators::Generator;
 use futures_util::TryFutureExt;
-use std::fs::OpenOptions;
-use std::io::Write;
 use tera::Tera;
 pub fn render_index_page() -> Result<String, tera::Error> {
     // ensure this is a rustyroad project
@@ -114,7 +113,7 @@ pub async fn update_index_controller() -> Result<String, tera::Error> {
     //
 
     // read the index controller
-    let mut index_controller = std::fs::read_to_string("./src/controllers/index.rs")
+    let mut index_controller = Generator::read_to_string("./src/controllers/index.rs")
         .unwrap_or_else(|err| {
             println!("Error reading index controller: {}", err);
             panic!("Error: {}", err);
@@ -122,45 +121,24 @@ pub async fn update_index_controller() -> Result<String, tera::Error> {
 
     save_index_page().await;
 
-    let new_index_code = r#"
-      let result = Page::get_page_by_slug("index".to_string()).await;
-      match result {
-            Ok(page) => {
-                context.insert("title", "Create Page");
-                context.insert("route_name", "create_page");
-                context.insert("html_content", &page.html_content);
-                context.insert("page_id", &page.id);
-                let s = tmpl.render("pages/page.html.tera", &context).unwrap();
-                HttpResponse::Ok()
-                    .content_type("text/html; charset=utf-8")
-                    .body(s)
-            }
-            Err(e) => {
-                context.insert("error", &e.to_string());
-                let s = tmpl.render("pages/404.html.tera", &context).unwrap();
-                HttpResponse::Ok()
-                    .content_type("text/html; charset=utf-8")
-                    .body(s)
-            }
-        }
-    "#
-    .to_string();
+    let new_index_code = r#"let page = Page::get_page_by_slug("index".to_string()).await?;
+    context.insert("title", "Create Page");
+    context.insert("route_name", "create_page");
+    context.insert("html_content", &page.html_content);
+    context.insert("page_id", &page.id);
+    let rendered = tmpl.render("pages/page.html.tera", &context)?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(rendered))"#
+        .to_string();
 
     index_controller = index_controller.replace(
-        "let rendered = tmpl.render(\"pages/index.html.tera\", &context).unwrap();
-    HttpResponse::Ok().body(rendered)",
+        "let rendered = tmpl.render(\"pages/index.html.tera\", &context)?;
+    Ok(HttpResponse::Ok().body(rendered))",
         &new_index_code,
     );
 
-    let mut file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open("./src/controllers/index.rs")
-        .unwrap();
-    // Write the updated contents to the file
-    writeln!(file, "{}", index_controller).unwrap();
-
-    file.flush()?;
+    Generator::update_file("./src/controllers/index.rs", &format!("{}\n", index_controller)).unwrap();
 
     Ok("".to_string())
 }
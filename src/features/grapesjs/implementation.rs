@@ -6,9 +6,6 @@ use crate::writers::{
 use crate::writers::{write_to_file, write_to_page_dashboard_get_controller};
 use chrono::Local;
 use std::env;
-use std::fs;
-use std::fs::{create_dir, OpenOptions};
-use std::io::Write;
 use std::path::Path;
 
 use crate::database::{run_migration, Database, DatabaseType, MigrationDirection};
@@ -16,7 +13,7 @@ use crate::features::grapesjs::grapesjs_page_controllers::{
     write_to_all_page_controllers, write_to_image_upload_controller,
 };
 use crate::features::{update_cargo_toml_for_grapesjs, update_index_controller};
-use crate::generators::create_file;
+use crate::generators::{create_file, Generator};
 use color_eyre::eyre::Result;
 use eyre::Error;
 
@@ -44,9 +41,9 @@ impl GrapesJs {
             );
             // check if the page directory exists
             let path_path = Path::new(&page_template_directory);
-            if !path_path.exists() {
+            if !Generator::exists(path_path) {
                 // create the page directory
-                create_dir(page_template_directory.clone())
+                Generator::create_dir_all(&page_template_directory)
                     .expect("Couldn't create page directory");
             }
 
@@ -78,10 +75,6 @@ impl GrapesJs {
 
             println!("Writing to create page html");
             write_to_create_page_html().expect("Couldn't write to create page html");
-            // run the migrations
-            run_migration("page".to_string(), MigrationDirection::Up)
-                .await
-                .expect("Couldn't run page migration");
 
             write_to_all_page_controllers().expect("Couldn't write to all page controllers");
 
@@ -130,6 +123,13 @@ To access the page builder, go to the /page/{pageId} URL. For example, if you ar
         }
         Ok(())
     }
+
+    /// Runs the `page` migration written by [`GrapesJs::add_page`].
+    pub async fn run_page_migration(&self) -> Result<(), Error> {
+        run_migration("page".to_string(), MigrationDirection::Up)
+            .await
+            .map_err(|e| eyre::eyre!("Couldn't run the page migration: {}", e))
+    }
 }
 /// # Name: write_to_page_model
 /// ### Description: Writes to the page model
@@ -140,7 +140,7 @@ To access the page builder, go to the /page/{pageId} URL. For example, if you ar
 /// let result = write_to_page_model();
 /// ```
 pub async fn write_to_page_model() -> Result<(), Error> {
-    fs::read_to_string("./rustyroad.toml").unwrap_or_else(|_| {
+    Generator::read_to_string("./rustyroad.toml").unwrap_or_else(|_| {
         let current_dir_path = std::env::current_dir().unwrap_or_else(|_| ".".into());
         let current_dir = current_dir_path.display();
         panic!(
@@ -157,11 +157,6 @@ pub async fn write_to_page_model() -> Result<(), Error> {
     create_file(page_model_file_location)
         .unwrap_or_else(|_| panic!("Error: Could not create {}", page_model_file_location));
 
-    let mut page_model_file = OpenOptions::new()
-        .append(true)
-        .open(page_model_file_location)
-        .unwrap_or_else(|_| panic!("Error: Could not open {}", page_model_file_location));
-
     // Original SQL queries with format! macro
     let create_page_sql = "r#\"\
     INSERT INTO page (
@@ -281,34 +276,40 @@ pub async fn write_to_page_model() -> Result<(), Error> {
 
     let pool_connection_code = match database.database_type {
         DatabaseType::Postgres => r#"
-               let pool = Database::get_db_pool(database).await.unwrap();
-
-                let pool_connection = match pool {
-                    PoolConnection::Pg(pool) => pool,
-
-                    _ => panic!("Error getting pg pool"),
-                };
-                "#
+            let pool_connection = match Database::get_db_pool(database).await {
+                Ok(PoolConnection::Pg(pool)) => pool,
+                Ok(_) => {
+                    return Err(sqlx::Error::Configuration(
+                        "rustyroad.toml points to a different database backend".into(),
+                    ))
+                }
+                Err(e) => return Err(sqlx::Error::Configuration(e.to_string().into())),
+            };
+            "#
         .to_string(),
         DatabaseType::Mysql => r#"
-                let pool = Database::get_db_pool(database).await.unwrap();
-
-                let pool_connection = match pool {
-                    PoolConnection::MySql(pool) => pool,
-
-                    _ => panic!("Error getting mysql pool"),
-                };
-                "#
+            let pool_connection = match Database::get_db_pool(database).await {
+                Ok(PoolConnection::MySql(pool)) => pool,
+                Ok(_) => {
+                    return Err(sqlx::Error::Configuration(
+                        "rustyroad.toml points to a different database backend".into(),
+                    ))
+                }
+                Err(e) => return Err(sqlx::Error::Configuration(e.to_string().into())),
+            };
+            "#
         .to_string(),
         DatabaseType::Sqlite => r#"
-                let pool = Database::get_db_pool(database).await.unwrap();
-
-                let pool_connection = match pool {
-                    PoolConnection::Sqlite(pool) => pool,
-
-                    _ => panic!("Error getting sqlite pool"),
-                };
-                "#
+            let pool_connection = match Database::get_db_pool(database).await {
+                Ok(PoolConnection::Sqlite(pool)) => pool,
+                Ok(_) => {
+                    return Err(sqlx::Error::Configuration(
+                        "rustyroad.toml points to a different database backend".into(),
+                    ))
+                }
+                Err(e) => return Err(sqlx::Error::Configuration(e.to_string().into())),
+            };
+            "#
         .to_string(),
         DatabaseType::Mongo => {
             todo!("Implement MongoDatabaseType.get_database_types")
@@ -483,7 +484,8 @@ use sqlx::FromRow;
         ) -> Result<serde_json::Value, sqlx::Error> {{
             let sql = {create_page_sql};
 
-            let database = Database::get_database_from_rustyroad_toml().unwrap();
+            let database = Database::get_database_from_rustyroad_toml()
+            .map_err(|e| sqlx::Error::Configuration(e.into()))?;
 
             {pool_connection_code}
 
@@ -556,7 +558,8 @@ use sqlx::FromRow;
     /// ```
         pub async fn get_page_by_id(id: i32) -> Result<Page, sqlx::Error> {{
             let sql = {get_page_page_html}
-            let database = Database::get_database_from_rustyroad_toml().unwrap();
+            let database = Database::get_database_from_rustyroad_toml()
+            .map_err(|e| sqlx::Error::Configuration(e.into()))?;
             {pool_connection_code}
             let page: Page = sqlx::query_as(&sql).bind(id).fetch_one(&pool_connection).await?;
             Ok(page)
@@ -579,7 +582,8 @@ use sqlx::FromRow;
         page: Page,
     ) -> Result<serde_json::Value, sqlx::Error> {{
         let sql = {update_page_sql};
-        let database = Database::get_database_from_rustyroad_toml().unwrap();
+        let database = Database::get_database_from_rustyroad_toml()
+            .map_err(|e| sqlx::Error::Configuration(e.into()))?;
         {pool_connection_code}
         let updated_page: Page = sqlx::query_as(&sql)
             .bind(page.title)
@@ -650,7 +654,8 @@ use sqlx::FromRow;
     /// ```
     pub async fn get_all_pages() -> Result<serde_json::Value, sqlx::Error> {{
         let sql = "SELECT * FROM page";
-        let database = Database::get_database_from_rustyroad_toml().unwrap();
+        let database = Database::get_database_from_rustyroad_toml()
+            .map_err(|e| sqlx::Error::Configuration(e.into()))?;
         {pool_connection_code}
         let pages: Vec<Page> = sqlx::query_as(&sql).fetch_all(&pool_connection).await?;
         Ok(serde_json::json!({{
@@ -673,7 +678,8 @@ use sqlx::FromRow;
     /// ```
     pub async fn get_page_by_slug(slug: String) -> Result<Page, sqlx::Error> {{
         let sql = {get_page_by_slug_sql}
-        let database = Database::get_database_from_rustyroad_toml().unwrap();
+        let database = Database::get_database_from_rustyroad_toml()
+            .map_err(|e| sqlx::Error::Configuration(e.into()))?;
 
 
         {pool_connection_code}
@@ -699,7 +705,8 @@ use sqlx::FromRow;
     /// ```
     pub async fn delete_page(id: i32) -> Result<serde_json::Value, sqlx::Error> {{
         let sql = "DELETE FROM page WHERE id = $1";
-        let database = Database::get_database_from_rustyroad_toml().unwrap();
+        let database = Database::get_database_from_rustyroad_toml()
+            .map_err(|e| sqlx::Error::Configuration(e.into()))?;
         {pool_connection_code}
         sqlx::query(&sql).bind(id).execute(&pool_connection).await?;
         Ok(serde_json::json!({{
@@ -724,8 +731,7 @@ where
         pool_connection_code = pool_connection_code
     );
 
-    page_model_file
-        .write_all(page_model_contents.as_bytes())
+    Generator::append_file(page_model_file_location, &page_model_contents)
         .unwrap_or_else(|_| panic!("Error: Could not write to {}", page_model_file_location));
 
     let database = Database::get_database_from_rustyroad_toml()
@@ -910,7 +916,7 @@ CREATE TABLE IF NOT EXISTS page (
     );
 
     // create the migration directory
-    create_dir(folder_name.clone())
+    Generator::create_dir_all(&folder_name)
         .unwrap_or_else(|_| panic!("Error: Could not create migration directory for page"));
     // get the migration directory
     let page_migration_directory = folder_name.clone();
@@ -949,13 +955,7 @@ pub fn append_graped_js_to_header() -> Result<(), Error> {
 
     let header_file_location = "src/views/sections/header.html.tera";
 
-    let mut header_file = OpenOptions::new()
-        .append(true)
-        .open(header_file_location)
-        .unwrap_or_else(|_| panic!("Error: Could not open {}", header_file_location));
-
-    header_file
-        .write_all(contents.as_bytes())
+    Generator::append_file(header_file_location, &contents)
         .unwrap_or_else(|_| panic!("Error: Could not write to {}", header_file_location));
 
     Ok(())
@@ -992,7 +992,7 @@ pub fn create_page_list_page() -> Result<(), Error> {
     .to_string();
 
     let path = Path::new("src/views/layouts/authenticated_page/page/page_list.html.tera");
-    if !path.exists() {
+    if !Generator::exists(path) {
         println!("Creating the page_list.html.tera file...");
         create_file("src/views/layouts/authenticated_page/page/page_list.html.tera")
             .expect("Error creating the page_list.html.tera file");
@@ -157,6 +157,22 @@ impl Page {
         }
     }
 
+    /// # Name: get_db_pool
+    /// ### Description: Opens the PostgreSQL pool configured in rustyroad.toml
+    /// ### Returns: Result<sqlx::PgPool, sqlx::Error>, a `Configuration` error when the settings
+    /// cannot be read, the database cannot be reached or it is not PostgreSQL
+    pub async fn get_db_pool() -> Result<sqlx::PgPool, sqlx::Error> {
+        let database = Database::get_database_from_rustyroad_toml()
+            .map_err(|e| sqlx::Error::Configuration(e.into()))?;
+        match Database::get_db_pool(database).await {
+            Ok(PoolConnection::Pg(pool)) => Ok(pool),
+            Ok(_) => Err(sqlx::Error::Configuration(
+                "the page builder needs a PostgreSQL database".into(),
+            )),
+            Err(e) => Err(sqlx::Error::Configuration(e.to_string().into())),
+        }
+    }
+
     /// # Name: create_page
     /// ### Description: Creates a new database page
     /// ### Parameters: new_html: Page
@@ -226,15 +242,7 @@ impl Page {
         ) RETURNING *;
         "#;
 
-        let database = Database::get_database_from_rustyroad_toml().unwrap();
-
-        let pool = Database::get_db_pool(database).await.unwrap();
-
-        let pool_connection = match pool {
-            PoolConnection::Pg(pool) => pool,
-
-            _ => panic!("Error getting pg pool"),
-        };
+        let pool_connection = Self::get_db_pool().await?;
 
         let new_page: Page = query_as(sql)
             .bind(new_html.title)
@@ -306,15 +314,7 @@ impl Page {
     /// ```
     pub async fn get_page_by_id(id: i32) -> Result<Page, sqlx::Error> {
         let sql = r#"SELECT * FROM page WHERE id = $1"#;
-        let database = Database::get_database_from_rustyroad_toml().unwrap();
-
-        let pool = Database::get_db_pool(database).await.unwrap();
-
-        let pool_connection = match pool {
-            PoolConnection::Pg(pool) => pool,
-
-            _ => panic!("Error getting pg pool"),
-        };
+        let pool_connection = Self::get_db_pool().await?;
 
         let page: Page = query_as(sql).bind(id).fetch_one(&pool_connection).await?;
         Ok(page)
@@ -332,15 +332,7 @@ impl Page {
     /// ```
     pub async fn get_page_by_slug(slug: String) -> Result<Page, sqlx::Error> {
         let sql = r#"SELECT * FROM page WHERE slug = $1"#;
-        let database = Database::get_database_from_rustyroad_toml().unwrap();
-
-        let pool = Database::get_db_pool(database).await.unwrap();
-
-        let pool_connection = match pool {
-            PoolConnection::Pg(pool) => pool,
-
-            _ => panic!("Error getting pg pool"),
-        };
+        let pool_connection = Self::get_db_pool().await?;
 
         let page: Page = query_as(sql).bind(slug).fetch_one(&pool_connection).await?;
         Ok(page)
@@ -412,15 +404,7 @@ impl Page {
         RETURNING *;
               "#;
 
-        let database = Database::get_database_from_rustyroad_toml().unwrap();
-
-        let pool = Database::get_db_pool(database).await.unwrap();
-
-        let pool_connection = match pool {
-            PoolConnection::Pg(pool) => pool,
-
-            _ => panic!("Error getting pg pool"),
-        };
+        let pool_connection = Self::get_db_pool().await?;
 
         let updated_page: Page = query_as(sql)
             .bind(new_html.title)
@@ -491,15 +475,7 @@ impl Page {
     /// ```
     pub async fn get_all_pages() -> Result<serde_json::Value, sqlx::Error> {
         let sql = "SELECT * FROM page";
-        let database = Database::get_database_from_rustyroad_toml().unwrap();
-
-        let pool = Database::get_db_pool(database).await.unwrap();
-
-        let pool_connection = match pool {
-            PoolConnection::Pg(pool) => pool,
-
-            _ => panic!("Error getting pg pool"),
-        };
+        let pool_connection = Self::get_db_pool().await?;
 
         let pages: Vec<Page> = query_as(sql).fetch_all(&pool_connection).await?;
         Ok(serde_json::json!({
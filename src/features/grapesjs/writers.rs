@@ -1,47 +1,37 @@
-use crate::generators::create_file;
+use crate::generators::{create_file, Generator};
 use crate::helpers::helpers::{
     add_or_update_import, determine_controller_path, prompt_to_create_controller,
 };
 use crate::writers::{add_new_controller_to_main_rs, write_to_file};
 use color_eyre::eyre::Result;
 use eyre::Error;
-use std::fs;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::PathBuf;
 
 pub fn write_to_get_page_by_id() -> Result<(), Error> {
     let contents = r#"
 #[get("/page/{id}")]
-async fn get_page_by_id(tmpl: Data<Tera>, id: Path<i32>, user: Option<Identity>) -> HttpResponse {
-    let result = Page::get_page_by_id(id.into_inner()).await;
-    match result {
-        Ok(page) => {
-            let mut context = Context::new();
-            if let Some(_user) = user {
-                context.insert("username", &_user.id().unwrap());
-            }
-            context.insert("title", "Create Page");
-            context.insert("route_name", "create_page");
-            context.insert("html_content", &page.html_content);
-            context.insert("page_id", &page.id.unwrap());
-            let s = tmpl.render("pages/page.html.tera", &context).unwrap();
-            HttpResponse::Ok()
-                .content_type("text/html; charset=utf-8")
-                .body(s)
-        }
-        Err(e) => {
-            let mut context = Context::new();
-            context.insert("error", &e.to_string());
-            if let Some(_user) = user {
-                context.insert("username", &_user.id().unwrap());
-            }
-            let s = tmpl.render("pages/404.html.tera", &context).unwrap();
-            HttpResponse::Ok()
-                .content_type("text/html; charset=utf-8")
-                .body(s)
-        }
+async fn get_page_by_id(
+    tmpl: Data<Tera>,
+    id: Path<i32>,
+    user: Option<Identity>,
+) -> Result<HttpResponse, AppError> {
+    let page = Page::get_page_by_id(id.into_inner()).await?;
+
+    let mut context = Context::new();
+    if let Some(user) = user {
+        let username = user
+            .id()
+            .map_err(|e| AppError::Internal(format!("Failed to read the session: {}", e)))?;
+        context.insert("username", &username);
     }
+    context.insert("title", "Create Page");
+    context.insert("route_name", "create_page");
+    context.insert("html_content", &page.html_content);
+    context.insert("page_id", &page.id);
+    let rendered = tmpl.render("pages/page.html.tera", &context)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(rendered))
 }
 "#;
 
@@ -50,13 +40,13 @@ async fn get_page_by_id(tmpl: Data<Tera>, id: Path<i32>, user: Option<Identity>)
 
     // Ensure the controller file exists, or create it
     // Ensure the controller file exists, or create it
-    let file_exists = PathBuf::from(&path).exists();
+    let file_exists = Generator::exists(&path);
     if !file_exists {
         prompt_to_create_controller(&path).expect("Error prompting to create controller");
     }
 
     // Read and update the file contents
-    let mut file_contents = fs::read_to_string(&path)?;
+    let mut file_contents = Generator::read_to_string(&path)?;
 
     // Update imports
     file_contents = add_or_update_import(&file_contents, "actix_web", "get");
@@ -67,21 +57,14 @@ async fn get_page_by_id(tmpl: Data<Tera>, id: Path<i32>, user: Option<Identity>)
     file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Data");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Path");
+    file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
     // Add the new controller to the file
     file_contents.push_str("\n\n");
     file_contents.push_str(contents);
 
     println!("Writing the get_page_by_id controller to the file...");
-    let mut file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open(&path)
-        .unwrap();
-    // Write the updated contents to the file
-    writeln!(file, "{}", file_contents)?;
-
-    file.flush()?;
+    Generator::update_file(&path, &format!("{}\n", file_contents))?;
 
     // Add the get_page_by_id controller to the main.rs file
     println!("Adding the get_page_by_id controller to the main.rs file...");
@@ -111,15 +94,17 @@ pub fn write_to_get_page_by_id_html() -> Result<(), Error> {
 }
 
 pub fn update_cargo_toml_for_grapesjs() -> Result<(), Error> {
-    let contents = r##"
+    // The page builder's generated code uses this version's `rustyroad::web` and `database` APIs
+    let contents = format!(
+        r##"
     [dependencies]
 actix-cors = "0.6.4"
 actix-web = "4.0.0-beta.8"
 actix-files = "0.6.2"
-actix-session = {version = "0.7.2", features = ["cookie-session"]}
+actix-session = {{version = "0.7.2", features = ["cookie-session"]}}
 actix-identity = "0.5.2"
-tokio = { version = "1", features = ["macros", "rt-multi-thread"] }
-serde = { version = "1.0", features = ["derive"] }
+tokio = {{ version = "1", features = ["macros", "rt-multi-thread"] }}
+serde = {{ version = "1.0", features = ["derive"] }}
 serde_json = "1.0.82"
 random-string = "1.0.0"
 env_logger = "0.10.0"
@@ -127,9 +112,10 @@ local-ip-address = "0.5.0"
 futures = "0.3.23"
 tera = "1.17.1"
 reqwest = "0.11"
-rustyroad = "0.1.8-beta-2.0.5"
+rustyroad = "{version}"
+rustyroad-derive = "{version}"
 rand = "0.8.5"
-chrono = { version = "0.4.24", features = ["serde"] }
+chrono = {{ version = "0.4.24", features = ["serde"] }}
 base64 = "0.21.0"
 dotenv = "0.15.0"
 bcrypt = "0.14.0"
@@ -143,13 +129,15 @@ sanitize-filename = "0.5"
 
 [dependencies.sqlx]
 features = ["postgres", "macros", "chrono", "json", "uuid", "runtime-tokio", "time"]
-version = "0.7.2"
-    "##;
+version = "0.8.2"
+    "##,
+        version = env!("CARGO_PKG_VERSION"),
+    );
 
     println!("Updating the Cargo.toml file...");
 
     // Read the existing Cargo.toml file
-    let mut cargo_toml = fs::read_to_string("./Cargo.toml")?;
+    let mut cargo_toml = Generator::read_to_string("./Cargo.toml")?;
 
     // Remove the current dependencies section
     if let Some(dependencies_start) = cargo_toml.find("[dependencies]") {
@@ -158,17 +146,10 @@ version = "0.7.2"
 
     // Append the new dependencies
     cargo_toml.push_str("\n\n");
-    cargo_toml.push_str(contents);
+    cargo_toml.push_str(&contents);
 
     // Write the updated contents to the file
-    let mut file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open("./Cargo.toml")?;
-
-    writeln!(file, "{}", cargo_toml)?;
-
-    file.flush()?;
+    Generator::update_file("./Cargo.toml", &format!("{}\n", cargo_toml))?;
 
     println!("Cargo.toml updated successfully.");
 
@@ -1,16 +1,12 @@
-use std::fs::File;
+use crate::generators::Generator;
 use std::io::Error;
 
 /// # Name: create_file
-/// # Description: Creates a file
+/// # Description: Creates an empty file, truncating it if it exists. Planned inside a generator session.
 /// # Arguments:
 /// * `name` - The name of the file
 pub fn create_file(name: &str) -> Result<(), Error> {
-    let result = match File::create(name) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(e),
-    };
-    match result {
+    match Generator::write_file(name, "") {
         Ok(_) => Ok(()),
         Err(e) => {
             println!("Error creating file: {:?}", e);
@@ -1,7 +1,11 @@
 pub mod directory;
 pub mod file;
+pub mod runtime;
+pub mod templates;
 
 pub use directory::*;
 pub use file::*;
+pub use runtime::*;
+pub use templates::*;
 // Path: src\generators\components
 // need to create a reuseable module generator.
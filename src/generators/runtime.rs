@@ -0,0 +1,765 @@
+use clap::{Arg, ArgAction, ArgMatches};
+use dialoguer::Select;
+use eyre::{eyre, Error};
+use similar::TextDiff;
+use std::cell::RefCell;
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// What to do when a generator would replace a file that exists with different contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Prompt for each conflict when stdin is a terminal; skip it otherwise.
+    Ask,
+    Force,
+    Skip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneratorOptions {
+    /// Print the planned changes as unified diffs without writing anything.
+    pub pretend: bool,
+    pub conflict: ConflictPolicy,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self {
+            pretend: false,
+            conflict: ConflictPolicy::Ask,
+        }
+    }
+}
+
+impl GeneratorOptions {
+    /// # Name: args
+    /// ## Description
+    /// The `--pretend`, `--force` and `--skip` flags shared by every generator command.
+    pub fn args() -> [Arg; 3] {
+        [
+            Self::pretend_arg(),
+            Arg::new("force")
+                .long("force")
+                .short('f')
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .conflicts_with("skip")
+                .help("Overwrite files that already exist"),
+            Arg::new("skip")
+                .long("skip")
+                .short('s')
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Keep files that already exist"),
+        ]
+    }
+
+    /// Just `--pretend`, for commands such as `destroy` that never overwrite files.
+    pub fn pretend_arg() -> Arg {
+        Arg::new("pretend")
+            .long("pretend")
+            .short('p')
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Show the changes as a diff without writing anything")
+    }
+
+    /// Reads the flags added by [`GeneratorOptions::args`].
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        let flag = |name: &str| matches!(matches.try_get_one::<bool>(name), Ok(Some(true)));
+        let conflict = if flag("force") {
+            ConflictPolicy::Force
+        } else if flag("skip") {
+            ConflictPolicy::Skip
+        } else {
+            ConflictPolicy::Ask
+        };
+        Self {
+            pretend: flag("pretend"),
+            conflict,
+        }
+    }
+}
+
+/// How a planned file change was recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChangeKind {
+    /// Generated contents; replacing a different existing file is a conflict.
+    Write,
+    /// An edit of an existing file, such as a new line in main.rs or mod.rs.
+    Update,
+    /// A file deleted by `rustyroad destroy`.
+    Remove,
+}
+
+#[derive(Debug)]
+struct PlannedFile {
+    path: PathBuf,
+    contents: String,
+    kind: ChangeKind,
+}
+
+#[derive(Debug)]
+struct Session {
+    options: GeneratorOptions,
+    root: PathBuf,
+    files: Vec<PlannedFile>,
+    dirs: Vec<PathBuf>,
+    removed_dirs: Vec<PathBuf>,
+    messages: Vec<String>,
+}
+
+impl Session {
+    fn planned(&self, path: &Path) -> Option<&PlannedFile> {
+        self.files.iter().find(|f| f.path == path)
+    }
+
+    fn is_removed(&self, path: &Path) -> bool {
+        self.planned(path)
+            .is_some_and(|f| f.kind == ChangeKind::Remove)
+            || self.removed_dirs.iter().any(|dir| path.starts_with(dir))
+    }
+
+    fn plan(&mut self, path: PathBuf, contents: String, kind: ChangeKind) {
+        match self.files.iter_mut().find(|f| f.path == path) {
+            Some(file) => {
+                file.contents = contents;
+                // Once regenerated, the file as a whole is generated content
+                if kind != ChangeKind::Update || file.kind == ChangeKind::Remove {
+                    file.kind = kind;
+                }
+            }
+            None => self.files.push(PlannedFile {
+                path,
+                contents,
+                kind,
+            }),
+        }
+    }
+
+    fn display(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+thread_local! {
+    // Per thread, so generators running in parallel tests don't see each other's sessions
+    static SESSION: RefCell<Option<Session>> = const { RefCell::new(None) };
+}
+
+fn with_session<T>(f: impl FnOnce(Option<&mut Session>) -> T) -> T {
+    SESSION.with(|session| f(session.borrow_mut().as_mut()))
+}
+
+/// Makes planned paths comparable however callers spell them (`./src/main.rs`, `src/main.rs`, absolute).
+fn normalize(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    absolute
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+/// What happened, or with `--pretend` would happen, to each file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GeneratorSummary {
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+    pub identical: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+impl fmt::Display for GeneratorSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} created, {} modified, ",
+            self.created.len(),
+            self.modified.len()
+        )?;
+        if !self.removed.is_empty() {
+            write!(f, "{} removed, ", self.removed.len())?;
+        }
+        write!(
+            f,
+            "{} identical, {} skipped",
+            self.identical.len(),
+            self.skipped.len()
+        )
+    }
+}
+
+fn status(status: &str, path: &str) {
+    println!("{:>12}  {}", status, path);
+}
+
+fn print_diff(path: &str, old: Option<&str>, new: Option<&str>) {
+    let header = |prefix: &str, side: Option<&str>| match side {
+        Some(_) => format!("{}/{}", prefix, path),
+        None => "/dev/null".to_string(),
+    };
+    let diff = TextDiff::from_lines(old.unwrap_or(""), new.unwrap_or(""));
+    print!(
+        "{}",
+        diff.unified_diff()
+            .context_radius(3)
+            .header(&header("a", old), &header("b", new))
+    );
+}
+
+/// # Name: Generator
+/// ## Description
+/// The runtime every generator writes through. Between [`Generator::start`] and
+/// [`Generator::finish`], writes are only planned: reads see the planned contents, and
+/// `finish` then resolves conflicts with existing files (prompt, `--force` or `--skip`),
+/// writes everything, and prints a Rails-style summary. With `--pretend` it prints unified
+/// diffs instead. Outside a session every call goes straight to disk, as before.
+/// ## Example
+/// ```no_run
+/// use rustyroad::generators::{Generator, GeneratorOptions};
+///
+/// Generator::run(GeneratorOptions::default(), || {
+///     Generator::write_file("src/views/pages/about.html.tera", "<h1>About</h1>\n")?;
+///     Ok(())
+/// })
+/// .unwrap();
+/// ```
+pub struct Generator;
+
+impl Generator {
+    /// Starts collecting planned changes, replacing any session that was never finished.
+    pub fn start(options: GeneratorOptions) {
+        let root = std::env::current_dir().unwrap_or_default();
+        SESSION.with(|session| {
+            *session.borrow_mut() = Some(Session {
+                options,
+                root,
+                files: Vec::new(),
+                dirs: Vec::new(),
+                removed_dirs: Vec::new(),
+                messages: Vec::new(),
+            })
+        });
+    }
+
+    pub fn is_active() -> bool {
+        with_session(|session| session.is_some())
+    }
+
+    /// Prints a message, such as next steps, after the summary; dropped with `--pretend`.
+    pub fn say(message: impl Into<String>) {
+        let message = message.into();
+        let queued = with_session(|session| match session {
+            Some(s) => {
+                s.messages.push(message.clone());
+                true
+            }
+            None => false,
+        });
+        if !queued {
+            println!("{}", message);
+        }
+    }
+
+    /// Drops the planned changes without writing anything.
+    pub fn abort() {
+        SESSION.with(|session| session.borrow_mut().take());
+    }
+
+    /// Runs `generate` in a session; nothing is written if it fails.
+    pub fn run(
+        options: GeneratorOptions,
+        generate: impl FnOnce() -> Result<(), Error>,
+    ) -> Result<GeneratorSummary, Error> {
+        Self::start(options);
+        if let Err(e) = generate() {
+            Self::abort();
+            return Err(e);
+        }
+        Self::finish()
+    }
+
+    /// [`Generator::run`] for async generators, such as those that introspect the database.
+    pub async fn run_async(
+        options: GeneratorOptions,
+        generate: impl Future<Output = Result<(), Error>>,
+    ) -> Result<GeneratorSummary, Error> {
+        Self::start(options);
+        if let Err(e) = generate.await {
+            Self::abort();
+            return Err(e);
+        }
+        Self::finish()
+    }
+
+    /// Reads a file as the generator currently plans it to be.
+    pub fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+        let path = path.as_ref();
+        let normalized = normalize(path);
+        let planned = with_session(|session| {
+            session.and_then(|s| {
+                if s.is_removed(&normalized) {
+                    Some(None)
+                } else {
+                    s.planned(&normalized).map(|f| Some(f.contents.clone()))
+                }
+            })
+        });
+        match planned {
+            Some(Some(contents)) => Ok(contents),
+            Some(None) => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is planned to be removed", path.display()),
+            )),
+            None => fs::read_to_string(path),
+        }
+    }
+
+    pub fn exists(path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        let normalized = normalize(path);
+        let planned = with_session(|session| {
+            session.map(|s| {
+                let kept = |f: &&PlannedFile| f.kind != ChangeKind::Remove;
+                if s.planned(&normalized).filter(kept).is_some()
+                    || s.dirs.contains(&normalized)
+                    || s.files
+                        .iter()
+                        .filter(kept)
+                        .any(|f| f.path.starts_with(&normalized))
+                {
+                    Some(true)
+                } else if s.is_removed(&normalized) {
+                    Some(false)
+                } else {
+                    None
+                }
+            })
+        });
+        planned.flatten().unwrap_or_else(|| path.exists())
+    }
+
+    /// Writes generated contents. Replacing an existing file with different contents is a conflict.
+    pub fn write_file(path: impl AsRef<Path>, contents: &str) -> io::Result<()> {
+        Self::plan(path.as_ref(), contents.to_string(), ChangeKind::Write)
+    }
+
+    /// Saves an edit of an existing file, such as a route added to main.rs; never a conflict.
+    pub fn update_file(path: impl AsRef<Path>, contents: &str) -> io::Result<()> {
+        Self::plan(path.as_ref(), contents.to_string(), ChangeKind::Update)
+    }
+
+    /// Appends to a file that exists or is planned.
+    pub fn append_file(path: impl AsRef<Path>, contents: &str) -> io::Result<()> {
+        let path = path.as_ref();
+        if !Self::is_active() {
+            let mut file = fs::OpenOptions::new().append(true).open(path)?;
+            return file.write_all(contents.as_bytes());
+        }
+        let mut current = Self::read_to_string(path)?;
+        current.push_str(contents);
+        Self::plan(path, current, ChangeKind::Update)
+    }
+
+    /// Deletes a file. Files that don't exist are ignored.
+    pub fn remove_file(path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let planned = with_session(|session| match session {
+            Some(s) => {
+                s.plan(normalize(path), String::new(), ChangeKind::Remove);
+                true
+            }
+            None => false,
+        });
+        if planned {
+            return Ok(());
+        }
+        match fs::remove_file(path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Deletes a directory and everything in it. Missing directories are ignored.
+    pub fn remove_dir_all(path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if !Self::is_active() {
+            return match fs::remove_dir_all(path) {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+                result => result,
+            };
+        }
+        // Each file is reported, so plan them one by one
+        if path.is_dir() {
+            for entry in fs::read_dir(path)? {
+                let entry = entry?.path();
+                if entry.is_dir() {
+                    Self::remove_dir_all(&entry)?;
+                } else {
+                    Self::remove_file(&entry)?;
+                }
+            }
+        }
+        let normalized = normalize(path);
+        with_session(|session| {
+            if let Some(s) = session {
+                s.files
+                    .retain(|f| !f.path.starts_with(&normalized) || f.kind == ChangeKind::Remove);
+                s.dirs.retain(|dir| !dir.starts_with(&normalized));
+                s.removed_dirs.push(normalized);
+            }
+        });
+        Ok(())
+    }
+
+    pub fn create_dir_all(path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let normalized = normalize(path);
+        let planned = with_session(|session| match session {
+            Some(s) => {
+                if !path.is_dir() && !s.dirs.contains(&normalized) {
+                    s.dirs.push(normalized);
+                }
+                true
+            }
+            None => false,
+        });
+        if planned {
+            Ok(())
+        } else {
+            fs::create_dir_all(path)
+        }
+    }
+
+    fn plan(path: &Path, contents: String, kind: ChangeKind) -> io::Result<()> {
+        let planned = with_session(|session| match session {
+            Some(s) => {
+                s.plan(normalize(path), contents.clone(), kind);
+                true
+            }
+            None => false,
+        });
+        if planned {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+    }
+
+    /// # Name: finish
+    /// ## Description
+    /// Ends the session: applies the planned changes, or with `--pretend` prints them as
+    /// unified diffs, then prints the summary.
+    pub fn finish() -> Result<GeneratorSummary, Error> {
+        let session = SESSION
+            .with(|session| session.borrow_mut().take())
+            .ok_or_else(|| eyre!("No generator session was started"))?;
+        let pretend = session.options.pretend;
+        let mut policy = session.options.conflict;
+        let mut summary = GeneratorSummary::default();
+        let mut unresolved = 0;
+
+        for dir in &session.dirs {
+            if dir.is_dir() || session.files.iter().any(|f| f.path.starts_with(dir)) {
+                continue;
+            }
+            let shown = format!("{}/", session.display(dir));
+            if !pretend {
+                fs::create_dir_all(dir)?;
+            }
+            status("create", &shown);
+            summary.created.push(shown);
+        }
+
+        for file in &session.files {
+            let shown = session.display(&file.path);
+            let original = fs::read(&file.path)
+                .ok()
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+            if file.kind == ChangeKind::Remove {
+                let Some(original) = original else {
+                    continue;
+                };
+                status("remove", &shown);
+                if pretend {
+                    print_diff(&shown, Some(&original), None);
+                } else {
+                    fs::remove_file(&file.path)?;
+                }
+                summary.removed.push(shown);
+                continue;
+            }
+            let Some(original) = original else {
+                status("create", &shown);
+                if pretend {
+                    print_diff(&shown, None, Some(&file.contents));
+                } else {
+                    write_planned(file)?;
+                }
+                summary.created.push(shown);
+                continue;
+            };
+            if original == file.contents {
+                status("identical", &shown);
+                summary.identical.push(shown);
+                continue;
+            }
+            if file.kind == ChangeKind::Update {
+                status("modify", &shown);
+                if pretend {
+                    print_diff(&shown, Some(&original), Some(&file.contents));
+                } else {
+                    write_planned(file)?;
+                }
+                summary.modified.push(shown);
+                continue;
+            }
+
+            let overwrite = match policy {
+                ConflictPolicy::Force => true,
+                ConflictPolicy::Skip => false,
+                ConflictPolicy::Ask if pretend => {
+                    status("conflict", &shown);
+                    print_diff(&shown, Some(&original), Some(&file.contents));
+                    summary.skipped.push(shown);
+                    unresolved += 1;
+                    continue;
+                }
+                ConflictPolicy::Ask => {
+                    status("conflict", &shown);
+                    match resolve_conflict(&shown, &original, &file.contents)? {
+                        Some((overwrite, remember)) => {
+                            if remember {
+                                policy = if overwrite {
+                                    ConflictPolicy::Force
+                                } else {
+                                    ConflictPolicy::Skip
+                                };
+                            }
+                            overwrite
+                        }
+                        None => {
+                            unresolved += 1;
+                            false
+                        }
+                    }
+                }
+            };
+            if overwrite {
+                status("force", &shown);
+                if pretend {
+                    print_diff(&shown, Some(&original), Some(&file.contents));
+                } else {
+                    write_planned(file)?;
+                }
+                summary.modified.push(shown);
+            } else {
+                status("skip", &shown);
+                summary.skipped.push(shown);
+            }
+        }
+
+        for dir in &session.removed_dirs {
+            if !dir.is_dir() {
+                continue;
+            }
+            let shown = format!("{}/", session.display(dir));
+            status("remove", &shown);
+            if !pretend {
+                fs::remove_dir_all(dir)?;
+            }
+            if !summary.removed.iter().any(|f| f.starts_with(&shown)) {
+                summary.removed.push(shown);
+            }
+        }
+
+        println!();
+        if pretend {
+            println!("Pretend run, nothing was written: {}", summary);
+        } else {
+            println!("{}", summary);
+        }
+        if unresolved > 0 {
+            println!(
+                "{} file(s) already exist with different contents; rerun with --force to overwrite or --skip to keep them.",
+                unresolved
+            );
+        }
+        if !pretend {
+            for message in &session.messages {
+                println!("{}", message);
+            }
+        }
+        Ok(summary)
+    }
+}
+
+fn write_planned(file: &PlannedFile) -> io::Result<()> {
+    if let Some(parent) = file.path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&file.path, &file.contents)
+}
+
+/// Asks what to do with a conflicting file: `Some((overwrite, for_all_remaining))`, or `None`
+/// when there is no terminal to ask on.
+fn resolve_conflict(
+    shown: &str,
+    original: &str,
+    contents: &str,
+) -> Result<Option<(bool, bool)>, Error> {
+    if !io::stdin().is_terminal() {
+        return Ok(None);
+    }
+    let choices = [
+        "Overwrite",
+        "Skip",
+        "Overwrite this and all remaining conflicts",
+        "Skip this and all remaining conflicts",
+        "Show the diff",
+    ];
+    loop {
+        let choice = Select::new()
+            .with_prompt(format!("{} already exists", shown))
+            .items(&choices)
+            .default(0)
+            .interact()?;
+        match choice {
+            0 => return Ok(Some((true, false))),
+            1 => return Ok(Some((false, false))),
+            2 => return Ok(Some((true, true))),
+            3 => return Ok(Some((false, true))),
+            _ => print_diff(shown, Some(original), Some(contents)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_plans_then_applies_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("main.rs"), "fn main() {}\n").unwrap();
+        fs::write(root.join("same.rs"), "same\n").unwrap();
+        fs::write(root.join("mine.rs"), "mine\n").unwrap();
+
+        let plan = |options| {
+            Generator::run(options, || {
+                Generator::write_file(root.join("new/model.rs"), "struct Model;\n")?;
+                Generator::append_file(root.join("new/model.rs"), "impl Model {}\n")?;
+                assert_eq!(
+                    Generator::read_to_string(root.join("new/./model.rs"))?,
+                    "struct Model;\nimpl Model {}\n"
+                );
+                assert!(Generator::exists(root.join("new")));
+                Generator::update_file(root.join("main.rs"), "mod model;\nfn main() {}\n")?;
+                Generator::write_file(root.join("same.rs"), "same\n")?;
+                Generator::write_file(root.join("mine.rs"), "generated\n")?;
+                Ok(())
+            })
+            .unwrap()
+        };
+
+        let summary = plan(GeneratorOptions {
+            pretend: true,
+            conflict: ConflictPolicy::Ask,
+        });
+        assert_eq!(
+            summary.to_string(),
+            "1 created, 1 modified, 1 identical, 1 skipped"
+        );
+        assert!(!root.join("new").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("main.rs")).unwrap(),
+            "fn main() {}\n"
+        );
+
+        let summary = plan(GeneratorOptions {
+            pretend: false,
+            conflict: ConflictPolicy::Skip,
+        });
+        assert_eq!(summary.skipped.len(), 1);
+        assert_eq!(fs::read_to_string(root.join("mine.rs")).unwrap(), "mine\n");
+        assert_eq!(
+            fs::read_to_string(root.join("main.rs")).unwrap(),
+            "mod model;\nfn main() {}\n"
+        );
+
+        let summary = plan(GeneratorOptions {
+            pretend: false,
+            conflict: ConflictPolicy::Force,
+        });
+        assert_eq!(
+            summary.to_string(),
+            "0 created, 1 modified, 3 identical, 0 skipped"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("mine.rs")).unwrap(),
+            "generated\n"
+        );
+
+        // A failing generator writes nothing
+        let result = Generator::run(GeneratorOptions::default(), || {
+            Generator::write_file(root.join("partial.rs"), "")?;
+            Err(eyre!("boom"))
+        });
+        assert!(result.is_err());
+        assert!(!Generator::is_active());
+        assert!(!root.join("partial.rs").exists());
+    }
+
+    #[test]
+    fn test_session_plans_removals() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("views/post")).unwrap();
+        fs::write(root.join("views/post/index.html.tera"), "index\n").unwrap();
+        fs::write(root.join("views/post/show.html.tera"), "show\n").unwrap();
+        fs::write(root.join("post.rs"), "struct Post;\n").unwrap();
+
+        let plan = |pretend| {
+            Generator::run(
+                GeneratorOptions {
+                    pretend,
+                    ..Default::default()
+                },
+                || {
+                    Generator::remove_dir_all(root.join("views/post"))?;
+                    Generator::remove_file(root.join("post.rs"))?;
+                    Generator::remove_file(root.join("missing.rs"))?;
+                    assert!(!Generator::exists(root.join("views/post/show.html.tera")));
+                    assert!(Generator::read_to_string(root.join("post.rs")).is_err());
+                    Ok(())
+                },
+            )
+            .unwrap()
+        };
+
+        let summary = plan(true);
+        assert_eq!(
+            summary.to_string(),
+            "0 created, 0 modified, 3 removed, 0 identical, 0 skipped"
+        );
+        assert!(root.join("views/post/index.html.tera").exists());
+
+        plan(false);
+        assert!(!root.join("views/post").exists());
+        assert!(!root.join("post.rs").exists());
+    }
+}
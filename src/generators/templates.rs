@@ -0,0 +1,208 @@
+use crate::generators::Generator;
+use eyre::{eyre, Error};
+use std::path::{Path, PathBuf};
+use tera::{Context, Tera};
+
+/// Where a project keeps its own copies of the generator templates.
+pub const TEMPLATES_DIR: &str = "config/templates";
+
+/// One built-in generator template, `<generator>/<file>.tera`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneratorTemplate {
+    pub generator: &'static str,
+    /// The name of the file the template renders, e.g. `controller.rs`.
+    pub file: &'static str,
+    pub source: &'static str,
+}
+
+macro_rules! template {
+    ($generator:literal, $file:literal) => {
+        GeneratorTemplate {
+            generator: $generator,
+            file: $file,
+            source: include_str!(concat!("templates/", $generator, "/", $file, ".tera")),
+        }
+    };
+}
+
+/// The templates built into RustyRoad. Each one starts with a comment listing its context.
+pub const GENERATOR_TEMPLATES: &[GeneratorTemplate] = &[
+    template!("model", "model.rs"),
+    template!("controller", "controller.rs"),
+    template!("controller", "index.html"),
+    template!("controller", "show.html"),
+    template!("controller", "new.html"),
+    template!("controller", "edit.html"),
+    template!("controller", "_form.html"),
+    template!("controller", "tests.rs"),
+    template!("api", "mod.rs"),
+    template!("api", "controller.rs"),
+    template!("new", "main.rs"),
+    template!("new", "controller.rs"),
+    template!("new", "login.rs"),
+    template!("new", "login.html"),
+    template!("new", "navbar.html"),
+    template!("actions", "get.rs"),
+    template!("actions", "get_all.rs"),
+    template!("actions", "authorized_get.rs"),
+    template!("actions", "authenticated_view.rs"),
+    template!("actions", "create.rs"),
+    template!("actions", "update.rs"),
+    template!("actions", "delete.rs"),
+    template!("actions", "previous_get.rs"),
+    template!("actions", "previous_create.rs"),
+    template!("actions", "page.html"),
+    template!("actions", "authorized_page.html"),
+    template!("page", "page_dashboard.rs"),
+    template!("page", "create_page_dashboard.rs"),
+    template!("page", "edit_page.rs"),
+];
+
+/// The generators whose templates `rustyroad templates eject` accepts. A scaffold is a model
+/// and a controller, so it ejects both. `new` is `rustyroad new`, `actions` the single-action
+/// controllers and `page` the GrapesJS page builder.
+pub fn template_generators(generator: &str) -> Result<Vec<&'static str>, Error> {
+    match generator {
+        "scaffold" => Ok(vec!["model", "controller"]),
+        "all" => Ok(vec!["model", "controller", "api", "new", "actions", "page"]),
+        _ => GENERATOR_TEMPLATES
+            .iter()
+            .find(|t| t.generator == generator)
+            .map(|t| vec![t.generator])
+            .ok_or_else(|| {
+                eyre!(
+                    "There are no templates for '{}'. Choose one of: model, controller, api, new, actions, page, scaffold, all",
+                    generator
+                )
+            }),
+    }
+}
+
+/// `config/templates/<generator>/<file>.tera`
+pub fn template_override_path(generator: &str, file: &str) -> PathBuf {
+    Path::new(TEMPLATES_DIR)
+        .join(generator)
+        .join(format!("{}.tera", file))
+}
+
+/// The error and its sources on one line, since Tera puts the useful part in the source.
+fn describe(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    message
+}
+
+/// # Name: render_template
+/// ## Description
+/// Renders the `<generator>/<file>` template with `context`. A project-local
+/// `config/templates/<generator>/<file>.tera` takes the place of the built-in template, so
+/// generated code can follow a project's own conventions. Output is not HTML-escaped.
+/// ## Example
+/// ```
+/// use rustyroad::generators::render_template;
+/// use tera::Context;
+///
+/// let mut context = Context::new();
+/// context.insert("name", "post");
+/// assert!(render_template("api", "mod.rs", &context).unwrap().contains("pub fn configure"));
+/// ```
+pub fn render_template(generator: &str, file: &str, context: &Context) -> Result<String, Error> {
+    let builtin = GENERATOR_TEMPLATES
+        .iter()
+        .find(|t| t.generator == generator && t.file == file)
+        .ok_or_else(|| eyre!("There is no '{}/{}' template", generator, file))?;
+    let path = template_override_path(generator, file);
+    let (name, source) = if Generator::exists(&path) {
+        let source = Generator::read_to_string(&path)
+            .map_err(|e| eyre!("Could not read {}: {}", path.display(), e))?;
+        (path.display().to_string(), source)
+    } else {
+        (
+            format!("{}/{}.tera", generator, file),
+            builtin.source.to_string(),
+        )
+    };
+
+    let mut tera = Tera::default();
+    tera.autoescape_on(Vec::new());
+    tera.add_raw_template(&name, &source)
+        .map_err(|e| eyre!("Could not parse {}: {}", name, describe(&e)))?;
+    tera.render(&name, context)
+        .map_err(|e| eyre!("Could not render {}: {}", name, describe(&e)))
+}
+
+fn write_templates(generator: &str) -> Result<(), Error> {
+    for generator in template_generators(generator)? {
+        for template in GENERATOR_TEMPLATES
+            .iter()
+            .filter(|t| t.generator == generator)
+        {
+            Generator::write_file(
+                template_override_path(template.generator, template.file),
+                template.source,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// # Name: eject_templates
+/// ## Description
+/// Copies the built-in templates of `generator` (`model`, `controller`, `api`, `new`, `actions`,
+/// `page`, `scaffold` or `all`) to `config/templates/<generator>/`, where generators pick them up instead of the
+/// built-in ones. Existing copies are conflicts for the generator runtime.
+pub fn eject_templates(generator: &str) -> Result<(), Error> {
+    if !Path::new("rustyroad.toml").exists() {
+        return Err(eyre!(
+            "Could not find rustyroad.toml. Run this command from your RustyRoad project root."
+        ));
+    }
+    write_templates(generator)?;
+    Generator::say(format!(
+        "\nEdit the templates under {}/; generators use them instead of the built-in ones.",
+        TEMPLATES_DIR
+    ));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::GeneratorOptions;
+
+    #[test]
+    fn test_templates_parse_and_eject() {
+        for template in GENERATOR_TEMPLATES {
+            let mut tera = Tera::default();
+            tera.add_raw_template(template.file, template.source)
+                .unwrap_or_else(|e| panic!("{}/{}: {}", template.generator, template.file, e));
+        }
+        assert_eq!(
+            template_generators("scaffold").unwrap(),
+            ["model", "controller"]
+        );
+        assert!(template_generators("migration").is_err());
+
+        // Planned in a session that is never finished, so nothing reaches the disk
+        Generator::start(GeneratorOptions::default());
+        let result = (|| -> Result<(), Error> {
+            write_templates("api")?;
+            assert!(Generator::exists(template_override_path(
+                "api",
+                "controller.rs"
+            )));
+            // The project's copy wins over the built-in template
+            Generator::update_file(template_override_path("api", "mod.rs"), "// {{ name }}\n")?;
+            let mut context = Context::new();
+            context.insert("name", "post");
+            assert_eq!(render_template("api", "mod.rs", &context)?, "// post\n");
+            Ok(())
+        })();
+        Generator::abort();
+        result.unwrap();
+    }
+}
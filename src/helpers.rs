@@ -154,9 +154,8 @@ pub mod helpers {
             let parent_dir = Path::new(path)
                 .parent()
                 .ok_or("Failed to find parent directory")?;
-            fs::create_dir_all(parent_dir)?;
-            println!("Creating controller at path {}", path);
-            fs::File::create(path)?;
+            crate::generators::Generator::create_dir_all(parent_dir)?;
+            crate::generators::Generator::write_file(path, "")?;
         } else {
             return Err(Box::new(std::io::Error::other(
                 "Controller creation aborted by user",
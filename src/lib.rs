@@ -46,6 +46,9 @@
 #![deny(warnings)]
 #![allow(dead_code)]
 
+// Lets `#[derive(Model)]` output, which names `::rustyroad`, compile inside this crate too
+extern crate self as rustyroad;
+
 use clap::{arg, Arg, Command, Parser};
 use color_eyre::eyre::Result;
 use dialoguer::Confirm;
@@ -56,19 +59,25 @@ use sqlx::postgres::PgConnectOptions;
 use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::ConnectOptions;
 use std::env;
+use std::io::IsTerminal;
 use std::{fs::OpenOptions, io::Write};
 use tokio::io;
 
 pub mod database;
 pub mod features;
 pub mod generators;
+pub mod model;
+pub mod testing;
+pub mod web;
 
 use crate::features::add_feature;
 use database::*;
 
 pub mod helpers;
 pub mod writers;
-use crate::generators::create_directories_for_new_project;
+use crate::generators::{
+    create_directories_for_new_project, eject_templates, Generator, GeneratorOptions,
+};
 use crate::helpers::helpers::get_project_name_from_rustyroad_toml;
 use crate::writers::*;
 /**
@@ -98,6 +107,24 @@ pub enum CRUDType {
     Delete,
 }
 
+impl std::str::FromStr for CRUDType {
+    type Err = Error;
+
+    /// Parses a CRUD action name, accepting the HTTP verbs and handler names as aliases.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "read" | "get" | "index" | "show" => Ok(CRUDType::Read),
+            "create" | "post" | "new" => Ok(CRUDType::Create),
+            "update" | "put" | "edit" => Ok(CRUDType::Update),
+            "delete" | "destroy" => Ok(CRUDType::Delete),
+            other => Err(eyre::eyre!(
+                "Unknown action '{}'. Use read, create, update or delete",
+                other
+            )),
+        }
+    }
+}
+
 /// # Name: Project
 /// ## Type: Struct
 /// ## Description
@@ -174,6 +201,7 @@ pub struct Project {
     pub postcss_config: String,
     pub not_found_html: String,
     pub server_error_html: String,
+    pub forbidden_html: String,
     pub favicon_ico: String,
     pub robots_txt: String,
     pub login_page_html: String,
@@ -551,6 +579,14 @@ static/styles.css
             println!("Failed to write to 404: {:?}", why.to_string());
         });
 
+        write_to_403_html(&project.forbidden_html).unwrap_or_else(|why| {
+            println!("Failed to write to 403: {:?}", why.to_string());
+        });
+
+        write_to_500_html(&project.server_error_html).unwrap_or_else(|why| {
+            println!("Failed to write to 500: {:?}", why.to_string());
+        });
+
         write_to_not_found_controller(&project).expect("Failed to write to not_found controller");
 
         write_to_authenticated_layout(project.clone()).unwrap_or_else(|why| {
@@ -816,27 +852,37 @@ static/styles.css
             .subcommand(
                 Command::new("generate")
                     .about("Generates a new controller, model, or controller")
+                    .args(GeneratorOptions::args())
                     .subcommand(
                         Command::new("controller")
-                            .about("Generates a new controller")
+                            .about("Generates CRUD controller handlers and views for a model")
                             .long_about(
-                                "Interactively creates a new controller for a model.\n\n\
+                                "Generates controller handlers and views for an existing model.\n\n\
                                 PREREQUISITES:\n\
                                  - Must be run from your RustyRoad project root (where rustyroad.toml exists)\n\
-                                 - The model you reference should already exist\n\n\
-                                PROMPTS:\n\
-                                 1. Controller type: GET (Read), POST (Create), PUT (Update), DELETE\n\
-                                 2. Model name to create a controller for\n\n\
+                                 - src/models/<model>.rs must exist (see `rustyroad generate model`)\n\n\
+                                ACTIONS:\n\
+                                 read (index, show), create (new, create), update (edit, update), delete\n\
+                                 Defaults to all four. When the model or --actions is omitted and stdin is\n\
+                                 a terminal, you are prompted for them instead.\n\n\
+                                EXISTING CONTROLLERS:\n\
+                                 If src/controllers/<model>.rs or src/controllers/<model>/<model>.rs exists,\n\
+                                 only the missing handlers, imports, views and routes are added.\n\n\
                                 FILES CREATED:\n\
-                                 - src/controllers/<model_name>/mod.rs\n\
-                                 - src/controllers/<model_name>/<action>.rs\n\n\
+                                 - src/controllers/<model>/mod.rs and <model>.rs\n\
+                                 - src/views/pages/<model>/ (the templates the actions render)\n\
+                                 - src/controllers/<model>/<model>_tests.rs, unless it exists (see `generate test`)\n\
+                                 - Updates src/controllers/mod.rs and src/main.rs\n\n\
                                 EXAMPLE:\n\
-                                 rustyroad generate controller\n\
-                                 # Then follow the prompts\n"
+                                 rustyroad generate controller post --actions read,create\n"
                             )
-                            .subcommand_required(false)
-                            .arg_required_else_help(false)
-                            .allow_external_subcommands(false),
+                            .arg(arg!([model] "The model to generate the controller for"))
+                            .arg(
+                                Arg::new("actions")
+                                    .long("actions")
+                                    .value_name("ACTIONS")
+                                    .help("Comma separated actions: read, create, update, delete")
+                            ),
                     )
                     .subcommand(
                         Command::new("model")
@@ -847,19 +893,136 @@ static/styles.css
                                   Reads database type from ./rustyroad.toml to generate database-specific code.\n\n\
                                 PREREQUISITES:\n\
                                   - Must be run from your RustyRoad project root\n\n\
+                                FIELDS:\n\
+                                  name:type[:constraints], the same syntax as `rustyroad migration generate`,\n\
+                                  e.g. author_id:integer:references=users. The CREATE TABLE migration is written\n\
+                                  from the same fields; pass --skip-migration when the table already exists.\n\
+                                  When no fields are given and stdin is a terminal, you are prompted for them.\n\n\
+                                FROM AN EXISTING TABLE:\n\
+                                  --from-table <table> introspects the live table instead. Nullable columns become\n\
+                                  Option<T>; timestamps, uuid, json and decimal columns use chrono, uuid::Uuid,\n\
+                                  serde_json::Value and rust_decimal::Decimal. The model gets all, find, insert,\n\
+                                  update and delete keyed on the table's primary key.\n\n\
                                 FILES CREATED:\n\
-                                  - src/models/<name>.rs\n\
+                                  - config/database/migrations/<timestamp>-create_<names>/{up.sql,down.sql}\n\
+                                  - src/models/<name>.rs (sqlx FromRow struct with all/find/create/update/delete)\n\
                                   - Updates src/models/mod.rs\n\n\
                                 EXAMPLE:\n\
-                                  rustyroad generate model user\n\
-                                  rustyroad generate model blog_post\n"
+                                  rustyroad generate model post title:string:not_null body:text\n\
+                                  rustyroad generate model article title:string:not_null author_id:integer:references=users\n\
+                                  rustyroad generate model blog_post title:string published:boolean --skip-migration\n\
+                                  rustyroad generate model customer --from-table customers\n"
                             )
                             .arg(arg!(<name> "The name of the model"))
-                            .subcommand_required(false)
-                            .arg_required_else_help(true)
-                            .allow_external_subcommands(false),
+                            .arg(
+                                Arg::new("fields")
+                                    .num_args(1..)
+                                    .help("Fields as name:type[:constraints]")
+                            )
+                            .arg(
+                                Arg::new("from-table")
+                                    .long("from-table")
+                                    .value_name("TABLE")
+                                    .conflicts_with("fields")
+                                    .help("Generate the model from an existing table in the configured database")
+                            )
+                            .arg(
+                                Arg::new("skip-migration")
+                                    .long("skip-migration")
+                                    .action(clap::ArgAction::SetTrue)
+                                    .help("Only write the model, for a table that already exists")
+                            )
+                            .arg_required_else_help(true),
+                    )
+                    .subcommand(
+                        Command::new("scaffold")
+                            .about("Generates a migration, model, CRUD controllers and views in one step")
+                            .long_about(
+                                "Generates a complete CRUD resource without any prompts.\n\n\
+                                CONFIG:\n\
+                                  Reads the database type from ./rustyroad.toml so the migration and model SQL match your backend.\n\n\
+                                FIELDS:\n\
+                                  name:type[:constraints], the same syntax as `rustyroad migration generate`.\n\
+                                  Types: string, text, integer, bigint, boolean, float, date, datetime, time\n\
+                                  Columns are nullable unless marked not_null. An `id` primary key is added automatically.\n\n\
+                                FILES CREATED:\n\
+                                  - config/database/migrations/<timestamp>-create_<names>/{up.sql,down.sql}\n\
+                                  - src/models/<name>.rs (sqlx FromRow struct with all/find/create/update/delete)\n\
+                                  - src/controllers/<name>/ (index, show, new, edit, create, update, delete)\n\
+                                  - src/views/pages/<name>/ (index, show, new, edit and a shared _form)\n\
+                                  - src/controllers/<name>/<name>_tests.rs (see `generate test`)\n\
+                                  - Updates src/models/mod.rs, src/controllers/mod.rs and src/main.rs\n\n\
+                                EXAMPLE:\n\
+                                  rustyroad generate scaffold post title:string:not_null body:text published:boolean\n\
+                                  rustyroad migration run create_posts\n"
+                            )
+                            .arg(arg!(<name> "The singular name of the resource, e.g. post"))
+                            .arg(
+                                Arg::new("fields")
+                                    .help("Fields as name:type[:constraints]")
+                                    .num_args(1..)
+                                    .required(true),
+                            )
+                            .arg_required_else_help(true),
+                    )
+                    .subcommand(
+                        Command::new("api")
+                            .about("Generates a JSON API resource under /api")
+                            .long_about(
+                                "Generates JSON handlers for a model at /api/<names>.\n\n\
+                                CONFIG:\n\
+                                  Reads the database type from ./rustyroad.toml so the queries match your backend.\n\n\
+                                FIELDS:\n\
+                                  name:type[:constraints], as for `generate scaffold`. With fields, the migration and\n\
+                                  model are generated first; without, src/models/<name>.rs must already exist.\n\n\
+                                ENDPOINTS:\n\
+                                  GET    /api/<names>       ?limit=&offset= or ?after=<id>, ?sort=[-]field, ?field=value\n\
+                                  GET    /api/<names>/<id>  200, or 404\n\
+                                  POST   /api/<names>       201 with a Location header, or 422 with field errors\n\
+                                  PUT    /api/<names>/<id>  200; PATCH is the same, missing fields are kept\n\
+                                  DELETE /api/<names>/<id>  204, or 404\n\
+                                  Errors use one envelope: {\"error\": {\"status\", \"message\", \"fields\"}}.\n\
+                                  The handlers do no authentication; wrap the /api scope in your own middleware.\n\n\
+                                FILES CREATED:\n\
+                                  - src/controllers/api/<name>.rs\n\
+                                  - src/controllers/api/mod.rs (error envelope, on first use)\n\
+                                  - Updates src/controllers/mod.rs and mounts the /api scope in src/main.rs\n\n\
+                                EXAMPLE:\n\
+                                  rustyroad generate api post title:string:not_null body:text\n\
+                                  rustyroad generate api post\n"
+                            )
+                            .arg(arg!(<name> "The singular name of the resource, e.g. post"))
+                            .arg(
+                                Arg::new("fields")
+                                    .num_args(1..)
+                                    .help("Fields as name:type[:constraints]; omit them to use an existing model")
+                            )
+                            .arg_required_else_help(true),
+                    )
+                    .subcommand(
+                        Command::new("test")
+                            .about("Generates integration tests for a controller")
+                            .long_about(
+                                "Writes actix_web::test tests for the handlers of a generated controller.\n\n\
+                                PREREQUISITES:\n\
+                                  - Must be run from your RustyRoad project root (where rustyroad.toml exists)\n\
+                                  - src/controllers/<controller>.rs or src/controllers/<controller>/<controller>.rs\n\
+                                    and src/models/<controller>.rs must exist\n\n\
+                                TESTS:\n\
+                                  For each action the controller has, signed in: the pages render (200), forms save\n\
+                                  (303) or are re-rendered with their errors (422), missing records are 404 and\n\
+                                  records are deleted. Signed out, every route redirects to /login. Each test runs\n\
+                                  on its own rustyroad::testing::TestDatabase with the migrations and the fixtures in\n\
+                                  tests/fixtures, and creates its records with factories. Run them with `cargo test`.\n\n\
+                                FILES CREATED:\n\
+                                  - src/controllers/<controller>/<controller>_tests.rs, next to the controller\n\
+                                  - Declares it with #[cfg(test)] in the controller's mod.rs\n\n\
+                                EXAMPLE:\n\
+                                  rustyroad generate test post\n"
+                            )
+                            .arg(arg!(<controller> "The controller to test, named after its model, e.g. post"))
+                            .arg_required_else_help(true),
                     )
-                    .subcommand(Command::new("controller").about("Generates a new controller"))
                     .after_help(
                         "EXAMPLES:
                 To generate a new controller:
@@ -868,11 +1031,124 @@ static/styles.css
                     rustyroad generate model <name>
                 To generate a new controller:
                     rustyroad generate controller <name>
+                To generate a full CRUD resource:
+                    rustyroad generate scaffold post title:string body:text published:boolean
+                To generate tests for a controller:
+                    rustyroad generate test post
                 To generate a new migration:
                     rustyroad generate migration <name>",
                     )
                     .subcommand_required(true),
             )
+            .subcommand(
+                Command::new("destroy")
+                    .alias("d")
+                    .about("Removes what a generator created")
+                    .long_about(
+                        "Reverses `rustyroad generate`: deletes the generated files, removes their `pub mod` lines\n\
+                        from mod.rs and unregisters their `.service(..)` calls from src/main.rs.\n\n\
+                        Migrations that are applied are refused; roll them back first with\n\
+                        `rustyroad migration rollback <name>`.",
+                    )
+                    .arg(GeneratorOptions::pretend_arg())
+                    .subcommand(
+                        Command::new("controller")
+                            .about("Deletes a controller, its views and its routes")
+                            .arg(arg!(<name> "The name of the controller"))
+                            .arg_required_else_help(true),
+                    )
+                    .subcommand(
+                        Command::new("model")
+                            .about("Deletes a model and its create-table migration")
+                            .arg(arg!(<name> "The name of the model"))
+                            .arg_required_else_help(true),
+                    )
+                    .subcommand(
+                        Command::new("scaffold")
+                            .about("Deletes a scaffold's model, migration, controller, views and routes")
+                            .arg(arg!(<name> "The name of the scaffold"))
+                            .arg_required_else_help(true),
+                    )
+                    .subcommand(
+                        Command::new("migration")
+                            .about("Deletes a migration that is not applied")
+                            .arg(arg!(<name> "The migration name, without the timestamp"))
+                            .arg_required_else_help(true),
+                    )
+                    .after_help(
+                        "EXAMPLES:
+                    rustyroad destroy scaffold post
+                    rustyroad destroy controller post --pretend
+                    rustyroad destroy migration create_tags",
+                    )
+                    .subcommand_required(true),
+            )
+            .subcommand(
+                Command::new("templates")
+                    .about("Customizes the code generators write")
+                    .long_about(
+                        "The model, controller, scaffold and api generators, the files of `rustyroad new` (new),\n\
+                        the single-action controller writers (actions) and the page builder controllers (page)\n\
+                        render Tera templates. A copy under\n\
+                        config/templates/<generator>/ replaces the built-in template, so generated code can\n\
+                        follow the project's own conventions. Each template lists its context in a comment\n\
+                        at the top.",
+                    )
+                    .subcommand(
+                        Command::new("eject")
+                            .about("Copies the built-in templates into config/templates/")
+                            .arg(
+                                arg!(<generator> "model, controller, api, new, actions, page, scaffold (model and controller) or all")
+                                    .value_parser([
+                                        "model",
+                                        "controller",
+                                        "api",
+                                        "new",
+                                        "actions",
+                                        "page",
+                                        "scaffold",
+                                        "all",
+                                    ]),
+                            )
+                            .args(GeneratorOptions::args())
+                            .arg_required_else_help(true),
+                    )
+                    .after_help(
+                        "EXAMPLES:
+                    rustyroad templates eject scaffold
+                    rustyroad templates eject api --force",
+                    )
+                    .subcommand_required(true),
+            )
+            .subcommand(
+                Command::new("routes")
+                    .about("Lists the routes of the app")
+                    .long_about(
+                        "Lists every route registered from src/main.rs: its method, full path (with the\n\
+                        prefixes of the web::scope(..)s it is mounted in), handler and guards, and whether\n\
+                        the handler takes an Identity.\n\n\
+                        Also lists the handlers under src/controllers that are never registered, and the\n\
+                        registrations whose handler does not exist.",
+                    )
+                    .arg(
+                        Arg::new("controller")
+                            .long("controller")
+                            .value_name("NAME")
+                            .help("Only list the routes of this controller")
+                    )
+                    .arg(
+                        Arg::new("format")
+                            .long("format")
+                            .value_name("FORMAT")
+                            .help("Output format (text, json)")
+                            .value_parser(["text", "json"])
+                    )
+                    .after_help(
+                        "EXAMPLES:
+                    rustyroad routes
+                    rustyroad routes --controller post --format json",
+                    ),
+            )
             .subcommand(
                 Command::new("migration")
                     .about("Database schema migrations")
@@ -888,12 +1164,15 @@ static/styles.css
                             .alias("create")
                             .alias("make")
                             .about("Generate a new migration folder with up.sql and down.sql")
+                            .args(GeneratorOptions::args())
                             .long_about(
 "Generates UP and DOWN SQL migration files for creating a new table.
 Specify the table name and the columns with their types and optional constraints.
 
 Column Format: name:type[:constraints]
 Constraints are comma-separated (e.g., primary_key, not_null, unique, default=value).
+Shortcuts add the columns of model behaviours: timestamps (created_at, updated_at),
+soft_delete (deleted_at) and lock_version. add_timestamps_to_<table> and the like add them too.
 
 Example:
 rustyroad migration generate create_users id:serial:primary_key email:string:not_null,unique created_at:timestamp:default=now"
@@ -1062,6 +1341,7 @@ rustyroad migration generate create_users id:serial:primary_key email:string:not
                     .subcommand(
                         Command::new("add")
                             .about("Adds a feature to the project")
+                            .args(GeneratorOptions::args())
                             .subcommand(
                                 Command::new("grapesjs").about("Adds grapesjs to the project")
                             )
@@ -1155,9 +1435,171 @@ Example:\n\
                                  - Must be run from your RustyRoad project root\n\
                                  - Database must be reachable\n\n\
                                 Supports: PostgreSQL, MySQL, SQLite\n\n\
+                                DIAGRAMS:\n\
+                                 --format mermaid|dot|plantuml prints an entity-relationship diagram\n\
+                                 built from the introspected tables, columns and foreign keys.\n\
+                                 Mermaid renders directly in GitHub markdown.\n\n\
                                 EXAMPLE:\n\
                                  rustyroad db schema\n\
-                                 ENVIRONMENT=prod rustyroad db schema\n"
+                                 ENVIRONMENT=prod rustyroad db schema\n\
+                                 rustyroad db schema --format mermaid > docs/schema.mmd\n\
+                                 rustyroad db schema --format dot --tables users,posts | dot -Tsvg > schema.svg\n"
+                            )
+                            .arg(
+                                Arg::new("format")
+                                    .long("format")
+                                    .value_name("FORMAT")
+                                    .help("Output format (text, json, mermaid, dot, plantuml)")
+                                    .value_parser(["text", "json", "mermaid", "dot", "plantuml"])
+                            )
+                            .arg(
+                                Arg::new("tables")
+                                    .long("tables")
+                                    .value_name("TABLES")
+                                    .help("Comma-separated list of tables to include")
+                                    .value_delimiter(',')
+                            )
+                    )
+                    .subcommand(
+                        Command::new("export")
+                            .about("Export table data to NDJSON or CSV files")
+                            .long_about(
+                                "Writes each table to <DIR>/<table>.ndjson (or .csv) plus a manifest.json\n\
+                                describing the columns and foreign keys. The dump can be imported into any\n\
+                                supported backend with `rustyroad db import`.\n\n\
+                                CONFIG:\n\
+                                 Reads from ./rustyroad.toml (or ./rustyroad.<ENVIRONMENT>.toml if ENVIRONMENT is set).\n\n\
+                                NOTES:\n\
+                                 RustyRoad's own _rustyroad_* tables are skipped unless listed in --tables.\n\
+                                 In CSV files an empty unquoted field is NULL and \"\" is an empty string.\n\n\
+                                EXAMPLE:\n\
+                                 rustyroad db export --dir dump/\n\
+                                 rustyroad db export --dir dump/ --tables users,posts --format csv\n"
+                            )
+                            .arg(
+                                Arg::new("dir")
+                                    .long("dir")
+                                    .value_name("DIR")
+                                    .help("Directory to write the dump to")
+                                    .required(true)
+                            )
+                            .arg(
+                                Arg::new("tables")
+                                    .long("tables")
+                                    .value_name("TABLES")
+                                    .help("Comma-separated list of tables to export (default: all)")
+                                    .value_delimiter(',')
+                            )
+                            .arg(
+                                Arg::new("format")
+                                    .long("format")
+                                    .value_name("FORMAT")
+                                    .help("Data file format (ndjson, csv)")
+                                    .value_parser(["ndjson", "csv"])
+                                    .default_value("ndjson")
+                            )
+                            .arg(
+                                Arg::new("batch-size")
+                                    .long("batch-size")
+                                    .value_name("ROWS")
+                                    .help("Rows fetched per query")
+                                    .value_parser(clap::value_parser!(usize))
+                            )
+                    )
+                    .subcommand(
+                        Command::new("import")
+                            .about("Import table data written by `db export`")
+                            .long_about(
+                                "Loads a dump written by `rustyroad db export` into the configured database.\n\
+                                The source and target backends may differ, e.g. SQLite to PostgreSQL.\n\n\
+                                BEHAVIOR:\n\
+                                 - Tables are loaded in foreign-key dependency order\n\
+                                 - Rows are inserted in batches; values are coerced to the target column types\n\
+                                 - Columns missing in the target table are skipped with a warning\n\
+                                 - Sequences and auto-increment counters are moved past the imported ids\n\
+                                 - A summary of rows written per table is printed\n\n\
+                                PREREQUISITES:\n\
+                                 - The tables must exist: run `rustyroad migration all` first\n\n\
+                                EXAMPLE:\n\
+                                 rustyroad db export --dir dump/\n\
+                                 ENVIRONMENT=prod rustyroad db import --dir dump/\n"
+                            )
+                            .arg(
+                                Arg::new("dir")
+                                    .long("dir")
+                                    .value_name("DIR")
+                                    .help("Directory containing manifest.json")
+                                    .required(true)
+                            )
+                            .arg(
+                                Arg::new("tables")
+                                    .long("tables")
+                                    .value_name("TABLES")
+                                    .help("Comma-separated list of tables to import (default: all)")
+                                    .value_delimiter(',')
+                            )
+                            .arg(
+                                Arg::new("batch-size")
+                                    .long("batch-size")
+                                    .value_name("ROWS")
+                                    .help("Maximum rows per INSERT statement")
+                                    .value_parser(clap::value_parser!(usize))
+                            )
+                    )
+                    .subcommand(
+                        Command::new("stats")
+                            .about("Show row counts, table and index sizes and bloat hints")
+                            .long_about(
+                                "Prints a health overview of the database configured in rustyroad.toml.\n\n\
+                                REPORTS:\n\
+                                 - Estimated row counts per table (exact COUNT(*) with --exact)\n\
+                                 - Table and index size on disk\n\
+                                 - PostgreSQL: dead rows, last vacuum and last analyze\n\
+                                 - PostgreSQL, MySQL: indexes that have never been used\n\
+                                 - SQLite: page count and freelist size\n\
+                                 - Hints for VACUUM / OPTIMIZE TABLE and dropping unused indexes\n\n\
+                                CONFIG:\n\
+                                 Reads from ./rustyroad.toml (or ./rustyroad.<ENVIRONMENT>.toml if ENVIRONMENT is set).\n\n\
+                                EXAMPLE:\n\
+                                 rustyroad db stats\n\
+                                 rustyroad db stats --exact --format json\n"
+                            )
+                            .arg(
+                                Arg::new("exact")
+                                    .long("exact")
+                                    .help("Also count the rows of every table exactly (can be slow)")
+                                    .action(clap::ArgAction::SetTrue)
+                            )
+                            .arg(
+                                Arg::new("format")
+                                    .long("format")
+                                    .value_name("FORMAT")
+                                    .help("Output format (text, json)")
+                                    .value_parser(["text", "json"])
+                            )
+                    )
+                    .subcommand(
+                        Command::new("console")
+                            .about("Open an interactive SQL console")
+                            .long_about(
+                                "Starts an interactive SQL console for the database configured in rustyroad.toml.\n\
+                                No psql, mysql or sqlite3 client needs to be installed.\n\n\
+                                CONFIG:\n\
+                                 Reads from ./rustyroad.toml (or ./rustyroad.<ENVIRONMENT>.toml if ENVIRONMENT is set).\n\n\
+                                USAGE:\n\
+                                 Statements end with ';' and may span several lines.\n\
+                                 Tab completes table names, column names (also table.column) and SQL keywords.\n\
+                                 History is saved to ~/.rustyroad_console_history.\n\n\
+                                META-COMMANDS:\n\
+                                 \\dt            List tables\n\
+                                 \\d <table>     Describe a table's columns and foreign keys\n\
+                                 \\x             Toggle expanded output\n\
+                                 \\timing        Toggle statement timing\n\
+                                 \\i <file.sql>  Execute statements from a file\n\
+                                 \\q             Quit\n\n\
+                                EXAMPLE:\n\
+                                 rustyroad db console\n\
+                                 ENVIRONMENT=prod rustyroad db console\n"
                             )
                     )
                     .subcommand_required(true)
@@ -1175,10 +1617,31 @@ Example:\n\
                          - Database must be reachable\n\n\
                         EXAMPLES:\n\
                          rustyroad query \"SELECT * FROM users\"\n\
-                         ENVIRONMENT=prod rustyroad query \"SELECT COUNT(*) FROM orders\"\n\n\
-                        WARNING: Destructive queries (UPDATE, DELETE, DROP) execute immediately with no confirmation.\n"
+                         ENVIRONMENT=prod rustyroad query \"SELECT COUNT(*) FROM orders\"\n\
+                         rustyroad query --explain \"SELECT * FROM posts WHERE user_id = 1\"\n\n\
+                        EXPLAIN:\n\
+                         --explain prints the query plan as a tree instead of running the query.\n\
+                         --explain-analyze also runs the query and reports actual timings.\n\
+                         Uses EXPLAIN (FORMAT JSON) on PostgreSQL, EXPLAIN FORMAT=JSON on MySQL and\n\
+                         EXPLAIN QUERY PLAN on SQLite. Sequential scans, unused indexes and the\n\
+                         estimated cost are highlighted. Combine with --format json for machine output.\n\n\
+                        WARNING: Destructive queries (UPDATE, DELETE, DROP) execute immediately with no confirmation.\n\
+                        --explain-analyze executes the statement too, including any writes.\n"
                     )
                     .arg(arg!(<QUERY> "SQL query to execute"))
+                    .arg(
+                        Arg::new("explain")
+                            .long("explain")
+                            .help("Show the query plan instead of running the query")
+                            .action(clap::ArgAction::SetTrue)
+                            .conflicts_with("explain-analyze")
+                    )
+                    .arg(
+                        Arg::new("explain-analyze")
+                            .long("explain-analyze")
+                            .help("Run the query and show the plan with actual timings")
+                            .action(clap::ArgAction::SetTrue)
+                    )
                     .arg_required_else_help(true)
             )
     }
@@ -1392,56 +1855,163 @@ Example:\n\
             }
             // Generate new controllers, models, controllers and migrations
             Some(("generate", matches)) => match matches.subcommand() {
-                Some(("controller", _matches)) => {
-                    // because we removed the arguments from the user, we need to auto define the controller name below
-                    println!("What type of controller would you like to create?");
-                    println!("1. GET");
-                    println!("2. POST");
-                    println!("3. PUT");
-                    println!("4. DELETE");
-                    let mut controller_type_choice = String::new();
-                    std::io::stdin()
-                        .read_line(&mut controller_type_choice)
-                        .expect("Failed to read line");
-                    let controller_type_choice_int =
-                        controller_type_choice.trim().parse::<u32>().unwrap();
-                    let crud_type = match controller_type_choice_int {
-                        1 => CRUDType::Read,
-                        2 => CRUDType::Create,
-                        3 => CRUDType::Update,
-                        4 => CRUDType::Delete,
-                        _ => {
-                            println!("Invalid controller type choice");
+                Some(("controller", matches)) => {
+                    let interactive = std::io::stdin().is_terminal();
+                    let model_name = match matches.get_one::<String>("model") {
+                        Some(model) => model.clone(),
+                        None if interactive => match prompt_for_model_name() {
+                            Ok(model) => model,
+                            Err(e) => {
+                                println!("Error reading the model name: {}", e);
+                                return;
+                            }
+                        },
+                        None => {
+                            println!(
+                                "Missing model name. Usage: rustyroad generate controller <model> --actions read,create,update,delete"
+                            );
                             return;
                         }
                     };
+                    let actions = match matches.get_one::<String>("actions") {
+                        Some(actions) => parse_crud_actions(actions),
+                        None if interactive => prompt_for_crud_actions(),
+                        None => Ok(CRUD_ACTIONS.to_vec()),
+                    };
 
-                    // ask the user the name of the controller
-                    println!(
-                        "What is the name of the model you want to create a controller for?: "
-                    );
-                    println!("In order to work out of the box, ensure the model already exists.");
-                    let mut input = String::new();
-                    std::io::stdin().read_line(&mut input).unwrap();
-                    // this pattern needs to be repeated for each CRUD type
-                    let model_name = input.trim();
-
-                    create_new_controller(model_name.to_string(), crud_type)
-                        .await
-                        .expect("Error creating controller");
+                    let options = GeneratorOptions::from_matches(matches);
+                    actions
+                        .and_then(|actions| {
+                            Generator::run(options, || generate_controller(&model_name, &actions))
+                        })
+                        .map(|_| ())
+                        .unwrap_or_else(|e| println!("Error generating controller: {}", e));
                 }
                 Some(("model", matches)) => {
-                    // derive the name of the model from the arguments
-                    let model_name = matches.get_one::<String>("name").unwrap().as_str();
+                    let model_name = matches.get_one::<String>("name").unwrap();
+                    let options = GeneratorOptions::from_matches(matches);
+                    if let Some(table) = matches.get_one::<String>("from-table") {
+                        Generator::run_async(options, generate_model_from_table(model_name, table))
+                            .await
+                            .map(|_| ())
+                            .unwrap_or_else(|e| println!("Error generating model: {}", e));
+                        return;
+                    }
+                    let mut fields: Vec<String> = matches
+                        .get_many::<String>("fields")
+                        .unwrap_or_default()
+                        .cloned()
+                        .collect();
+                    if fields.is_empty() {
+                        if !std::io::stdin().is_terminal() {
+                            println!(
+                                "Missing fields. Usage: rustyroad generate model <name> title:string:not_null ..."
+                            );
+                            return;
+                        }
+                        match prompt_for_model_fields() {
+                            Ok(prompted) => fields = prompted,
+                            Err(e) => {
+                                println!("Error reading the fields: {}", e);
+                                return;
+                            }
+                        }
+                    }
 
-                    create_base_model(model_name)
-                        .await
-                        .expect("Error creating model");
+                    Generator::run(options, || {
+                        generate_model(model_name, &fields, matches.get_flag("skip-migration"))
+                    })
+                    .map(|_| ())
+                    .unwrap_or_else(|e| println!("Error generating model: {}", e));
+                }
+                Some(("scaffold", matches)) => {
+                    let name = matches.get_one::<String>("name").unwrap();
+                    let fields: Vec<String> = matches
+                        .get_many::<String>("fields")
+                        .unwrap_or_default()
+                        .cloned()
+                        .collect();
+
+                    Generator::run(GeneratorOptions::from_matches(matches), || {
+                        generate_scaffold(name, &fields)
+                    })
+                    .map(|_| ())
+                    .unwrap_or_else(|e| println!("Error generating scaffold: {}", e));
+                }
+                Some(("api", matches)) => {
+                    let name = matches.get_one::<String>("name").unwrap();
+                    let fields: Vec<String> = matches
+                        .get_many::<String>("fields")
+                        .unwrap_or_default()
+                        .cloned()
+                        .collect();
+
+                    Generator::run(GeneratorOptions::from_matches(matches), || {
+                        generate_api(name, &fields)
+                    })
+                    .map(|_| ())
+                    .unwrap_or_else(|e| println!("Error generating API: {}", e));
+                }
+                Some(("test", matches)) => {
+                    let controller = matches.get_one::<String>("controller").unwrap();
+                    Generator::run(GeneratorOptions::from_matches(matches), || {
+                        generate_test(controller)
+                    })
+                    .map(|_| ())
+                    .unwrap_or_else(|e| println!("Error generating tests: {}", e));
                 }
                 _ => {
                     println!("Invalid generate choice");
                 }
             },
+            Some(("destroy", matches)) => {
+                let options = GeneratorOptions::from_matches(matches);
+                let result = match matches.subcommand() {
+                    Some(("controller", matches)) => {
+                        let name = matches.get_one::<String>("name").unwrap();
+                        Generator::run(options, || destroy_controller(name))
+                    }
+                    Some(("model", matches)) => {
+                        let name = matches.get_one::<String>("name").unwrap();
+                        Generator::run_async(options, destroy_model(name)).await
+                    }
+                    Some(("scaffold", matches)) => {
+                        let name = matches.get_one::<String>("name").unwrap();
+                        Generator::run_async(options, destroy_scaffold(name)).await
+                    }
+                    Some(("migration", matches)) => {
+                        let name = matches.get_one::<String>("name").unwrap();
+                        Generator::run_async(options, destroy_migration(name)).await
+                    }
+                    _ => {
+                        println!("Invalid destroy choice");
+                        return;
+                    }
+                };
+                if let Err(e) = result {
+                    println!("Error: {}", e);
+                }
+            }
+            Some(("templates", matches)) => {
+                if let Some(("eject", matches)) = matches.subcommand() {
+                    let generator = matches.get_one::<String>("generator").unwrap();
+                    Generator::run(GeneratorOptions::from_matches(matches), || {
+                        eject_templates(generator)
+                    })
+                    .map(|_| ())
+                    .unwrap_or_else(|e| println!("Error ejecting templates: {}", e));
+                }
+            }
+            Some(("routes", matches)) => {
+                let routes_format = matches
+                    .get_one::<String>("format")
+                    .map(|f| f.as_str())
+                    .unwrap_or(format);
+                let controller = matches.get_one::<String>("controller").map(|c| c.as_str());
+                if let Err(e) = print_routes(controller, routes_format) {
+                    println!("Error: {}", e);
+                }
+            }
             // Migration Case - Can generate migrations, run migrations, and rollback migrations
             Some(("migration", matches)) => {
                 // Check for rogue migrations at the start of any migration command
@@ -1461,9 +2031,11 @@ Example:\n\
 
                         println!("Generating migration: {}", name);
                         // Pass the captured columns vector to the updated create_migration function
-                        create_migration(&name, columns)
-                            .await
-                            .expect("Error creating migration");
+                        Generator::run_async(GeneratorOptions::from_matches(matches), async {
+                            create_migration(&name, columns).await.map_err(Error::from)
+                        })
+                        .await
+                        .expect("Error creating migration");
                     }
                     Some(("all", _)) => {
                         Self::print_config_info();
@@ -1651,7 +2223,7 @@ Example:\n\
             // Add Feature Case
             Some(("feature", matches)) => match matches.subcommand() {
                 Some(("add", matches)) => match matches.subcommand() {
-                    Some(("grapesjs", _matches)) => {
+                    Some(("grapesjs", matches)) => {
                         // ask the user if they are sure they want to add grapesjs to the project
                         // if they are sure, add grapesjs to the project
                         // if they are not sure, cancel the command
@@ -1662,13 +2234,16 @@ Example:\n\
                             .expect("Error adding grapesjs to the project: ");
 
                         if confirmation {
-                            add_feature("grapesjs".to_string())
-                                .await
+                            add_feature(
+                                "grapesjs".to_string(),
+                                GeneratorOptions::from_matches(matches),
+                            )
+                            .await
                                 .expect("Error adding grapesjs to the project");
                         }
                     }
-                    Some(("non_interactive_grapesjs", _matches)) => {
-                        add_feature("grapesjs".to_string())
+                    Some(("non_interactive_grapesjs", matches)) => {
+                        add_feature("grapesjs".to_string(), GeneratorOptions::from_matches(matches))
                             .await
                             .expect("Error adding grapesjs to the project");
                     }
@@ -1805,10 +2380,75 @@ Example:\n\
                 }
             }
             Some(("db", matches)) => match matches.subcommand() {
-                Some(("schema", _)) => {
-                    inspect_schema(format)
+                Some(("schema", matches)) => {
+                    let schema_format = matches
+                        .get_one::<String>("format")
+                        .map(|f| f.as_str())
+                        .unwrap_or(format);
+                    let tables: Vec<String> = matches
+                        .get_many::<String>("tables")
+                        .map(|vals| vals.map(|s| s.trim().to_string()).collect())
+                        .unwrap_or_default();
+
+                    match schema_format.parse::<DiagramFormat>() {
+                        Ok(diagram_format) => print_er_diagram(diagram_format, &tables)
+                            .await
+                            .unwrap_or_else(|e| println!("Error rendering diagram: {}", e)),
+                        Err(_) => inspect_schema(schema_format, &tables)
+                            .await
+                            .unwrap_or_else(|e| println!("Error inspecting schema: {}", e)),
+                    }
+                }
+                Some(("export", matches)) => {
+                    let dir = matches.get_one::<String>("dir").unwrap();
+                    let tables: Vec<String> = matches
+                        .get_many::<String>("tables")
+                        .map(|vals| vals.map(|s| s.trim().to_string()).collect())
+                        .unwrap_or_default();
+                    let data_format = matches
+                        .get_one::<String>("format")
+                        .and_then(|f| f.parse::<DataFormat>().ok())
+                        .unwrap_or(DataFormat::Ndjson);
+                    let batch_size = matches
+                        .get_one::<usize>("batch-size")
+                        .copied()
+                        .unwrap_or(DEFAULT_BATCH_SIZE);
+
+                    if let Err(e) =
+                        export_data(std::path::Path::new(dir), &tables, data_format, batch_size).await
+                    {
+                        println!("Error exporting data: {}", e);
+                    }
+                }
+                Some(("import", matches)) => {
+                    let dir = matches.get_one::<String>("dir").unwrap();
+                    let tables: Vec<String> = matches
+                        .get_many::<String>("tables")
+                        .map(|vals| vals.map(|s| s.trim().to_string()).collect())
+                        .unwrap_or_default();
+                    let batch_size = matches
+                        .get_one::<usize>("batch-size")
+                        .copied()
+                        .unwrap_or(DEFAULT_BATCH_SIZE);
+
+                    match import_data(std::path::Path::new(dir), &tables, batch_size).await {
+                        Ok(summary) => print_import_summary(&summary),
+                        Err(e) => println!("Error importing data: {}", e),
+                    }
+                }
+                Some(("stats", matches)) => {
+                    let stats_format = matches
+                        .get_one::<String>("format")
+                        .map(|f| f.as_str())
+                        .unwrap_or(format);
+                    print_database_stats(matches.get_flag("exact"), stats_format)
                         .await
-                        .unwrap_or_else(|e| println!("Error inspecting schema: {}", e));
+                        .unwrap_or_else(|e| println!("Error collecting stats: {}", e));
+                }
+                Some(("console", _)) => {
+                    run_console()
+                        .await
+                        .unwrap_or_else(|e| println!("Error running console: {}", e));
                 }
                 _ => {
                     println!("Invalid db command");
@@ -1816,9 +2456,22 @@ Example:\n\
             },
             Some(("query", matches)) => {
                 let query = matches.get_one::<String>("QUERY").unwrap();
-                execute_query(query, format)
-                    .await
-                    .unwrap_or_else(|e| println!("Error executing query: {}", e));
+                let explain_mode = if matches.get_flag("explain-analyze") {
+                    Some(ExplainMode::Analyze)
+                } else if matches.get_flag("explain") {
+                    Some(ExplainMode::Plan)
+                } else {
+                    None
+                };
+
+                match explain_mode {
+                    Some(mode) => explain_query(query, mode, format)
+                        .await
+                        .unwrap_or_else(|e| println!("Error explaining query: {}", e)),
+                    None => execute_query(query, format)
+                        .await
+                        .unwrap_or_else(|e| println!("Error executing query: {}", e)),
+                }
             }
             _ => {
                 println!("Invalid choice");
@@ -27,7 +27,11 @@
 //! ```
 
 use regex::Regex;
-use rustyroad::database::{Database, DatabaseConnection};
+use rustyroad::database::{
+    collect_database_stats, explain_statement, introspect_schema, render_er_diagram, Database,
+    DatabaseConnection, DiagramFormat, ExplainMode,
+};
+use rustyroad::writers::collect_routes;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use sqlx::{Column, Row};
@@ -107,7 +111,7 @@ impl McpServer {
         vec![
             Tool {
                 name: "rustyroad_query".to_string(),
-                description: "Execute a SQL query against the RustyRoad database. Returns results as JSON. Use this instead of psql or direct database connections. Always use this for database queries.".to_string(),
+                description: "Execute a SQL query against the RustyRoad database. Returns results as JSON. Use this instead of psql or direct database connections. Always use this for database queries. Set explain to get the normalized query plan (with sequential scans, unused indexes and estimated cost flagged) instead of the rows.".to_string(),
                 input_schema: json!({
                     "type": "object",
                     "properties": {
@@ -115,6 +119,14 @@ impl McpServer {
                             "type": "string",
                             "description": "The SQL query to execute"
                         },
+                        "explain": {
+                            "type": "boolean",
+                            "description": "Optional: Return the query plan instead of running the query"
+                        },
+                        "analyze": {
+                            "type": "boolean",
+                            "description": "Optional: With explain, also run the query and report actual timings (executes writes too)"
+                        },
                         "env": {
                             "type": "string",
                             "description": "Environment to use (dev, prod, test). Defaults to current environment.",
@@ -126,7 +138,7 @@ impl McpServer {
             },
             Tool {
                 name: "rustyroad_schema".to_string(),
-                description: "Get the database schema (tables and columns). Use this to understand what tables exist before writing queries.".to_string(),
+                description: "Get the database schema (tables and columns). Use this to understand what tables exist before writing queries. Set format to mermaid, dot or plantuml to get an entity-relationship diagram built from tables, columns and foreign keys.".to_string(),
                 input_schema: json!({
                     "type": "object",
                     "properties": {
@@ -134,6 +146,17 @@ impl McpServer {
                             "type": "string",
                             "description": "Optional: Get schema for a specific table only"
                         },
+                        "format": {
+                            "type": "string",
+                            "description": "Output format. 'json' (default) returns columns; 'mermaid', 'dot' and 'plantuml' return an ER diagram as text.",
+                            "enum": ["json", "mermaid", "dot", "plantuml"],
+                            "default": "json"
+                        },
+                        "tables": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Optional: Tables to include in the ER diagram (default: all)"
+                        },
                         "env": {
                             "type": "string",
                             "description": "Environment to use (dev, prod, test)",
@@ -142,6 +165,24 @@ impl McpServer {
                     }
                 }),
             },
+            Tool {
+                name: "rustyroad_stats".to_string(),
+                description: "Get database health stats: estimated (or exact) row counts, table and index sizes, dead rows and last vacuum/analyze on Postgres, unused indexes on Postgres/MySQL, SQLite page and freelist counts, and bloat hints.".to_string(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {
+                        "exact": {
+                            "type": "boolean",
+                            "description": "Optional: Also run COUNT(*) on every table (can be slow on large tables)"
+                        },
+                        "env": {
+                            "type": "string",
+                            "description": "Environment to use (dev, prod, test). Defaults to current environment.",
+                            "enum": ["dev", "prod", "test"]
+                        }
+                    }
+                }),
+            },
             Tool {
                 name: "rustyroad_migrate".to_string(),
                 description: "Run database migrations. Use 'up' to apply pending migrations, 'down' to rollback.".to_string(),
@@ -228,7 +269,7 @@ impl McpServer {
             },
             Tool {
                 name: "rustyroad_routes".to_string(),
-                description: "Scan src/controllers/ for Actix route definitions and return all routes as structured JSON. Parses #[get], #[post], #[put], #[delete] attributes and .route()/.service() calls.".to_string(),
+                description: "List the routes registered from src/main.rs as structured JSON: method, full path (with web::scope prefixes), handler (module::fn), guards and whether the handler takes an Identity. Also lists route handlers in src/controllers/ that are never registered and registrations whose handler is missing.".to_string(),
                 input_schema: json!({
                     "type": "object",
                     "properties": {
@@ -300,6 +341,7 @@ impl McpServer {
         match name {
             "rustyroad_query" => self.handle_query(arguments).await,
             "rustyroad_schema" => self.handle_schema(arguments).await,
+            "rustyroad_stats" => self.handle_stats(arguments).await,
             "rustyroad_migrate" => self.handle_migrate(arguments).await,
             "rustyroad_migration_generate" => self.handle_migration_generate(arguments).await,
             "rustyroad_config" => self.handle_config(arguments),
@@ -337,6 +379,24 @@ impl McpServer {
             .await
             .map_err(|e| format!("Failed to connect to database: {}", e))?;
 
+        if args.get("explain").and_then(|v| v.as_bool()) == Some(true) {
+            let mode = if args.get("analyze").and_then(|v| v.as_bool()) == Some(true) {
+                ExplainMode::Analyze
+            } else {
+                ExplainMode::Plan
+            };
+            let plan = explain_statement(&connection, sql, mode)
+                .await
+                .map_err(|e| format!("Explain failed: {}", e))?;
+
+            return Ok(json!({
+                "success": true,
+                "environment": env,
+                "database": database.name,
+                "plan": plan
+            }));
+        }
+
         // Execute query
         let result = execute_query_internal(&connection, sql).await?;
 
@@ -355,6 +415,15 @@ impl McpServer {
             .unwrap_or(&self.environment);
 
         let table_filter = args.get("table").and_then(|v| v.as_str());
+        let format = args
+            .get("format")
+            .and_then(|v| v.as_str())
+            .unwrap_or("json");
+
+        let diagram_format = match format {
+            "json" => None,
+            other => Some(other.parse::<DiagramFormat>()?),
+        };
 
         env::set_var("ENVIRONMENT", env);
         let _guard = self.change_to_project_dir()?;
@@ -366,6 +435,37 @@ impl McpServer {
             .await
             .map_err(|e| format!("Failed to connect to database: {}", e))?;
 
+        if let Some(diagram_format) = diagram_format {
+            let mut tables: Vec<String> = args
+                .get("tables")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            if let Some(table) = table_filter {
+                tables.push(table.to_string());
+            }
+
+            let mut schema = introspect_schema(&connection)
+                .await
+                .map_err(|e| format!("Failed to introspect schema: {}", e))?;
+            if tables.is_empty() {
+                schema = schema.without_internal_tables();
+            }
+            schema.retain_tables(&tables);
+
+            return Ok(json!({
+                "success": true,
+                "environment": env,
+                "database": database.name,
+                "format": format,
+                "diagram": render_er_diagram(&schema, diagram_format)
+            }));
+        }
+
         let schema = get_schema_internal(&connection, table_filter).await?;
 
         Ok(json!({
@@ -376,6 +476,35 @@ impl McpServer {
         }))
     }
 
+    async fn handle_stats(&self, args: Value) -> Result<Value, String> {
+        let env = args
+            .get("env")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&self.environment);
+        let exact = args.get("exact").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        env::set_var("ENVIRONMENT", env);
+        let _guard = self.change_to_project_dir()?;
+
+        let database = Database::get_database_from_rustyroad_toml()
+            .map_err(|e| format!("Failed to get database config: {}", e))?;
+
+        let connection = Database::create_database_connection(&database)
+            .await
+            .map_err(|e| format!("Failed to connect to database: {}", e))?;
+
+        let stats = collect_database_stats(&connection, &database.name, exact)
+            .await
+            .map_err(|e| format!("Failed to collect stats: {}", e))?;
+
+        Ok(json!({
+            "success": true,
+            "environment": env,
+            "database": database.name,
+            "stats": stats
+        }))
+    }
+
     async fn handle_migrate(&self, args: Value) -> Result<Value, String> {
         let direction = args
             .get("direction")
@@ -940,162 +1069,17 @@ impl McpServer {
     async fn handle_routes(&self, args: Value) -> Result<Value, String> {
         let controller_filter = args.get("controller").and_then(|v| v.as_str());
 
-        let _guard = self.change_to_project_dir()?;
-
-        let controllers_dir = self.project_dir.join("src").join("controllers");
-        if !controllers_dir.exists() {
-            return Err(format!(
-                "Controllers directory not found: {}",
-                controllers_dir.display()
-            ));
-        }
-
-        let mut routes: Vec<Value> = Vec::new();
-
-        // Recursively find all .rs files in src/controllers/
-        fn collect_rs_files(dir: &PathBuf, files: &mut Vec<PathBuf>) -> Result<(), String> {
-            let entries = fs::read_dir(dir)
-                .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
-
-            for entry in entries {
-                let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-                let path = entry.path();
-                if path.is_dir() {
-                    collect_rs_files(&path, files)?;
-                } else if path.extension().is_some_and(|ext| ext == "rs") {
-                    // Skip mod.rs files as they typically don't contain routes
-                    if path.file_name().is_some_and(|n| n != "mod.rs") {
-                        files.push(path);
-                    }
-                }
-            }
-            Ok(())
-        }
-
-        let mut rs_files: Vec<PathBuf> = Vec::new();
-        collect_rs_files(&controllers_dir, &mut rs_files)?;
-
-        // Filter by controller name if specified
+        let mut table = collect_routes(&self.project_dir).map_err(|e| e.to_string())?;
         if let Some(filter) = controller_filter {
-            let filter_name = filter.trim_end_matches(".rs");
-            rs_files.retain(|path| {
-                path.file_stem()
-                    .is_some_and(|stem| stem.to_string_lossy().contains(filter_name))
-            });
-        }
-
-        // Regex patterns for Actix route attributes
-        // Matches: #[get("/path")], #[post("/path")], etc.
-        let attr_route_re =
-            Regex::new(r#"#\[(get|post|put|delete|patch|head|options)\("([^"]+)"\)\]"#)
-                .map_err(|e| format!("Regex error: {}", e))?;
-
-        // Matches: .route("/path", web::get().to(...))
-        let route_method_re = Regex::new(
-            r#"\.route\(\s*"([^"]+)"\s*,\s*web::(get|post|put|delete|patch|head|options)\(\)"#,
-        )
-        .map_err(|e| format!("Regex error: {}", e))?;
-
-        // Matches: web::resource("/path").route(web::get().to(...))
-        let resource_re = Regex::new(r#"web::resource\(\s*"([^"]+)"\s*\)"#)
-            .map_err(|e| format!("Regex error: {}", e))?;
-
-        // Matches: web::scope("/prefix")
-        let scope_re = Regex::new(r#"web::scope\(\s*"([^"]+)"\s*\)"#)
-            .map_err(|e| format!("Regex error: {}", e))?;
-
-        // Matches function name after route attribute
-        let fn_name_re = Regex::new(r#"(?:pub\s+)?async\s+fn\s+(\w+)"#)
-            .map_err(|e| format!("Regex error: {}", e))?;
-
-        for file_path in rs_files {
-            let content = fs::read_to_string(&file_path)
-                .map_err(|e| format!("Failed to read {}: {}", file_path.display(), e))?;
-
-            let relative_path = file_path
-                .strip_prefix(&self.project_dir)
-                .unwrap_or(&file_path)
-                .display()
-                .to_string();
-
-            let lines: Vec<&str> = content.lines().collect();
-
-            // Parse attribute-based routes (#[get("/path")])
-            for (line_num, line) in lines.iter().enumerate() {
-                if let Some(caps) = attr_route_re.captures(line) {
-                    let method = caps.get(1).map_or("", |m| m.as_str()).to_uppercase();
-                    let path = caps.get(2).map_or("", |m| m.as_str());
-
-                    // Look for the function name in the next few lines
-                    let mut handler = String::new();
-                    for i in 1..=5 {
-                        if line_num + i < lines.len() {
-                            if let Some(fn_caps) = fn_name_re.captures(lines[line_num + i]) {
-                                handler = fn_caps.get(1).map_or("", |m| m.as_str()).to_string();
-                                break;
-                            }
-                        }
-                    }
-
-                    routes.push(json!({
-                        "method": method,
-                        "path": path,
-                        "handler": handler,
-                        "file": relative_path,
-                        "line": line_num + 1,
-                        "type": "attribute"
-                    }));
-                }
-            }
-
-            // Parse .route() calls
-            for caps in route_method_re.captures_iter(&content) {
-                let path = caps.get(1).map_or("", |m| m.as_str());
-                let method = caps.get(2).map_or("", |m| m.as_str()).to_uppercase();
-
-                routes.push(json!({
-                    "method": method,
-                    "path": path,
-                    "handler": "",
-                    "file": relative_path,
-                    "line": 0,
-                    "type": "route_call"
-                }));
-            }
-
-            // Parse web::resource() calls
-            for caps in resource_re.captures_iter(&content) {
-                let path = caps.get(1).map_or("", |m| m.as_str());
-
-                routes.push(json!({
-                    "method": "RESOURCE",
-                    "path": path,
-                    "handler": "",
-                    "file": relative_path,
-                    "line": 0,
-                    "type": "resource"
-                }));
-            }
-
-            // Parse web::scope() calls
-            for caps in scope_re.captures_iter(&content) {
-                let path = caps.get(1).map_or("", |m| m.as_str());
-
-                routes.push(json!({
-                    "method": "SCOPE",
-                    "path": path,
-                    "handler": "",
-                    "file": relative_path,
-                    "line": 0,
-                    "type": "scope"
-                }));
-            }
+            table.retain_controller(filter);
         }
 
         Ok(json!({
             "success": true,
-            "route_count": routes.len(),
-            "routes": routes
+            "route_count": table.routes.len(),
+            "routes": table.routes,
+            "unregistered": table.unregistered,
+            "missing": table.missing
         }))
     }
 
@@ -1742,6 +1726,7 @@ fn register_with_opencode() -> Result<(), String> {
     println!("\nThe following tools are now available:");
     println!("  - rustyroad_query: Execute SQL queries");
     println!("  - rustyroad_schema: Get database schema");
+    println!("  - rustyroad_stats: Row counts, sizes and bloat hints");
     println!("  - rustyroad_migrate: Run migrations");
     println!("  - rustyroad_migration_generate: Create new migrations");
     println!("  - rustyroad_config: View configuration");
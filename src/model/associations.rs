@@ -0,0 +1,368 @@
+use crate::database::{Executor, Order, QueryBuilder, SqlValue};
+use crate::model::Model;
+use sqlx::{ColumnIndex, Decode, FromRow, MySql, Postgres, Row, Sqlite, Type};
+use std::collections::HashMap;
+
+/// A key type that decodes from every backend, such as the primary key of a model.
+pub trait KeyValue:
+    for<'r> Decode<'r, Postgres>
+    + Type<Postgres>
+    + for<'r> Decode<'r, MySql>
+    + Type<MySql>
+    + for<'r> Decode<'r, Sqlite>
+    + Type<Sqlite>
+    + Into<SqlValue>
+    + Send
+    + Unpin
+{
+}
+
+impl<K> KeyValue for K where
+    K: for<'r> Decode<'r, Postgres>
+        + Type<Postgres>
+        + for<'r> Decode<'r, MySql>
+        + Type<MySql>
+        + for<'r> Decode<'r, Sqlite>
+        + Type<Sqlite>
+        + Into<SqlValue>
+        + Send
+        + Unpin
+{
+}
+
+/// A row of a many-to-many target with the join table key of the record that owns it, which
+/// is selected as the last column.
+struct Owned<T, K> {
+    record: T,
+    owner: K,
+}
+
+impl<'r, R, T, K> FromRow<'r, R> for Owned<T, K>
+where
+    R: Row,
+    usize: ColumnIndex<R>,
+    T: FromRow<'r, R>,
+    K: Decode<'r, R::Database> + Type<R::Database>,
+{
+    fn from_row(row: &'r R) -> Result<Self, sqlx::Error> {
+        Ok(Owned {
+            record: T::from_row(row)?,
+            owner: row.try_get(row.len() - 1)?,
+        })
+    }
+}
+
+/// The value eager loading matches rows on. Integers of any width compare equal, so an `i32`
+/// foreign key finds an `i64` primary key; NULL matches nothing.
+pub(crate) fn match_key(value: &SqlValue) -> Option<String> {
+    match value {
+        _ if value.is_null() => None,
+        SqlValue::Int(Some(v)) => Some(v.to_string()),
+        SqlValue::BigInt(Some(v)) => Some(v.to_string()),
+        SqlValue::Text(Some(v)) => Some(v.clone()),
+        SqlValue::Uuid(Some(v)) => Some(v.to_string()),
+        other => Some(format!("{:?}", other)),
+    }
+}
+
+pub(crate) fn column_value<M: Model>(record: &M, column: &str) -> SqlValue {
+    record
+        .values()
+        .into_iter()
+        .find(|(name, _)| *name == column)
+        .map(|(_, value)| value)
+        .unwrap_or_else(|| panic!("`{}` has no `{}` column", M::TABLE, column))
+}
+
+/// The distinct non-NULL keys of `records`, to load with one `IN (..)`.
+fn distinct_keys(keys: impl Iterator<Item = SqlValue>) -> Vec<SqlValue> {
+    let mut seen = Vec::new();
+    let mut distinct = Vec::new();
+    for key in keys {
+        if let Some(matched) = match_key(&key) {
+            if !seen.contains(&matched) {
+                seen.push(matched);
+                distinct.push(key);
+            }
+        }
+    }
+    distinct
+}
+
+/// # Name: belongs_to
+/// ## Description
+/// Loads the `P` whose primary key is `key`, the value of a foreign key column. A NULL key,
+/// or a key no row has, is `None`. `#[model(belongs_to(..))]` calls this.
+pub async fn belongs_to<P: Model>(
+    conn: &impl Executor,
+    key: SqlValue,
+) -> Result<Option<P>, sqlx::Error> {
+    if key.is_null() {
+        return Ok(None);
+    }
+    P::query()
+        .where_eq(P::PRIMARY_KEY, key)
+        .fetch_optional(conn)
+        .await
+}
+
+/// # Name: has_many
+/// ## Description
+/// Loads every `C` whose `foreign_key` column is `key`, ordered by primary key.
+pub async fn has_many<C: Model>(
+    conn: &impl Executor,
+    foreign_key: &str,
+    key: SqlValue,
+) -> Result<Vec<C>, sqlx::Error> {
+    C::query()
+        .where_eq(foreign_key, key)
+        .order_by(C::PRIMARY_KEY, Order::Asc)
+        .fetch_all(conn)
+        .await
+}
+
+fn through_query<T: Model>(through: &str, target_key: &str) -> QueryBuilder {
+    QueryBuilder::select(T::TABLE)
+        .columns([format!("{}.*", T::TABLE).as_str()])
+        .join(
+            through,
+            &format!("{}.{}", through, target_key),
+            &format!("{}.{}", T::TABLE, T::PRIMARY_KEY),
+        )
+}
+
+/// # Name: has_many_through
+/// ## Description
+/// Loads every `T` linked to `key` by the join table `through`, whose `source_key` column
+/// holds `key` and whose `target_key` column holds the primary key of a `T`.
+pub async fn has_many_through<T: Model>(
+    conn: &impl Executor,
+    through: &str,
+    source_key: &str,
+    target_key: &str,
+    key: SqlValue,
+) -> Result<Vec<T>, sqlx::Error> {
+    through_query::<T>(through, target_key)
+        .where_eq(&format!("{}.{}", through, source_key), key)
+        .order_by(&format!("{}.{}", T::TABLE, T::PRIMARY_KEY), Order::Asc)
+        .fetch_all(conn)
+        .await
+}
+
+/// # Name: preload_belongs_to
+/// ## Description
+/// Pairs each of `records` with the `P` its `foreign_key` column points to, loading all of
+/// them with a single query instead of one per record.
+pub async fn preload_belongs_to<C: Model, P: Model + Clone>(
+    conn: &impl Executor,
+    records: Vec<C>,
+    foreign_key: &str,
+) -> Result<Vec<(C, Option<P>)>, sqlx::Error> {
+    let keys = distinct_keys(records.iter().map(|r| column_value(r, foreign_key)));
+    let parents: Vec<P> = if keys.is_empty() {
+        Vec::new()
+    } else {
+        P::query()
+            .where_in(P::PRIMARY_KEY, keys)
+            .fetch_all(conn)
+            .await?
+    };
+    let parents: HashMap<String, P> = parents
+        .into_iter()
+        .filter_map(|p| match_key(&p.primary_key()).map(|key| (key, p)))
+        .collect();
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let parent = match_key(&column_value(&record, foreign_key))
+                .and_then(|key| parents.get(&key).cloned());
+            (record, parent)
+        })
+        .collect())
+}
+
+/// # Name: preload_has_many
+/// ## Description
+/// Pairs each of `records` with the `C`s whose `foreign_key` column holds its primary key,
+/// loading all of them with a single query instead of one per record.
+pub async fn preload_has_many<P: Model, C: Model>(
+    conn: &impl Executor,
+    records: Vec<P>,
+    foreign_key: &str,
+) -> Result<Vec<(P, Vec<C>)>, sqlx::Error> {
+    let keys = distinct_keys(records.iter().map(|r| r.primary_key()));
+    let children: Vec<C> = if keys.is_empty() {
+        Vec::new()
+    } else {
+        C::query()
+            .where_in(foreign_key, keys)
+            .order_by(C::PRIMARY_KEY, Order::Asc)
+            .fetch_all(conn)
+            .await?
+    };
+    let mut groups: HashMap<String, Vec<C>> = HashMap::new();
+    for child in children {
+        if let Some(key) = match_key(&column_value(&child, foreign_key)) {
+            groups.entry(key).or_default().push(child);
+        }
+    }
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let children = match_key(&record.primary_key())
+                .and_then(|key| groups.remove(&key))
+                .unwrap_or_default();
+            (record, children)
+        })
+        .collect())
+}
+
+/// # Name: preload_has_many_through
+/// ## Description
+/// Pairs each of `records` with the `T`s the join table `through` links it to, loading all
+/// of them with a single query. `K` is the type of the records' primary key.
+pub async fn preload_has_many_through<P: Model, T: Model, K: KeyValue>(
+    conn: &impl Executor,
+    records: Vec<P>,
+    through: &str,
+    source_key: &str,
+    target_key: &str,
+) -> Result<Vec<(P, Vec<T>)>, sqlx::Error> {
+    let keys = distinct_keys(records.iter().map(|r| r.primary_key()));
+    let owner_column = format!("{}.{}", through, source_key);
+    let rows: Vec<Owned<T, K>> = if keys.is_empty() {
+        Vec::new()
+    } else {
+        through_query::<T>(through, target_key)
+            .columns([owner_column.as_str()])
+            .where_in(&owner_column, keys)
+            .order_by(&format!("{}.{}", T::TABLE, T::PRIMARY_KEY), Order::Asc)
+            .fetch_all(conn)
+            .await?
+    };
+    let mut groups: HashMap<String, Vec<T>> = HashMap::new();
+    for row in rows {
+        if let Some(key) = match_key(&row.owner.into()) {
+            groups.entry(key).or_default().push(row.record);
+        }
+    }
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let targets = match_key(&record.primary_key())
+                .and_then(|key| groups.remove(&key))
+                .unwrap_or_default();
+            (record, targets)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::DatabaseConnection;
+    use crate::model::Model;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, PartialEq, Model)]
+    #[model(has_many(Post, foreign_key = "author_id"))]
+    struct User {
+        id: i64,
+        name: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Model)]
+    #[model(has_many(Tag, through = "post_tags"))]
+    struct Post {
+        id: i64,
+        #[model(belongs_to(User))]
+        author_id: i64,
+        #[model(belongs_to(User, name = "reviewer"))]
+        editor_id: Option<i64>,
+        title: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Model)]
+    struct Tag {
+        id: i32,
+        label: String,
+    }
+
+    async fn blog() -> DatabaseConnection {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for statement in [
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)",
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY, author_id INTEGER NOT NULL REFERENCES users(id), \
+             editor_id INTEGER REFERENCES users(id), title TEXT NOT NULL)",
+            "CREATE TABLE tags (id INTEGER PRIMARY KEY, label TEXT NOT NULL)",
+            "CREATE TABLE post_tags (post_id INTEGER REFERENCES posts(id), tag_id INTEGER REFERENCES tags(id))",
+            "INSERT INTO users (id, name) VALUES (1, 'Ann'), (2, 'Bo'), (3, 'Cy')",
+            "INSERT INTO posts (id, author_id, editor_id, title) VALUES \
+             (1, 1, 2, 'First'), (2, 1, NULL, 'Second'), (3, 2, 1, 'Third')",
+            "INSERT INTO tags (id, label) VALUES (1, 'rust'), (2, 'web')",
+            "INSERT INTO post_tags (post_id, tag_id) VALUES (1, 1), (1, 2), (3, 2)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        DatabaseConnection::Sqlite(Arc::new(pool))
+    }
+
+    #[tokio::test]
+    async fn test_associations_load_and_preload() {
+        let conn = blog().await;
+        let ann = User::find(&conn, 1).await.unwrap();
+        let posts = ann.posts(&conn).await.unwrap();
+        assert_eq!(
+            posts.iter().map(|p| p.title.as_str()).collect::<Vec<_>>(),
+            ["First", "Second"]
+        );
+        assert_eq!(posts[0].author(&conn).await.unwrap(), ann);
+        assert_eq!(posts[0].reviewer(&conn).await.unwrap().unwrap().name, "Bo");
+        assert_eq!(posts[1].reviewer(&conn).await.unwrap(), None);
+        let labels: Vec<String> = posts[0]
+            .tags(&conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.label)
+            .collect();
+        assert_eq!(labels, ["rust", "web"]);
+
+        let all = Post::all(&conn).await.unwrap();
+        let authors: Vec<(i64, String)> = Post::include_author(&conn, all.clone())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(post, author)| (post.id, author.name))
+            .collect();
+        assert_eq!(
+            authors,
+            [
+                (1, "Ann".to_string()),
+                (2, "Ann".to_string()),
+                (3, "Bo".to_string())
+            ]
+        );
+        let reviewers = Post::include_reviewer(&conn, all.clone()).await.unwrap();
+        assert_eq!(reviewers[1].1, None);
+        assert_eq!(reviewers[2].1.as_ref().unwrap().name, "Ann");
+
+        let tags = Post::include_tags(&conn, all).await.unwrap();
+        let counts: Vec<usize> = tags.iter().map(|(_, tags)| tags.len()).collect();
+        assert_eq!(counts, [2, 0, 1]);
+
+        let users = User::include_posts(&conn, User::all(&conn).await.unwrap())
+            .await
+            .unwrap();
+        let counts: Vec<usize> = users.iter().map(|(_, posts)| posts.len()).collect();
+        assert_eq!(counts, [2, 1, 0]);
+        assert!(User::include_posts(&conn, Vec::new())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
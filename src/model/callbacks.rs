@@ -0,0 +1,250 @@
+use crate::database::Transaction;
+use crate::model::ModelError;
+use std::future::Future;
+
+/// # Name: Callbacks
+/// ## Description
+/// Hooks a `Model` runs around its writes. They run inside the transaction of the write, and
+/// queries they make on `tx` commit or roll back with it. An error from any hook rolls the write
+/// back and is returned by it, so a `before_*` hook can stop the write from happening.
+///
+/// The order of a write:
+/// * `insert` - `before_validation`, the `Validate` rules, `before_save`, INSERT, `after_create`
+/// * `update` - `before_validation`, the `Validate` rules, `before_save`, UPDATE, `after_update`
+/// * `delete` and `force_delete` - `before_destroy`, DELETE, and `after_destroy` once a row was
+///   deleted
+///
+/// The `before_validation` and `before_save` hooks may change the record that is written. The
+/// `after_*` hooks see the row as stored. `#[derive(Model)]` implements the trait without any
+/// hooks, unless the struct has `#[model(callbacks)]` and implements it itself.
+/// ## Example
+/// ```no_run
+/// use rustyroad::database::{QueryBuilder, Transaction};
+/// use rustyroad::model::{Callbacks, Model, ModelError};
+///
+/// #[derive(Debug, Clone, Model)]
+/// #[model(table = "pages", callbacks)]
+/// struct Page {
+///     id: i32,
+///     title: String,
+///     slug: String,
+///     locked: bool,
+/// }
+///
+/// impl Callbacks for Page {
+///     async fn before_save(&mut self, _tx: &Transaction) -> Result<(), ModelError> {
+///         if self.slug.is_empty() {
+///             self.slug = self.title.to_lowercase().replace(' ', "-");
+///         }
+///         Ok(())
+///     }
+///
+///     async fn after_update(&self, tx: &Transaction) -> Result<(), ModelError> {
+///         QueryBuilder::insert("audit_log")
+///             .value("page_id", self.id)
+///             .value("action", "update")
+///             .execute(tx)
+///             .await?;
+///         Ok(())
+///     }
+///
+///     async fn before_destroy(&self, _tx: &Transaction) -> Result<(), ModelError> {
+///         if self.locked {
+///             return Err(sqlx::Error::InvalidArgument("the page is locked".into()).into());
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait Callbacks: Sized + Send + Sync {
+    /// Runs before the `Validate` rules of `insert` and `update` are checked.
+    fn before_validation(
+        &mut self,
+        _tx: &Transaction,
+    ) -> impl Future<Output = Result<(), ModelError>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Runs after validation, right before the row of `insert` or `update` is written.
+    fn before_save(
+        &mut self,
+        _tx: &Transaction,
+    ) -> impl Future<Output = Result<(), ModelError>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Runs on the row `insert` stored.
+    fn after_create(
+        &self,
+        _tx: &Transaction,
+    ) -> impl Future<Output = Result<(), ModelError>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Runs on the row `update` stored.
+    fn after_update(
+        &self,
+        _tx: &Transaction,
+    ) -> impl Future<Output = Result<(), ModelError>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Runs before `delete` or `force_delete` removes the row.
+    fn before_destroy(
+        &self,
+        _tx: &Transaction,
+    ) -> impl Future<Output = Result<(), ModelError>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Runs once `delete` or `force_delete` has removed the row.
+    fn after_destroy(
+        &self,
+        _tx: &Transaction,
+    ) -> impl Future<Output = Result<(), ModelError>> + Send {
+        async { Ok(()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{DatabaseConnection, Executor};
+    use crate::model::Model;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, Model)]
+    #[model(callbacks)]
+    struct Article {
+        id: i64,
+        title: String,
+        slug: String,
+        locked: bool,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Model)]
+    struct AuditEntry {
+        id: i64,
+        article_id: i64,
+        action: String,
+    }
+
+    async fn audit(tx: &Transaction, article: &Article, action: &str) -> Result<(), ModelError> {
+        let entry = AuditEntry {
+            id: 0,
+            article_id: article.id,
+            action: action.to_string(),
+        };
+        entry.insert(tx).await?;
+        Ok(())
+    }
+
+    fn refused(reason: &str) -> ModelError {
+        sqlx::Error::InvalidArgument(reason.to_string()).into()
+    }
+
+    impl Callbacks for Article {
+        async fn before_validation(&mut self, _tx: &Transaction) -> Result<(), ModelError> {
+            self.title = self.title.trim().to_string();
+            Ok(())
+        }
+
+        async fn before_save(&mut self, _tx: &Transaction) -> Result<(), ModelError> {
+            if self.slug.is_empty() {
+                self.slug = self.title.to_lowercase().replace(' ', "-");
+            }
+            Ok(())
+        }
+
+        async fn after_create(&self, tx: &Transaction) -> Result<(), ModelError> {
+            audit(tx, self, "create").await
+        }
+
+        async fn after_update(&self, tx: &Transaction) -> Result<(), ModelError> {
+            audit(tx, self, "update").await?;
+            if self.title == "Unsaved" {
+                return Err(refused("the audit refused the title"));
+            }
+            Ok(())
+        }
+
+        async fn before_destroy(&self, _tx: &Transaction) -> Result<(), ModelError> {
+            if self.locked {
+                return Err(refused("the article is locked"));
+            }
+            Ok(())
+        }
+
+        async fn after_destroy(&self, tx: &Transaction) -> Result<(), ModelError> {
+            audit(tx, self, "destroy").await
+        }
+    }
+
+    async fn actions(conn: &DatabaseConnection) -> Vec<String> {
+        AuditEntry::all(conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.action)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_hooks_run_in_the_transaction_of_the_write() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for table in [
+            "CREATE TABLE articles (id INTEGER PRIMARY KEY AUTOINCREMENT, title TEXT NOT NULL, \
+             slug TEXT NOT NULL, locked BOOLEAN NOT NULL)",
+            "CREATE TABLE audit_entries (id INTEGER PRIMARY KEY AUTOINCREMENT, \
+             article_id INTEGER NOT NULL, action TEXT NOT NULL)",
+        ] {
+            sqlx::query(table).execute(&pool).await.unwrap();
+        }
+        let conn = DatabaseConnection::Sqlite(Arc::new(pool));
+
+        let draft = Article {
+            id: 0,
+            title: "  Hello World ".to_string(),
+            slug: String::new(),
+            locked: false,
+        };
+        let mut article = draft.insert(&conn).await.unwrap();
+        assert_eq!(
+            (article.title.as_str(), article.slug.as_str()),
+            ("Hello World", "hello-world")
+        );
+        assert_eq!(actions(&conn).await, ["create"]);
+
+        article.title = "Unsaved".to_string();
+        assert!(matches!(
+            article.update(&conn).await,
+            Err(ModelError::Database(sqlx::Error::InvalidArgument(_)))
+        ));
+        assert_eq!(Article::find(&conn, 1).await.unwrap().title, "Hello World");
+        assert_eq!(actions(&conn).await, ["create"]);
+
+        article.locked = true;
+        article.title = "Locked".to_string();
+        let article = article.update(&conn).await.unwrap();
+        assert!(article.delete(&conn).await.is_err());
+        assert_eq!(Article::count(&conn).await.unwrap(), 1);
+        assert_eq!(actions(&conn).await, ["create", "update"]);
+
+        let mut unlocked = article.clone();
+        unlocked.locked = false;
+        assert_eq!(unlocked.delete(&conn).await.unwrap(), 1);
+        assert_eq!(actions(&conn).await, ["create", "update", "destroy"]);
+
+        let tx = conn.begin().await.unwrap();
+        draft.insert(&tx).await.unwrap();
+        assert_eq!(Article::count(&tx).await.unwrap(), 1);
+        tx.rollback().await.unwrap();
+        assert_eq!(Article::count(&conn).await.unwrap(), 0);
+        assert_eq!(actions(&conn).await.len(), 3);
+    }
+}
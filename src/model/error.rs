@@ -0,0 +1,56 @@
+use crate::model::ValidationErrors;
+use std::fmt;
+
+/// # Name: ModelError
+/// ## Description
+/// Why a `Model` write failed. Reads return `sqlx::Error`, which converts into `Database` with
+/// `?`, so functions that both read and write can return `Result<_, ModelError>`.
+/// ## Example
+/// ```
+/// use rustyroad::model::ModelError;
+///
+/// let error = ModelError::StaleObject { table: "posts", id: "7".to_string() };
+/// assert_eq!(
+///     error.to_string(),
+///     "the posts row 7 was changed or deleted since it was loaded"
+/// );
+/// assert!(matches!(ModelError::from(sqlx::Error::RowNotFound), ModelError::Database(_)));
+/// ```
+#[derive(Debug)]
+pub enum ModelError {
+    /// The query failed, or found no row to write.
+    Database(sqlx::Error),
+    /// An update of a `lock_version` model found the stored row at another version, or gone.
+    StaleObject { table: &'static str, id: String },
+    /// The record broke its `Validate` rules, so nothing was written.
+    Validation(ValidationErrors),
+}
+
+impl From<sqlx::Error> for ModelError {
+    fn from(err: sqlx::Error) -> ModelError {
+        ModelError::Database(err)
+    }
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelError::Database(err) => write!(f, "{}", err),
+            ModelError::StaleObject { table, id } => write!(
+                f,
+                "the {} row {} was changed or deleted since it was loaded",
+                table, id
+            ),
+            ModelError::Validation(errors) => write!(f, "validation failed: {}", errors),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ModelError::Database(err) => Some(err),
+            ModelError::StaleObject { .. } | ModelError::Validation(_) => None,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+pub mod associations;
+pub mod callbacks;
+pub mod error;
+pub mod model;
+pub mod validation;
+
+pub use associations::*;
+pub use callbacks::*;
+pub use error::*;
+pub use model::*;
+pub use validation::*;
@@ -0,0 +1,585 @@
+use crate::database::{
+    DatabaseType, Executor, FromDatabaseRow, Order, QueryBuilder, SqlValue, Transaction,
+};
+use crate::model::{column_value, match_key, Callbacks, ModelError, Validate};
+use chrono::{DateTime, Utc};
+use std::future::Future;
+
+pub use rustyroad_derive::Model;
+
+#[doc(hidden)]
+pub use sqlx;
+
+/// The columns `#[model(timestamps)]`, `#[model(soft_delete)]` and `#[model(lock_version)]` use,
+/// which the `timestamps`, `soft_delete` and `lock_version` migration shortcuts create.
+pub const CREATED_AT: &str = "created_at";
+pub const UPDATED_AT: &str = "updated_at";
+pub const DELETED_AT: &str = "deleted_at";
+pub const LOCK_VERSION: &str = "lock_version";
+
+/// # Name: Model
+/// ## Description
+/// A struct stored as one row of a table, with the usual finders and writes working over any
+/// `Executor`: a `DatabaseConnection` to PostgreSQL, MySQL or SQLite, or a `Transaction` on one.
+/// `#[derive(Model)]` implements it, along with `sqlx::FromRow`, from the struct's fields.
+/// Anything the finders don't cover starts from `query()`, a `QueryBuilder` over the model's
+/// table.
+///
+/// `insert` leaves the primary key to the database when it is `None`, `0` or the nil UUID.
+/// PostgreSQL and SQLite return written rows with `RETURNING *`; MySQL reads them back by
+/// primary key. Every write runs in a transaction, together with the model's `Callbacks` hooks.
+/// `insert` and `update` check the `Validate` rules first, and write nothing when one is broken.
+///
+/// Three behaviours are opt-in, each through a struct attribute of the derive:
+/// * `timestamps` - `insert` sets `created_at` and `updated_at` to the current time, and
+///   `update` sets `updated_at` and leaves `created_at` alone.
+/// * `soft_delete` - `delete` sets `deleted_at` instead of deleting the row, and `query()`, and
+///   so every finder and association, skips rows where it is set. `with_deleted()` includes
+///   them, `restore` clears `deleted_at` again and `force_delete` deletes for real.
+/// * `lock_version` - `update` only writes the row while its `lock_version` still matches the
+///   one that was read, and increments it. Otherwise it fails with `ModelError::StaleObject`.
+/// ## Example
+/// ```no_run
+/// use rustyroad::database::{DatabaseConnection, Order};
+/// use rustyroad::model::{Model, ModelError};
+///
+/// #[derive(Debug, Clone, Model)]
+/// #[model(table = "posts", timestamps, soft_delete, lock_version)]
+/// struct Post {
+///     id: i32,
+///     title: String,
+///     #[model(column = "body_text")]
+///     body: Option<String>,
+///     #[model(skip)]
+///     excerpt: String,
+///     created_at: chrono::NaiveDateTime,
+///     updated_at: chrono::NaiveDateTime,
+///     deleted_at: Option<chrono::NaiveDateTime>,
+///     lock_version: i32,
+/// }
+///
+/// async fn publish(conn: &DatabaseConnection, draft: Post) -> Result<(), ModelError> {
+///     let mut post = draft.insert(conn).await?;
+///     let drafts = Post::where_(conn, [("title", "Hello".into())]).await?;
+///     assert_eq!(drafts.len() as i64, Post::count(conn).await?);
+///     let latest: Vec<Post> = Post::query()
+///         .order_by("id", Order::Desc)
+///         .limit(5)
+///         .fetch_all(conn)
+///         .await?;
+///     post.title = "Hello again".into();
+///     match post.update(conn).await {
+///         Err(ModelError::StaleObject { .. }) => println!("someone else saved it first"),
+///         result => post = result?,
+///     }
+///     post.delete(conn).await?;
+///     post.restore(conn).await?;
+///     Ok(())
+/// }
+/// ```
+pub trait Model: FromDatabaseRow + Validate + Callbacks + Clone + Sync + 'static {
+    const TABLE: &'static str;
+    const PRIMARY_KEY: &'static str;
+    /// Every column, in field order.
+    const COLUMNS: &'static [&'static str];
+    /// Whether writes maintain `created_at` and `updated_at`.
+    const TIMESTAMPS: bool = false;
+    /// Whether `delete` sets `deleted_at` and queries skip deleted rows.
+    const SOFT_DELETE: bool = false;
+    /// Whether `update` checks and increments `lock_version`.
+    const LOCK_VERSION: bool = false;
+
+    /// Each column with the value of its field, in `COLUMNS` order.
+    fn values(&self) -> Vec<(&'static str, SqlValue)>;
+
+    /// The value of the primary key column.
+    fn primary_key(&self) -> SqlValue {
+        self.values()
+            .into_iter()
+            .find(|(column, _)| *column == Self::PRIMARY_KEY)
+            .map(|(_, value)| value)
+            .expect("the primary key is one of the model's columns")
+    }
+
+    /// `SELECT * FROM` the model's table, to narrow down further. Soft-deleted rows are left out.
+    fn query() -> QueryBuilder {
+        let query = Self::with_deleted();
+        if Self::SOFT_DELETE {
+            query.where_null(&format!("{}.{}", Self::TABLE, DELETED_AT))
+        } else {
+            query
+        }
+    }
+
+    /// `SELECT * FROM` the model's table, soft-deleted rows included.
+    fn with_deleted() -> QueryBuilder {
+        QueryBuilder::select(Self::TABLE)
+    }
+
+    /// Returns the row whose primary key is `id`, or `sqlx::Error::RowNotFound`.
+    fn find(
+        conn: &impl Executor,
+        id: impl Into<SqlValue>,
+    ) -> impl Future<Output = Result<Self, sqlx::Error>> + Send {
+        let query = Self::query().where_eq(Self::PRIMARY_KEY, id);
+        async move { query.fetch_one(conn).await }
+    }
+
+    /// Returns every row, ordered by primary key.
+    fn all(conn: &impl Executor) -> impl Future<Output = Result<Vec<Self>, sqlx::Error>> + Send {
+        let query = Self::query().order_by(Self::PRIMARY_KEY, Order::Asc);
+        async move { query.fetch_all(conn).await }
+    }
+
+    /// Returns the rows where every column equals its value, ordered by primary key. A `None`
+    /// value matches NULL. Naming a column the model does not have is
+    /// `sqlx::Error::ColumnNotFound`.
+    fn where_<'c>(
+        conn: &impl Executor,
+        conditions: impl IntoIterator<Item = (&'c str, SqlValue)>,
+    ) -> impl Future<Output = Result<Vec<Self>, sqlx::Error>> + Send {
+        let mut query = Self::query();
+        let mut unknown = None;
+        for (column, value) in conditions {
+            if !Self::COLUMNS.contains(&column) {
+                unknown.get_or_insert_with(|| column.to_string());
+            }
+            query = query.where_eq(column, value);
+        }
+        let query = query.order_by(Self::PRIMARY_KEY, Order::Asc);
+        async move {
+            if let Some(column) = unknown {
+                return Err(sqlx::Error::ColumnNotFound(column));
+            }
+            query.fetch_all(conn).await
+        }
+    }
+
+    /// Returns the number of rows in the table.
+    fn count(conn: &impl Executor) -> impl Future<Output = Result<i64, sqlx::Error>> + Send {
+        let query = Self::query();
+        async move { query.count(conn).await }
+    }
+
+    /// Inserts this row and returns it as stored, with the primary key the database assigned.
+    /// The `Callbacks` hooks and `Validate` rules run first, in the transaction of the INSERT.
+    fn insert(
+        &self,
+        conn: &impl Executor,
+    ) -> impl Future<Output = Result<Self, ModelError>> + Send {
+        save(conn, self.clone(), true)
+    }
+
+    /// Writes every column, matched by primary key, and returns the row as stored. The
+    /// `Callbacks` hooks and `Validate` rules run first, in the transaction of the UPDATE. A row
+    /// that no longer exists is `sqlx::Error::RowNotFound`, or `ModelError::StaleObject` for a
+    /// `lock_version` model.
+    fn update(
+        &self,
+        conn: &impl Executor,
+    ) -> impl Future<Output = Result<Self, ModelError>> + Send {
+        save(conn, self.clone(), false)
+    }
+
+    /// Deletes this row by primary key and returns the number of deleted rows. A `soft_delete`
+    /// model sets `deleted_at` instead. The destroy hooks of `Callbacks` run around it.
+    fn delete(&self, conn: &impl Executor) -> impl Future<Output = Result<u64, ModelError>> + Send {
+        let query = if Self::SOFT_DELETE {
+            let deleted_at = column_value(self, DELETED_AT);
+            QueryBuilder::update(Self::TABLE)
+                .value(DELETED_AT, timestamp_like(&deleted_at, Utc::now()))
+                .where_eq(Self::PRIMARY_KEY, self.primary_key())
+                .where_null(DELETED_AT)
+        } else {
+            QueryBuilder::delete(Self::TABLE).where_eq(Self::PRIMARY_KEY, self.primary_key())
+        };
+        destroy(conn, self, query)
+    }
+
+    /// Deletes this row by primary key, even from a `soft_delete` model, and returns the number
+    /// of deleted rows. The destroy hooks of `Callbacks` run around it.
+    fn force_delete(
+        &self,
+        conn: &impl Executor,
+    ) -> impl Future<Output = Result<u64, ModelError>> + Send {
+        let query =
+            QueryBuilder::delete(Self::TABLE).where_eq(Self::PRIMARY_KEY, self.primary_key());
+        destroy(conn, self, query)
+    }
+
+    /// Clears `deleted_at` of this soft-deleted row and returns it as stored. Models without
+    /// `soft_delete` cannot be restored: `sqlx::Error::InvalidArgument`.
+    fn restore(
+        &self,
+        conn: &impl Executor,
+    ) -> impl Future<Output = Result<Self, ModelError>> + Send {
+        let query = Self::SOFT_DELETE.then(|| {
+            QueryBuilder::update(Self::TABLE)
+                .value(DELETED_AT, null_like(&column_value(self, DELETED_AT)))
+                .where_eq(Self::PRIMARY_KEY, self.primary_key())
+        });
+        let read_back = Self::with_deleted().where_eq(Self::PRIMARY_KEY, self.primary_key());
+        async move {
+            let Some(query) = query else {
+                return Err(sqlx::Error::InvalidArgument(format!(
+                    "{} does not use soft deletes",
+                    Self::TABLE
+                ))
+                .into());
+            };
+            query.execute(conn).await?;
+            Ok(read_back.fetch_one(conn).await?)
+        }
+    }
+}
+
+/// Runs the hooks, the validation and the write of `insert` or `update` in one transaction.
+async fn save<M: Model>(
+    conn: &impl Executor,
+    mut record: M,
+    insert: bool,
+) -> Result<M, ModelError> {
+    let tx = conn.begin().await?;
+    let result = async {
+        record.before_validation(&tx).await?;
+        record.validate(&tx).await?;
+        record.before_save(&tx).await?;
+        if insert {
+            let stored = insert_row(&tx, &record).await?;
+            stored.after_create(&tx).await?;
+            Ok(stored)
+        } else {
+            let stored = update_row(&tx, &record).await?;
+            stored.after_update(&tx).await?;
+            Ok(stored)
+        }
+    }
+    .await;
+    tx.finish(result).await
+}
+
+async fn insert_row<M: Model>(tx: &Transaction, record: &M) -> Result<M, ModelError> {
+    let now = Utc::now();
+    let values: Vec<(&'static str, SqlValue)> = record
+        .values()
+        .into_iter()
+        .filter(|(column, value)| *column != M::PRIMARY_KEY || !is_unassigned(value))
+        .map(|(column, value)| match column {
+            CREATED_AT | UPDATED_AT if M::TIMESTAMPS => (column, timestamp_like(&value, now)),
+            _ => (column, value),
+        })
+        .collect();
+    let assigned_key = values
+        .iter()
+        .find(|(column, _)| *column == M::PRIMARY_KEY)
+        .map(|(_, value)| value.clone());
+    let query = QueryBuilder::insert(M::TABLE).values(values);
+    if tx.database_type() != DatabaseType::Mysql {
+        return Ok(query.returning(["*"]).fetch_one(tx).await?);
+    }
+    let (_, inserted_id) = query.execute(tx).await?;
+    let id = assigned_key.unwrap_or(SqlValue::BigInt(Some(inserted_id as i64)));
+    Ok(M::find(tx, id).await?)
+}
+
+async fn update_row<M: Model>(tx: &Transaction, record: &M) -> Result<M, ModelError> {
+    let now = Utc::now();
+    let id = record.primary_key();
+    let mut read_version = None;
+    let mut query = QueryBuilder::update(M::TABLE);
+    for (column, value) in record.values() {
+        let value = match column {
+            _ if column == M::PRIMARY_KEY => continue,
+            CREATED_AT if M::TIMESTAMPS => continue,
+            UPDATED_AT if M::TIMESTAMPS => timestamp_like(&value, now),
+            LOCK_VERSION if M::LOCK_VERSION => {
+                let next = incremented(&value);
+                read_version = Some(value);
+                next
+            }
+            _ => value,
+        };
+        query = query.value(column, value);
+    }
+    let mut query = query.where_eq(M::PRIMARY_KEY, id.clone());
+    if let Some(version) = &read_version {
+        if !matches!(version, SqlValue::Int(Some(_)) | SqlValue::BigInt(Some(_))) {
+            return Err(sqlx::Error::InvalidArgument(format!(
+                "{}.{} must be a non-NULL integer",
+                M::TABLE,
+                LOCK_VERSION
+            ))
+            .into());
+        }
+        query = query.where_eq(LOCK_VERSION, version.clone());
+    }
+    let stale = || ModelError::StaleObject {
+        table: M::TABLE,
+        id: match_key(&id).unwrap_or_else(|| "NULL".to_string()),
+    };
+    if tx.database_type() != DatabaseType::Mysql {
+        return match query.returning(["*"]).fetch_optional(tx).await? {
+            Some(row) => Ok(row),
+            None if read_version.is_some() => Err(stale()),
+            None => Err(sqlx::Error::RowNotFound.into()),
+        };
+    }
+    // MySQL counts unchanged rows as unaffected, so the row is read back instead
+    let (updated, _) = query.execute(tx).await?;
+    if updated == 0 && read_version.is_some() {
+        return Err(stale());
+    }
+    Ok(M::with_deleted()
+        .where_eq(M::PRIMARY_KEY, id.clone())
+        .fetch_one(tx)
+        .await?)
+}
+
+/// Runs the destroy hooks and the DELETE, or soft-delete UPDATE, of `record` in one transaction.
+async fn destroy<M: Model>(
+    conn: &impl Executor,
+    record: &M,
+    query: QueryBuilder,
+) -> Result<u64, ModelError> {
+    let tx = conn.begin().await?;
+    let result = async {
+        record.before_destroy(&tx).await?;
+        let (deleted, _) = query.execute(&tx).await?;
+        if deleted > 0 {
+            record.after_destroy(&tx).await?;
+        }
+        Ok(deleted)
+    }
+    .await;
+    tx.finish(result).await
+}
+
+/// A primary key the database should assign: `None`, the `0` of a new integer id or the nil
+/// UUID of a new UUID.
+pub(crate) fn is_unassigned(value: &SqlValue) -> bool {
+    match value {
+        SqlValue::Int(Some(0)) | SqlValue::BigInt(Some(0)) => true,
+        SqlValue::Uuid(Some(id)) => id.is_nil(),
+        _ => value.is_null(),
+    }
+}
+
+/// `now` in the type of the timestamp field `value` was read from: a `NaiveDateTime` in UTC, a
+/// `DateTime<Utc>`, or text in the format sqlx writes dates to SQLite in.
+fn timestamp_like(value: &SqlValue, now: DateTime<Utc>) -> SqlValue {
+    match value {
+        SqlValue::DateTime(_) => SqlValue::DateTime(Some(now.naive_utc())),
+        SqlValue::Text(_) => SqlValue::Text(Some(now.naive_utc().to_string())),
+        _ => SqlValue::Timestamp(Some(now)),
+    }
+}
+
+/// A NULL of the same type as `value`.
+fn null_like(value: &SqlValue) -> SqlValue {
+    match value {
+        SqlValue::DateTime(_) => SqlValue::DateTime(None),
+        SqlValue::Text(_) => SqlValue::Text(None),
+        _ => SqlValue::Timestamp(None),
+    }
+}
+
+/// The `lock_version` after `value`.
+fn incremented(value: &SqlValue) -> SqlValue {
+    match value {
+        SqlValue::Int(v) => SqlValue::Int(v.map(|v| v + 1)),
+        SqlValue::BigInt(v) => SqlValue::BigInt(v.map(|v| v + 1)),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseConnection;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, PartialEq, Model)]
+    struct BlogPost {
+        id: i64,
+        title: String,
+        #[model(column = "body_text")]
+        body: Option<String>,
+        published: bool,
+        #[model(skip)]
+        draft_note: String,
+    }
+
+    #[derive(Debug, Clone, Model)]
+    #[model(table = "people")]
+    struct Person {
+        #[model(primary_key)]
+        email: String,
+        name: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Model)]
+    #[model(timestamps, soft_delete, lock_version)]
+    struct Note {
+        id: i32,
+        body: String,
+        created_at: chrono::NaiveDateTime,
+        updated_at: chrono::NaiveDateTime,
+        deleted_at: Option<chrono::NaiveDateTime>,
+        lock_version: i32,
+    }
+
+    #[test]
+    fn test_derive_maps_fields_to_columns() {
+        assert_eq!(BlogPost::TABLE, "blog_posts");
+        assert_eq!(BlogPost::PRIMARY_KEY, "id");
+        assert_eq!(BlogPost::COLUMNS, ["id", "title", "body_text", "published"]);
+        assert_eq!((Person::TABLE, Person::PRIMARY_KEY), ("people", "email"));
+        assert_eq!(
+            Person::query()
+                .where_eq(Person::PRIMARY_KEY, "a@b.c")
+                .build(&DatabaseType::Mysql)
+                .unwrap()
+                .0,
+            "SELECT * FROM `people` WHERE `email` = ?"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_model_round_trip_on_sqlite() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE blog_posts (id INTEGER PRIMARY KEY AUTOINCREMENT, title TEXT NOT NULL, \
+             body_text TEXT, published BOOLEAN NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let conn = DatabaseConnection::Sqlite(Arc::new(pool));
+
+        let draft = BlogPost {
+            id: 0,
+            title: "Hello".to_string(),
+            body: None,
+            published: false,
+            draft_note: "not stored".to_string(),
+        };
+        let mut post = draft.insert(&conn).await.unwrap();
+        assert_eq!(post.id, 1);
+        assert_eq!(post.draft_note, "");
+        draft.insert(&conn).await.unwrap();
+        assert_eq!(BlogPost::count(&conn).await.unwrap(), 2);
+
+        post.body = Some("World".to_string());
+        post.published = true;
+        assert_eq!(post.update(&conn).await.unwrap(), post);
+        assert_eq!(BlogPost::find(&conn, 1).await.unwrap(), post);
+
+        let published = BlogPost::where_(&conn, [("published", true.into())])
+            .await
+            .unwrap();
+        assert_eq!(published, [post.clone()]);
+        let without_body = BlogPost::where_(&conn, [("body_text", None::<String>.into())])
+            .await
+            .unwrap();
+        assert_eq!(without_body.len(), 1);
+        assert!(matches!(
+            BlogPost::where_(&conn, [("body", "World".into())]).await,
+            Err(sqlx::Error::ColumnNotFound(_))
+        ));
+
+        assert_eq!(post.delete(&conn).await.unwrap(), 1);
+        assert!(matches!(
+            BlogPost::find(&conn, 1).await,
+            Err(sqlx::Error::RowNotFound)
+        ));
+        assert_eq!(BlogPost::all(&conn).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timestamps_soft_delete_and_locking() {
+        const {
+            assert!(Note::TIMESTAMPS && Note::SOFT_DELETE && Note::LOCK_VERSION);
+            assert!(!BlogPost::TIMESTAMPS && !BlogPost::SOFT_DELETE && !BlogPost::LOCK_VERSION);
+        }
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE notes (id INTEGER PRIMARY KEY AUTOINCREMENT, body TEXT NOT NULL, \
+             created_at TIMESTAMP NOT NULL, updated_at TIMESTAMP NOT NULL, deleted_at TIMESTAMP, \
+             lock_version INTEGER NOT NULL DEFAULT 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let conn = DatabaseConnection::Sqlite(Arc::new(pool));
+
+        let before = Utc::now().naive_utc();
+        let draft = Note {
+            id: 0,
+            body: "Draft".to_string(),
+            created_at: chrono::NaiveDateTime::default(),
+            updated_at: chrono::NaiveDateTime::default(),
+            deleted_at: None,
+            lock_version: 0,
+        };
+        let note = draft.insert(&conn).await.unwrap();
+        assert!(note.created_at >= before && note.updated_at == note.created_at);
+
+        let mut edited = note.clone();
+        edited.body = "Edited".to_string();
+        edited.created_at = chrono::NaiveDateTime::default();
+        let edited = edited.update(&conn).await.unwrap();
+        assert_eq!(edited.lock_version, 1);
+        assert_eq!(edited.created_at, note.created_at);
+        assert!(edited.updated_at >= note.updated_at);
+        match note.update(&conn).await {
+            Err(ModelError::StaleObject { table, id }) => {
+                assert_eq!((table, id.as_str()), ("notes", "1"))
+            }
+            other => panic!("expected a stale object error, got {:?}", other),
+        }
+
+        assert_eq!(edited.delete(&conn).await.unwrap(), 1);
+        assert_eq!(edited.delete(&conn).await.unwrap(), 0);
+        assert_eq!(Note::count(&conn).await.unwrap(), 0);
+        assert!(matches!(
+            Note::find(&conn, 1).await,
+            Err(sqlx::Error::RowNotFound)
+        ));
+        let deleted: Note = Note::with_deleted().fetch_one(&conn).await.unwrap();
+        assert!(deleted.deleted_at.is_some());
+
+        let restored = deleted.restore(&conn).await.unwrap();
+        assert_eq!(restored.deleted_at, None);
+        assert_eq!(
+            Note::all(&conn).await.unwrap(),
+            std::slice::from_ref(&restored)
+        );
+        assert_eq!(restored.force_delete(&conn).await.unwrap(), 1);
+        assert_eq!(Note::with_deleted().count(&conn).await.unwrap(), 0);
+        assert!(matches!(
+            restored.update(&conn).await,
+            Err(ModelError::StaleObject { .. })
+        ));
+
+        let post = BlogPost {
+            id: 1,
+            title: String::new(),
+            body: None,
+            published: false,
+            draft_note: String::new(),
+        };
+        assert!(matches!(
+            post.restore(&conn).await,
+            Err(ModelError::Database(sqlx::Error::InvalidArgument(_)))
+        ));
+    }
+}
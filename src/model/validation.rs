@@ -0,0 +1,486 @@
+use crate::database::{Executor, Op, SqlValue};
+use crate::model::{column_value, is_unassigned, match_key, Model, ModelError};
+pub use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::OnceLock;
+
+/// The field errors about the record as a whole, such as those of a struct-level custom rule.
+pub const BASE: &str = "base";
+
+/// # Name: ValidationErrors
+/// ## Description
+/// The messages of every failed rule, keyed by field name. It serializes as
+/// `{"title": ["can't be blank"]}`, for templates and JSON responses alike.
+/// ## Example
+/// ```
+/// use rustyroad::model::ValidationErrors;
+///
+/// let mut errors = ValidationErrors::new();
+/// errors.add("title", "can't be blank");
+/// assert_eq!(errors.get("title"), ["can't be blank"]);
+/// assert_eq!(errors.to_string(), "title can't be blank");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct ValidationErrors(BTreeMap<String, Vec<String>>);
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a message to `field`.
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.0.entry(field.into()).or_default().push(message.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The messages of `field`, empty when it is valid.
+    pub fn get(&self, field: &str) -> &[String] {
+        self.0.get(field).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Each invalid field with its messages, in field name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.0
+            .iter()
+            .map(|(field, messages)| (field.as_str(), messages.as_slice()))
+    }
+}
+
+impl From<ValidationErrors> for BTreeMap<String, Vec<String>> {
+    fn from(errors: ValidationErrors) -> Self {
+        errors.0
+    }
+}
+
+impl From<BTreeMap<String, Vec<String>>> for ValidationErrors {
+    fn from(errors: BTreeMap<String, Vec<String>>) -> Self {
+        ValidationErrors(errors)
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self
+            .iter()
+            .flat_map(|(field, messages)| {
+                messages.iter().map(move |message| match field {
+                    BASE => message.clone(),
+                    _ => format!("{} {}", field, message),
+                })
+            })
+            .collect();
+        write!(f, "{}", messages.join(", "))
+    }
+}
+
+/// # Name: Rule
+/// ## Description
+/// A check on one field. Every rule except `Presence` and `Custom` passes a NULL value, so
+/// optional fields are only checked when they are set.
+pub enum Rule<M> {
+    /// Not NULL, and not blank when it is text.
+    Presence,
+    /// The number of characters of text, or bytes of binary data.
+    Length {
+        min: Option<usize>,
+        max: Option<usize>,
+    },
+    /// Text that matches the pattern; see `Rule::format`.
+    Format(Regex),
+    /// One of the listed values.
+    Inclusion(Vec<SqlValue>),
+    /// A number within the bounds.
+    Range { min: Option<f64>, max: Option<f64> },
+    /// No other row of the table, soft-deleted rows aside, holds the same value.
+    Uniqueness,
+    /// A function of the whole record, returning the message when the field is invalid.
+    Custom(fn(&M) -> Result<(), String>),
+}
+
+impl<M> Rule<M> {
+    /// `Rule::Format` from a regular expression, which is compiled here and so must be valid.
+    pub fn format(pattern: &str) -> Self {
+        match Regex::new(pattern) {
+            Ok(regex) => Rule::Format(regex),
+            Err(e) => panic!("invalid format pattern `{}`: {}", pattern, e),
+        }
+    }
+
+    /// `Rule::format`, compiling the pattern only the first time `cache` is used.
+    /// `#[derive(Model)]` gives each `format` rule its own cache, since the rules are built
+    /// again for every validation.
+    pub fn format_cached(cache: &'static OnceLock<Regex>, pattern: &str) -> Self {
+        let regex = cache.get_or_init(|| match Regex::new(pattern) {
+            Ok(regex) => regex,
+            Err(e) => panic!("invalid format pattern `{}`: {}", pattern, e),
+        });
+        Rule::Format(regex.clone())
+    }
+
+    /// The message when `value` breaks the rule. `Uniqueness` and `Custom` are checked by
+    /// `Validate::validate`, which has the record and a connection.
+    fn check(&self, value: &SqlValue) -> Option<String> {
+        if value.is_null() {
+            return matches!(self, Rule::Presence).then(|| "can't be blank".to_string());
+        }
+        match self {
+            Rule::Presence => match value {
+                SqlValue::Text(Some(text)) if text.trim().is_empty() => {
+                    Some("can't be blank".to_string())
+                }
+                SqlValue::Bytes(Some(bytes)) if bytes.is_empty() => {
+                    Some("can't be blank".to_string())
+                }
+                _ => None,
+            },
+            Rule::Length { min, max } => {
+                let length = match value {
+                    SqlValue::Text(Some(text)) => text.chars().count(),
+                    SqlValue::Bytes(Some(bytes)) => bytes.len(),
+                    _ => return None,
+                };
+                match (min, max) {
+                    (Some(min), _) if length < *min => {
+                        Some(format!("is too short (minimum is {} characters)", min))
+                    }
+                    (_, Some(max)) if length > *max => {
+                        Some(format!("is too long (maximum is {} characters)", max))
+                    }
+                    _ => None,
+                }
+            }
+            Rule::Format(regex) => match value {
+                SqlValue::Text(Some(text)) if !regex.is_match(text) => {
+                    Some("is invalid".to_string())
+                }
+                _ => None,
+            },
+            Rule::Inclusion(allowed) => {
+                let key = match_key(value);
+                (!allowed.iter().any(|option| match_key(option) == key))
+                    .then(|| "is not included in the list".to_string())
+            }
+            Rule::Range { min, max } => {
+                let number = match value {
+                    SqlValue::Int(Some(v)) => f64::from(*v),
+                    SqlValue::BigInt(Some(v)) => *v as f64,
+                    SqlValue::Float(Some(v)) => *v,
+                    SqlValue::Decimal(Some(v)) => v.to_string().parse().ok()?,
+                    _ => return None,
+                };
+                match (min, max) {
+                    (Some(min), _) if number < *min => {
+                        Some(format!("must be greater than or equal to {}", min))
+                    }
+                    (_, Some(max)) if number > *max => {
+                        Some(format!("must be less than or equal to {}", max))
+                    }
+                    _ => None,
+                }
+            }
+            Rule::Uniqueness | Rule::Custom(_) => None,
+        }
+    }
+}
+
+/// # Name: Validation
+/// ## Description
+/// A rule and the field it checks. `column` is where the field's value is stored, and is
+/// empty for `Custom` rules of fields that are not columns, or of the record as a whole
+/// (the `BASE` field).
+pub struct Validation<M> {
+    pub field: &'static str,
+    pub column: &'static str,
+    pub rule: Rule<M>,
+}
+
+/// # Name: Validate
+/// ## Description
+/// The rules a model checks before `Model::insert` and `Model::update` write it, which fail
+/// with `ModelError::Validation` holding a message for every broken rule instead.
+/// `#[derive(Model)]` implements it from the `#[validate(..)]` attributes:
+/// * `presence` - The field is set and, for text, not blank.
+/// * `length(min = 1, max = 255)` - Either bound may be left out.
+/// * `format = "^[a-z0-9-]+$"` - The text matches the regular expression; an invalid one is a compile error.
+/// * `inclusion("draft", "published")` - The value is one of those listed.
+/// * `range(min = 0, max = 100)` - The number is within the bounds, either of which may be
+///   left out.
+/// * `unique` - No other row has the same value; this one runs a query.
+/// * `custom = "path::to::check"` - Calls `fn check(value: &FieldType) -> Result<(), String>`,
+///   where the error is the field's message. On the struct, `check` takes the record instead
+///   and its message is filed under `base`.
+/// ## Example
+/// ```no_run
+/// use rustyroad::database::DatabaseConnection;
+/// use rustyroad::model::{Model, ModelError, Validate};
+///
+/// #[derive(Debug, Clone, Model)]
+/// #[validate(custom = "check_price")]
+/// struct Product {
+///     id: i32,
+///     #[validate(presence, length(max = 80))]
+///     name: String,
+///     #[validate(unique, format = "^[a-z0-9-]+$")]
+///     slug: String,
+///     #[validate(inclusion("draft", "published"))]
+///     status: String,
+///     #[validate(range(min = 0))]
+///     price_cents: i64,
+///     sale_price_cents: Option<i64>,
+/// }
+///
+/// fn check_price(product: &Product) -> Result<(), String> {
+///     match product.sale_price_cents {
+///         Some(sale) if sale > product.price_cents => Err("the sale price is higher".into()),
+///         _ => Ok(()),
+///     }
+/// }
+///
+/// async fn save(conn: &DatabaseConnection, product: Product) -> Result<Product, ModelError> {
+///     match product.insert(conn).await {
+///         Err(ModelError::Validation(errors)) => {
+///             println!("{:?}", errors.get("slug"));
+///             Err(ModelError::Validation(errors))
+///         }
+///         result => result,
+///     }
+/// }
+/// ```
+pub trait Validate: Sized {
+    /// The rules, in the order they are checked.
+    fn validations() -> Vec<Validation<Self>> {
+        Vec::new()
+    }
+
+    /// Checks every rule, and fails with `ModelError::Validation` when any is broken. Only a
+    /// failing uniqueness query is `ModelError::Database`.
+    fn validate(
+        &self,
+        conn: &impl Executor,
+    ) -> impl Future<Output = Result<(), ModelError>> + Send
+    where
+        Self: Model,
+    {
+        let mut errors = ValidationErrors::new();
+        let mut unique = Vec::new();
+        let id = self.primary_key();
+        for validation in Self::validations() {
+            let message = match &validation.rule {
+                Rule::Custom(check) => check(self).err(),
+                Rule::Uniqueness => {
+                    let value = column_value(self, validation.column);
+                    if !value.is_null() {
+                        let mut query = Self::query().where_eq(validation.column, value);
+                        if !is_unassigned(&id) {
+                            query = query.where_(Self::PRIMARY_KEY, Op::Ne, id.clone());
+                        }
+                        unique.push((validation.field, query));
+                    }
+                    None
+                }
+                rule => rule.check(&column_value(self, validation.column)),
+            };
+            if let Some(message) = message {
+                errors.add(validation.field, message);
+            }
+        }
+        async move {
+            for (field, query) in unique {
+                if query.count(conn).await? > 0 {
+                    errors.add(field, "has already been taken");
+                }
+            }
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(ModelError::Validation(errors))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseConnection;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, Model)]
+    #[validate(custom = "check_sale")]
+    struct Product {
+        id: i32,
+        #[validate(presence, length(max = 10))]
+        name: String,
+        #[validate(unique, format = "^[a-z-]+$")]
+        slug: String,
+        #[validate(inclusion("draft", "live"))]
+        status: String,
+        #[validate(range(min = 0), custom = "check_even")]
+        price: i64,
+        sale: Option<i64>,
+    }
+
+    fn check_sale(product: &Product) -> Result<(), String> {
+        match product.sale {
+            Some(sale) if sale > product.price => Err("the sale price is too high".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_even(price: &i64) -> Result<(), String> {
+        match price % 2 {
+            0 => Ok(()),
+            _ => Err("must be even".to_string()),
+        }
+    }
+
+    fn message(rule: Rule<()>, value: impl Into<SqlValue>) -> Option<String> {
+        rule.check(&value.into())
+    }
+
+    #[test]
+    fn test_rules_check_values() {
+        assert_eq!(
+            message(Rule::Presence, "  "),
+            Some("can't be blank".to_string())
+        );
+        assert_eq!(
+            message(Rule::Presence, None::<i32>),
+            Some("can't be blank".to_string())
+        );
+        assert_eq!(message(Rule::Presence, 0), None);
+
+        let length = || Rule::Length {
+            min: Some(2),
+            max: Some(3),
+        };
+        assert_eq!(
+            message(length(), "a"),
+            Some("is too short (minimum is 2 characters)".to_string())
+        );
+        assert_eq!(message(length(), "äöü"), None);
+        assert_eq!(
+            message(length(), "abcd"),
+            Some("is too long (maximum is 3 characters)".to_string())
+        );
+        assert_eq!(message(length(), None::<String>), None);
+
+        assert_eq!(message(Rule::format("^[a-z-]+$"), "a-slug"), None);
+        assert_eq!(
+            message(Rule::format("^[a-z-]+$"), "A slug"),
+            Some("is invalid".to_string())
+        );
+
+        let inclusion = || Rule::Inclusion(vec![1.into(), 2.into()]);
+        assert_eq!(message(inclusion(), 2_i64), None);
+        assert_eq!(
+            message(inclusion(), 3),
+            Some("is not included in the list".to_string())
+        );
+
+        let range = || Rule::Range {
+            min: Some(0.0),
+            max: Some(1.5),
+        };
+        assert_eq!(message(range(), 1.5), None);
+        assert_eq!(
+            message(range(), -1),
+            Some("must be greater than or equal to 0".to_string())
+        );
+        assert_eq!(
+            message(range(), 2_i64),
+            Some("must be less than or equal to 1.5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validation_errors_display_and_serialize() {
+        let mut errors = ValidationErrors::new();
+        errors.add("title", "can't be blank");
+        errors.add(BASE, "dates overlap");
+        errors.add("title", "is invalid");
+        assert_eq!(
+            errors.to_string(),
+            "dates overlap, title can't be blank, title is invalid"
+        );
+        assert_eq!(
+            serde_json::to_value(&errors).unwrap(),
+            serde_json::json!({ "base": ["dates overlap"], "title": ["can't be blank", "is invalid"] })
+        );
+        assert!(errors.get("body").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_derived_rules_guard_writes() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE products (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL, \
+             slug TEXT NOT NULL, status TEXT NOT NULL, price INTEGER NOT NULL, sale INTEGER)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let conn = DatabaseConnection::Sqlite(Arc::new(pool));
+
+        let product = Product {
+            id: 0,
+            name: "Lamp".to_string(),
+            slug: "lamp".to_string(),
+            status: "live".to_string(),
+            price: 40,
+            sale: Some(30),
+        };
+        let lamp = product.insert(&conn).await.unwrap();
+        // Its own slug is not taken
+        lamp.update(&conn).await.unwrap();
+
+        let invalid = Product {
+            id: 0,
+            name: " ".to_string(),
+            slug: "lamp".to_string(),
+            status: "gone".to_string(),
+            price: -3,
+            sale: Some(10),
+        };
+        let errors = match invalid.insert(&conn).await {
+            Err(ModelError::Validation(errors)) => errors,
+            other => panic!("expected validation errors, got {:?}", other),
+        };
+        assert_eq!(errors.get("name"), ["can't be blank"]);
+        assert_eq!(errors.get("slug"), ["has already been taken"]);
+        assert_eq!(errors.get("status"), ["is not included in the list"]);
+        assert_eq!(
+            errors.get("price"),
+            ["must be greater than or equal to 0", "must be even"]
+        );
+        assert_eq!(errors.get(BASE), ["the sale price is too high"]);
+        assert_eq!(Product::count(&conn).await.unwrap(), 1);
+
+        let renamed = Product {
+            name: "A very long lamp".to_string(),
+            slug: "Lamp!".to_string(),
+            ..lamp
+        };
+        let errors = match renamed.update(&conn).await {
+            Err(ModelError::Validation(errors)) => errors,
+            other => panic!("expected validation errors, got {:?}", other),
+        };
+        assert_eq!(
+            errors.to_string(),
+            "name is too long (maximum is 10 characters), slug is invalid"
+        );
+    }
+}
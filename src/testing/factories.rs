@@ -0,0 +1,245 @@
+use crate::database::Executor;
+use crate::model::{Model, ModelError};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use sqlx::types::Uuid;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Numbers the records factories build, so unique columns get distinct values.
+static SEQUENCE: AtomicU64 = AtomicU64::new(1);
+
+/// # Name: FactoryValue
+/// ## Description
+/// The value a factory gives a column of this type. Types without an implementation fall back
+/// to `Default`, so numbers start at 0, booleans at `false` and optional columns at `None`;
+/// a primary key left at 0 is assigned by the database.
+pub trait FactoryValue {
+    /// The value for `column` of the `sequence`th record a factory builds.
+    fn factory_value(column: &str, sequence: u64) -> Self;
+}
+
+impl FactoryValue for String {
+    /// `user<n>@example.com` for email columns, and the column name and number otherwise.
+    fn factory_value(column: &str, sequence: u64) -> Self {
+        if column.contains("email") {
+            format!("user{}@example.com", sequence)
+        } else {
+            format!("{} {}", column, sequence)
+        }
+    }
+}
+
+impl FactoryValue for NaiveDateTime {
+    fn factory_value(_column: &str, _sequence: u64) -> Self {
+        Utc::now().naive_utc()
+    }
+}
+
+impl FactoryValue for DateTime<Utc> {
+    fn factory_value(_column: &str, _sequence: u64) -> Self {
+        Utc::now()
+    }
+}
+
+impl FactoryValue for NaiveDate {
+    fn factory_value(_column: &str, _sequence: u64) -> Self {
+        Utc::now().date_naive()
+    }
+}
+
+impl FactoryValue for Uuid {
+    fn factory_value(_column: &str, sequence: u64) -> Self {
+        Uuid::from_u128(sequence as u128)
+    }
+}
+
+impl FactoryValue for serde_json::Value {
+    fn factory_value(_column: &str, _sequence: u64) -> Self {
+        serde_json::json!({})
+    }
+}
+
+/// # Name: Factory
+/// ## Description
+/// Builds a record with every column filled, for tests and demo data. `#[derive(Model)]`
+/// implements it with `FactoryValue` for each column, and `Default` for skipped fields.
+/// Implementing it by hand is not possible alongside the derive; set columns that need another
+/// value with `FactoryBuilder::with` instead.
+pub trait Factory: Model {
+    /// The record with default values, numbered `sequence`.
+    fn build(sequence: u64) -> Self;
+}
+
+/// # Name: factory
+/// ## Description
+/// Starts building a record of `M` from the defaults of its columns.
+/// ## Example
+/// ```no_run
+/// use rustyroad::model::Model;
+/// use rustyroad::testing::{factory, TestDatabase};
+///
+/// #[derive(Debug, Clone, Model)]
+/// struct User {
+///     id: i32,
+///     email: String,
+///     admin: bool,
+/// }
+///
+/// # async fn run() {
+/// let db = TestDatabase::new().await.unwrap();
+/// // user<n>@example.com, not an admin
+/// let user = factory::<User>().create(&db).await.unwrap();
+/// let admin = factory::<User>()
+///     .with(|u| u.admin = true)
+///     .create(&db)
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub fn factory<M: Factory>() -> FactoryBuilder<M> {
+    FactoryBuilder {
+        record: M::build(SEQUENCE.fetch_add(1, Ordering::Relaxed)),
+    }
+}
+
+/// A record being built by `factory`.
+pub struct FactoryBuilder<M> {
+    record: M,
+}
+
+impl<M: Factory> FactoryBuilder<M> {
+    /// Changes the record, e.g. to set a foreign key or a column the test is about.
+    pub fn with(mut self, change: impl FnOnce(&mut M)) -> Self {
+        change(&mut self.record);
+        self
+    }
+
+    /// The record, without saving it.
+    pub fn build(self) -> M {
+        self.record
+    }
+
+    /// Inserts the record with `Model::insert` and returns it as stored.
+    pub async fn create(self, conn: &impl Executor) -> Result<M, ModelError> {
+        self.record.insert(conn).await
+    }
+}
+
+/// Picks the default of a column by its type at compile time: `FactoryValue` when the type
+/// implements it, `Default` otherwise. Used by `#[derive(Model)]`.
+#[doc(hidden)]
+pub struct FactoryProbe<T>(PhantomData<T>);
+
+impl<T> FactoryProbe<T> {
+    #[doc(hidden)]
+    pub fn new() -> Self {
+        FactoryProbe(PhantomData)
+    }
+}
+
+impl<T> Default for FactoryProbe<T> {
+    fn default() -> Self {
+        FactoryProbe::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaFactoryValue<T> {
+    fn factory_default(&self, column: &str, sequence: u64) -> T;
+}
+
+impl<T: FactoryValue> ViaFactoryValue<T> for &&FactoryProbe<T> {
+    fn factory_default(&self, column: &str, sequence: u64) -> T {
+        T::factory_value(column, sequence)
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaDefault<T> {
+    fn factory_default(&self, column: &str, sequence: u64) -> T;
+}
+
+impl<T: Default> ViaDefault<T> for &FactoryProbe<T> {
+    fn factory_default(&self, _column: &str, _sequence: u64) -> T {
+        T::default()
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaNothing<T> {
+    fn factory_default(&self, column: &str, sequence: u64) -> T;
+}
+
+impl<T> ViaNothing<T> for FactoryProbe<T> {
+    fn factory_default(&self, column: &str, _sequence: u64) -> T {
+        panic!(
+            "a factory has no default for `{}`; give it one with `.with(..)` or implement `FactoryValue` for its type",
+            column
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseConnection;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, Model)]
+    struct Author {
+        id: i64,
+        email: String,
+        name: String,
+        admin: bool,
+        created_at: NaiveDateTime,
+    }
+
+    #[derive(Debug, Clone, Model)]
+    struct Book {
+        id: i64,
+        author_id: i64,
+        title: String,
+        published_on: Option<NaiveDate>,
+    }
+
+    async fn connection() -> DatabaseConnection {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for table in [
+            "CREATE TABLE authors (id INTEGER PRIMARY KEY AUTOINCREMENT, email TEXT NOT NULL \
+             UNIQUE, name TEXT NOT NULL, admin BOOLEAN NOT NULL DEFAULT 0, \
+             created_at DATETIME NOT NULL)",
+            "CREATE TABLE books (id INTEGER PRIMARY KEY AUTOINCREMENT, author_id INTEGER NOT \
+             NULL REFERENCES authors (id), title TEXT NOT NULL, published_on DATE)",
+        ] {
+            sqlx::query(table).execute(&pool).await.unwrap();
+        }
+        DatabaseConnection::Sqlite(Arc::new(pool))
+    }
+
+    #[tokio::test]
+    async fn test_factories_fill_columns_by_type() {
+        let conn = connection().await;
+        let first = factory::<Author>().create(&conn).await.unwrap();
+        let admin = factory::<Author>()
+            .with(|a| a.admin = true)
+            .create(&conn)
+            .await
+            .unwrap();
+        assert!(first.email.ends_with("@example.com") && first.email != admin.email);
+        assert!(first.name.starts_with("name ") && !first.admin && admin.admin);
+
+        let book = factory::<Book>()
+            .with(|b| b.author_id = admin.id)
+            .create(&conn)
+            .await
+            .unwrap();
+        assert_eq!(book.published_on, None);
+        assert_eq!(Book::count(&conn).await.unwrap(), 1);
+        assert!(factory::<Book>().build().title.starts_with("title "));
+    }
+}
@@ -0,0 +1,565 @@
+use crate::database::migrations::CustomMigrationError;
+use crate::database::{
+    introspect_schema, DatabaseConnection, DatabaseType, Executor, QueryBuilder, SqlValue,
+};
+use crate::model::Model;
+use crate::writers::rust_type_for_column;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::mysql::MySqlRow;
+use sqlx::postgres::PgRow;
+use sqlx::sqlite::SqliteRow;
+use sqlx::types::Uuid;
+use sqlx::{FromRow, Row};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where a project keeps its fixtures, relative to its root.
+pub const FIXTURES_DIR: &str = "./tests/fixtures";
+
+/// # Name: FixtureError
+/// ## Description
+/// Why fixtures could not be read or inserted.
+#[derive(Debug)]
+pub enum FixtureError {
+    /// A fixture file could not be read.
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    /// A fixture file is not TOML, or not a table of labelled rows.
+    Parse {
+        path: PathBuf,
+        message: String,
+    },
+    /// A fixture file is named after a table the database does not have.
+    UnknownTable(String),
+    /// A fixture sets a column its table does not have.
+    UnknownColumn {
+        table: String,
+        column: String,
+    },
+    /// A value does not fit the type of its column.
+    InvalidValue {
+        table: String,
+        label: String,
+        column: String,
+        message: String,
+    },
+    /// A fixture refers to a row of its own table that is inserted after it.
+    UnresolvedLabel {
+        table: String,
+        column: String,
+        label: String,
+    },
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixtureError::Io { path, error } => {
+                write!(f, "could not read {}: {}", path.display(), error)
+            }
+            FixtureError::Parse { path, message } => {
+                write!(f, "could not parse {}: {}", path.display(), message)
+            }
+            FixtureError::UnknownTable(table) => {
+                write!(
+                    f,
+                    "there are fixtures for `{}`, which is not a table",
+                    table
+                )
+            }
+            FixtureError::UnknownColumn { table, column } => {
+                write!(f, "`{}` has no column `{}`", table, column)
+            }
+            FixtureError::InvalidValue {
+                table,
+                label,
+                column,
+                message,
+            } => write!(f, "{}.{} of `{}`: {}", table, column, label, message),
+            FixtureError::UnresolvedLabel {
+                table,
+                column,
+                label,
+            } => write!(
+                f,
+                "{}.{} refers to `{}`, which is not inserted yet; list it first",
+                table, column, label
+            ),
+            FixtureError::Database(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for FixtureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FixtureError::Io { error, .. } => Some(error),
+            FixtureError::Database(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<sqlx::Error> for FixtureError {
+    fn from(err: sqlx::Error) -> Self {
+        FixtureError::Database(err)
+    }
+}
+
+impl From<CustomMigrationError> for FixtureError {
+    fn from(err: CustomMigrationError) -> Self {
+        match err {
+            CustomMigrationError::SqlxError(err) => FixtureError::Database(err),
+            other => FixtureError::Database(sqlx::Error::Protocol(other.to_string())),
+        }
+    }
+}
+
+/// # Name: Fixtures
+/// ## Description
+/// Rows to insert into a database, read from one TOML file per table. Each top-level table of
+/// `<table>.toml` is a row, under a label the other fixtures can refer to it by:
+///
+/// ```toml
+/// # tests/fixtures/users.toml
+/// [ada]
+/// email = "ada@example.com"
+///
+/// # tests/fixtures/posts.toml
+/// [first_post]
+/// title = "Hello"
+/// user_id = "ada"
+/// ```
+///
+/// A string in a foreign key column that is the label of a fixture of the referenced table
+/// stands for that row's key. Tables are inserted so that referenced rows come first, and rows
+/// of one table in the order of their labels. Columns a fixture leaves out get their database
+/// default, except `created_at` and `updated_at`, which are set to the current time. Values are
+/// converted to the column's type, so dates and times may be written as strings.
+/// ## Example
+/// ```no_run
+/// use rustyroad::testing::{Fixtures, TestDatabase};
+///
+/// # async fn run() {
+/// let db = TestDatabase::sqlite().await.unwrap();
+/// let fixtures = Fixtures::load("tests/fixtures").unwrap();
+/// let loaded = fixtures.insert(db.connection()).await.unwrap();
+/// println!("ada is {:?}", loaded.id("users", "ada"));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    tables: BTreeMap<String, toml::Table>,
+}
+
+impl Fixtures {
+    /// Reads every `*.toml` file in `dir`; the file name is the table. A missing directory has
+    /// no fixtures.
+    pub fn load(dir: impl AsRef<Path>) -> Result<Fixtures, FixtureError> {
+        let dir = dir.as_ref();
+        let mut fixtures = Fixtures::default();
+        if !dir.is_dir() {
+            return Ok(fixtures);
+        }
+        let entries = fs::read_dir(dir).map_err(|error| FixtureError::Io {
+            path: dir.to_path_buf(),
+            error,
+        })?;
+        for entry in entries {
+            let path = entry
+                .map_err(|error| FixtureError::Io {
+                    path: dir.to_path_buf(),
+                    error,
+                })?
+                .path();
+            if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+                continue;
+            }
+            let table = match path.file_stem().and_then(|s| s.to_str()) {
+                Some(table) => table.to_string(),
+                None => continue,
+            };
+            let contents = fs::read_to_string(&path).map_err(|error| FixtureError::Io {
+                path: path.clone(),
+                error,
+            })?;
+            let rows: toml::Table =
+                toml::from_str(&contents).map_err(|err| FixtureError::Parse {
+                    path: path.clone(),
+                    message: err.to_string(),
+                })?;
+            if let Some((label, _)) = rows.iter().find(|(_, row)| !row.is_table()) {
+                return Err(FixtureError::Parse {
+                    path,
+                    message: format!("`{}` is not a table of columns", label),
+                });
+            }
+            fixtures.tables.insert(table, rows);
+        }
+        Ok(fixtures)
+    }
+
+    /// The tables there are fixtures for.
+    pub fn tables(&self) -> impl Iterator<Item = &str> {
+        self.tables.keys().map(String::as_str)
+    }
+
+    fn has_label(&self, table: &str, label: &str) -> bool {
+        self.tables
+            .get(table)
+            .is_some_and(|rows| rows.contains_key(label))
+    }
+
+    /// Inserts the fixtures in one transaction, so either all of them are inserted or none.
+    pub async fn insert(&self, conn: &DatabaseConnection) -> Result<LoadedFixtures, FixtureError> {
+        let schema = introspect_schema(conn).await?.without_internal_tables();
+        if let Some(table) = self.tables().find(|table| schema.table(table).is_none()) {
+            return Err(FixtureError::UnknownTable(table.to_string()));
+        }
+        let database_type = conn.database_type();
+        let now = Utc::now();
+
+        let tx = conn.begin().await?;
+        let result = async {
+            let mut loaded = LoadedFixtures::default();
+            for table in schema.dependency_order() {
+                let Some(rows) = self.tables.get(&table) else {
+                    continue;
+                };
+                let info = schema.table(&table).expect("checked above");
+                let foreign_keys = schema.foreign_keys_from(&table);
+                let primary_key = info.primary_key();
+                if let Some(column) = primary_key.first() {
+                    loaded
+                        .primary_keys
+                        .insert(table.clone(), column.to_string());
+                }
+                // the primary key, and whatever other fixtures may refer to
+                let mut key_columns: Vec<&str> = primary_key.clone();
+                for fk in schema.foreign_keys_to(&table) {
+                    if !key_columns.contains(&fk.referenced_column.as_str()) {
+                        key_columns.push(&fk.referenced_column);
+                    }
+                }
+
+                for (label, row) in rows {
+                    let row = row.as_table().expect("checked by load");
+                    let mut values = Vec::new();
+                    for (column, value) in row {
+                        let column_info =
+                            info.column(column)
+                                .ok_or_else(|| FixtureError::UnknownColumn {
+                                    table: table.clone(),
+                                    column: column.clone(),
+                                })?;
+                        let reference = foreign_keys.iter().find(|fk| &fk.column == column);
+                        let value = match (reference, value.as_str()) {
+                            (Some(fk), Some(target))
+                                if self.has_label(&fk.referenced_table, target) =>
+                            {
+                                loaded
+                                    .key(&fk.referenced_table, target, &fk.referenced_column)
+                                    .cloned()
+                                    .ok_or_else(|| FixtureError::UnresolvedLabel {
+                                        table: table.clone(),
+                                        column: column.clone(),
+                                        label: target.to_string(),
+                                    })?
+                            }
+                            _ => convert(
+                                value,
+                                rust_type_for_column(&column_info.data_type, &database_type),
+                            )
+                            .map_err(|message| {
+                                FixtureError::InvalidValue {
+                                    table: table.clone(),
+                                    label: label.clone(),
+                                    column: column.clone(),
+                                    message,
+                                }
+                            })?,
+                        };
+                        values.push((column.as_str(), value));
+                    }
+                    for stamp in ["created_at", "updated_at"] {
+                        if let (None, Some(column)) = (row.get(stamp), info.column(stamp)) {
+                            let rust_type = rust_type_for_column(&column.data_type, &database_type);
+                            values.push((stamp, timestamp(now, rust_type)));
+                        }
+                    }
+
+                    let mut keys: HashMap<String, SqlValue> = values
+                        .iter()
+                        .filter(|(column, _)| key_columns.contains(column))
+                        .map(|(column, value)| (column.to_string(), value.clone()))
+                        .collect();
+                    let missing: Vec<&str> = key_columns
+                        .iter()
+                        .copied()
+                        .filter(|column| !keys.contains_key(*column))
+                        .collect();
+                    let query = QueryBuilder::insert(&table).values(values);
+                    if missing.is_empty() {
+                        query.execute(&tx).await?;
+                    } else if database_type == DatabaseType::Mysql {
+                        // MySQL has no RETURNING, but reports the AUTO_INCREMENT key it assigned
+                        let (_, id) = query.execute(&tx).await?;
+                        if let Some(column) = missing.iter().find(|c| primary_key.contains(c)) {
+                            keys.insert(column.to_string(), SqlValue::BigInt(Some(id as i64)));
+                        }
+                    } else {
+                        let Keys(returned) =
+                            query.returning(missing.clone()).fetch_one(&tx).await?;
+                        keys.extend(missing.into_iter().map(String::from).zip(returned));
+                    }
+                    loaded.keys.insert((table.clone(), label.clone()), keys);
+                }
+            }
+            Ok::<_, FixtureError>(loaded)
+        }
+        .await;
+        tx.finish(result).await
+    }
+}
+
+/// # Name: LoadedFixtures
+/// ## Description
+/// The keys of the rows `Fixtures::insert` inserted, by table and label.
+#[derive(Debug, Clone, Default)]
+pub struct LoadedFixtures {
+    keys: HashMap<(String, String), HashMap<String, SqlValue>>,
+    primary_keys: HashMap<String, String>,
+}
+
+impl LoadedFixtures {
+    fn key(&self, table: &str, label: &str, column: &str) -> Option<&SqlValue> {
+        self.keys
+            .get(&(table.to_string(), label.to_string()))
+            .and_then(|keys| keys.get(column))
+    }
+
+    /// The primary key of the row labelled `label` in `table`.
+    pub fn id(&self, table: &str, label: &str) -> Option<&SqlValue> {
+        self.key(table, label, self.primary_keys.get(table)?)
+    }
+
+    /// Loads the row labelled `label` as the model of its table.
+    pub async fn get<M: Model>(&self, conn: &impl Executor, label: &str) -> Result<M, sqlx::Error> {
+        let id = self
+            .key(M::TABLE, label, M::PRIMARY_KEY)
+            .cloned()
+            .ok_or(sqlx::Error::RowNotFound)?;
+        M::find(conn, id).await
+    }
+}
+
+/// Converts a fixture value to the Rust type of its column, or keeps its TOML type when the
+/// column's type is unknown.
+fn convert(value: &toml::Value, rust_type: Option<&str>) -> Result<SqlValue, String> {
+    use toml::Value;
+
+    let text = match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Datetime(d) => Some(d.to_string()),
+        _ => None,
+    };
+    let mismatch = || format!("`{}` is not a {}", value, rust_type.unwrap_or("value"));
+
+    Ok(match (rust_type, value) {
+        (Some("bool"), Value::Boolean(b)) => SqlValue::from(*b),
+        (Some("bool"), Value::Integer(i)) => SqlValue::from(*i != 0),
+        (Some("i8" | "i16" | "i32"), Value::Integer(i)) => {
+            SqlValue::from(i32::try_from(*i).map_err(|e| e.to_string())?)
+        }
+        (Some("i64"), Value::Integer(i)) => SqlValue::from(*i),
+        (Some("f32" | "f64"), Value::Float(f)) => SqlValue::from(*f),
+        (Some("f32" | "f64"), Value::Integer(i)) => SqlValue::from(*i as f64),
+        (Some("rust_decimal::Decimal"), Value::Integer(i)) => SqlValue::from(Decimal::from(*i)),
+        (Some("rust_decimal::Decimal"), Value::Float(f)) => {
+            SqlValue::from(Decimal::try_from(*f).map_err(|e| e.to_string())?)
+        }
+        (Some("rust_decimal::Decimal"), Value::String(s)) => {
+            SqlValue::from(s.parse::<Decimal>().map_err(|e| e.to_string())?)
+        }
+        (Some("chrono::NaiveDate"), _) => {
+            let text = text.ok_or_else(mismatch)?;
+            SqlValue::from(NaiveDate::parse_from_str(&text, "%Y-%m-%d").map_err(|e| e.to_string())?)
+        }
+        (Some("chrono::NaiveDateTime"), _) => {
+            SqlValue::from(naive_date_time(&text.ok_or_else(mismatch)?)?)
+        }
+        (Some("chrono::DateTime<chrono::Utc>"), _) => {
+            let text = text.ok_or_else(mismatch)?;
+            let time = match DateTime::parse_from_rfc3339(&text) {
+                Ok(time) => time.with_timezone(&Utc),
+                Err(_) => naive_date_time(&text)?.and_utc(),
+            };
+            SqlValue::from(time)
+        }
+        (Some("chrono::NaiveTime"), _) => {
+            let text = text.ok_or_else(mismatch)?;
+            SqlValue::from(
+                NaiveTime::parse_from_str(&text, "%H:%M:%S%.f").map_err(|e| e.to_string())?,
+            )
+        }
+        (Some("uuid::Uuid"), _) => {
+            let text = text.ok_or_else(mismatch)?;
+            SqlValue::from(Uuid::parse_str(&text).map_err(|e| e.to_string())?)
+        }
+        (Some("serde_json::Value"), _) => {
+            SqlValue::from(serde_json::to_value(value).map_err(|e| e.to_string())?)
+        }
+        (Some("Vec<u8>"), Value::String(s)) => SqlValue::from(s.as_bytes().to_vec()),
+        (Some("String"), Value::String(s)) => SqlValue::from(s.as_str()),
+        (Some("String"), Value::Integer(_) | Value::Float(_) | Value::Boolean(_)) => {
+            SqlValue::from(value.to_string())
+        }
+        (Some(_), Value::Array(_) | Value::Table(_)) => return Err(mismatch()),
+        (_, Value::Boolean(b)) => SqlValue::from(*b),
+        (_, Value::Integer(i)) => SqlValue::from(*i),
+        (_, Value::Float(f)) => SqlValue::from(*f),
+        (_, Value::String(s)) => SqlValue::from(s.as_str()),
+        (_, Value::Datetime(d)) => SqlValue::from(d.to_string()),
+        (_, Value::Array(_) | Value::Table(_)) => {
+            SqlValue::from(serde_json::to_value(value).map_err(|e| e.to_string())?)
+        }
+    })
+}
+
+fn naive_date_time(text: &str) -> Result<NaiveDateTime, String> {
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f"))
+        .map_err(|e| e.to_string())
+}
+
+/// `now` in the type of a timestamp column.
+fn timestamp(now: DateTime<Utc>, rust_type: Option<&str>) -> SqlValue {
+    match rust_type {
+        Some("chrono::DateTime<chrono::Utc>") => SqlValue::from(now),
+        _ => SqlValue::from(now.naive_utc()),
+    }
+}
+
+/// The key columns an INSERT returned, decoded as whichever key type they hold.
+struct Keys(Vec<SqlValue>);
+
+macro_rules! impl_keys_from_row {
+    ($row:ty => $($key:ty),+) => {
+        impl<'r> FromRow<'r, $row> for Keys {
+            fn from_row(row: &'r $row) -> Result<Self, sqlx::Error> {
+                (0..row.len())
+                    .map(|index| {
+                        $(
+                            if let Ok(value) = row.try_get::<$key, _>(index) {
+                                return Ok(SqlValue::from(value));
+                            }
+                        )+
+                        Err(sqlx::Error::ColumnDecode {
+                            index: index.to_string(),
+                            source: "a fixture key must be an integer, text or a UUID".into(),
+                        })
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(Keys)
+            }
+        }
+    };
+}
+
+impl_keys_from_row!(PgRow => i64, i32, i16, String, Uuid);
+impl_keys_from_row!(MySqlRow => i64, String);
+impl_keys_from_row!(SqliteRow => i64, String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, Model)]
+    struct Author {
+        id: i64,
+        email: String,
+        admin: bool,
+        created_at: NaiveDateTime,
+    }
+
+    #[derive(Debug, Clone, Model)]
+    struct Book {
+        id: i64,
+        author_id: i64,
+        title: String,
+        published_on: Option<NaiveDate>,
+    }
+
+    #[tokio::test]
+    async fn test_fixtures_are_inserted_in_foreign_key_order() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for table in [
+            "CREATE TABLE authors (id INTEGER PRIMARY KEY AUTOINCREMENT, email TEXT NOT NULL, \
+             admin BOOLEAN NOT NULL DEFAULT 0, created_at DATETIME NOT NULL)",
+            "CREATE TABLE books (id INTEGER PRIMARY KEY AUTOINCREMENT, author_id INTEGER NOT \
+             NULL REFERENCES authors (id), title TEXT NOT NULL, published_on DATE)",
+        ] {
+            sqlx::query(table).execute(&pool).await.unwrap();
+        }
+        let conn = DatabaseConnection::Sqlite(Arc::new(pool));
+
+        let dir = tempfile::tempdir().unwrap();
+        // `books` sorts first, but refers to `authors`
+        fs::write(
+            dir.path().join("books.toml"),
+            "[dune]\nauthor_id = \"frank\"\ntitle = \"Dune\"\npublished_on = 1965-08-01\n\n\
+             [emma]\nauthor_id = \"jane\"\ntitle = \"Emma\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("authors.toml"),
+            "[frank]\nemail = \"frank@example.com\"\n\n\
+             [jane]\nemail = \"jane@example.com\"\nadmin = true\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("README.md"), "not a fixture").unwrap();
+
+        let loaded = Fixtures::load(dir.path())
+            .unwrap()
+            .insert(&conn)
+            .await
+            .unwrap();
+        let dune: Book = loaded.get(&conn, "dune").await.unwrap();
+        let frank: Author = loaded.get(&conn, "frank").await.unwrap();
+        let jane: Author = loaded.get(&conn, "jane").await.unwrap();
+        assert_eq!(dune.author_id, frank.id);
+        assert_eq!(dune.published_on, NaiveDate::from_ymd_opt(1965, 8, 1));
+        assert_eq!(
+            loaded.id("authors", "frank"),
+            Some(&SqlValue::from(frank.id))
+        );
+        assert!(jane.admin && !frank.admin);
+
+        // a book without an author fails the whole load, authors included
+        fs::write(dir.path().join("books.toml"), "[it]\ntitle = \"It\"\n").unwrap();
+        fs::write(
+            dir.path().join("authors.toml"),
+            "[stephen]\nemail = \"stephen@example.com\"\n",
+        )
+        .unwrap();
+        let failed = Fixtures::load(dir.path()).unwrap().insert(&conn).await;
+        assert!(matches!(failed, Err(FixtureError::Database(_))));
+        assert_eq!(Author::count(&conn).await.unwrap(), 2);
+
+        fs::write(dir.path().join("books.toml"), "[it]\npages = 1138\n").unwrap();
+        let failed = Fixtures::load(dir.path()).unwrap().insert(&conn).await;
+        assert!(matches!(failed, Err(FixtureError::UnknownColumn { .. })));
+    }
+}
@@ -0,0 +1,7 @@
+pub mod factories;
+pub mod fixtures;
+pub mod test_database;
+
+pub use factories::*;
+pub use fixtures::*;
+pub use test_database::*;
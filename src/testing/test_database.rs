@@ -0,0 +1,416 @@
+use crate::database::{
+    Database, DatabaseConnection, DatabaseType, Executor, ExecutorRef, Transaction, MIGRATIONS_DIR,
+};
+use crate::testing::{FixtureError, Fixtures, LoadedFixtures, FIXTURES_DIR};
+use sqlx::mysql::{MySqlConnectOptions, MySqlPoolOptions};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{ConnectOptions, Connection, Executor as _};
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tempfile::TempDir;
+
+/// What `TestDatabase` removes once the test is done.
+enum Cleanup {
+    /// The temporary directory holding the SQLite file.
+    File(TempDir),
+    Postgres(PgConnectOptions, String),
+    MySql(MySqlConnectOptions, String),
+}
+
+impl Cleanup {
+    async fn run(self) -> Result<(), sqlx::Error> {
+        match self {
+            Cleanup::File(dir) => dir.close().map_err(sqlx::Error::Io),
+            Cleanup::Postgres(options, name) => {
+                let mut conn = options.connect().await?;
+                // FORCE ends connections a pool that was never closed may still hold
+                conn.execute(
+                    format!(
+                        "DROP DATABASE IF EXISTS {} WITH (FORCE)",
+                        DatabaseType::Postgres.quote_identifier(&name)
+                    )
+                    .as_str(),
+                )
+                .await?;
+                conn.close().await
+            }
+            Cleanup::MySql(options, name) => {
+                let mut conn = options.connect().await?;
+                conn.execute(
+                    format!(
+                        "DROP DATABASE IF EXISTS {}",
+                        DatabaseType::Mysql.quote_identifier(&name)
+                    )
+                    .as_str(),
+                )
+                .await?;
+                conn.close().await
+            }
+        }
+    }
+}
+
+tokio::task_local! {
+    /// The test database `TestDatabase::scope` is running a test against.
+    static SCOPED: DatabaseConnection;
+}
+
+/// The connection of the `TestDatabase::scope` the current task runs in, if any.
+pub(crate) fn scoped_connection() -> Option<DatabaseConnection> {
+    SCOPED.try_with(|connection| connection.clone()).ok()
+}
+
+/// # Name: TestDatabase
+/// ## Description
+/// A throwaway database for one test, with every migration in `config/database/migrations`
+/// applied. SQLite databases are a file in a temporary directory. PostgreSQL and MySQL databases
+/// are created on the server `rustyroad.toml` points to, under a unique name, so tests can run in
+/// parallel. `teardown` drops the database; a `TestDatabase` dropped without it drops the
+/// database on a background thread.
+///
+/// Queries run on it like on its `DatabaseConnection`. For tests that should leave nothing
+/// behind even within the database, `transaction()` starts a transaction that rolls back when
+/// it is dropped.
+/// ## Example
+/// ```no_run
+/// use rustyroad::model::Model;
+/// use rustyroad::testing::TestDatabase;
+///
+/// # #[derive(Debug, Clone, Model)]
+/// # struct User { id: i32, email: String }
+/// #[tokio::test]
+/// async fn test_users() {
+///     let db = TestDatabase::new().await.unwrap();
+///     let user = User { id: 0, email: "ada@example.com".into() };
+///     user.insert(&db).await.unwrap();
+///     assert_eq!(User::count(&db).await.unwrap(), 1);
+///
+///     let tx = db.transaction().await.unwrap();
+///     user.insert(&tx).await.unwrap();
+///     drop(tx); // rolled back
+///     assert_eq!(User::count(&db).await.unwrap(), 1);
+///     db.teardown().await.unwrap();
+/// }
+/// ```
+pub struct TestDatabase {
+    connection: DatabaseConnection,
+    name: String,
+    cleanup: Option<Cleanup>,
+}
+
+impl TestDatabase {
+    /// Creates a database on the backend of `rustyroad.toml`, or `rustyroad.<ENVIRONMENT>.toml`
+    /// when `ENVIRONMENT` is set, and applies the project's migrations.
+    pub async fn new() -> Result<TestDatabase, sqlx::Error> {
+        let database = Database::get_database_from_rustyroad_toml()
+            .map_err(|err| sqlx::Error::Configuration(err.into()))?;
+        TestDatabase::create(&database, MIGRATIONS_DIR).await
+    }
+
+    /// Creates a SQLite database, whatever `rustyroad.toml` says, and applies the project's
+    /// migrations.
+    pub async fn sqlite() -> Result<TestDatabase, sqlx::Error> {
+        let database = Database::new(
+            "test".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            0,
+            "sqlite",
+        );
+        TestDatabase::create(&database, MIGRATIONS_DIR).await
+    }
+
+    /// Creates a database next to `database`, on the same server and with the same
+    /// credentials, and applies the migrations in `migrations`, if that directory exists.
+    pub async fn create(
+        database: &Database,
+        migrations: impl AsRef<Path>,
+    ) -> Result<TestDatabase, sqlx::Error> {
+        let name = unique_name(&database.name);
+        let test_database = match database.database_type {
+            DatabaseType::Sqlite => {
+                let dir = TempDir::new()?;
+                let options = SqliteConnectOptions::new()
+                    .filename(dir.path().join(format!("{}.db", name)))
+                    .create_if_missing(true);
+                let pool = SqlitePoolOptions::new().connect_with(options).await?;
+                TestDatabase {
+                    connection: DatabaseConnection::Sqlite(Arc::new(pool)),
+                    name,
+                    cleanup: Some(Cleanup::File(dir)),
+                }
+            }
+            DatabaseType::Postgres => {
+                let server = PgConnectOptions::new()
+                    .host(&database.host)
+                    .port(database.port)
+                    .username(&database.username)
+                    .password(&database.password);
+                let admin = server.clone().database("postgres");
+                let mut conn = admin.connect().await?;
+                conn.execute(
+                    format!(
+                        "CREATE DATABASE {}",
+                        DatabaseType::Postgres.quote_identifier(&name)
+                    )
+                    .as_str(),
+                )
+                .await?;
+                conn.close().await?;
+                let pool = PgPoolOptions::new()
+                    .connect_with(server.database(&name))
+                    .await?;
+                TestDatabase {
+                    connection: DatabaseConnection::Pg(Arc::new(pool)),
+                    name: name.clone(),
+                    cleanup: Some(Cleanup::Postgres(admin, name)),
+                }
+            }
+            DatabaseType::Mysql => {
+                let server = MySqlConnectOptions::new()
+                    .host(&database.host)
+                    .port(database.port)
+                    .username(&database.username)
+                    .password(&database.password);
+                let mut conn = server.connect().await?;
+                conn.execute(
+                    format!(
+                        "CREATE DATABASE {}",
+                        DatabaseType::Mysql.quote_identifier(&name)
+                    )
+                    .as_str(),
+                )
+                .await?;
+                conn.close().await?;
+                let pool = MySqlPoolOptions::new()
+                    .connect_with(server.clone().database(&name))
+                    .await?;
+                TestDatabase {
+                    connection: DatabaseConnection::MySql(Arc::new(pool)),
+                    name: name.clone(),
+                    cleanup: Some(Cleanup::MySql(server, name)),
+                }
+            }
+            DatabaseType::Mongo => {
+                return Err(sqlx::Error::Configuration(
+                    "TestDatabase needs a SQL database, not MongoDB".into(),
+                ))
+            }
+        };
+        let migrations = migrations.as_ref();
+        if migrations.is_dir() {
+            test_database.migrate(migrations).await?;
+        }
+        Ok(test_database)
+    }
+
+    /// The connection to the test database.
+    pub fn connection(&self) -> &DatabaseConnection {
+        &self.connection
+    }
+
+    /// The name of the database, unique to this test.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Runs the `up.sql` of each `<timestamp>-<name>` directory in `migrations`, oldest first.
+    pub async fn migrate(&self, migrations: impl AsRef<Path>) -> Result<(), sqlx::Error> {
+        let mut dirs: Vec<_> = fs::read_dir(migrations)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.join("up.sql").is_file())
+            .collect();
+        dirs.sort();
+        for dir in dirs {
+            let sql = fs::read_to_string(dir.join("up.sql"))?;
+            match &self.connection {
+                DatabaseConnection::Pg(pool) => {
+                    pool.execute(sql.as_str()).await?;
+                }
+                DatabaseConnection::MySql(pool) => {
+                    pool.execute(sql.as_str()).await?;
+                }
+                DatabaseConnection::Sqlite(pool) => {
+                    pool.execute(sql.as_str()).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts the project's fixtures in `tests/fixtures`, returning their keys by label.
+    pub async fn load_fixtures(&self) -> Result<LoadedFixtures, FixtureError> {
+        Fixtures::load(FIXTURES_DIR)?.insert(&self.connection).await
+    }
+
+    /// Runs `test` with `Database::get_db_pool` handing out this database instead of the one in
+    /// `rustyroad.toml`, so code that opens its own pool, like generated models and the
+    /// controllers calling them, reads and writes the test database. Requests sent with
+    /// `actix_web::test::call_service` are handled within the scope.
+    /// ## Example
+    /// ```no_run
+    /// use rustyroad::testing::TestDatabase;
+    ///
+    /// #[tokio::test]
+    /// async fn test_pages() {
+    ///     let db = TestDatabase::new().await.unwrap();
+    ///     db.scope(async {
+    ///         // Page::all() and the handlers using it query `db`
+    ///     })
+    ///     .await;
+    ///     db.teardown().await.unwrap();
+    /// }
+    /// ```
+    pub async fn scope<F: Future>(&self, test: F) -> F::Output {
+        SCOPED.scope(self.connection.clone(), test).await
+    }
+
+    /// Starts a transaction that rolls back when it is dropped without being committed. Run the
+    /// test on it to leave the database as the migrations left it.
+    pub async fn transaction(&self) -> Result<Transaction, sqlx::Error> {
+        self.connection.begin().await
+    }
+
+    /// Closes the connection and drops the database.
+    pub async fn teardown(mut self) -> Result<(), sqlx::Error> {
+        close(&self.connection).await;
+        match self.cleanup.take() {
+            Some(cleanup) => cleanup.run().await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Executor for TestDatabase {
+    fn executor(&self) -> ExecutorRef<'_> {
+        self.connection.executor()
+    }
+}
+
+impl Drop for TestDatabase {
+    fn drop(&mut self) {
+        let cleanup = match self.cleanup.take() {
+            None | Some(Cleanup::File(_)) => return,
+            Some(cleanup) => cleanup,
+        };
+        // Drop cannot await, and the test's runtime may be shutting down, so use a runtime of
+        // its own on another thread
+        let dropped = std::thread::spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map(|runtime| runtime.block_on(cleanup.run()))
+        })
+        .join();
+        if !matches!(dropped, Ok(Ok(Ok(())))) {
+            eprintln!("RustyRoad could not drop the test database '{}'", self.name);
+        }
+    }
+}
+
+async fn close(connection: &DatabaseConnection) {
+    match connection {
+        DatabaseConnection::Pg(pool) => pool.close().await,
+        DatabaseConnection::MySql(pool) => pool.close().await,
+        DatabaseConnection::Sqlite(pool) => pool.close().await,
+    }
+}
+
+/// `<base>_test_<process>_<n>_<nanoseconds>`, which no other test or run picks, trimmed to the
+/// 63 characters PostgreSQL allows.
+fn unique_name(base: &str) -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let base: String = base
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .take(24)
+        .collect::<String>()
+        .to_lowercase();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos())
+        .unwrap_or_default();
+    format!(
+        "{}_test_{}_{}_{}",
+        if base.is_empty() { "rustyroad" } else { &base },
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+        nanos
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::QueryBuilder;
+
+    #[tokio::test]
+    async fn test_sqlite_database_is_migrated_and_removed() {
+        let migrations = TempDir::new().unwrap();
+        for (dir, sql) in [
+            (
+                "20240101000000-create_users",
+                "CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT, email TEXT NOT NULL);",
+            ),
+            (
+                "20240102000000-create_posts",
+                "CREATE TABLE posts (id INTEGER PRIMARY KEY AUTOINCREMENT, \
+                 user_id INTEGER NOT NULL REFERENCES users(id)); \
+                 CREATE INDEX posts_user_id ON posts (user_id);",
+            ),
+        ] {
+            fs::create_dir(migrations.path().join(dir)).unwrap();
+            fs::write(migrations.path().join(dir).join("up.sql"), sql).unwrap();
+        }
+        let config = Database::new(
+            "My App".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            0,
+            "sqlite",
+        );
+
+        let db = TestDatabase::create(&config, migrations.path())
+            .await
+            .unwrap();
+        let other = TestDatabase::create(&config, migrations.path())
+            .await
+            .unwrap();
+        assert!(db.name().starts_with("myapp_test_"));
+        assert_ne!(db.name(), other.name());
+        let file = match &db.cleanup {
+            Some(Cleanup::File(dir)) => dir.path().join(format!("{}.db", db.name())),
+            _ => panic!("a SQLite test database is a temporary file"),
+        };
+
+        let insert = QueryBuilder::insert("users").value("email", "ada@example.com");
+        insert.execute(&db).await.unwrap();
+        QueryBuilder::insert("posts")
+            .value("user_id", 1)
+            .execute(&db)
+            .await
+            .unwrap();
+        let tx = db.transaction().await.unwrap();
+        insert.execute(&tx).await.unwrap();
+        assert_eq!(QueryBuilder::select("users").count(&tx).await.unwrap(), 2);
+        drop(tx);
+        insert.execute(&db).await.unwrap();
+        assert_eq!(QueryBuilder::select("users").count(&db).await.unwrap(), 2);
+        assert_eq!(
+            QueryBuilder::select("users").count(&other).await.unwrap(),
+            0
+        );
+
+        assert!(file.exists());
+        db.teardown().await.unwrap();
+        assert!(!file.exists());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::model::{ModelError, ValidationErrors, BASE};
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use serde_json::json;
+use std::fmt;
+
+/// # Name: AppError
+/// ## Description
+/// Why a request of a generated app failed. Handlers and models return `Result<_, AppError>`, and
+/// `sqlx::Error`, `ModelError` and `tera::Error` convert into it with `?`.
+///
+/// As an actix-web `ResponseError` it answers with the JSON error envelope of the generated APIs,
+/// `{"error": {"status": 404, "message": "Post not found"}}`, plus `fields` for validation errors.
+/// `ErrorPages` turns it into an HTML page for browser requests.
+///
+/// The message of an `Internal` error is logged, never sent: clients get "Internal server error".
+/// ## Example
+/// ```
+/// use actix_web::http::StatusCode;
+/// use actix_web::ResponseError;
+/// use rustyroad::web::AppError;
+///
+/// let error = AppError::from(sqlx::Error::RowNotFound);
+/// assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+///
+/// let error = AppError::NotFound("Post not found".to_string());
+/// assert_eq!(error.message(), "Post not found");
+/// assert_eq!(AppError::Internal("connection refused".to_string()).message(), "Internal server error");
+/// ```
+#[derive(Debug)]
+pub enum AppError {
+    /// 404, with what was not found, e.g. "Post not found".
+    NotFound(String),
+    /// 401: nobody is signed in. `ErrorPages` redirects browsers to `/login`.
+    Unauthorized,
+    /// 403: the signed-in user may not do this.
+    Forbidden,
+    /// 422, with the messages of each invalid field.
+    Validation(ValidationErrors),
+    /// 500, with the cause, which is logged but not shown.
+    Internal(String),
+}
+
+impl AppError {
+    /// The message shown to the client.
+    pub fn message(&self) -> String {
+        match self {
+            AppError::NotFound(message) => message.clone(),
+            AppError::Unauthorized => "You need to sign in first".to_string(),
+            AppError::Forbidden => "You are not allowed to do that".to_string(),
+            AppError::Validation(_) => "Validation failed".to_string(),
+            AppError::Internal(_) => "Internal server error".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Validation(errors) => write!(f, "validation failed: {}", errors),
+            AppError::Internal(cause) => write!(f, "internal server error: {}", cause),
+            e => write!(f, "{}", e.message()),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// A missing row is a 404 and a broken constraint, such as a duplicate unique value, is the
+/// client's fault; anything else is a 500.
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> AppError {
+        match err {
+            sqlx::Error::RowNotFound => AppError::NotFound("Not found".to_string()),
+            sqlx::Error::Database(e) if !matches!(e.kind(), sqlx::error::ErrorKind::Other) => {
+                let mut errors = ValidationErrors::new();
+                errors.add(BASE, e.message());
+                AppError::Validation(errors)
+            }
+            e => AppError::Internal(e.to_string()),
+        }
+    }
+}
+
+/// A stale `lock_version` is reported like a validation error, so the form can be submitted again.
+impl From<ModelError> for AppError {
+    fn from(err: ModelError) -> AppError {
+        match err {
+            ModelError::Database(e) => AppError::from(e),
+            ModelError::Validation(errors) => AppError::Validation(errors),
+            e @ ModelError::StaleObject { .. } => {
+                let mut errors = ValidationErrors::new();
+                errors.add(BASE, e.to_string());
+                AppError::Validation(errors)
+            }
+        }
+    }
+}
+
+impl From<tera::Error> for AppError {
+    fn from(err: tera::Error) -> AppError {
+        // The cause of a template error is in its source chain, e.g. an undefined variable
+        let mut cause = err.to_string();
+        let mut source = std::error::Error::source(&err);
+        while let Some(e) = source {
+            cause = format!("{}: {}", cause, e);
+            source = e.source();
+        }
+        AppError::Internal(cause)
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Unauthorized => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden => StatusCode::FORBIDDEN,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if let AppError::Internal(cause) = self {
+            eprintln!("Internal server error: {}", cause);
+        }
+        let status = self.status_code();
+        let mut error = json!({ "status": status.as_u16(), "message": self.message() });
+        if let AppError::Validation(errors) = self {
+            error["fields"] = json!(errors);
+        }
+        HttpResponse::build(status).json(json!({ "error": error }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+
+    async fn body_of(error: AppError) -> serde_json::Value {
+        let body = to_bytes(error.error_response().into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_error_response_is_the_api_error_envelope() {
+        let body = body_of(AppError::NotFound("Post not found".to_string())).await;
+        assert_eq!(
+            body,
+            json!({ "error": { "status": 404, "message": "Post not found" } })
+        );
+
+        let mut errors = ValidationErrors::new();
+        errors.add("title", "can't be blank");
+        let body = body_of(AppError::Validation(errors)).await;
+        assert_eq!(body["error"]["status"], 422);
+        assert_eq!(
+            body["error"]["fields"],
+            json!({ "title": ["can't be blank"] })
+        );
+
+        let body = body_of(AppError::Internal(
+            "password authentication failed".to_string(),
+        ))
+        .await;
+        assert_eq!(body["error"]["message"], "Internal server error");
+    }
+
+    #[test]
+    fn test_conversions_keep_the_status() {
+        let stale = ModelError::StaleObject {
+            table: "posts",
+            id: "7".to_string(),
+        };
+        match AppError::from(stale) {
+            AppError::Validation(errors) => assert_eq!(
+                errors.get(BASE),
+                ["the posts row 7 was changed or deleted since it was loaded"]
+            ),
+            e => panic!("expected a validation error, got {:?}", e),
+        }
+        let error = AppError::from(ModelError::Database(sqlx::Error::PoolTimedOut));
+        assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            AppError::Unauthorized.status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(AppError::Forbidden.status_code(), StatusCode::FORBIDDEN);
+    }
+}
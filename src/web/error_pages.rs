@@ -0,0 +1,229 @@
+use crate::web::AppError;
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::{header, StatusCode};
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use tera::{Context, Tera};
+
+/// # Name: ErrorPages
+/// ## Description
+/// Middleware that shows browsers a page instead of the JSON body of an error response.
+///
+/// When a handler returns an `AppError`, or no route matches, a request that accepts HTML and is
+/// not under `/api` gets `pages/404.html.tera`, `pages/403.html.tera` or `pages/500.html.tera`,
+/// rendered with the app's `web::Data<Tera>` and the `title` and `error` of the failure.
+/// `AppError::Unauthorized` redirects to `/login` instead. API requests, and pages that fail to
+/// render, keep the JSON error envelope.
+/// ## Example
+/// ```
+/// use actix_web::{web, App};
+/// use rustyroad::web::ErrorPages;
+/// use tera::Tera;
+///
+/// let app = App::new()
+///     .wrap(ErrorPages)
+///     .app_data(web::Data::new(Tera::default()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ErrorPages;
+
+impl<S, B> Transform<S, ServiceRequest> for ErrorPages
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = ErrorPagesMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ErrorPagesMiddleware { service }))
+    }
+}
+
+pub struct ErrorPagesMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for ErrorPagesMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let response = self.service.call(req);
+        Box::pin(async move { Ok(error_page(response.await?)) })
+    }
+}
+
+/// Whether `req` comes from a browser rather than an API client.
+fn wants_html(req: &HttpRequest) -> bool {
+    if req.path() == "/api" || req.path().starts_with("/api/") {
+        return false;
+    }
+    match req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+    {
+        Some(accept) => accept.contains("text/html") || !accept.contains("application/json"),
+        None => true,
+    }
+}
+
+fn error_page<B>(res: ServiceResponse<B>) -> ServiceResponse<EitherBody<B>> {
+    if !wants_html(res.request()) {
+        return res.map_into_left_body();
+    }
+    let error = res
+        .response()
+        .error()
+        .and_then(|e| e.as_error::<AppError>());
+    let (status, message) = match error {
+        Some(AppError::Unauthorized) => {
+            let redirect = HttpResponse::SeeOther()
+                .append_header((header::LOCATION, "/login"))
+                .finish();
+            return res.into_response(redirect).map_into_right_body();
+        }
+        Some(AppError::Validation(_)) => return res.map_into_left_body(),
+        Some(e) => (page_status(e), e.message()),
+        // No route matched
+        None if res.status() == StatusCode::NOT_FOUND && res.response().error().is_none() => (
+            StatusCode::NOT_FOUND,
+            "The page you are looking for does not exist".to_string(),
+        ),
+        None => return res.map_into_left_body(),
+    };
+    let Some(tera) = res.request().app_data::<web::Data<Tera>>() else {
+        return res.map_into_left_body();
+    };
+    let mut context = Context::new();
+    context.insert("title", &page_title(status));
+    context.insert("error", &message);
+    let template = format!("pages/{}.html.tera", status.as_u16());
+    match tera.render(&template, &context) {
+        Ok(body) => {
+            let page = HttpResponse::build(status)
+                .content_type("text/html; charset=utf-8")
+                .body(body);
+            res.into_response(page).map_into_right_body()
+        }
+        Err(e) => {
+            eprintln!("Failed to render {}: {}", template, e);
+            res.map_into_left_body()
+        }
+    }
+}
+
+fn page_title(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::NOT_FOUND => "Page not found",
+        StatusCode::FORBIDDEN => "Forbidden",
+        _ => "Something went wrong",
+    }
+}
+
+/// The page shown for `error`: 404, 403 or 500.
+fn page_status(error: &AppError) -> StatusCode {
+    match error {
+        AppError::NotFound(_) => StatusCode::NOT_FOUND,
+        AppError::Forbidden => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{get, test, App};
+
+    #[get("/posts/{id}")]
+    async fn show_post(id: web::Path<i32>) -> Result<HttpResponse, AppError> {
+        match id.into_inner() {
+            1 => Ok(HttpResponse::Ok().body("Hello")),
+            2 => Err(AppError::Unauthorized),
+            3 => Err(AppError::Internal("connection refused".to_string())),
+            _ => Err(AppError::NotFound("Post not found".to_string())),
+        }
+    }
+
+    #[get("/api/posts/{id}")]
+    async fn api_show_post() -> Result<HttpResponse, AppError> {
+        Err(AppError::NotFound("Post not found".to_string()))
+    }
+
+    fn tera() -> Tera {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(vec![
+            (
+                "pages/404.html.tera",
+                "<h1>{{ title }}</h1><p>{{ error }}</p>",
+            ),
+            (
+                "pages/500.html.tera",
+                "<h1>{{ title }}</h1><p>{{ error }}</p>",
+            ),
+        ])
+        .unwrap();
+        tera
+    }
+
+    #[actix_web::test]
+    async fn test_browsers_get_error_pages_and_api_clients_json() {
+        let app = test::init_service(
+            App::new()
+                .wrap(ErrorPages)
+                .app_data(web::Data::new(tera()))
+                .service(show_post)
+                .service(api_show_post),
+        )
+        .await;
+        let get = |uri: &str| test::TestRequest::get().uri(uri).to_request();
+
+        let res = test::call_service(&app, get("/posts/1")).await;
+        assert_eq!(test::read_body(res).await, "Hello");
+
+        let res = test::call_service(&app, get("/posts/9")).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        let body = test::read_body(res).await;
+        assert_eq!(body, "<h1>Page not found</h1><p>Post not found</p>");
+
+        let res = test::call_service(&app, get("/posts/2")).await;
+        assert_eq!(res.status(), StatusCode::SEE_OTHER);
+        assert_eq!(res.headers().get(header::LOCATION).unwrap(), "/login");
+
+        let res = test::call_service(&app, get("/posts/3")).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = test::read_body(res).await;
+        assert!(!String::from_utf8_lossy(&body).contains("connection refused"));
+
+        let res = test::call_service(&app, get("/nowhere")).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert!(String::from_utf8_lossy(&test::read_body(res).await).contains("Page not found"));
+
+        let res = test::call_service(&app, get("/api/posts/9")).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        let body: serde_json::Value = test::read_body_json(res).await;
+        assert_eq!(body["error"]["message"], "Post not found");
+
+        let req = test::TestRequest::get()
+            .uri("/posts/9")
+            .insert_header((header::ACCEPT, "application/json"))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["error"]["status"], 404);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod app_error;
+pub mod error_pages;
+
+pub use app_error::*;
+pub use error_pages::*;
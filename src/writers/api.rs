@@ -0,0 +1,419 @@
+use crate::database::DatabaseType;
+use crate::generators::{render_template, Generator};
+use crate::writers::scaffold::{write_new_file, FieldContext};
+use crate::writers::{
+    add_services_to_app, generate_model, FieldType, Scaffold, ScaffoldField, CRUD_ACTIONS,
+};
+use color_eyre::eyre::Result;
+use eyre::{eyre, Error};
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+use tera::Context;
+
+/// The service `generate api` registers in main.rs; every API resource hangs off this one scope.
+const API_SCOPE_SERVICE: &str =
+    "actix_web::web::scope(\"/api\").configure(controllers::api::configure)";
+
+/// # Name: render_api_mod
+/// ## Description
+/// Renders `src/controllers/api/mod.rs` from the `api/mod.rs` template: the JSON error envelope
+/// shared by every resource and the `configure` function main.rs mounts under `/api`. Resources
+/// are added by `generate api`.
+pub fn render_api_mod() -> Result<String, Error> {
+    render_template("api", "mod.rs", &Context::new())
+}
+
+/// How one field is read from query strings and JSON bodies in the generated API.
+#[derive(Debug, Clone, Serialize)]
+struct ApiType {
+    /// Binds a `?field=value` filter; `value` is the raw query string value.
+    filter_bind: &'static str,
+    /// The `json_*` parser for request bodies.
+    parser: &'static str,
+    /// A valid and an invalid JSON value, used by the generated tests.
+    sample: &'static str,
+    wrong: &'static str,
+    /// The value an update keeps when the body leaves the field out.
+    current: String,
+    /// Whether a missing value is a validation error. Unchecked checkboxes are `false`.
+    required_check: bool,
+    /// Turns the parsed `Option` into the form field's type.
+    finish: &'static str,
+}
+
+/// One field in the context of the `api/controller.rs` template.
+#[derive(Debug, Clone, Serialize)]
+struct ApiField {
+    #[serde(flatten)]
+    field: FieldContext,
+    api: ApiType,
+}
+
+fn api_type(field: &ScaffoldField) -> ApiType {
+    let (filter_bind, parser, sample, wrong) = match field.field_type {
+        FieldType::String | FieldType::Text if field.required => (
+            "value.to_string()",
+            "json_present_string",
+            "\"example\"",
+            "1",
+        ),
+        FieldType::String | FieldType::Text => {
+            ("value.to_string()", "json_string", "\"example\"", "1")
+        }
+        FieldType::Integer => (
+            "parse_param::<i32>(value, \"an integer\")?",
+            "json_i32",
+            "1",
+            "\"x\"",
+        ),
+        FieldType::BigInt => (
+            "parse_param::<i64>(value, \"an integer\")?",
+            "json_i64",
+            "1",
+            "\"x\"",
+        ),
+        FieldType::Float => (
+            "parse_param::<f64>(value, \"a number\")?",
+            "json_f64",
+            "1.5",
+            "\"x\"",
+        ),
+        FieldType::Boolean => ("parse_bool(value)?", "json_bool", "true", "\"x\""),
+        FieldType::Date => (
+            "parse_param::<chrono::NaiveDate>(value, \"a date like 2024-01-31\")?",
+            "json_date",
+            "\"2024-01-31\"",
+            "\"x\"",
+        ),
+        FieldType::DateTime => (
+            "parse_param::<chrono::NaiveDateTime>(value, \"a date and time like 2024-01-31T09:30:00\")?",
+            "json_datetime",
+            "\"2024-01-31T09:30:00\"",
+            "\"x\"",
+        ),
+        FieldType::Time => (
+            "parse_param::<chrono::NaiveTime>(value, \"a time like 09:30:00\")?",
+            "json_time",
+            "\"09:30:00\"",
+            "\"x\"",
+        ),
+    };
+    let clone = if matches!(field.field_type, FieldType::String | FieldType::Text) {
+        ".clone()"
+    } else {
+        ""
+    };
+    ApiType {
+        filter_bind,
+        parser,
+        sample,
+        wrong,
+        current: if field.required {
+            format!("current.map(|c| c.{}{})", field.name, clone)
+        } else {
+            format!("current.and_then(|c| c.{}{})", field.name, clone)
+        },
+        required_check: field.required && field.field_type != FieldType::Boolean,
+        finish: if field.field_type == FieldType::Boolean {
+            ".unwrap_or(false)"
+        } else if field.required {
+            ".unwrap_or_default()"
+        } else {
+            ""
+        },
+    }
+}
+
+impl Scaffold {
+    /// # Name: render_api_controller
+    /// ## Description
+    /// Renders `src/controllers/api/<name>.rs` from the `api/controller.rs` template: JSON handlers
+    /// for `/<names>` with limit/offset and cursor (`?after=<id>`) pagination, `?field=value`
+    /// filters and `?sort=[-]field` restricted to the model's columns, field-level validation
+    /// errors, and 201/204/404/422 status codes.
+    pub fn render_api_controller(&self) -> Result<String, Error> {
+        let fields: Vec<ApiField> = self
+            .field_contexts()
+            .into_iter()
+            .zip(&self.fields)
+            .map(|(context, field)| ApiField {
+                field: context,
+                api: api_type(field),
+            })
+            .collect();
+        let mut parsers: Vec<&str> = Vec::new();
+        for field in &fields {
+            // json_present_string is built on json_string
+            if field.api.parser == "json_present_string" && !parsers.contains(&"json_string") {
+                parsers.push("json_string");
+            }
+            if !parsers.contains(&field.api.parser) {
+                parsers.push(field.api.parser);
+            }
+        }
+
+        let mut context = self.template_context(&CRUD_ACTIONS);
+        context.insert(
+            "db_type",
+            match self.database_type {
+                DatabaseType::Mysql => "MySql",
+                DatabaseType::Sqlite => "Sqlite",
+                _ => "Postgres",
+            },
+        );
+        context.insert("parsers", &parsers);
+        context.insert("fields", &fields);
+        render_template("api", "controller.rs", &context)
+    }
+}
+
+/// Adds `pub mod <name>;` after the last module declaration of `mod_rs`, without a glob re-export.
+fn declare_module(name: &str, mod_rs: &Path) -> Result<(), Error> {
+    let contents = Generator::read_to_string(mod_rs).unwrap_or_default();
+    if contents.contains(&format!("pub mod {};", name)) {
+        return Ok(());
+    }
+    let declaration = format!("pub mod {};\n", name);
+    let re_mod = Regex::new(r"(?m)^pub mod \w+;\n?").unwrap();
+    let updated = match re_mod.find_iter(&contents).last() {
+        Some(last) if last.as_str().ends_with('\n') => {
+            format!(
+                "{}{}{}",
+                &contents[..last.end()],
+                declaration,
+                &contents[last.end()..]
+            )
+        }
+        Some(last) => format!(
+            "{}\n{}{}",
+            &contents[..last.end()],
+            declaration.trim_end(),
+            &contents[last.end()..]
+        ),
+        // Inner doc comments and attributes have to stay at the top of the file
+        None => {
+            let header_end = contents
+                .lines()
+                .take_while(|l| l.starts_with("//!") || l.starts_with("#!["))
+                .map(|l| l.len() + 1)
+                .sum::<usize>()
+                .min(contents.len());
+            if header_end == 0 {
+                format!("{}{}", declaration, contents)
+            } else {
+                format!(
+                    "{}\n{}{}",
+                    &contents[..header_end],
+                    declaration,
+                    &contents[header_end..]
+                )
+            }
+        }
+    };
+    Generator::update_file(mod_rs, &updated)?;
+    Ok(())
+}
+
+/// Adds `cfg.configure(<name>::configure);` to `controllers::api::configure`, before its closing brace.
+fn add_to_api_configure(name: &str, api_mod_rs: &Path) -> Result<(), Error> {
+    let contents = Generator::read_to_string(api_mod_rs)?;
+    let call = format!("cfg.configure({}::configure);", name);
+    if contents.contains(&call) {
+        return Ok(());
+    }
+    let start = contents
+        .find("pub fn configure(cfg: &mut web::ServiceConfig) {")
+        .ok_or_else(|| {
+            eyre!(
+                "Could not find `pub fn configure` in {}; add `{}` to it yourself",
+                api_mod_rs.display(),
+                call
+            )
+        })?;
+    // The function body ends at the first line that is just `}`
+    let end = contents[start..]
+        .find("\n}")
+        .map(|i| start + i + 1)
+        .ok_or_else(|| {
+            eyre!(
+                "Could not find the end of `configure` in {}",
+                api_mod_rs.display()
+            )
+        })?;
+    let updated = format!("{}    {}\n{}", &contents[..end], call, &contents[end..]);
+    Generator::update_file(api_mod_rs, &updated)?;
+    Ok(())
+}
+
+/// Mounts `controllers::api::configure` under `/api` in main.rs, next to the other services.
+fn add_api_scope_to_main_rs(main_rs: &Path) -> Result<(), Error> {
+    let contents = Generator::read_to_string(main_rs)?;
+    let updated = add_services_to_app(&contents, &[API_SCOPE_SERVICE]).map_err(|e| {
+        eyre!(
+            "{}: {}; add `.service({})` to your App yourself",
+            main_rs.display(),
+            e,
+            API_SCOPE_SERVICE
+        )
+    })?;
+    if let Some(updated) = updated {
+        Generator::update_file(main_rs, &updated)?;
+    }
+    Ok(())
+}
+
+/// # Name: generate_api
+/// ## Description
+/// Generates a JSON API resource at `/api/<names>` for a model. With `fields`, the model and its
+/// migration are generated first, as by `generate model`; without, `src/models/<name>.rs` must exist.
+/// Writes `src/controllers/api/<name>.rs`, creates `src/controllers/api/mod.rs` with the shared
+/// error envelope on first use, and mounts the `/api` scope in main.rs.
+/// ## Example
+/// ```no_run
+/// use rustyroad::writers::generate_api;
+///
+/// generate_api("post", &["title:string:not_null".to_string()]).unwrap();
+/// ```
+pub fn generate_api(name: &str, fields: &[String]) -> Result<(), Error> {
+    if !Path::new("rustyroad.toml").exists() {
+        return Err(eyre!(
+            "Could not find rustyroad.toml. Run this command from your RustyRoad project root."
+        ));
+    }
+    let database = crate::database::Database::get_database_from_rustyroad_toml()
+        .map_err(|e| eyre!("Could not read the database settings: {}", e))?;
+    // Validate the name and fields before anything is written
+    let scaffold_name = if fields.is_empty() {
+        crate::writers::scaffold::to_snake_case(name)
+    } else {
+        Scaffold::new(name, fields, database.database_type.clone())?.name
+    };
+
+    let api_dir = Path::new("src/controllers/api");
+    let controller_path = api_dir.join(format!("{}.rs", scaffold_name));
+
+    let model_path = Path::new("src/models").join(format!("{}.rs", scaffold_name));
+    if !fields.is_empty() {
+        generate_model(name, fields, false)?;
+    } else if !Generator::exists(&model_path) {
+        return Err(eyre!(
+            "{} does not exist. Pass fields to create it, e.g. rustyroad generate api {} title:string",
+            model_path.display(),
+            scaffold_name
+        ));
+    }
+    let source = Generator::read_to_string(&model_path)?;
+    let scaffold = Scaffold::from_model_source(&scaffold_name, &source, database.database_type)?;
+
+    let api_mod = api_dir.join("mod.rs");
+    if !Generator::exists(&api_mod) {
+        write_new_file(&api_mod, &render_api_mod()?)?;
+    }
+    write_new_file(&controller_path, &scaffold.render_api_controller()?)?;
+    declare_module(&scaffold.name, &api_mod)?;
+    add_to_api_configure(&scaffold.name, &api_mod)?;
+    declare_module("api", Path::new("src/controllers/mod.rs"))?;
+    add_api_scope_to_main_rs(Path::new("src/main.rs"))?;
+
+    Generator::say(format!(
+        "\nJSON API for '{}' created at /api/{}.",
+        scaffold.name, scaffold.table_name
+    ));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn post_scaffold(database_type: DatabaseType) -> Scaffold {
+        let fields = vec![
+            "title:string:not_null".to_string(),
+            "body:text".to_string(),
+            "published:boolean".to_string(),
+            "rating:integer".to_string(),
+            "published_on:date".to_string(),
+        ];
+        Scaffold::new("post", &fields, database_type).unwrap()
+    }
+
+    #[test]
+    fn test_render_api_controller() {
+        let source = post_scaffold(DatabaseType::Postgres)
+            .render_api_controller()
+            .unwrap();
+        assert!(syn::parse_file(&source).is_ok());
+        for route in [
+            "#[get(\"/posts\")]",
+            "#[get(\"/posts/{id}\")]",
+            "#[post(\"/posts\")]",
+            "#[route(\"/posts/{id}\", method = \"PUT\", method = \"PATCH\")]",
+            "#[delete(\"/posts/{id}\")]",
+        ] {
+            assert!(source.contains(route), "missing {}", route);
+        }
+        assert!(source.contains(
+            "const SORTABLE: [&str; 6] = [\"id\", \"title\", \"body\", \"published\", \"rating\", \"published_on\"];"
+        ));
+        assert!(
+            source.contains("QueryBuilder::<Postgres>::new(\"SELECT * FROM posts WHERE 1 = 1\")")
+        );
+        assert!(source.contains("query.push(\"published = \").push_bind(parse_bool(value)?);"));
+        assert!(source.contains("fn json_present_string("));
+        assert!(source.contains("fn json_date("));
+        assert!(!source.contains("fn json_datetime("));
+        assert!(source.contains("HttpResponse::Created()"));
+        assert!(source.contains("HttpResponse::NoContent()"));
+        assert!(source.contains("use rustyroad::web::AppError;"));
+        assert_eq!(source.matches(".map_err(invalid)?;").count(), 2);
+        assert!(source.contains("let post = Post::update(id, form).await?;"));
+        assert!(source.contains("let required: [&str; 1] = [\"title\"];"));
+
+        let source = post_scaffold(DatabaseType::Mysql)
+            .render_api_controller()
+            .unwrap();
+        assert!(source.contains("use sqlx::{MySql, QueryBuilder};"));
+        assert!(source.contains("Ok(PoolConnection::MySql(pool)) => Ok(pool),"));
+    }
+
+    #[test]
+    fn test_registration_edits() {
+        let dir = std::env::temp_dir().join(format!("rustyroad_api_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let api_mod = dir.join("mod.rs");
+        fs::write(&api_mod, render_api_mod().unwrap()).unwrap();
+        declare_module("post", &api_mod).unwrap();
+        declare_module("comment", &api_mod).unwrap();
+        add_to_api_configure("post", &api_mod).unwrap();
+        add_to_api_configure("comment", &api_mod).unwrap();
+        add_to_api_configure("post", &api_mod).unwrap();
+        let contents = fs::read_to_string(&api_mod).unwrap();
+        assert!(contents.starts_with(
+            "//! JSON API resources, mounted under `/api` in main.rs.\n\npub mod post;\npub mod comment;\n\nuse "
+        ));
+        assert!(contents.contains(
+            "    }));\n    cfg.configure(post::configure);\n    cfg.configure(comment::configure);\n}\n"
+        ));
+        assert!(syn::parse_file(&contents).is_ok());
+
+        let main_rs = dir.join("main.rs");
+        fs::write(
+            &main_rs,
+            "fn main() {\n    App::new()\n            .service(controllers::index::index)\n            .service(Files::new(\"/static\", \"./static\"))\n}\n",
+        )
+        .unwrap();
+        add_api_scope_to_main_rs(&main_rs).unwrap();
+        add_api_scope_to_main_rs(&main_rs).unwrap();
+        let contents = fs::read_to_string(&main_rs).unwrap();
+        assert!(contents.contains(&format!(
+            "            .service(controllers::index::index)\n            .service({})\n            .service(Files",
+            API_SCOPE_SERVICE
+        )));
+        assert_eq!(contents.matches("controllers::api::configure").count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
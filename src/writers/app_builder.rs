@@ -0,0 +1,451 @@
+use color_eyre::eyre::Result;
+use eyre::{eyre, Error};
+use proc_macro2::LineColumn;
+use quote::ToTokens;
+use std::collections::HashSet;
+use syn::visit::{self, Visit};
+use syn::{Expr, ExprCall, ExprMethodCall};
+
+/// The `App::new()` builder chain found in main.rs, innermost call first.
+pub(crate) struct AppChain<'a> {
+    root: &'a ExprCall,
+    pub(crate) calls: Vec<&'a ExprMethodCall>,
+}
+
+#[derive(Default)]
+struct ChainFinder<'a> {
+    chains: Vec<AppChain<'a>>,
+}
+
+impl<'a> Visit<'a> for ChainFinder<'a> {
+    fn visit_expr_method_call(&mut self, call: &'a ExprMethodCall) {
+        match app_chain(call) {
+            // Everything inside the chain belongs to it, so don't descend
+            Some(chain) => self.chains.push(chain),
+            None => visit::visit_expr_method_call(self, call),
+        }
+    }
+
+    fn visit_expr_call(&mut self, call: &'a ExprCall) {
+        // Only a bare `App::new()` is reached here; chains stop in visit_expr_method_call
+        if path_ends_with(&call.func, &["App", "new"]) {
+            self.chains.push(AppChain {
+                root: call,
+                calls: Vec::new(),
+            });
+        } else {
+            visit::visit_expr_call(self, call);
+        }
+    }
+}
+
+pub(crate) fn path_ends_with(expr: &Expr, segments: &[&str]) -> bool {
+    let Expr::Path(path) = expr else {
+        return false;
+    };
+    let names: Vec<String> = path
+        .path
+        .segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect();
+    names.len() >= segments.len() && names[names.len() - segments.len()..] == *segments
+}
+
+/// Splits `root().a().b()` into its root call and method calls, innermost first.
+pub(crate) fn method_chain(call: &ExprMethodCall) -> (Option<&ExprCall>, Vec<&ExprMethodCall>) {
+    let mut calls = vec![call];
+    let mut receiver = &*call.receiver;
+    loop {
+        match receiver {
+            Expr::MethodCall(inner) => {
+                calls.push(inner);
+                receiver = &inner.receiver;
+            }
+            Expr::Paren(paren) => receiver = &paren.expr,
+            Expr::Group(group) => receiver = &group.expr,
+            Expr::Call(root) => {
+                calls.reverse();
+                return (Some(root), calls);
+            }
+            _ => {
+                calls.reverse();
+                return (None, calls);
+            }
+        }
+    }
+}
+
+fn app_chain(call: &ExprMethodCall) -> Option<AppChain<'_>> {
+    match method_chain(call) {
+        (Some(root), calls) if path_ends_with(&root.func, &["App", "new"]) => {
+            Some(AppChain { root, calls })
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn tokens(expr: &Expr) -> String {
+    expr.to_token_stream()
+        .to_string()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect()
+}
+
+/// What a `.service(..)` argument registers: the argument itself, or for a `web::scope(..)`
+/// the services and `configure` functions inside it, so a service moved into a scope still counts.
+fn registrations(service: &Expr, out: &mut Vec<String>) {
+    if let Expr::MethodCall(call) = service {
+        if let (Some(root), calls) = method_chain(call) {
+            if path_ends_with(&root.func, &["scope"]) {
+                let before = out.len();
+                for call in calls {
+                    collect_registration(call, out);
+                }
+                if out.len() > before {
+                    return;
+                }
+            }
+        }
+    }
+    out.push(format!("service:{}", tokens(service)));
+}
+
+fn collect_registration(call: &ExprMethodCall, out: &mut Vec<String>) {
+    let Some(arg) = call.args.first() else {
+        return;
+    };
+    match call.method.to_string().as_str() {
+        "service" => registrations(arg, out),
+        "configure" => out.push(format!("configure:{}", tokens(arg))),
+        _ => {}
+    }
+}
+
+/// Every `.service(..)` call in the chain, including those inside scopes.
+fn service_calls<'a>(calls: &[&'a ExprMethodCall], out: &mut Vec<&'a ExprMethodCall>) {
+    for call in calls {
+        if call.method != "service" {
+            continue;
+        }
+        out.push(call);
+        if let Some(Expr::MethodCall(scope)) = call.args.first() {
+            if let (Some(root), inner) = method_chain(scope) {
+                if path_ends_with(&root.func, &["scope"]) {
+                    service_calls(&inner, out);
+                }
+            }
+        }
+    }
+}
+
+/// Byte offsets of the start of each line, for turning span positions into string indices.
+struct Lines<'a> {
+    source: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        Lines { source, starts }
+    }
+
+    fn offset(&self, position: LineColumn) -> usize {
+        let start = self.starts[position.line - 1];
+        self.source[start..]
+            .char_indices()
+            .nth(position.column)
+            .map_or(self.source.len(), |(i, _)| start + i)
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.source[..offset].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self, offset: usize) -> usize {
+        self.source[offset..]
+            .find('\n')
+            .map_or(self.source.len(), |i| offset + i)
+    }
+}
+
+/// Every `App::new()` builder chain in a parsed main.rs.
+pub(crate) fn app_chains(file: &syn::File) -> Vec<AppChain<'_>> {
+    let mut finder = ChainFinder::default();
+    finder.visit_file(file);
+    finder.chains
+}
+
+fn parse_app_chain(file: &syn::File) -> Result<AppChain<'_>, Error> {
+    let mut chains = app_chains(file);
+    match chains.len() {
+        0 => Err(eyre!(
+            "Could not find an `App::new()` builder chain in main.rs to register services on"
+        )),
+        1 => Ok(chains.remove(0)),
+        n => Err(eyre!(
+            "Found {} `App::new()` builder chains in main.rs; register the service yourself",
+            n
+        )),
+    }
+}
+
+/// # Name: add_services_to_app
+/// ## Description
+/// Adds `.service(<service>)` calls to the `App::new()` builder chain in a main.rs source, for
+/// each service that isn't registered yet. A service counts as registered anywhere in the chain,
+/// including inside a `web::scope(..)`, and a `web::scope(..).configure(f)` is registered when
+/// `f` is. New calls go after the last `.service(controllers::..)` (or the last call in the
+/// chain), on their own line with its indentation; the rest of the file is left as it is.
+/// Returns `None` when every service is already registered.
+/// ## Example
+/// ```
+/// use rustyroad::writers::add_services_to_app;
+///
+/// let main_rs = "fn app() { App::new()\n    .service(controllers::index::index) // home\n; }\n";
+/// let updated = add_services_to_app(main_rs, &["controllers::about::about"]).unwrap().unwrap();
+/// assert!(updated.contains("    .service(controllers::about::about)\n"));
+/// assert!(add_services_to_app(&updated, &["controllers::about::about"]).unwrap().is_none());
+/// ```
+pub fn add_services_to_app(source: &str, services: &[&str]) -> Result<Option<String>, Error> {
+    let file = syn::parse_file(source).map_err(|e| eyre!("Could not parse main.rs: {}", e))?;
+    let chain = parse_app_chain(&file)?;
+
+    let mut registered = Vec::new();
+    for call in &chain.calls {
+        collect_registration(call, &mut registered);
+    }
+    let registered: HashSet<String> = registered.into_iter().collect();
+
+    let mut missing = Vec::new();
+    for service in services {
+        let expr: Expr = syn::parse_str(service)
+            .map_err(|e| eyre!("`{}` is not a valid service: {}", service, e))?;
+        let mut needed = Vec::new();
+        registrations(&expr, &mut needed);
+        if needed.iter().any(|r| !registered.contains(r)) && !missing.contains(service) {
+            missing.push(*service);
+        }
+    }
+    if missing.is_empty() {
+        return Ok(None);
+    }
+
+    let lines = Lines::new(source);
+    let anchor = chain
+        .calls
+        .iter()
+        .rev()
+        .find(|call| {
+            call.method == "service"
+                && call
+                    .args
+                    .first()
+                    .is_some_and(|arg| tokens(arg).starts_with("controllers::"))
+        })
+        .or_else(|| {
+            chain
+                .calls
+                .iter()
+                .rev()
+                .find(|call| call.method == "service")
+        })
+        .or(chain.calls.last());
+
+    let (end, dot) = match anchor {
+        Some(call) => (
+            lines.offset(call.paren_token.span.close().end()),
+            Some(lines.offset(call.dot_token.span.start())),
+        ),
+        None => (
+            lines.offset(chain.root.paren_token.span.close().end()),
+            None,
+        ),
+    };
+    // A chain written one call per line gets one line per service; otherwise stay inline
+    let indent = dot.and_then(|dot| {
+        let start = lines.line_start(dot);
+        let before = &source[start..dot];
+        before.trim().is_empty().then(|| before.to_string())
+    });
+    let rest_of_line = &source[end..lines.line_end(end)];
+    let rest = rest_of_line.trim();
+    // Keep a trailing `// comment` with the line it belongs to
+    let at = if indent.is_some() && (rest.is_empty() || rest.starts_with("//")) {
+        lines.line_end(end)
+    } else {
+        end
+    };
+
+    let mut insertion = String::new();
+    for service in missing {
+        match &indent {
+            Some(indent) => insertion.push_str(&format!("\n{}.service({})", indent, service)),
+            None => insertion.push_str(&format!(".service({})", service)),
+        }
+    }
+    let mut updated = String::with_capacity(source.len() + insertion.len());
+    updated.push_str(&source[..at]);
+    // `at` is the end of the line, before a `\r` in CRLF files
+    if at > 0 && source[..at].ends_with('\r') && indent.is_some() {
+        updated.pop();
+        updated.push_str(&insertion.replace('\n', "\r\n"));
+        updated.push('\r');
+    } else {
+        updated.push_str(&insertion);
+    }
+    updated.push_str(&source[at..]);
+    Ok(Some(updated))
+}
+
+/// # Name: remove_services_from_app
+/// ## Description
+/// Removes the `.service(..)` calls of the `App::new()` chain (and its scopes) whose argument,
+/// printed without whitespace, satisfies `matches`. Calls on their own line are removed with
+/// the line. Returns the updated source and how many calls were removed.
+pub fn remove_services_from_app(
+    source: &str,
+    matches: impl Fn(&str) -> bool,
+) -> Result<(String, usize), Error> {
+    let file = syn::parse_file(source).map_err(|e| eyre!("Could not parse main.rs: {}", e))?;
+    let chain = parse_app_chain(&file)?;
+    let mut services = Vec::new();
+    service_calls(&chain.calls, &mut services);
+
+    let lines = Lines::new(source);
+    let mut ranges: Vec<(usize, usize)> = services
+        .into_iter()
+        .filter(|call| call.args.first().is_some_and(|arg| matches(&tokens(arg))))
+        .map(|call| {
+            let start = lines.offset(call.dot_token.span.start());
+            let end = lines.offset(call.paren_token.span.close().end());
+            let line_start = lines.line_start(start);
+            let line_end = lines.line_end(end);
+            let rest = source[end..line_end].trim();
+            if source[line_start..start].trim().is_empty()
+                && (rest.is_empty() || rest.starts_with("//"))
+                && line_start > 0
+            {
+                // From the end of the previous line, so the line disappears entirely
+                let previous_end = line_start - 1;
+                let previous_end = if source[..previous_end].ends_with('\r') {
+                    previous_end - 1
+                } else {
+                    previous_end
+                };
+                let line_end = if source[..line_end].ends_with('\r') {
+                    line_end - 1
+                } else {
+                    line_end
+                };
+                (previous_end, line_end)
+            } else {
+                (start, end)
+            }
+        })
+        .collect();
+    ranges.sort();
+
+    let mut updated = String::with_capacity(source.len());
+    let mut copied = 0;
+    for (start, end) in &ranges {
+        // A scope removed as a whole already covers the services inside it
+        if *start < copied {
+            continue;
+        }
+        updated.push_str(&source[copied..*start]);
+        copied = *end;
+    }
+    updated.push_str(&source[copied..]);
+    Ok((updated, ranges.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN_RS: &str = r#"use actix_web::{web, App, HttpServer};
+mod controllers;
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    HttpServer::new(move || {
+        App::new()
+            .wrap(cors) // allow everything
+            .service(controllers::index::index)
+            .service(controllers::login::login_controller) // sign in
+            .service(Files::new("/static", "./static"))
+    })
+    .run()
+    .await
+}
+"#;
+
+    #[test]
+    fn test_add_services_is_idempotent() {
+        let updated = add_services_to_app(
+            MAIN_RS,
+            &[
+                "controllers::post::index_posts",
+                "controllers::post::show_post",
+            ],
+        )
+        .unwrap()
+        .unwrap();
+        assert!(updated.contains(
+            "            .service(controllers::login::login_controller) // sign in\n            .service(controllers::post::index_posts)\n            .service(controllers::post::show_post)\n            .service(Files::new"
+        ));
+        assert!(
+            add_services_to_app(&updated, &["controllers::post::show_post"])
+                .unwrap()
+                .is_none()
+        );
+
+        let (removed, count) =
+            remove_services_from_app(&updated, |s| s.starts_with("controllers::post::")).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(removed, MAIN_RS);
+    }
+
+    #[test]
+    fn test_add_services_understands_scopes_and_formatting() {
+        // Reformatted onto one line, with the API already mounted in a user scope
+        let main_rs = "fn main() { HttpServer::new(|| App::new().service(web::scope(\"/v1\").configure(controllers::api::configure)).service(controllers::index::index)); }\n";
+        let api = "actix_web::web::scope(\"/api\").configure(controllers::api::configure)";
+        assert!(add_services_to_app(main_rs, &[api]).unwrap().is_none());
+
+        let updated = add_services_to_app(main_rs, &["controllers::about::about"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            updated,
+            "fn main() { HttpServer::new(|| App::new().service(web::scope(\"/v1\").configure(controllers::api::configure)).service(controllers::index::index).service(controllers::about::about)); }\n"
+        );
+
+        let nested = "fn main() { App::new().service(web::scope(\"/admin\").service(controllers::post::index_posts)); }";
+        assert!(
+            add_services_to_app(nested, &["controllers::post::index_posts"])
+                .unwrap()
+                .is_none()
+        );
+        let (removed, count) =
+            remove_services_from_app(nested, |s| s.starts_with("controllers::post::")).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            removed,
+            "fn main() { App::new().service(web::scope(\"/admin\")); }"
+        );
+    }
+
+    #[test]
+    fn test_add_services_without_an_app_is_an_error() {
+        let error = add_services_to_app("fn main() {}", &["controllers::index::index"])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("App::new()"));
+        assert!(add_services_to_app("fn main() {", &["controllers::index::index"]).is_err());
+    }
+}
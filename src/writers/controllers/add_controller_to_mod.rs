@@ -1,6 +1,6 @@
 use eyre::Error;
 use regex::Regex;
-use std::fs;
+use crate::generators::Generator;
 use std::path::Path;
 
 /// # Name: add_module_declaration
@@ -22,7 +22,7 @@ use std::path::Path;
 /// ```
 pub fn add_module_declaration(model_name: String, file_path: &Path) -> Result<(), Error> {
     // Read the file into a string
-    let mut contents = fs::read_to_string(file_path).map_err(|e| {
+    let mut contents = Generator::read_to_string(file_path).map_err(|e| {
         Error::msg(format!(
             "Failed to read file: {:?}, error: {}",
             file_path, e
@@ -50,7 +50,7 @@ pub fn add_module_declaration(model_name: String, file_path: &Path) -> Result<()
     );
 
     // Write the updated contents back to the file
-    fs::write(file_path, contents).map_err(|e| {
+    Generator::update_file(file_path, &contents).map_err(|e| {
         Error::msg(format!(
             "Failed to write to file: {:?}, error: {}",
             file_path, e
@@ -63,7 +63,7 @@ pub fn add_module_declaration(model_name: String, file_path: &Path) -> Result<()
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
+    use std::fs::{self, File};
     use std::io::Write;
     use tempfile::tempdir;
 
@@ -1,13 +1,12 @@
+use crate::generators::{render_template, Generator};
 use crate::helpers::helpers::*;
 use crate::writers::{add_new_controller_to_main_rs, write_to_file, write_to_module};
 use color_eyre;
 use color_eyre::eyre::Result;
 use color_eyre::owo_colors::OwoColorize;
 use eyre::*;
-use std::fs;
-use std::fs::OpenOptions;
-use std::io::Write;
 use std::path::{Path, PathBuf};
+use tera::Context;
 
 /// # Name: write_to_controller_name_html
 /// This function generates the html for a controller's view.
@@ -22,19 +21,7 @@ use std::path::{Path, PathBuf};
 /// write_to_controller_name_html("test").expect("Error writing to controllerName.html.tera");
 /// ```
 pub fn write_to_controller_name_html(controller_name: &str) -> Result<(), Error> {
-    let contents = r#"{% extends 'base.html.tera' %}
-{% block title %}Index{% endblock title %}
-{% block head %}
-{{ super() }}
-{% endblock head %}
-{% block content %}
-<div class='relative px-6 lg:px-8'>
-<div class='mx-auto  max-w-2xl py-32 sm:py-48 lg:py-56' >
-<h1 class='text-4xl sm:text-5xl lg:text-6xl font-extrabold leading-none mb-4'>Your controller's Name: {{controller_name}}</h1>
-<p class='text-xl sm:text-2xl lg:text-3xl font-medium mb-8'>This is a rustyroad project</p>
-</div>
-</div>
-{% endblock content %}"#.to_string();
+    let contents = render_template("actions", "page.html", &Context::new())?;
 
     // write to the file
     write_to_file(
@@ -68,22 +55,9 @@ pub fn write_to_controller_name_html_with_authorized_view(
     controller_name: &str,
     folder_name: &str,
 ) -> Result<(), Error> {
-    let contents = format!(
-        r"{{% extends 'layouts/authenticated_page/{}.html.tera' %}}
-            {{% block title %}}Index{{% endblock title %}}
-            {{% block head %}}
-            {{{{ super() }}}}
-            {{% endblock head %}}
-            {{% block content %}}
-            <div class='relative px-6 lg:px-8'>
-            <div class='mx-auto  max-w-2xl py-32 sm:py-48 lg:py-56' >
-            <h1 class='text-4xl sm:text-5xl lg:text-6xl font-extrabold leading-none mb-4'>Your controller's Name: {{{{controller_name}}}}</h1>
-            <p class='text-xl sm:text-2xl lg:text-3xl font-medium mb-8'>This is a rustyroad project</p>
-            </div>
-            </div>
-            {{% endblock content %}}",
-        folder_name
-    );
+    let mut context = Context::new();
+    context.insert("folder", folder_name);
+    let contents = render_template("actions", "authorized_page.html", &context)?;
 
     // write to the file
     write_to_file(
@@ -124,7 +98,7 @@ pub fn write_to_new_get_all_controller(model_name: String) -> Result<(), Error>
     // look for the model in the models folder
     let model_path = format!("./src/models/{}.rs", model_name);
     let model_file_path = PathBuf::from(&model_path);
-    if !model_file_path.exists() {
+    if !Generator::exists(&model_file_path) {
         println!(
             "The model {} does not exist. Would you like to create it? (y/n)",
             model_name
@@ -143,36 +117,22 @@ pub fn write_to_new_get_all_controller(model_name: String) -> Result<(), Error>
 
     let capitalize_model_name = capitalize_first(&model_name); // Assumed to be defined elsewhere
 
-    let controller_contents = format!(
-        r#"#[get("/{}/all")]
-pub async fn get_all_{}s() -> HttpResponse {{
-    let result = {}::get_all_{}s().await;
-    match result {{
-        Ok({}) => HttpResponse::Ok().json({}),
-        Err(e) => HttpResponse::BadRequest().json(e.to_string()),
-    }}
-}}
-
-"#,
-        model_name,
-        model_name,
-        capitalize_model_name,
-        model_name,
-        capitalize_model_name,
-        capitalize_model_name
-    );
+    let mut context = Context::new();
+    context.insert("name", &model_name);
+    context.insert("struct_name", &capitalize_model_name);
+    let controller_contents = render_template("actions", "get_all.rs", &context)?;
 
     // Determine the controller file path
     let path = determine_controller_path(&model_name);
 
     // Ensure the controller file exists, or create it
-    let file_exists = PathBuf::from(&path).exists();
+    let file_exists = Generator::exists(&path);
     if !file_exists {
         prompt_to_create_controller(&path).expect("Error prompting to create controller");
     }
 
     // Read and update the file contents
-    let mut file_contents = fs::read_to_string(&path)?;
+    let mut file_contents = Generator::read_to_string(&path)?;
 
     // Update imports
     file_contents = add_or_update_import(&file_contents, "actix_web", "web");
@@ -182,21 +142,14 @@ pub async fn get_all_{}s() -> HttpResponse {{
     file_contents = add_or_update_import(&file_contents, "models", &capitalize_model_name);
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Data");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Json");
+    file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
     // Add the new controller content to the file
     file_contents.push_str("\n\n");
     file_contents.push_str(&controller_contents);
 
     // Write the updated contents to the file
-    let mut file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open(&path)
-        .unwrap();
-    // Write the updated contents to the file
-    writeln!(file, "{}", file_contents)?;
-
-    file.flush()?;
+    Generator::update_file(&path, &format!("{}\n", file_contents))?;
 
     add_new_controller_to_main_rs(
         None,
@@ -221,7 +174,7 @@ pub fn write_to_new_get_controller(model_name: String) -> Result<(), Error> {
     // look for the model in the models folder
     let model_path = format!("./src/models/{}.rs", model_name);
     let model_file_path = PathBuf::from(&model_path);
-    if !model_file_path.exists() {
+    if !Generator::exists(&model_file_path) {
         println!(
             "The model {} does not exist. Would you like to create it? (y/n)",
             model_name
@@ -241,33 +194,21 @@ pub fn write_to_new_get_controller(model_name: String) -> Result<(), Error> {
     // Define the contents to be written to the file
     // This includes importing necessary Actix Web and Tera modules, defining the controller handler function,
     // and setting up the Tera template rendering
-    let new_controller_content = format!(
-        r#"  /// Alert: This is a generated controller.
-        /// The controller is generated by the rustyroad CLI.
-        /// It is a best guess at what the controller should look like.
-        /// Please review the controller and make any necessary changes.
-        #[get("/{}")]
-        pub async fn get_{}(tmpl: web::Data<Tera>) -> HttpResponse {{
-            let mut context = Context::new();
-            context.insert("title", "{}");
-            context.insert("controller_name", "{}");
-            let rendered = tmpl.render("pages/{}.html.tera", &context).unwrap();
-            HttpResponse::Ok().body(rendered)
-        }}"#,
-        &model_name, &model_name, &model_name, &model_name, &model_name
-    );
+    let mut context = Context::new();
+    context.insert("name", &model_name);
+    let new_controller_content = render_template("actions", "get.rs", &context)?;
 
     // Determine the controller file path
     let path = determine_controller_path(&model_name);
 
     // Ensure the controller file exists, or create it
-    let file_exists = PathBuf::from(&path).exists();
+    let file_exists = Generator::exists(&path);
     if !file_exists {
         prompt_to_create_controller(&path).expect("Error prompting to create controller");
     }
 
     // Read and update the file contents
-    let mut file_contents = fs::read_to_string(&path)?;
+    let mut file_contents = Generator::read_to_string(&path)?;
 
     // Update imports
     file_contents = add_or_update_import(&file_contents, "actix_web", "get");
@@ -275,6 +216,7 @@ pub fn write_to_new_get_controller(model_name: String) -> Result<(), Error> {
     file_contents = add_or_update_import(&file_contents, "actix_web", "HttpResponse");
     file_contents = add_or_update_import(&file_contents, "tera", "Context");
     file_contents = add_or_update_import(&file_contents, "tera", "Tera");
+    file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
     // Add the new controller content to the file
     file_contents.push_str("\n\n");
@@ -282,7 +224,7 @@ pub fn write_to_new_get_controller(model_name: String) -> Result<(), Error> {
     file_contents.push_str("\n\n");
 
     // Write the updated contents to the file
-    fs::write(PathBuf::from(&path), file_contents.as_bytes())?;
+    Generator::update_file(&path, &file_contents)?;
 
     add_new_controller_to_main_rs(None, Some(&model_name), &format!("get_{}", &model_name))?; // Assuming this function exists
 
@@ -308,7 +250,7 @@ pub fn write_to_initial_get_controller_authorized_view(model_name: String) -> Re
     // look for the model in the models folder
     let model_path = format!("./src/models/{}.rs", model_name);
     let model_file_path = PathBuf::from(&model_path);
-    if !model_file_path.exists() {
+    if !Generator::exists(&model_file_path) {
         println!(
             "The model {} does not exist. Would you like to create it? (y/n)",
             model_name
@@ -326,44 +268,21 @@ pub fn write_to_initial_get_controller_authorized_view(model_name: String) -> Re
     }
 
     // Define the contents to be written to the file
-    let new_controller_content = format!(
-        r#"
-#[get("/{}")]
-pub async fn {}_controller_with_authorized_view(
-    tmpl: web::Data<Tera>,
-    user: Option<Identity>
-) -> HttpResponse {{
-    if let Some(_user) = user {{
-        let mut context = Context::new();
-        context.insert("username", &user.id().unwrap());
-        context.insert("title", "{}");
-        context.insert("controller_name", "{}");
-        let rendered = tmpl.render("layouts/authenticated_page/{}/{}.html.tera", &context).unwrap();
-        HttpResponse::Ok().body(rendered)
-    }} else {{
-        let mut context = Context::new();
-        context.insert("title", "Login");
-        context.insert("route_name", "login");
-        context.insert("error", "You must be logged in to view this page.");
-        HttpResponse::Found()
-            .append_header((actix_web::http::header::LOCATION, "/login"))
-            .finish()
-    }}
-}}"#,
-        &model_name, &model_name, &model_name, &model_name, &model_name, &model_name
-    );
+    let mut context = Context::new();
+    context.insert("name", &model_name);
+    let new_controller_content = render_template("actions", "authorized_get.rs", &context)?;
 
     // Determine the controller file path
     let path = determine_controller_path(&model_name);
 
     // Ensure the controller file exists, or create it
-    let file_exists = PathBuf::from(&path).exists();
+    let file_exists = Generator::exists(&path);
     if !file_exists {
         prompt_to_create_controller(&path).expect("Error prompting to create controller");
     }
 
     // Read and update the file contents
-    let mut file_contents = fs::read_to_string(&path)?;
+    let mut file_contents = Generator::read_to_string(&path)?;
 
     // Update imports
     file_contents = add_or_update_import(&file_contents, "actix_web", "get");
@@ -380,13 +299,14 @@ pub async fn {}_controller_with_authorized_view(
         format!("models::{}", &model_name).as_str(),
         &capitalized_model_name,
     );
+    file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
     // Add the new controller content to the file
     file_contents.push_str("\n\n");
     file_contents.push_str(&new_controller_content);
     file_contents.push_str("\n\n");
     // Write the updated contents to the file
-    fs::write(PathBuf::from(&path), file_contents.as_bytes())?;
+    Generator::update_file(&path, &file_contents)?;
 
     add_new_controller_to_main_rs(
         None,
@@ -435,72 +355,34 @@ pub fn write_to_new_post_controller(model_name: String) -> Result<(), Error> {
     // }
 
     // Define the contents to be written to the file
-    let controller_contents = format!(
-        r#"
-/// Alert: This is a generated controller.
-/// The controller is generated by the rustyroad CLI.
-/// It is a best guess at what the controller should look like.
-/// Please review the controller and make any necessary changes.
-#[post("/{}")]
-pub async fn create_{}({}: Json<{}>,user: Option<Identity>) -> HttpResponse {{
-    if let Some(_user) = user {{
-        let result = {}::create_{}({}.into_inner()).await;
-        match result {{
-            Ok(page) => HttpResponse::Ok().json(page),
-            Err(e) => {{
-                eprintln!("Error creating page: {{:?}}", e); // Log the error
-                HttpResponse::BadRequest().json(e.to_string())
-            }}
-        }}
-    }} else {{
-      // redirect to login page
-      let mut context = Context::new();
-        context.insert("title", "Login");
-        context.insert("route_name", "login");
-        context.insert("error", "You must be logged in to create a new {}.");
-        HttpResponse::Found()
-            .append_header((actix_web::http::header::LOCATION, "/login"))
-            .finish()
-    }}
-   // before we allow the user to create a new {} we need to check if they are logged in
-   // if they are not logged in, we need to redirect them to the login page
-}}
-"#,
-        &model_name,
-        &model_name,
-        &model_name,
-        &capitalized_model_name,
-        &capitalized_model_name,
-        &model_name,
-        &model_name,
-        &model_name,
-        &model_name
-    );
+    let mut context = Context::new();
+    context.insert("name", &model_name);
+    context.insert("struct_name", &capitalized_model_name);
+    let controller_contents = render_template("actions", "create.rs", &context)?;
 
     // Determine the controller file path
     let path = determine_controller_path(&model_name);
 
     // Ensure the controller file exists, or create it
-    let file_exists = PathBuf::from(&path).exists();
+    let file_exists = Generator::exists(&path);
     if !file_exists {
         prompt_to_create_controller(&path).expect("Error prompting to create controller");
     }
 
     // Read and update the file contents
-    let mut file_contents = fs::read_to_string(&path)?;
+    let mut file_contents = Generator::read_to_string(&path)?;
     if !file_contents.contains(&controller_signature) {
         // Update imports
         file_contents = add_or_update_import(&file_contents, "actix_web", "web");
 
         file_contents = add_or_update_import(&file_contents, "actix_web", "post");
         file_contents = add_or_update_import(&file_contents, "actix_web", "HttpResponse");
-        file_contents = add_or_update_import(&file_contents, "tera", "Context");
-        file_contents = add_or_update_import(&file_contents, "tera", "Tera");
         file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
         file_contents = add_or_update_import(&file_contents, "crate", "models");
         file_contents = add_or_update_import(&file_contents, "models", &capitalized_model_name);
         file_contents = add_or_update_import(&file_contents, "actix_web", "web::Json");
         file_contents = add_or_update_import(&file_contents, "actix_web", "web::Data");
+        file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
         // Add the new controller content to the file
         file_contents.push_str("\n\n");
@@ -510,15 +392,7 @@ pub async fn create_{}({}: Json<{}>,user: Option<Identity>) -> HttpResponse {{
 
         println!("Controller contents: {}", &controller_contents.green());
 
-        let mut file = OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .open(&path)
-            .unwrap();
-        // Write the updated contents to the file
-        writeln!(file, "{}", file_contents)?;
-
-        file.flush()?;
+        Generator::update_file(&path, &format!("{}\n", file_contents))?;
 
         add_new_controller_to_main_rs(None, Some(&model_name), &format!("create_{}", &model_name))?; // Assuming this function exists
 
@@ -549,7 +423,7 @@ pub fn write_to_new_delete_controller(model_name: String) -> Result<(), Error> {
     // look for the model in the models folder
     let model_path = format!("./src/models/{}.rs", model_name);
     let model_file_path = PathBuf::from(&model_path);
-    if !model_file_path.exists() {
+    if !Generator::exists(&model_file_path) {
         println!(
             "The model {} does not exist. Would you like to create it? (y/n)",
             model_name
@@ -568,40 +442,27 @@ pub fn write_to_new_delete_controller(model_name: String) -> Result<(), Error> {
 
     // Define the contents to be written to the file
 
-    let contents = format!(
-        r#"#[delete("/{}/{{id}}")]
-        pub async fn delete_{}(id: Path<i32>, user: Option<Identity>) -> HttpResponse {{
-            if let Some(_user) = user {{
-                let result = {}::delete_{}(id.into_inner()).await;
-                match result {{
-                    Ok(_) => HttpResponse::Ok().json("Successfully deleted."),
-                    Err(e) => HttpResponse::BadRequest().json(e.to_string()),
-                }}
-            }} else {{
-                HttpResponse::Unauthorized().json("You must be logged in to delete.")
-            }}
-        }}"#,
-        &model_name, &model_name, &capitalized_model_name, &model_name
-    );
+    let mut context = Context::new();
+    context.insert("name", &model_name);
+    context.insert("struct_name", &capitalized_model_name);
+    let contents = render_template("actions", "delete.rs", &context)?;
 
     // Determine the controller file path
     let path = determine_controller_path(&model_name);
 
     // Ensure the controller file exists, or create it
-    let file_exists = PathBuf::from(&path).exists();
+    let file_exists = Generator::exists(&path);
     if !file_exists {
         prompt_to_create_controller(&path).expect("Error prompting to create controller");
     }
 
     // Read and update the file contents
-    let mut file_contents = fs::read_to_string(&path)?;
+    let mut file_contents = Generator::read_to_string(&path)?;
 
     // Update imports
     file_contents = add_or_update_import(&file_contents, "actix_web", "delete");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web");
     file_contents = add_or_update_import(&file_contents, "actix_web", "HttpResponse");
-    file_contents = add_or_update_import(&file_contents, "tera", "Context");
-    file_contents = add_or_update_import(&file_contents, "tera", "Tera");
     file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
     file_contents = add_or_update_import(&file_contents, "crate", "models");
     file_contents = add_or_update_import(&file_contents, "models::user", "UserLogin");
@@ -610,13 +471,14 @@ pub fn write_to_new_delete_controller(model_name: String) -> Result<(), Error> {
         format!("models::{}", &model_name).as_str(),
         &capitalized_model_name,
     );
+    file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
     // Add the new controller content to the file
     file_contents.push_str("\n\n");
     file_contents.push_str(&contents);
 
     // Write the updated contents to the file
-    fs::write(PathBuf::from(&path), file_contents.as_bytes())?;
+    Generator::update_file(&path, &file_contents)?;
 
     add_new_controller_to_main_rs(None, Some(&model_name), &format!("delete_{}", &model_name))?; // Assuming this function exists
 
@@ -641,7 +503,7 @@ pub fn write_to_new_update_controller(model_name: String) -> Result<(), Error> {
     // look for the model in the models folder
     let model_path = format!("./src/models/{}.rs", model_name);
     let model_file_path = PathBuf::from(&model_path);
-    if !model_file_path.exists() {
+    if !Generator::exists(&model_file_path) {
         println!(
             "The model {} does not exist. Would you like to create it? (y/n)",
             model_name
@@ -659,65 +521,39 @@ pub fn write_to_new_update_controller(model_name: String) -> Result<(), Error> {
     }
 
     // Define the contents to be written to the file
-    let new_controller_content = format!(
-        r#"#[patch("/{}/{{id}}")]
-        pub async fn update_{}(id: Path<i32>, {}: Json<{}>, user: Option<Identity>) -> HttpResponse {{
-            if let Some(_user) = user {{
-                let result = {}::update_{}(id.into_inner(), {}.into_inner()).await;
-                match result {{
-                    Ok(page) => HttpResponse::Ok().json(page),
-                    Err(e) => HttpResponse::BadRequest().json(e.to_string()),
-                }}
-            }} else {{
-                HttpResponse::Unauthorized().json("You must be logged in to update.")
-            }}
-        }}"#,
-        &model_name,
-        &model_name,
-        &model_name,
-        &capitalized_model_name,
-        &capitalized_model_name,
-        &model_name,
-        &model_name
-    );
+    let mut context = Context::new();
+    context.insert("name", &model_name);
+    context.insert("struct_name", &capitalized_model_name);
+    let new_controller_content = render_template("actions", "update.rs", &context)?;
 
     // Determine the controller file path
     let path = determine_controller_path(&model_name);
 
     // Ensure the controller file exists, or create it
-    let file_exists = PathBuf::from(&path).exists();
+    let file_exists = Generator::exists(&path);
     if !file_exists {
         prompt_to_create_controller(&path).expect("Error prompting to create controller");
     }
 
     // Read and update the file contents
-    let mut file_contents = fs::read_to_string(&path)?;
+    let mut file_contents = Generator::read_to_string(&path)?;
 
     // Update imports
     file_contents = add_or_update_import(&file_contents, "actix_web", "patch");
     file_contents = add_or_update_import(&file_contents, "actix_web", "HttpResponse");
-    file_contents = add_or_update_import(&file_contents, "tera", "Context");
-    file_contents = add_or_update_import(&file_contents, "tera", "Tera");
     file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
     file_contents = add_or_update_import(&file_contents, "crate", "models");
     file_contents = add_or_update_import(&file_contents, "models", &capitalized_model_name);
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Json");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Path");
+    file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
     // Add the new controller content to the file
     file_contents.push_str("\n\n");
     file_contents.push_str(&new_controller_content);
     file_contents.push_str("\n\n");
     // Write the updated contents to the file
-    let mut file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open(&path)
-        .unwrap();
-    // Write the updated contents to the file
-    writeln!(file, "{}", file_contents)?;
-
-    file.flush()?;
+    Generator::update_file(&path, &format!("{}\n", file_contents))?;
 
     add_new_controller_to_main_rs(None, Some(&model_name), &format!("update_{}", &model_name))?; // Assuming this function exists
 
@@ -744,7 +580,7 @@ pub fn write_to_new_get_controller_with_authorized_view(model_name: String) -> R
     // look for the model in the models folder
     let model_path = format!("./src/models/{}.rs", model_name);
     let model_file_path = PathBuf::from(&model_path);
-    if !model_file_path.exists() {
+    if !Generator::exists(&model_file_path) {
         println!(
             "The model {} does not exist. Would you like to create it? (y/n)",
             model_name
@@ -764,44 +600,21 @@ pub fn write_to_new_get_controller_with_authorized_view(model_name: String) -> R
     // Define the contents to be written to the file
     // This includes importing necessary Actix Web and Tera modules, defining the controller handler function,
     // and setting up the Tera template rendering
-    let new_controller_content = format!(
-        r#"#[get("/{}")]
-                async fn authenticated_view_for_{}(
-                    tmpl: web::Data<Tera>,
-                    user: Option<Identity>
-                ) -> impl Responder {{
-                    if let Some(_user) = user
-                    {{
-                          let mut context = Context::new();
-                          context.insert("username", &user.id().unwrap());
-                          context.insert("title", "{}");
-                          context.insert("controller_name", "{}");
-                          let rendered = tmpl.render("layouts/authenticated_page/{}/{}.html.tera", &context).unwrap();
-                          HttpResponse::Ok().body(rendered)
-                    }} else {{
-                        let mut context = Context::new();
-                        context.insert("title", "Login");
-                        context.insert("route_name", "login");
-                        context.insert("error", "You must be logged in to view this page.");
-                        HttpResponse::Found()
-                            .append_header((actix_web::http::header::LOCATION, "/login"))
-                            .finish()
-                        }}
-                    }}"#,
-        &model_name, &model_name, &model_name, &model_name, &model_name, &model_name
-    );
+    let mut context = Context::new();
+    context.insert("name", &model_name);
+    let new_controller_content = render_template("actions", "authenticated_view.rs", &context)?;
 
     // Determine the controller file path
     let path = determine_controller_path(&model_name);
 
     // Ensure the controller file exists, or create it
-    let file_exists = PathBuf::from(&path).exists();
+    let file_exists = Generator::exists(&path);
     if !file_exists {
         prompt_to_create_controller(&path).expect("Error prompting to create controller");
     }
 
     // Read and update the file contents
-    let mut file_contents = fs::read_to_string(&path)?;
+    let mut file_contents = Generator::read_to_string(&path)?;
 
     if !file_contents.contains(&controller_signature) {
         // Update imports
@@ -813,13 +626,14 @@ pub fn write_to_new_get_controller_with_authorized_view(model_name: String) -> R
         file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
         file_contents = add_or_update_import(&file_contents, "crate", "models");
         file_contents = add_or_update_import(&file_contents, "models::user", "UserLogin");
+        file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
         // Add the new controller content to the file
         file_contents.push_str("\n\n");
         file_contents.push_str(&new_controller_content);
         file_contents.push_str("\n\n");
         // Write the updated contents to the file
-        fs::write(PathBuf::from(&path), file_contents.as_bytes())?;
+        Generator::update_file(&path, &file_contents)?;
 
         add_new_controller_to_main_rs(
             None,
@@ -859,22 +673,10 @@ pub fn write_to_previous_get_controller(
     // Define the contents to be written to the file
     // This includes importing necessary Actix Web and Tera modules, defining the controller handler function,
     // and setting up the Tera template rendering
-    let contents = format!(
-        r#"
-
-        #[get("/{}/{}")]
-        async fn {}(tmpl: web::Data<Tera>) -> impl Responder {{
-            let mut context = Context::new();
-            context.insert("controller_name", "{}");
-            let rendered = tmpl.render("pages/{}.html.tera", &context).unwrap();
-            HttpResponse::Ok().body(rendered)
-        }}"#,
-        previous_controller_name,
-        new_controller_name,
-        new_controller_name,
-        new_controller_name,
-        new_controller_name
-    );
+    let mut context = Context::new();
+    context.insert("controller", &previous_controller_name);
+    context.insert("action", &new_controller_name);
+    let contents = render_template("actions", "previous_get.rs", &context)?;
 
     // Define the path to the file
     let path = format!(
@@ -884,8 +686,9 @@ pub fn write_to_previous_get_controller(
 
     // instead of overwriting the file, we need to append to the file
     // lets get the contents of the file first
-    let mut file_contents = fs::read_to_string(&path).unwrap();
+    let mut file_contents = Generator::read_to_string(&path).unwrap();
     if !file_contents.contains(&controller_signature) {
+        file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
         // and then append the new contents to the file
         file_contents.push_str(&contents);
 
@@ -925,50 +728,10 @@ pub fn write_to_previous_create_controller(
     // Define the contents to be written to the file
     // This includes importing necessary Actix Web and Tera modules, defining the controller handler function,
     // and setting up the Tera template rendering
-    let contents = format!(
-        r#"
-
-        use actix_identity::Identity;
-        use actix_web::{{post, web, HttpResponse}};
-        use crate::models::{};
-
-        /// Alert: This is a generated controller.
-        /// The controller is generated by the rustyroad CLI.
-        /// It is a best guess at what the controller should look like.
-        /// Please review the controller and make any necessary changes.
-        #[post("/{}/{}")]
-        pub async fn create_{}({}: web::Json<{}>,user: Option<Identity>) -> HttpResponse {{
-            if let Some(_user) = user {{
-                let result = {}::create_{}({}.into_inner()).await;
-                match result {{
-                    Ok(page) => HttpResponse::Ok().json(page),
-                    Err(e) => HttpResponse::BadRequest().json(e.to_string()),
-                }}
-            }} else {{
-              // redirect to login page
-              let mut context = Context::new();
-                context.insert("title", "Login");
-                context.insert("route_name", "login");
-                context.insert("error", "You must be logged in to create a new {}.");
-                HttpResponse::Found()
-                    .append_header((actix_web::http::header::LOCATION, "/login"))
-                    .finish()
-            }}
-           // before we allow the user to create a new {} we need to check if they are logged in
-           // if they are not logged in, we need to redirect them to the login page
-        }}"#,
-        &new_controller_name,
-        &new_controller_name,
-        &new_controller_name,
-        &new_controller_name,
-        &new_controller_name,
-        &new_controller_name,
-        &new_controller_name,
-        &new_controller_name,
-        &new_controller_name,
-        &new_controller_name,
-        &new_controller_name
-    );
+    let mut context = Context::new();
+    context.insert("controller", &previous_controller_name);
+    context.insert("action", &new_controller_name);
+    let contents = render_template("actions", "previous_create.rs", &context)?;
 
     // Define the path to the file
     let mut path = format!(
@@ -984,8 +747,9 @@ pub fn write_to_previous_create_controller(
 
     // instead of overwriting the file, we need to append to the file
     // lets get the contents of the file first
-    let mut file_contents = fs::read_to_string(&path).unwrap();
+    let mut file_contents = Generator::read_to_string(&path).unwrap();
     if !file_contents.contains(&controller_signature) {
+        file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
         // and then append the new contents to the file
         file_contents.push_str(&contents);
 
@@ -1023,25 +787,9 @@ pub fn write_to_initial_get_controller(controller_name: String) -> Result<(), Er
         .and_then(std::ffi::OsStr::to_str)
         .unwrap_or("");
 
-    let contents = format!(
-        r#"use actix_web::{{get, web, HttpResponse, HttpRequest, Error}};
-use tera::{{Context, Tera}};
-use crate::models;
-use rustyroad::database::Database;
-use models::user::UserLogin;
-
-#[get("/{}")]
-async fn {}_controller(tmpl: web::Data<Tera>) -> HttpResponse {{
     let mut context = Context::new();
-    context.insert("controller_name", "{}");
-    let rendered = tmpl.render("pages/{}.html.tera", &context).unwrap();
-    HttpResponse::Ok().body(rendered)
-}}"#,
-        controller_file_name.trim_end_matches(".rs"),
-        controller_file_name.trim_end_matches(".rs"),
-        controller_file_name.trim_end_matches(".rs"),
-        controller_file_name.trim_end_matches(".rs")
-    );
+    context.insert("name", controller_file_name.trim_end_matches(".rs"));
+    let contents = render_template("new", "controller.rs", &context)?;
 
     write_to_file(&controller_name.to_string(), contents.as_bytes()).unwrap_or_else(|why| {
         println!(
@@ -1073,38 +821,7 @@ pub fn write_to_initial_post_controller_authentication(
         .trim_start_matches("./src/controllers/")
         .trim_end_matches(".rs");
 
-    let contents = r#"
-
- use actix_web::post;
-
-#[post("/login")]
-async fn login_function(
-    form: web::Form<UserLogin>,
-    tmpl: web::Data<Tera>, // Updated line
-    db: web::Data<Database>,
-    req: HttpRequest
-) -> Result<HttpResponse, actix_web::Error> {
-     form.user_login(req, tmpl, db.get_ref().clone()).await
-}
-
-
-#[get("/logout")]
-async fn user_logout(
-    tmpl: web::Data<Tera>,
-    user: Option<actix_identity::Identity>,
-) -> Result<HttpResponse, Error> {
-    if let Some(user) = user {
-        UserLogin::user_logout(tmpl, user).await
-   } else {
-         let mut context = Context::new();
-         context.insert("controller_name", "login");
-         context.insert("error", "You must be logged in to logout.");
-         let rendered = tmpl.render("pages/login.html.tera", &context).unwrap();
-         Ok(HttpResponse::Ok().body(rendered))
-   }
-}
-"#
-    .to_string();
+    let contents = render_template("new", "login.rs", &Context::new())?;
 
     write_to_file(&controller_name.to_string(), contents.as_bytes()).unwrap_or_else(|why| {
         println!(
@@ -1149,7 +866,7 @@ pub fn write_to_controller_file_no_folder(
     let path = format!("./src/controllers/{}.rs", model_name);
 
     // Read the contents of the file so we don't overwrite it
-    let file_contents = match fs::read_to_string(&path) {
+    let file_contents = match Generator::read_to_string(&path) {
         std::result::Result::Ok(contents) => contents,
         Err(_) => String::new(), // If the file doesn't exist, start with an empty string
     };
@@ -1175,7 +892,7 @@ pub fn write_to_controller_file_no_folder(
     // Combine updated imports with the method contents
     let combined_contents = format!("{}\n\n{}", updated_imports, method_contents);
 
-    match fs::write(PathBuf::from(&path), combined_contents.as_bytes()) {
+    match Generator::update_file(&path, &combined_contents) {
         std::result::Result::Ok(()) => {
             add_new_controller_to_main_rs(
                 None,
@@ -1220,49 +937,7 @@ pub fn write_to_page_dashboard_get_controller() -> Result<(), Error> {
     // This includes importing necessary Actix Web and Tera modules, defining the controller handler function,
     // and setting up the Tera template rendering
     // Define the new controller content
-    let new_controller_content = r#"
-#[get("/page_dashboard")]
-async fn page_dashboard(tmpl: Data<Tera>, user: Option<Identity>) -> HttpResponse {
-    if let Some(user) = user {
-        let pages_result = Page::get_all_pages().await; // Await the future
-
-        let mut context = Context::new();
-        match pages_result {
-            Ok(pages) => {
-                let pages_data = pages.get("data").unwrap().as_array().unwrap();
-                context.insert("pages", pages_data);
-                context.insert("error", ""); // Insert an empty string for the error variable
-            }
-            Err(e) => {
-                println!("Error: {}", e);
-                let message = json!({"error": e.to_string()});
-                context.insert("error", &message);
-                context.insert("pages", &Vec::<Page>::new()); // Insert an empty vector for the pages variable
-            }
-        };
-        context.insert("username", &user.id().unwrap());
-        context.insert("title", "Dashboard");
-        context.insert("controller_name", "page_dashboard");
-
-        let rendered = tmpl
-            .render(
-                "layouts/authenticated_page/page/page_dashboard.html.tera",
-                &context,
-            )
-            .expect("Failed to render template");
-
-        HttpResponse::Ok().body(rendered)
-    } else {
-        let mut context = Context::new();
-        context.insert("title", "Login");
-        context.insert("route_name", "login");
-        context.insert("error", "You must be logged in to view this page.");
-        HttpResponse::Found()
-            .append_header((LOCATION, "/login"))
-            .finish()
-    }
-}
-"#;
+    let new_controller_content = render_template("page", "page_dashboard.rs", &Context::new())?;
 
     // Define the path to the file
     let path = "./src/controllers/dashboard.rs".to_string();
@@ -1272,7 +947,7 @@ async fn page_dashboard(tmpl: Data<Tera>, user: Option<Identity>) -> HttpRespons
     // If the file doesn't exist, the function will create it, and if it does exist, the function will overwrite it
 
     // Read the contents of the file so we don't overwrite it
-    let mut file_contents = fs::read_to_string(path.clone())?;
+    let mut file_contents = Generator::read_to_string(path.clone())?;
     if !file_contents.contains(controller_signature) {
         // Update imports in the file contents
         file_contents = add_or_update_import(&file_contents, "crate", "models");
@@ -1280,28 +955,19 @@ async fn page_dashboard(tmpl: Data<Tera>, user: Option<Identity>) -> HttpRespons
         file_contents = add_or_update_import(&file_contents, "actix_web", "get");
         file_contents = add_or_update_import(&file_contents, "actix_web", "web");
         file_contents = add_or_update_import(&file_contents, "actix_web", "HttpResponse");
-        file_contents = add_or_update_import(&file_contents, "actix_web", "http::header::LOCATION");
         file_contents = add_or_update_import(&file_contents, "tera", "Context");
         file_contents = add_or_update_import(&file_contents, "tera", "Tera");
         file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
-        file_contents = add_or_update_import(&file_contents, "serde_json", "json");
+        file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
         // Add two new lines to the end of the file
         file_contents.push_str("\n\n");
 
         // Add the new controller content to the file
-        file_contents.push_str(new_controller_content);
+        file_contents.push_str(&new_controller_content);
 
         // Write the updated contents to the file
-        let mut file = OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .open(&path)
-            .unwrap();
-        // Write the updated contents to the file
-        writeln!(file, "{}", file_contents)?;
-
-        file.flush()?;
+        Generator::update_file(&path, &format!("{}\n", file_contents))?;
 
         println!("Successfully written to dashboard.rs");
 
@@ -1331,51 +997,21 @@ pub fn write_to_create_page_dashboard_get_controller() -> Result<(), Error> {
     // This includes importing necessary Actix Web and Tera modules, defining the controller handler function,
     // and setting up the Tera template rendering
     // Define the new controller content
-    let new_controller_content = r#"
-#[get("/create_page")]
-async fn create_page_dashboard(tmpl: Data<Tera>, user: Option<Identity>) -> HttpResponse {
-    if let Some(user) = user {
-        let mut context = Context::new();
-        context.insert("title", "Create Page");
-        context.insert("route_name", "create_page");
-        let mut page = Page::new();
-        page.id = Some(0);
-        let html_content = "<h1 class=\"text-center\">Welcome To The Page Builder</h1><p class=\"text-center\">Drag a block from the right side to this area to get started.</p>";
-        page.html_content = html_content.to_string();
-        context.insert("page", &page);
-        context.insert("username", &user.id().unwrap());
-        context.insert("html_content", &page.html_content);
-        let s = tmpl
-            .render(
-                "layouts/authenticated_page/page/page_details.html.tera",
-                &context,
-            )
-            .unwrap();
-        HttpResponse::Ok().body(s)
-    } else {
-        let mut context = Context::new();
-        context.insert("title", "Login");
-        context.insert("route_name", "login");
-        context.insert("error", "You must be logged in to view this page.");
-        HttpResponse::Found()
-            .append_header((LOCATION, "/login"))
-            .finish()
-    }
-}
-"#;
+    let new_controller_content =
+        render_template("page", "create_page_dashboard.rs", &Context::new())?;
 
     let path = "./src/controllers/dashboard.rs".to_string();
 
-    let mut file_contents = fs::read_to_string(path.clone())?;
+    let mut file_contents = Generator::read_to_string(path.clone())?;
     if !file_contents.contains(controller_signature) {
         // Add two new lines to the end of the file
         file_contents.push_str("\n\n");
 
         // Add the new controller content to the file
-        file_contents.push_str(new_controller_content);
+        file_contents.push_str(&new_controller_content);
 
         // Write the updated contents to the file
-        fs::write(PathBuf::from(path), file_contents.as_bytes())?;
+        Generator::update_file(&path, &file_contents)?;
 
         println!("Successfully written to dashboard.rs");
 
@@ -1395,39 +1031,11 @@ pub fn write_to_edit_page_get_controller() -> Result<(), Error> {
     // This includes importing necessary Actix Web and Tera modules, defining the controller handler function,
     // and setting up the Tera template rendering
     // Define the new controller content
-    let new_controller_content = r#"
-#[get("/page/{id}/edit")]
-pub async fn edit_page(tmpl: Data<Tera>, id: Path<i32>, user: Option<Identity>) -> HttpResponse {
-    if let Some(_user) = user {
-        let result = Page::get_page_by_id(id.into_inner()).await;
-        match result {
-            Ok(page) => {
-                let mut context = Context::new();
-                context.insert("title", "Edit Page");
-                context.insert("route_name", "edit_page");
-                context.insert("html_content", &page.html_content);
-                context.insert("page", &page);
-                context.insert("page_id", &page.id);
-                context.insert("username", &_user.id().unwrap());
-                let s = tmpl.render("layouts/authenticated_page/page/edit_page.html.tera", &context).unwrap();
-                HttpResponse::Ok().body(s)
-            }
-            Err(e) => {
-                let mut context = Context::new();
-                context.insert("error", &e.to_string());
-                let s = tmpl.render("layouts/authenticated_page/page/edit_page.html.tera", &context).unwrap();
-                HttpResponse::Ok().body(s)
-            }
-        }
-    } else {
-        HttpResponse::Unauthorized().json("You must be logged in to edit.")
-    }
-}
-    "#;
+    let new_controller_content = render_template("page", "edit_page.rs", &Context::new())?;
 
     let path = "./src/controllers/page.rs".to_string();
 
-    let mut file_contents = fs::read_to_string(path.clone())?;
+    let mut file_contents = Generator::read_to_string(path.clone())?;
 
     if !file_contents.contains(controller_signature) {
         file_contents = add_or_update_import(&file_contents, "crate", "models");
@@ -1439,23 +1047,16 @@ pub async fn edit_page(tmpl: Data<Tera>, id: Path<i32>, user: Option<Identity>)
         file_contents = add_or_update_import(&file_contents, "actix_web", "web::Path");
         file_contents = add_or_update_import(&file_contents, "actix_web", "web::Data");
         file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
+        file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
         // Add two new lines to the end of the file
         file_contents.push_str("\n\n");
 
         // Add the new controller content to the file
-        file_contents.push_str(new_controller_content);
+        file_contents.push_str(&new_controller_content);
 
         // Write the updated contents to the file
-        let mut file = OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .open(&path)
-            .unwrap();
-        // Write the updated contents to the file
-        writeln!(file, "{}", file_contents)?;
-
-        file.flush()?;
+        Generator::update_file(&path, &format!("{}\n", file_contents))?;
 
         println!("Successfully written to dashboard.rs");
 
@@ -1474,44 +1075,12 @@ pub fn write_to_new_dashboard_get_controller_to_test(file_path: &Path) -> Result
     // This includes importing necessary Actix Web and Tera modules, defining the controller handler function,
     // and setting up the Tera template rendering
     // Define the new controller content
-    let new_controller_content = r#"
-#[get("/page_dashboard")]
-async fn page_dashboard(tmpl: web::Data<Tera>, user: Option<Identity>) -> impl Responder {
-
-    if let Some(_user) = user {
-
-        let mut context = Context::new();
-        let (pages, error) = match pages_result {
-            Ok(pages) if pages.is_empty() => (Vec::new(), "No pages found".to_string()),
-            Ok(pages) => (pages, "".to_string()),
-            Err(e) => (Vec::new(), e.to_string()),
-        };
-
-        context.insert("pages", &pages);
-        context.insert("error", &error);
-
-
-        context.insert("username", &user.id().unwrap());
-        context.insert("title", "Dashboard");
-        context.insert("controller_name", "page_dashboard");
-
-        let rendered = tmpl.render("layouts/authenticated_page/page/page_dashboard.html.tera", &context).unwrap();
-        HttpResponse::Ok().body(rendered)
-    } else {
-        let mut context = Context::new();
-        context.insert("title", "Login");
-        context.insert("route_name", "login");
-        context.insert("error", "You must be logged in to view this page.");
-        HttpResponse::Found()
-            .append_header((actix_web::http::header::LOCATION, "/login"))
-            .finish()
-    }
-}"#;
+    let new_controller_content = render_template("page", "page_dashboard.rs", &Context::new())?;
     // Write the contents to the file
     // The write_to_file function is assumed to be a function that takes a path and a byte slice and writes the bytes to the file at the path
     // If the file doesn't exist, the function will create it, and if it does exist, the function will overwrite it
     // Read the contents of the file so we don't overwrite it
-    let mut file_contents = fs::read_to_string(file_path)?;
+    let mut file_contents = Generator::read_to_string(file_path)?;
 
     // Update imports in the file contents
     file_contents = add_or_update_import(&file_contents, "actix_web", "get");
@@ -1520,15 +1089,16 @@ async fn page_dashboard(tmpl: web::Data<Tera>, user: Option<Identity>) -> impl R
     file_contents = add_or_update_import(&file_contents, "actix_web", "Responder");
     file_contents = add_or_update_import(&file_contents, "tera", "Tera");
     file_contents = add_or_update_import(&file_contents, "tera", "Context");
+    file_contents = add_or_update_import(&file_contents, "rustyroad::web", "AppError");
 
     // Add two new lines to the end of the file
     file_contents.push_str("\n\n");
 
     // Add the new controller content to the file
-    file_contents.push_str(new_controller_content);
+    file_contents.push_str(&new_controller_content);
 
     // Write the updated contents to the file
-    fs::write(PathBuf::from(file_path), file_contents.as_bytes())?;
+    Generator::update_file(file_path, &file_contents)?;
 
     println!("Successfully written to dashboard.rs");
     Ok(())
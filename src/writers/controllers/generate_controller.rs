@@ -0,0 +1,113 @@
+use crate::database::Database;
+use crate::generators::Generator;
+use crate::writers::{write_controller, Scaffold, CRUD_ACTIONS};
+use crate::CRUDType;
+use color_eyre::eyre::Result;
+use dialoguer::{Input, MultiSelect};
+use eyre::{eyre, Error};
+use std::path::Path;
+
+/// # Name: generate_controller
+/// ## Description
+/// Generates the controller handlers and views for `actions` on an existing model, reading the
+/// fields from `src/models/<model_name>.rs`. If the controller already exists, only the missing
+/// handlers, views and routes are added.
+/// ## Example
+/// ```no_run
+/// use rustyroad::writers::generate_controller;
+/// use rustyroad::CRUDType;
+///
+/// generate_controller("post", &[CRUDType::Read, CRUDType::Create]).unwrap();
+/// ```
+pub fn generate_controller(model_name: &str, actions: &[CRUDType]) -> Result<(), Error> {
+    if !Path::new("rustyroad.toml").exists() {
+        return Err(eyre!(
+            "Could not find rustyroad.toml. Run this command from your RustyRoad project root."
+        ));
+    }
+    if actions.is_empty() {
+        return Err(eyre!(
+            "Pick at least one action: read, create, update or delete"
+        ));
+    }
+    let database = Database::get_database_from_rustyroad_toml()
+        .map_err(|e| eyre!("Could not read the database settings: {}", e))?;
+
+    let model_path = Path::new("src/models").join(format!("{}.rs", model_name));
+    if !Generator::exists(&model_path) {
+        return Err(eyre!(
+            "{} does not exist. Create it first with: rustyroad generate model {} <fields...>",
+            model_path.display(),
+            model_name
+        ));
+    }
+    let source = Generator::read_to_string(&model_path)?;
+    let scaffold = Scaffold::from_model_source(model_name, &source, database.database_type)?;
+
+    write_controller(&scaffold, actions)
+}
+
+/// # Name: parse_crud_actions
+/// ## Description
+/// Parses a comma separated `--actions` value such as `read,create`. Duplicates are dropped.
+/// ## Example
+/// ```
+/// use rustyroad::writers::parse_crud_actions;
+/// use rustyroad::CRUDType;
+///
+/// let actions = parse_crud_actions("read, create,read").unwrap();
+/// assert_eq!(actions, vec![CRUDType::Read, CRUDType::Create]);
+/// ```
+pub fn parse_crud_actions(value: &str) -> Result<Vec<CRUDType>, Error> {
+    let mut actions = Vec::new();
+    for part in value.split(',').filter(|p| !p.trim().is_empty()) {
+        let action: CRUDType = part.parse()?;
+        if !actions.contains(&action) {
+            actions.push(action);
+        }
+    }
+    if actions.is_empty() {
+        return Err(eyre!(
+            "Pick at least one action: read, create, update or delete"
+        ));
+    }
+    Ok(actions)
+}
+
+/// Asks for the model name when `generate controller` is run without one.
+pub fn prompt_for_model_name() -> Result<String, Error> {
+    let name: String = Input::new()
+        .with_prompt("Which model should the controller use?")
+        .interact_text()?;
+    Ok(name.trim().to_string())
+}
+
+/// Asks which actions to generate when `--actions` is omitted; all are preselected.
+pub fn prompt_for_crud_actions() -> Result<Vec<CRUDType>, Error> {
+    let labels = ["read", "create", "update", "delete"];
+    let selected = MultiSelect::new()
+        .with_prompt("Which actions should the controller have? (space to toggle)")
+        .items(&labels)
+        .defaults(&[true; 4])
+        .interact()?;
+    Ok(selected.into_iter().map(|i| CRUD_ACTIONS[i]).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_crud_actions() {
+        assert_eq!(
+            parse_crud_actions("read,create,update,delete").unwrap(),
+            CRUD_ACTIONS.to_vec()
+        );
+        assert_eq!(
+            parse_crud_actions("GET, post,destroy,show").unwrap(),
+            vec![CRUDType::Read, CRUDType::Create, CRUDType::Delete]
+        );
+        assert!(parse_crud_actions("").is_err());
+        assert!(parse_crud_actions("read,publish").is_err());
+    }
+}
@@ -6,6 +6,7 @@ pub mod create_delete_controller;
 pub mod create_get_all_controller;
 pub mod create_read_controllers;
 pub mod create_update_controller;
+pub mod generate_controller;
 
 pub use add_controller_to_mod::*;
 pub use controller_writer::*;
@@ -15,3 +16,4 @@ pub use create_delete_controller::*;
 pub use create_get_all_controller::*;
 pub use create_read_controllers::*;
 pub use create_update_controller::*;
+pub use generate_controller::*;
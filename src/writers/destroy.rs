@@ -0,0 +1,281 @@
+use crate::database::{applied_migrations, find_create_table_migration, MIGRATIONS_DIR};
+use crate::generators::Generator;
+use crate::writers::remove_services_from_app;
+use crate::writers::scaffold::{pluralize, to_snake_case};
+use color_eyre::eyre::Result;
+use eyre::{eyre, Error};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn ensure_project_root() -> Result<(), Error> {
+    if !Path::new("rustyroad.toml").exists() {
+        return Err(eyre!(
+            "Could not find rustyroad.toml. Run this command from your RustyRoad project root."
+        ));
+    }
+    Ok(())
+}
+
+/// Removes the `pub mod <name>;` / `pub use <name>::*;` lines that `register_module` or
+/// [`add_module_declaration`](crate::writers::add_module_declaration) added to a mod.rs file.
+pub(crate) fn unregister_module(name: &str, mod_rs: &Path) -> Result<(), Error> {
+    if !Generator::exists(mod_rs) {
+        return Ok(());
+    }
+    let contents = Generator::read_to_string(mod_rs)?;
+    let declaration = Regex::new(&format!(
+        r"^\s*(pub\s+)?(mod\s+{0}\s*;|use\s+{0}::\*\s*;)\s*$",
+        regex::escape(name)
+    ))?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut removed = vec![false; lines.len()];
+    for (i, line) in lines.iter().enumerate() {
+        if !declaration.is_match(line) {
+            continue;
+        }
+        removed[i] = true;
+        // The tests module of a controller is declared under #[cfg(test)]
+        let mut first = i;
+        if i > 0 && lines[i - 1].trim() == "#[cfg(test)]" {
+            removed[i - 1] = true;
+            first = i - 1;
+        }
+        // add_module_declaration separates what it adds with a blank line
+        if first > 0 && lines[first - 1].trim().is_empty() && !removed[first - 1] {
+            removed[first - 1] = true;
+        } else if lines.get(i + 1).is_some_and(|next| next.trim().is_empty()) {
+            removed[i + 1] = true;
+        }
+    }
+    if !removed.contains(&true) {
+        return Ok(());
+    }
+    let mut updated = lines
+        .iter()
+        .zip(&removed)
+        .filter(|(_, removed)| !**removed)
+        .map(|(line, _)| *line)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if contents.ends_with('\n') && !updated.is_empty() {
+        updated.push('\n');
+    }
+    Generator::update_file(mod_rs, &updated)?;
+    Ok(())
+}
+
+/// Removes `<name>`'s `.service(controllers::<name>::..)` registrations from main.rs.
+/// Returns how many were removed.
+pub(crate) fn remove_services_from_main_rs(name: &str, main_rs: &Path) -> Result<usize, Error> {
+    if !Generator::exists(main_rs) {
+        return Ok(0);
+    }
+    let contents = Generator::read_to_string(main_rs)?;
+    let module = format!("controllers::{}", name);
+    let (updated, removed) = remove_services_from_app(&contents, |service| {
+        service == module || service.starts_with(&format!("{}::", module))
+    })
+    .map_err(|e| eyre!("{}: {}", main_rs.display(), e))?;
+    if removed > 0 {
+        Generator::update_file(main_rs, &updated)?;
+    }
+    Ok(removed)
+}
+
+/// The migration folders under `config/database/migrations` named `<timestamp>-<name>`.
+fn migration_folders(name: &str) -> Vec<PathBuf> {
+    let mut folders: Vec<PathBuf> = fs::read_dir(MIGRATIONS_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|n| n.to_str())
+                        .and_then(|n| n.split_once('-'))
+                        .is_some_and(|(_, migration)| migration == name)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    folders.sort();
+    folders
+}
+
+/// Refuses to go on while the migration in `folder` is applied.
+async fn ensure_rolled_back(folder: &Path) -> Result<(), Error> {
+    let name = folder
+        .file_name()
+        .and_then(|n| n.to_str())
+        .and_then(|n| n.split_once('-'))
+        .map(|(_, name)| name.to_string())
+        .ok_or_else(|| eyre!("{} is not a migration folder", folder.display()))?;
+    let applied = applied_migrations()
+        .await
+        .map_err(|e| eyre!("Could not check whether '{}' is applied: {}", name, e))?;
+    if applied.contains(&name) {
+        return Err(eyre!(
+            "Migration '{}' is applied. Roll it back first with:\n  rustyroad migration rollback {}",
+            name,
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Plans the removal of a controller, returning whether there was anything to remove.
+fn remove_controller(name: &str) -> Result<bool, Error> {
+    let controllers = Path::new("src/controllers");
+    let views = Path::new("src/views/pages");
+    let folder = controllers.join(name);
+    let flat = controllers.join(format!("{}.rs", name));
+    let flat_tests = controllers.join(format!("{}_tests.rs", name));
+    let view_folder = views.join(name);
+    let flat_view = views.join(format!("{}.html.tera", name));
+
+    let found = [&folder, &flat, &view_folder, &flat_view]
+        .iter()
+        .any(|p| Generator::exists(p));
+
+    Generator::remove_dir_all(&folder)?;
+    Generator::remove_file(&flat)?;
+    Generator::remove_file(&flat_tests)?;
+    Generator::remove_dir_all(&view_folder)?;
+    Generator::remove_file(&flat_view)?;
+    unregister_module(name, &controllers.join("mod.rs"))?;
+    unregister_module(&format!("{}_tests", name), &controllers.join("mod.rs"))?;
+    let services = remove_services_from_main_rs(name, Path::new("src/main.rs"))?;
+    Ok(found || services > 0)
+}
+
+/// Plans the removal of a model and its migration, returning whether there was anything to remove.
+async fn remove_model(name: &str) -> Result<bool, Error> {
+    let model = Path::new("src/models").join(format!("{}.rs", name));
+    let migration = find_create_table_migration(&pluralize(name)).map(PathBuf::from);
+    let found = Generator::exists(&model) || migration.is_some();
+
+    if let Some(migration) = &migration {
+        ensure_rolled_back(migration).await?;
+        Generator::remove_dir_all(migration)?;
+    }
+    Generator::remove_file(&model)?;
+    unregister_module(name, Path::new("src/models/mod.rs"))?;
+    Ok(found)
+}
+
+/// # Name: destroy_controller
+/// ## Description
+/// Reverses `generate controller`: deletes `src/controllers/<name>/` (or `<name>.rs` and
+/// `<name>_tests.rs`) and the views under `src/views/pages/<name>/`, removes the `pub mod` line from
+/// `src/controllers/mod.rs` and unregisters its services from `src/main.rs`.
+pub fn destroy_controller(name: &str) -> Result<(), Error> {
+    ensure_project_root()?;
+    let name = to_snake_case(name);
+    if !remove_controller(&name)? {
+        return Err(eyre!("There is no '{}' controller to destroy", name));
+    }
+    Ok(())
+}
+
+/// # Name: destroy_model
+/// ## Description
+/// Reverses `generate model`: deletes `src/models/<name>.rs`, removes it from
+/// `src/models/mod.rs`, and deletes the `create_<table>` migration. An applied migration
+/// must be rolled back first.
+pub async fn destroy_model(name: &str) -> Result<(), Error> {
+    ensure_project_root()?;
+    let name = to_snake_case(name);
+    if !remove_model(&name).await? {
+        return Err(eyre!("There is no '{}' model to destroy", name));
+    }
+    Ok(())
+}
+
+/// # Name: destroy_scaffold
+/// ## Description
+/// Reverses `generate scaffold`: the model, its migration, the controller, views and routes.
+/// Parts that were already removed by hand are passed over.
+pub async fn destroy_scaffold(name: &str) -> Result<(), Error> {
+    ensure_project_root()?;
+    let name = to_snake_case(name);
+    let model = remove_model(&name).await?;
+    if !remove_controller(&name)? && !model {
+        return Err(eyre!("There is no '{}' scaffold to destroy", name));
+    }
+    Ok(())
+}
+
+/// # Name: destroy_migration
+/// ## Description
+/// Deletes the latest `<timestamp>-<name>` migration folder, refusing while it is applied.
+/// `create_<table>` also finds the `<timestamp>-<table>` folders `migration generate` writes.
+/// ## Example
+/// ```no_run
+/// # async fn example() -> eyre::Result<()> {
+/// use rustyroad::writers::destroy_migration;
+///
+/// destroy_migration("create_posts").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn destroy_migration(name: &str) -> Result<(), Error> {
+    ensure_project_root()?;
+    // `migration generate create_<table>` names its folder after the table
+    let folder = migration_folders(name)
+        .pop()
+        .or_else(|| {
+            name.strip_prefix("create_")
+                .and_then(|table| migration_folders(table).pop())
+        })
+        .ok_or_else(|| eyre!("There is no '{}' migration in {}", name, MIGRATIONS_DIR))?;
+    ensure_rolled_back(&folder).await?;
+    Generator::remove_dir_all(&folder)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::GeneratorOptions;
+
+    #[test]
+    fn test_unregister_reverses_registration() {
+        let dir = tempfile::tempdir().unwrap();
+        let mod_rs = dir.path().join("mod.rs");
+        fs::write(
+            &mod_rs,
+            "pub mod user;\n\npub mod post;\npub use user::*;\n\npub use post::*;\n\n#[cfg(test)]\nmod post_tests;\n",
+        )
+        .unwrap();
+        let main_rs = dir.path().join("main.rs");
+        fs::write(
+            &main_rs,
+            "fn main() {\n    App::new()\n            .service(controllers::post::index_post)\n.service(controllers::post::show_post)\n            .service(controllers::poster::index_poster);\n}\n",
+        )
+        .unwrap();
+
+        Generator::run(GeneratorOptions::default(), || {
+            unregister_module("post", &mod_rs)?;
+            unregister_module("post_tests", &mod_rs)?;
+            assert_eq!(
+                Generator::read_to_string(&mod_rs)?,
+                "pub mod user;\npub use user::*;\n"
+            );
+            // Nothing left to remove
+            unregister_module("post", &mod_rs)?;
+            assert_eq!(remove_services_from_main_rs("post", &main_rs)?, 2);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(&mod_rs).unwrap(),
+            "pub mod user;\npub use user::*;\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&main_rs).unwrap(),
+            "fn main() {\n    App::new()\n            .service(controllers::poster::index_poster);\n}\n"
+        );
+    }
+}
@@ -1,8 +1,8 @@
-use std::io::Write;
+use crate::generators::Generator;
 
 /// # Name: write_to_file
 /// ## Description
-/// This function writes to a file
+/// This function appends to a file. Inside a generator session the write is planned, see [`Generator`].
 /// ## Arguments
 /// * `file` - The path to the file to write to
 /// * `message` - The message to write to the file
@@ -19,7 +19,5 @@ use std::io::Write;
 /// }
 /// ```
 pub fn write_to_file(file: &str, message: &[u8]) -> Result<(), std::io::Error> {
-    let mut file = std::fs::OpenOptions::new().append(true).open(file)?;
-    file.write_all(message)?;
-    Ok(())
+    Generator::append_file(file, &String::from_utf8_lossy(message))
 }
@@ -1,19 +1,29 @@
+pub mod api;
+pub mod app_builder;
 pub mod controllers;
+pub mod destroy;
 pub mod file;
 pub mod general;
 pub mod migrations;
 pub mod models;
 pub mod project_creation;
+pub mod routes;
+pub mod scaffold;
 pub mod templates;
 pub mod write_to_controllers_mod;
 pub mod write_to_main;
 
+pub use api::*;
+pub use app_builder::*;
 pub use controllers::*;
+pub use destroy::*;
 pub use file::*;
 pub use general::*;
 pub use migrations::*;
 pub use models::*;
 pub use project_creation::*;
+pub use routes::*;
+pub use scaffold::*;
 pub use templates::*;
 pub use write_to_controllers_mod::*;
 pub use write_to_main::*;
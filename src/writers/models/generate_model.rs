@@ -0,0 +1,194 @@
+use crate::database::{
+    find_create_table_migration, introspect_schema, write_create_table_migration, Database,
+};
+use crate::generators::{Generator, GeneratorOptions};
+use crate::writers::scaffold::{model_implements_model, register_module, write_new_file};
+use crate::writers::{write_model, Scaffold, TableModel};
+use color_eyre::eyre::Result;
+use dialoguer::Input;
+use eyre::{eyre, Error};
+use std::path::Path;
+
+/// # Name: generate_model
+/// ## Description
+/// Writes the CREATE TABLE migration `config/database/migrations/<timestamp>-create_<names>/` and
+/// `src/models/<name>.rs` from the same field definitions, so the table and the struct agree.
+/// The model is a `#[derive(Model)]` struct with a form struct and `all`/`find`/`create`/`update`/`delete`
+/// for the project's database, declared in `src/models/mod.rs`. Fields use the
+/// `name:type[:constraints]` syntax of `migration generate`, including `references=table`,
+/// which also gives the model a `belongs_to` method once the referenced table's model exists.
+/// Pass `skip_migration` when the table already exists. Outside a generator session the files are
+/// written in a session of their own, so a failure leaves neither behind.
+/// ## Example
+/// ```no_run
+/// use rustyroad::writers::generate_model;
+///
+/// let fields = vec![
+///     "title:string:not_null".to_string(),
+///     "author_id:integer:references=users".to_string(),
+/// ];
+/// generate_model("article", &fields, false).unwrap();
+/// ```
+pub fn generate_model(name: &str, fields: &[String], skip_migration: bool) -> Result<(), Error> {
+    if !Generator::is_active() {
+        return Generator::run(GeneratorOptions::default(), || {
+            generate_model(name, fields, skip_migration)
+        })
+        .map(|_| ());
+    }
+    if !Path::new("rustyroad.toml").exists() {
+        return Err(eyre!(
+            "Could not find rustyroad.toml. Run this command from your RustyRoad project root."
+        ));
+    }
+    let database = Database::get_database_from_rustyroad_toml()
+        .map_err(|e| eyre!("Could not read the database settings: {}", e))?;
+    let model = Scaffold::new(name, fields, database.database_type)?;
+
+    if !skip_migration {
+        if let Some(existing) = find_create_table_migration(&model.table_name) {
+            return Err(eyre!(
+                "{} already creates the '{}' table; pass --skip-migration to only write the model",
+                existing,
+                model.table_name
+            ));
+        }
+    }
+    write_model(&model)?;
+    if !skip_migration {
+        write_create_table_migration(
+            &model.table_name,
+            &model.column_definitions(),
+            &model.database_type,
+        )?;
+    }
+
+    if skip_migration {
+        Generator::say(format!(
+            "\nModel '{}' created for the existing '{}' table.",
+            model.struct_name, model.table_name
+        ));
+    } else {
+        Generator::say(format!(
+            "\nModel '{}' created. Create the '{}' table with:\n  rustyroad migration run create_{}",
+            model.struct_name, model.table_name, model.table_name
+        ));
+    }
+    Ok(())
+}
+
+/// # Name: generate_model_from_table
+/// ## Description
+/// Introspects `table` in the database configured in rustyroad.toml and writes
+/// `src/models/<name>.rs` with a `#[derive(Model)]` struct whose fields match the table's columns:
+/// `Option<T>` for nullable columns, chrono for dates and times, `uuid::Uuid`, `serde_json::Value`
+/// and `rust_decimal::Decimal`. The model gets `all`, `find`, `insert`, `update` and `delete`,
+/// and the associations the table's foreign keys describe, such as `post.author()` and
+/// `user.posts()`. An association is only written once the other table's model exists.
+/// ## Example
+/// ```no_run
+/// use rustyroad::writers::generate_model_from_table;
+///
+/// # async fn run() {
+/// generate_model_from_table("customer", "customers").await.unwrap();
+/// # }
+/// ```
+pub async fn generate_model_from_table(name: &str, table: &str) -> Result<(), Error> {
+    if !Path::new("rustyroad.toml").exists() {
+        return Err(eyre!(
+            "Could not find rustyroad.toml. Run this command from your RustyRoad project root."
+        ));
+    }
+    let database = Database::get_database_from_rustyroad_toml()
+        .map_err(|e| eyre!("Could not read the database settings: {}", e))?;
+
+    let connection = database
+        .create_database_connection()
+        .await
+        .map_err(|e| eyre!("Could not connect to the database: {}", e))?;
+    let schema = introspect_schema(&connection)
+        .await
+        .map_err(|e| eyre!("Could not read the database schema: {}", e))?
+        .without_internal_tables();
+    let table_info = schema.table(table).ok_or_else(|| {
+        eyre!(
+            "Table '{}' not found. Available tables: {}",
+            table,
+            schema
+                .tables
+                .iter()
+                .map(|t| t.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    })?;
+
+    let mut model = TableModel::from_table(name, table_info, database.database_type)?
+        .with_associations(&schema);
+    if !model.implements_model() && !model.associations.is_empty() {
+        Generator::say(
+            "\nSkipped the associations: sqlx cannot read DECIMAL columns on every backend, so the \
+             model derives sqlx::FromRow instead of rustyroad's Model.",
+        );
+    }
+    let (associations, skipped): (Vec<_>, Vec<_>) =
+        model.associations.drain(..).partition(|association| {
+            association.model == model.name || model_implements_model(&association.model)
+        });
+    model.associations = associations;
+    for association in skipped.iter().filter(|_| model.implements_model()) {
+        Generator::say(format!(
+            "Skipped the `{}` association: generate src/models/{}.rs for the '{}' table first, then \
+             regenerate this model.",
+            association.name, association.model, association.table
+        ));
+    }
+    let model_path = Path::new("src/models").join(format!("{}.rs", model.name));
+    write_new_file(&model_path, &model.render())?;
+    register_module(&model.name, Path::new("src/models/mod.rs"))?;
+
+    let dependencies = model.extra_dependencies();
+    if !dependencies.is_empty() {
+        Generator::say("\nThe model uses types from crates your Cargo.toml may not have yet:");
+        for dependency in dependencies {
+            Generator::say(format!("  {}", dependency));
+        }
+    }
+    Ok(())
+}
+
+/// Asks for the fields when `generate model` is run without any.
+pub fn prompt_for_model_fields() -> Result<Vec<String>, Error> {
+    let line: String = Input::new()
+        .with_prompt("Fields (name:type[:constraints], separated by spaces)")
+        .interact_text()?;
+    Ok(line.split_whitespace().map(str::to_string).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::{create_table_migration_sql, DatabaseType};
+    use crate::writers::Scaffold;
+
+    #[test]
+    fn test_model_and_migration_share_fields() {
+        let fields = vec![
+            "title:string:not_null".to_string(),
+            "author_id:integer:references=users".to_string(),
+        ];
+        let model = Scaffold::new("article", &fields, DatabaseType::Postgres).unwrap();
+        let (up, down) = create_table_migration_sql(
+            &model.table_name,
+            &model.column_definitions(),
+            &model.database_type,
+        );
+        assert!(up.contains("CREATE TABLE IF NOT EXISTS articles"));
+        assert!(up.contains("title VARCHAR(255) NOT NULL"));
+        assert!(up.contains("FOREIGN KEY (author_id) REFERENCES users(id)"));
+        assert_eq!(down, "DROP TABLE IF EXISTS articles;");
+
+        let source = model.render_model().unwrap();
+        assert!(source.contains("pub title: String,"));
+        assert!(source.contains("pub author_id: Option<i32>,"));
+    }
+}
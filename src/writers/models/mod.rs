@@ -1,3 +1,5 @@
-pub mod create_update_model;
+pub mod generate_model;
+pub mod table_model;
 
-pub use create_update_model::*;
+pub use generate_model::*;
+pub use table_model::*;
@@ -0,0 +1,1314 @@
+use crate::database::{DatabaseType, ForeignKeyInfo, SchemaInfo, TableInfo};
+use crate::writers::scaffold::{
+    association_name, humanize, model_name_for_table, render_pool_fn, to_pascal_case,
+    to_snake_case, CONNECTION_FN, RUST_KEYWORDS,
+};
+use color_eyre::eyre::Result;
+use eyre::{eyre, Error};
+
+/// Identifiers that are quoted in generated SQL because at least one backend reserves them.
+const SQL_RESERVED: &[&str] = &[
+    "all",
+    "and",
+    "asc",
+    "case",
+    "check",
+    "column",
+    "constraint",
+    "default",
+    "desc",
+    "end",
+    "from",
+    "grant",
+    "group",
+    "index",
+    "key",
+    "limit",
+    "offset",
+    "order",
+    "primary",
+    "references",
+    "select",
+    "table",
+    "to",
+    "unique",
+    "user",
+    "when",
+    "where",
+];
+
+/// # Name: rust_type_for_column
+/// ## Description
+/// Maps a column type reported by the database to the Rust type sqlx decodes it into.
+/// Returns `None` for types a generated model cannot hold, such as PostgreSQL enums and arrays.
+/// ## Example
+/// ```
+/// use rustyroad::database::DatabaseType;
+/// use rustyroad::writers::rust_type_for_column;
+///
+/// let rust_type = rust_type_for_column("timestamp with time zone", &DatabaseType::Postgres);
+/// assert_eq!(rust_type, Some("chrono::DateTime<chrono::Utc>"));
+/// assert_eq!(rust_type_for_column("VARCHAR(255)", &DatabaseType::Sqlite), Some("String"));
+/// ```
+pub fn rust_type_for_column(data_type: &str, database_type: &DatabaseType) -> Option<&'static str> {
+    let data_type = data_type.trim().to_lowercase();
+    // SQLite and MySQL may report a size, e.g. `varchar(255)`; it does not change the Rust type,
+    // except that MySQL stores BOOLEAN as `tinyint(1)`
+    let base = data_type.split('(').next().unwrap_or_default().trim();
+
+    let rust_type = match database_type {
+        DatabaseType::Postgres => match base {
+            "smallint" | "int2" | "smallserial" => "i16",
+            "integer" | "int" | "int4" | "serial" => "i32",
+            "bigint" | "int8" | "bigserial" => "i64",
+            "real" | "float4" => "f32",
+            "double precision" | "float8" => "f64",
+            "numeric" | "decimal" => "rust_decimal::Decimal",
+            "boolean" | "bool" => "bool",
+            "text" | "character varying" | "varchar" | "character" | "char" | "bpchar"
+            | "citext" | "name" => "String",
+            "date" => "chrono::NaiveDate",
+            "timestamp without time zone" | "timestamp" => "chrono::NaiveDateTime",
+            "timestamp with time zone" | "timestamptz" => "chrono::DateTime<chrono::Utc>",
+            "time without time zone" | "time" => "chrono::NaiveTime",
+            "uuid" => "uuid::Uuid",
+            "json" | "jsonb" => "serde_json::Value",
+            "bytea" => "Vec<u8>",
+            _ => return None,
+        },
+        // MySQL types are one word, which may be followed by `unsigned` or `zerofill`
+        DatabaseType::Mysql => match base.split_whitespace().next().unwrap_or_default() {
+            "tinyint" if data_type.starts_with("tinyint(1)") => "bool",
+            "bool" | "boolean" => "bool",
+            "tinyint" => "i8",
+            "smallint" => "i16",
+            "mediumint" | "int" | "integer" => "i32",
+            "bigint" => "i64",
+            "float" => "f32",
+            "double" | "real" => "f64",
+            "decimal" | "numeric" => "rust_decimal::Decimal",
+            "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" | "enum"
+            | "set" => "String",
+            "date" => "chrono::NaiveDate",
+            "datetime" => "chrono::NaiveDateTime",
+            "timestamp" => "chrono::DateTime<chrono::Utc>",
+            "time" => "chrono::NaiveTime",
+            "json" => "serde_json::Value",
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => "Vec<u8>",
+            _ => return None,
+        },
+        DatabaseType::Sqlite => match base {
+            "boolean" | "bool" => "bool",
+            "bigint" => "i64",
+            "date" => "chrono::NaiveDate",
+            "datetime" | "timestamp" => "chrono::NaiveDateTime",
+            "time" => "chrono::NaiveTime",
+            "json" => "serde_json::Value",
+            // Migrations store UUIDs as TEXT on SQLite
+            "uuid" => "String",
+            // Everything else follows SQLite's type affinity rules
+            other if other.contains("int") => "i32",
+            other if ["char", "clob", "text"].iter().any(|t| other.contains(t)) => "String",
+            other if other.contains("blob") => "Vec<u8>",
+            other
+                if ["real", "floa", "doub", "numeric", "decimal"]
+                    .iter()
+                    .any(|t| other.contains(t)) =>
+            {
+                "f64"
+            }
+            _ => return None,
+        },
+        DatabaseType::Mongo => return None,
+    };
+    Some(rust_type)
+}
+
+/// # Name: ModelColumn
+/// ## Description
+/// One column of an introspected table and the struct field it becomes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelColumn {
+    pub column: String,
+    pub field: String,
+    pub rust_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+    /// The database assigns the value on insert (auto-increment or a default).
+    pub generated: bool,
+    /// The character limit of a `VARCHAR(n)`-like text column, when the type names one.
+    pub max_length: Option<usize>,
+}
+
+impl ModelColumn {
+    /// The Rust type of the struct field, `Option<T>` when the column is nullable.
+    pub fn field_type(&self) -> String {
+        if self.nullable {
+            format!("Option<{}>", self.rust_type)
+        } else {
+            self.rust_type.clone()
+        }
+    }
+
+    /// The `#[validate(..)]` rules that mirror the column: `presence` for NOT NULL text the
+    /// database does not fill in, and `length(max = n)` for a limited text column.
+    pub fn validations(&self) -> Vec<String> {
+        let mut rules = Vec::new();
+        if self.rust_type == "String" && !self.nullable && !self.generated {
+            rules.push("presence".to_string());
+        }
+        if let Some(max) = self.max_length {
+            rules.push(format!("length(max = {})", max));
+        }
+        rules
+    }
+
+    fn is_copy(&self) -> bool {
+        !matches!(
+            self.rust_type.as_str(),
+            "String" | "Vec<u8>" | "serde_json::Value"
+        )
+    }
+}
+
+/// # Name: AssociationKind
+/// ## Description
+/// How a generated model is related to the rows of another table.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssociationKind {
+    /// The model's `column` holds the primary key of the other table.
+    BelongsTo { column: String },
+    /// The other table's `foreign_key` column holds the model's primary key.
+    HasMany { foreign_key: String },
+    /// The join table `through` pairs the model's primary key in `source_key` with the other
+    /// table's primary key in `target_key`.
+    HasManyThrough {
+        through: String,
+        source_key: String,
+        target_key: String,
+    },
+}
+
+/// # Name: ModelAssociation
+/// ## Description
+/// An association of a model generated from a table, read from the schema's foreign keys.
+/// It becomes an `<name>()` method and an eager-loading `include_<name>()` function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelAssociation {
+    pub name: String,
+    pub kind: AssociationKind,
+    /// The table of the associated rows.
+    pub table: String,
+    /// The module under `src/models/` of the associated model, e.g. `user`.
+    pub model: String,
+}
+
+/// The foreign keys of `table` when it is a join table: exactly two foreign keys and no columns
+/// besides them, `id`, `created_at` and `updated_at`.
+fn join_table<'a>(schema: &'a SchemaInfo, table: &str) -> Option<Vec<&'a ForeignKeyInfo>> {
+    let info = schema.table(table)?;
+    let foreign_keys = schema.foreign_keys_from(table);
+    let only_keys = info.columns.iter().all(|c| {
+        foreign_keys.iter().any(|fk| fk.column == c.name)
+            || ["id", "created_at", "updated_at"].contains(&c.name.as_str())
+    });
+    (foreign_keys.len() == 2 && only_keys).then_some(foreign_keys)
+}
+
+/// # Name: TableModel
+/// ## Description
+/// An sqlx model generated from a live table by `rustyroad generate model <name> --from-table <table>`.
+/// Unlike a scaffold model it keeps the table's own primary key, column names and types.
+/// `with_associations` adds the relationships the schema's foreign keys describe.
+/// ## Example
+/// ```
+/// use rustyroad::database::{ColumnInfo, DatabaseType, TableInfo};
+/// use rustyroad::writers::TableModel;
+///
+/// let table = TableInfo {
+///     name: "people".to_string(),
+///     columns: vec![ColumnInfo {
+///         name: "id".to_string(),
+///         data_type: "integer".to_string(),
+///         nullable: false,
+///         default: None,
+///         primary_key: true,
+///     }],
+/// };
+/// let model = TableModel::from_table("person", &table, DatabaseType::Postgres).unwrap();
+/// assert_eq!(model.struct_name, "Person");
+/// assert!(model.render().contains("FROM people"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableModel {
+    pub name: String,
+    pub struct_name: String,
+    pub table_name: String,
+    pub columns: Vec<ModelColumn>,
+    pub associations: Vec<ModelAssociation>,
+    pub database_type: DatabaseType,
+}
+
+impl TableModel {
+    /// # Name: from_table
+    /// ## Description
+    /// Maps every column of `table` to a struct field. Fails when a column type has no Rust
+    /// equivalent or the table does not have a single-column primary key.
+    pub fn from_table(
+        name: &str,
+        table: &TableInfo,
+        database_type: DatabaseType,
+    ) -> Result<Self, Error> {
+        let name = to_snake_case(name);
+        if name.is_empty() || RUST_KEYWORDS.contains(&name.as_str()) {
+            return Err(eyre!("Invalid model name '{}'", name));
+        }
+        if database_type == DatabaseType::Mongo {
+            return Err(eyre!(
+                "Models from tables are not supported for MongoDB projects"
+            ));
+        }
+        if table.primary_key().len() != 1 {
+            return Err(eyre!(
+                "Table '{}' needs a single-column primary key to generate find, update and delete",
+                table.name
+            ));
+        }
+
+        let mut columns: Vec<ModelColumn> = Vec::new();
+        let mut unsupported = Vec::new();
+        for column in &table.columns {
+            let Some(rust_type) = rust_type_for_column(&column.data_type, &database_type) else {
+                unsupported.push(format!("{} ({})", column.name, column.data_type));
+                continue;
+            };
+            let mut field = to_snake_case(&column.name);
+            if field.is_empty() || field.starts_with(|c: char| c.is_ascii_digit()) {
+                field = format!("column_{}", field);
+            }
+            if RUST_KEYWORDS.contains(&field.as_str()) {
+                field.push('_');
+            }
+            if columns.iter().any(|c| c.field == field) {
+                return Err(eyre!(
+                    "Columns '{}' and '{}' both map to the field '{}'",
+                    columns.iter().find(|c| c.field == field).unwrap().column,
+                    column.name,
+                    field
+                ));
+            }
+            let integer = matches!(rust_type, "i16" | "i32" | "i64");
+            let max_length = (rust_type == "String")
+                .then(|| column.data_type.split_once('('))
+                .flatten()
+                .and_then(|(_, size)| size.trim_end_matches(')').trim().parse().ok());
+            columns.push(ModelColumn {
+                column: column.name.clone(),
+                field,
+                rust_type: rust_type.to_string(),
+                nullable: column.nullable && !column.primary_key,
+                primary_key: column.primary_key,
+                // MySQL reports no default for AUTO_INCREMENT, and an identity column has none on PostgreSQL
+                generated: column.primary_key
+                    && (integer
+                        || (column.default.is_some() && database_type != DatabaseType::Mysql)),
+                max_length,
+            });
+        }
+        if !unsupported.is_empty() {
+            return Err(eyre!(
+                "Cannot map these columns of '{}' to Rust types: {}",
+                table.name,
+                unsupported.join(", ")
+            ));
+        }
+
+        Ok(TableModel {
+            struct_name: to_pascal_case(&name),
+            name,
+            table_name: table.name.clone(),
+            columns,
+            associations: Vec::new(),
+            database_type,
+        })
+    }
+
+    /// # Name: with_associations
+    /// ## Description
+    /// Adds an association for every foreign key of `schema` that refers to a primary key:
+    /// `belongs_to` for the table's own foreign keys, `has_many` for the foreign keys pointing
+    /// at it, and a many-to-many `through` association across each join table. A join table has
+    /// exactly two foreign keys and otherwise only `id`, `created_at` and `updated_at`.
+    /// When two associations would share a name, the `has_many` ones are suffixed with their
+    /// foreign key, e.g. `messages_as_sender`.
+    pub fn with_associations(mut self, schema: &SchemaInfo) -> Self {
+        let references_primary_key = |table: &str, column: &str| {
+            schema
+                .table(table)
+                .is_some_and(|t| t.primary_key() == [column])
+        };
+        let own_key = self.primary_key().column.clone();
+        let mut associations = Vec::new();
+
+        for fk in schema.foreign_keys_from(&self.table_name) {
+            let Some(column) = self.columns.iter().find(|c| c.column == fk.column) else {
+                continue;
+            };
+            if references_primary_key(&fk.referenced_table, &fk.referenced_column) {
+                associations.push(ModelAssociation {
+                    name: association_name(&column.column, &fk.referenced_table),
+                    kind: AssociationKind::BelongsTo {
+                        column: column.column.clone(),
+                    },
+                    table: fk.referenced_table.clone(),
+                    model: self.model_for_table(&fk.referenced_table),
+                });
+            }
+        }
+
+        for fk in schema.foreign_keys_to(&self.table_name) {
+            if fk.referenced_column != own_key {
+                continue;
+            }
+            let Some(join) = join_table(schema, &fk.table) else {
+                associations.push(ModelAssociation {
+                    name: to_snake_case(&fk.table),
+                    kind: AssociationKind::HasMany {
+                        foreign_key: fk.column.clone(),
+                    },
+                    table: fk.table.clone(),
+                    model: self.model_for_table(&fk.table),
+                });
+                continue;
+            };
+            for target in join.iter().filter(|other| other.column != fk.column) {
+                if references_primary_key(&target.referenced_table, &target.referenced_column) {
+                    associations.push(ModelAssociation {
+                        name: to_snake_case(&target.referenced_table),
+                        kind: AssociationKind::HasManyThrough {
+                            through: fk.table.clone(),
+                            source_key: fk.column.clone(),
+                            target_key: target.column.clone(),
+                        },
+                        table: target.referenced_table.clone(),
+                        model: self.model_for_table(&target.referenced_table),
+                    });
+                }
+            }
+        }
+
+        let names: Vec<String> = associations.iter().map(|a| a.name.clone()).collect();
+        for association in &mut associations {
+            let shared = names.iter().filter(|n| **n == association.name).count() > 1;
+            let key = match &association.kind {
+                AssociationKind::BelongsTo { .. } => continue,
+                AssociationKind::HasMany { foreign_key } => foreign_key,
+                AssociationKind::HasManyThrough { source_key, .. } => source_key,
+            };
+            if shared {
+                association.name = format!(
+                    "{}_as_{}",
+                    association.name,
+                    association_name(key, &self.table_name)
+                );
+            }
+        }
+        self.associations = associations;
+        self
+    }
+
+    /// The model module of `table`; the model itself for a self-referencing foreign key.
+    fn model_for_table(&self, table: &str) -> String {
+        if table == self.table_name {
+            self.name.clone()
+        } else {
+            model_name_for_table(table)
+        }
+    }
+
+    /// Whether the model can implement `rustyroad::model::Model`, which needs every field to
+    /// decode on every backend. sqlx cannot decode `rust_decimal::Decimal` from SQLite.
+    pub fn implements_model(&self) -> bool {
+        !self
+            .columns
+            .iter()
+            .any(|c| c.rust_type.starts_with("rust_decimal::"))
+    }
+
+    /// The `Model` behaviours the table has columns for: `timestamps` for `created_at` and
+    /// `updated_at`, `soft_delete` for a nullable `deleted_at` and `lock_version` for an integer
+    /// `lock_version`.
+    fn behaviours(&self) -> Vec<&'static str> {
+        let column = |name: &str| self.columns.iter().find(|c| c.column == name);
+        let mut behaviours = Vec::new();
+        if column("created_at").is_some() && column("updated_at").is_some() {
+            behaviours.push("timestamps");
+        }
+        if column("deleted_at").is_some_and(|c| c.nullable) {
+            behaviours.push("soft_delete");
+        }
+        if column("lock_version").is_some_and(|c| matches!(c.rust_type.as_str(), "i32" | "i64")) {
+            behaviours.push("lock_version");
+        }
+        behaviours
+    }
+
+    fn primary_key(&self) -> &ModelColumn {
+        self.columns
+            .iter()
+            .find(|c| c.primary_key)
+            .expect("from_table checks for a primary key")
+    }
+
+    fn placeholder(&self, position: usize) -> String {
+        match self.database_type {
+            DatabaseType::Postgres => format!("${}", position),
+            _ => "?".to_string(),
+        }
+    }
+
+    /// Quotes `name` for SQL when it is not a plain lowercase identifier or is a reserved word.
+    fn quote(&self, name: &str) -> String {
+        let plain = name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && !SQL_RESERVED.contains(&name);
+        match self.database_type {
+            _ if plain => name.to_string(),
+            DatabaseType::Mysql => format!("`{}`", name),
+            _ => format!("\"{}\"", name),
+        }
+    }
+
+    /// The types used by the model that come from crates a new project does not depend on yet.
+    pub fn extra_dependencies(&self) -> Vec<&'static str> {
+        let mut dependencies = Vec::new();
+        if self
+            .columns
+            .iter()
+            .any(|c| c.rust_type.starts_with("uuid::"))
+        {
+            dependencies.push(r#"uuid = { version = "1", features = ["serde"] }"#);
+        }
+        if self
+            .columns
+            .iter()
+            .any(|c| c.rust_type.starts_with("rust_decimal::"))
+        {
+            dependencies.push(r#"rust_decimal = "1" (and the "rust_decimal" feature of sqlx)"#);
+        }
+        dependencies
+    }
+
+    /// # Name: render
+    /// ## Description
+    /// Renders `src/models/<name>.rs`: a `#[derive(Model)]` struct with `all`, `find`, `insert`,
+    /// `update` and `delete`, and a method plus an `include_` function per association. They go
+    /// through `Model`, so they run the model's hooks, check the `#[validate(..)]` rules the
+    /// columns call for and honour the `soft_delete`, `lock_version` and `timestamps` behaviours
+    /// the table has columns for. A model that cannot implement `Model` (see `implements_model`)
+    /// uses plain sqlx written for the project's database, derives `sqlx::FromRow` instead and
+    /// has no associations, rules or behaviours.
+    pub fn render(&self) -> String {
+        let struct_name = &self.struct_name;
+        let implements_model = self.implements_model();
+        let associations: &[ModelAssociation] = if implements_model {
+            &self.associations
+        } else {
+            &[]
+        };
+
+        let mut out = String::new();
+        if implements_model {
+            out.push_str("use rustyroad::database::{Database, DatabaseConnection};\n");
+            let mut items = vec!["Model"];
+            if !associations.is_empty() {
+                items.insert(0, "associations");
+            }
+            // `update` is the only method that names `ModelError`
+            if self.columns.iter().any(|c| !c.primary_key) {
+                items.push("ModelError");
+            }
+            out.push_str(&format!(
+                "use rustyroad::model::{{{}}};\n",
+                items.join(", ")
+            ));
+        } else {
+            out.push_str("use rustyroad::database::{Database, PoolConnection};\n");
+        }
+        out.push_str("use rustyroad::web::AppError;\n");
+        out.push_str("use serde::{Deserialize, Serialize};\n\n");
+        out.push_str(&format!(
+            "/// # Name: {struct_name}\n/// ### Description: A row of the `{}` table.\n/// Generated by RustyRoad; edit freely.\n",
+            self.table_name
+        ));
+        if implements_model {
+            out.push_str("#[derive(Debug, Clone, Serialize, Deserialize, Model)]\n");
+            let mut attributes = vec![format!("table = {:?}", self.table_name)];
+            attributes.extend(self.behaviours().into_iter().map(String::from));
+            out.push_str(&format!("#[model({})]\n", attributes.join(", ")));
+        } else {
+            out.push_str("#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]\n");
+        }
+        out.push_str(&format!("pub struct {struct_name} {{\n"));
+        for column in &self.columns {
+            if column.field != column.column {
+                out.push_str(&format!("    #[serde(rename = {:?})]\n", column.column));
+                if implements_model {
+                    out.push_str(&format!("    #[model(column = {:?})]\n", column.column));
+                } else {
+                    out.push_str(&format!("    #[sqlx(rename = {:?})]\n", column.column));
+                }
+            }
+            if implements_model && column.primary_key && column.column != "id" {
+                out.push_str("    #[model(primary_key)]\n");
+            }
+            let validations = column.validations();
+            if implements_model && !validations.is_empty() {
+                out.push_str(&format!("    #[validate({})]\n", validations.join(", ")));
+            }
+            out.push_str(&format!(
+                "    pub {}: {},\n",
+                column.field,
+                column.field_type()
+            ));
+        }
+        out.push_str("}\n\n");
+
+        out.push_str(&format!("impl {struct_name} {{\n"));
+        if implements_model {
+            out.push_str(CONNECTION_FN);
+        } else {
+            out.push_str(&render_pool_fn(&self.database_type));
+        }
+        out.push_str(&format!(
+            r#"
+    fn not_found() -> AppError {{
+        AppError::NotFound("{title} not found".to_string())
+    }}
+"#,
+            title = humanize(&self.name),
+        ));
+
+        if implements_model {
+            out.push_str(&self.render_model_methods());
+        } else {
+            out.push_str(&self.render_sqlx_methods());
+        }
+        for association in associations {
+            out.push_str(&self.render_association(association));
+        }
+        out.push_str("}\n");
+
+        out
+    }
+
+    /// `all`, `find`, `insert`, `update` and `delete` of a model that implements `Model`.
+    fn render_model_methods(&self) -> String {
+        let struct_name = &self.struct_name;
+        let pk = self.primary_key();
+        let pk_field = &pk.field;
+        let pk_type = &pk.rust_type;
+        let mut out = String::new();
+        // `Model` reads skip soft-deleted rows, and its writes run the `Callbacks` hooks, check
+        // the `Validate` rules and `lock_version` in a transaction
+        out.push_str(&format!(
+            r#"
+    /// Returns every row ordered by `{pk_column_name}`.
+    pub async fn all() -> Result<Vec<{struct_name}>, AppError> {{
+        let conn = Self::connection().await?;
+        Ok(<Self as Model>::all(&conn).await?)
+    }}
+
+    /// Returns the row with the given `{pk_column_name}`, or `AppError::NotFound`.
+    pub async fn find({pk_field}: {pk_type}) -> Result<{struct_name}, AppError> {{
+        let conn = Self::connection().await?;
+        match <Self as Model>::find(&conn, {pk_field}).await {{
+            Err(sqlx::Error::RowNotFound) => Err(Self::not_found()),
+            result => Ok(result?),
+        }}
+    }}
+
+    /// Inserts this row and returns it as stored, including values the database assigned, or
+    /// `AppError::Validation` when it breaks a rule.
+    pub async fn insert(&self) -> Result<{struct_name}, AppError> {{
+        Ok(Model::insert(self, &Self::connection().await?).await?)
+    }}
+"#,
+            pk_column_name = pk.column,
+        ));
+        if self.columns.iter().any(|c| !c.primary_key) {
+            out.push_str(&format!(
+                r#"
+    /// Saves this row over the stored row with the same `{pk_column_name}` and returns it,
+    /// `AppError::Validation` when it breaks a rule, or `AppError::NotFound`.
+    pub async fn update(&self) -> Result<{struct_name}, AppError> {{
+        match Model::update(self, &Self::connection().await?).await {{
+            Err(ModelError::Database(sqlx::Error::RowNotFound)) => Err(Self::not_found()),
+            result => Ok(result?),
+        }}
+    }}
+"#,
+                pk_column_name = pk.column,
+            ));
+        }
+        out.push_str(&format!(
+            r#"
+    /// Deletes the row with the given `{pk_column_name}` and returns the number of deleted rows,
+    /// 0 when there is no such row.
+    pub async fn delete({pk_field}: {pk_type}) -> Result<u64, AppError> {{
+        let conn = Self::connection().await?;
+        match <Self as Model>::find(&conn, {pk_field}).await {{
+            Ok(record) => Ok(Model::delete(&record, &conn).await?),
+            Err(sqlx::Error::RowNotFound) => Ok(0),
+            Err(e) => Err(e.into()),
+        }}
+    }}
+"#,
+            pk_column_name = pk.column,
+        ));
+        out
+    }
+
+    /// `all`, `find`, `insert`, `update` and `delete` in plain sqlx, for a model that cannot
+    /// implement `Model`.
+    fn render_sqlx_methods(&self) -> String {
+        let struct_name = &self.struct_name;
+        let table = self.quote(&self.table_name);
+        let pk = self.primary_key();
+        let pk_column = self.quote(&pk.column);
+        let pk_field = &pk.field;
+        let pk_type = &pk.rust_type;
+        let returning = self.database_type != DatabaseType::Mysql;
+        let mut out = String::new();
+        let select_all = format!("SELECT * FROM {} ORDER BY {}", table, pk_column);
+        let select_one = format!(
+            "SELECT * FROM {} WHERE {} = {}",
+            table,
+            pk_column,
+            self.placeholder(1)
+        );
+        out.push_str(&format!(
+            r#"
+    /// Returns every row ordered by `{pk_column_name}`.
+    pub async fn all() -> Result<Vec<{struct_name}>, AppError> {{
+        let pool = Self::pool().await?;
+        Ok(sqlx::query_as({select_all:?}).fetch_all(&pool).await?)
+    }}
+
+    /// Returns the row with the given `{pk_column_name}`, or `AppError::NotFound`.
+    pub async fn find({pk_field}: {pk_type}) -> Result<{struct_name}, AppError> {{
+        let pool = Self::pool().await?;
+        match sqlx::query_as({select_one:?})
+            .bind({pk_field})
+            .fetch_one(&pool)
+            .await
+        {{
+            Err(sqlx::Error::RowNotFound) => Err(Self::not_found()),
+            result => Ok(result?),
+        }}
+    }}
+"#,
+            pk_column_name = pk.column,
+        ));
+
+        // Values the database assigns, such as an auto-increment id, are left out of the INSERT
+        let insertable: Vec<&ModelColumn> = self.columns.iter().filter(|c| !c.generated).collect();
+        let mut insert_sql = if insertable.is_empty() && self.database_type == DatabaseType::Mysql {
+            format!("INSERT INTO {} () VALUES ()", table)
+        } else if insertable.is_empty() {
+            format!("INSERT INTO {} DEFAULT VALUES", table)
+        } else {
+            format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                table,
+                insertable
+                    .iter()
+                    .map(|c| self.quote(&c.column))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                (1..=insertable.len())
+                    .map(|i| self.placeholder(i))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        let insert_binds: String = insertable
+            .iter()
+            .map(|c| format!("\n            .bind(&self.{})", c.field))
+            .collect();
+        let pk_value = if pk.is_copy() {
+            format!("self.{}", pk_field)
+        } else {
+            format!("self.{}.clone()", pk_field)
+        };
+
+        if returning {
+            insert_sql.push_str(" RETURNING *");
+            out.push_str(&format!(
+                r#"
+    /// Inserts this row and returns it as stored, including values the database assigned.
+    pub async fn insert(&self) -> Result<{struct_name}, AppError> {{
+        let pool = Self::pool().await?;
+        Ok(sqlx::query_as({insert_sql:?}){insert_binds}
+            .fetch_one(&pool)
+            .await?)
+    }}
+"#
+            ));
+        } else {
+            // MySQL has no RETURNING, so read the row back after writing it
+            let find_inserted = if pk.generated {
+                format!("Self::find(result.last_insert_id() as {})", pk_type)
+            } else {
+                format!("Self::find({})", pk_value)
+            };
+            let result = if pk.generated { "result" } else { "_result" };
+            out.push_str(&format!(
+                r#"
+    /// Inserts this row and returns it as stored, including values the database assigned.
+    pub async fn insert(&self) -> Result<{struct_name}, AppError> {{
+        let pool = Self::pool().await?;
+        let {result} = sqlx::query({insert_sql:?}){insert_binds}
+            .execute(&pool)
+            .await?;
+        {find_inserted}.await
+    }}
+"#
+            ));
+        }
+
+        let updatable: Vec<&ModelColumn> = self.columns.iter().filter(|c| !c.primary_key).collect();
+        if !updatable.is_empty() {
+            let mut update_sql = format!(
+                "UPDATE {} SET {} WHERE {} = {}",
+                table,
+                updatable
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| format!(
+                        "{} = {}",
+                        self.quote(&c.column),
+                        self.placeholder(i + 1)
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                pk_column,
+                self.placeholder(updatable.len() + 1)
+            );
+            let update_binds: String = updatable
+                .iter()
+                .chain(std::iter::once(&pk))
+                .map(|c| format!("\n            .bind(&self.{})", c.field))
+                .collect();
+            if returning {
+                update_sql.push_str(" RETURNING *");
+                out.push_str(&format!(
+                    r#"
+    /// Saves this row over the stored row with the same `{pk_column_name}` and returns it, or
+    /// `AppError::NotFound`.
+    pub async fn update(&self) -> Result<{struct_name}, AppError> {{
+        let pool = Self::pool().await?;
+        match sqlx::query_as({update_sql:?}){update_binds}
+            .fetch_one(&pool)
+            .await
+        {{
+            Err(sqlx::Error::RowNotFound) => Err(Self::not_found()),
+            result => Ok(result?),
+        }}
+    }}
+"#,
+                    pk_column_name = pk.column,
+                ));
+            } else {
+                out.push_str(&format!(
+                    r#"
+    /// Saves this row over the stored row with the same `{pk_column_name}` and returns it, or
+    /// `AppError::NotFound`.
+    pub async fn update(&self) -> Result<{struct_name}, AppError> {{
+        let pool = Self::pool().await?;
+        sqlx::query({update_sql:?}){update_binds}
+            .execute(&pool)
+            .await?;
+        Self::find({pk_value}).await
+    }}
+"#,
+                    pk_column_name = pk.column,
+                ));
+            }
+        }
+
+        let delete_sql = format!(
+            "DELETE FROM {} WHERE {} = {}",
+            table,
+            pk_column,
+            self.placeholder(1)
+        );
+        out.push_str(&format!(
+            r#"
+    /// Deletes the row with the given `{pk_column_name}` and returns the number of deleted rows.
+    pub async fn delete({pk_field}: {pk_type}) -> Result<u64, AppError> {{
+        let pool = Self::pool().await?;
+        let result = sqlx::query({delete_sql:?})
+            .bind({pk_field})
+            .execute(&pool)
+            .await?;
+        Ok(result.rows_affected())
+    }}
+"#,
+            pk_column_name = pk.column,
+        ));
+        out
+    }
+
+    /// The `<name>()` method and `include_<name>()` function of one association.
+    fn render_association(&self, association: &ModelAssociation) -> String {
+        let struct_name = &self.struct_name;
+        let name = &association.name;
+        let table = &association.table;
+        let title = humanize(&association.model);
+        let target = if association.model == self.name {
+            "Self".to_string()
+        } else {
+            format!(
+                "crate::models::{}::{}",
+                association.model,
+                to_pascal_case(&association.model)
+            )
+        };
+        match &association.kind {
+            AssociationKind::BelongsTo { column } => {
+                let column_info = self
+                    .columns
+                    .iter()
+                    .find(|c| c.column == *column)
+                    .expect("belongs_to columns come from the model's columns");
+                let key = if column_info.is_copy() {
+                    format!("self.{}.into()", column_info.field)
+                } else {
+                    format!("self.{}.clone().into()", column_info.field)
+                };
+                if column_info.nullable {
+                    format!(
+                        r#"
+    /// Returns the `{table}` row `{column}` refers to, if it is set.
+    pub async fn {name}(&self) -> Result<Option<{target}>, AppError> {{
+        let conn = Self::connection().await?;
+        Ok(associations::belongs_to(&conn, {key}).await?)
+    }}
+
+    /// Pairs each of `records` with its `{name}`, loading them all with one query.
+    pub async fn include_{name}(
+        records: Vec<{struct_name}>,
+    ) -> Result<Vec<({struct_name}, Option<{target}>)>, AppError> {{
+        let conn = Self::connection().await?;
+        Ok(associations::preload_belongs_to(&conn, records, {column:?}).await?)
+    }}
+"#
+                    )
+                } else {
+                    format!(
+                        r#"
+    /// Returns the `{table}` row `{column}` refers to, or `AppError::NotFound`.
+    pub async fn {name}(&self) -> Result<{target}, AppError> {{
+        let conn = Self::connection().await?;
+        associations::belongs_to(&conn, {key})
+            .await?
+            .ok_or_else(|| AppError::NotFound("{title} not found".to_string()))
+    }}
+
+    /// Pairs each of `records` with its `{name}`, loading them all with one query.
+    pub async fn include_{name}(
+        records: Vec<{struct_name}>,
+    ) -> Result<Vec<({struct_name}, {target})>, AppError> {{
+        let conn = Self::connection().await?;
+        associations::preload_belongs_to(&conn, records, {column:?})
+            .await?
+            .into_iter()
+            .map(|(record, parent)| {{
+                parent
+                    .map(|parent| (record, parent))
+                    .ok_or_else(|| AppError::NotFound("{title} not found".to_string()))
+            }})
+            .collect()
+    }}
+"#
+                    )
+                }
+            }
+            AssociationKind::HasMany { foreign_key } => format!(
+                r#"
+    /// Returns the `{table}` rows whose `{foreign_key}` refers to this row.
+    pub async fn {name}(&self) -> Result<Vec<{target}>, AppError> {{
+        let conn = Self::connection().await?;
+        Ok(associations::has_many(&conn, {foreign_key:?}, self.primary_key()).await?)
+    }}
+
+    /// Pairs each of `records` with its `{name}`, loading them all with one query.
+    pub async fn include_{name}(
+        records: Vec<{struct_name}>,
+    ) -> Result<Vec<({struct_name}, Vec<{target}>)>, AppError> {{
+        let conn = Self::connection().await?;
+        Ok(associations::preload_has_many(&conn, records, {foreign_key:?}).await?)
+    }}
+"#
+            ),
+            AssociationKind::HasManyThrough {
+                through,
+                source_key,
+                target_key,
+            } => {
+                let pk_type = &self.primary_key().rust_type;
+                format!(
+                    r#"
+    /// Returns the `{table}` rows the `{through}` table links to this row.
+    pub async fn {name}(&self) -> Result<Vec<{target}>, AppError> {{
+        let conn = Self::connection().await?;
+        Ok(associations::has_many_through(
+            &conn,
+            {through:?},
+            {source_key:?},
+            {target_key:?},
+            self.primary_key(),
+        )
+        .await?)
+    }}
+
+    /// Pairs each of `records` with its `{name}`, loading them all with one query.
+    pub async fn include_{name}(
+        records: Vec<{struct_name}>,
+    ) -> Result<Vec<({struct_name}, Vec<{target}>)>, AppError> {{
+        let conn = Self::connection().await?;
+        Ok(associations::preload_has_many_through::<_, _, {pk_type}>(
+            &conn,
+            records,
+            {through:?},
+            {source_key:?},
+            {target_key:?},
+        )
+        .await?)
+    }}
+"#
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::ColumnInfo;
+
+    fn column(name: &str, data_type: &str, nullable: bool, primary_key: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable,
+            default: None,
+            primary_key,
+        }
+    }
+
+    fn orders_table(database_type: &DatabaseType) -> TableInfo {
+        let (uuid, money, stamp, json) = match database_type {
+            DatabaseType::Postgres => ("uuid", "numeric", "timestamp with time zone", "jsonb"),
+            DatabaseType::Mysql => ("char", "decimal", "timestamp", "json"),
+            _ => ("TEXT", "REAL", "DATETIME", "JSON"),
+        };
+        TableInfo {
+            name: "orders".to_string(),
+            columns: vec![
+                column("id", "integer", false, true),
+                column("reference", uuid, false, false),
+                column("total", money, false, false),
+                column("placedAt", stamp, true, false),
+                column("type", "text", false, false),
+                column("details", json, true, false),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_rust_type_for_column() {
+        let pg = DatabaseType::Postgres;
+        assert_eq!(
+            rust_type_for_column("character varying", &pg),
+            Some("String")
+        );
+        assert_eq!(rust_type_for_column("bigint", &pg), Some("i64"));
+        assert_eq!(rust_type_for_column("uuid", &pg), Some("uuid::Uuid"));
+        assert_eq!(
+            rust_type_for_column("numeric", &pg),
+            Some("rust_decimal::Decimal")
+        );
+        assert_eq!(rust_type_for_column("USER-DEFINED", &pg), None);
+        assert_eq!(rust_type_for_column("ARRAY", &pg), None);
+
+        let mysql = DatabaseType::Mysql;
+        assert_eq!(rust_type_for_column("tinyint(1)", &mysql), Some("bool"));
+        assert_eq!(rust_type_for_column("tinyint(4)", &mysql), Some("i8"));
+        assert_eq!(rust_type_for_column("tinyint", &mysql), Some("i8"));
+        assert_eq!(
+            rust_type_for_column("int(11) unsigned", &mysql),
+            Some("i32")
+        );
+        assert_eq!(
+            rust_type_for_column("datetime", &mysql),
+            Some("chrono::NaiveDateTime")
+        );
+        assert_eq!(rust_type_for_column("longblob", &mysql), Some("Vec<u8>"));
+
+        let sqlite = DatabaseType::Sqlite;
+        assert_eq!(rust_type_for_column("INTEGER", &sqlite), Some("i32"));
+        assert_eq!(
+            rust_type_for_column("NVARCHAR(40)", &sqlite),
+            Some("String")
+        );
+        assert_eq!(
+            rust_type_for_column("DOUBLE PRECISION", &sqlite),
+            Some("f64")
+        );
+        assert_eq!(rust_type_for_column("", &sqlite), None);
+    }
+
+    #[test]
+    fn test_from_table_maps_fields() {
+        let model = TableModel::from_table(
+            "Order",
+            &orders_table(&DatabaseType::Postgres),
+            DatabaseType::Postgres,
+        )
+        .unwrap();
+        let source = model.render();
+        assert!(syn::parse_file(&source).is_ok());
+        assert!(source.contains("#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]"));
+        assert!(source.contains("pub reference: uuid::Uuid,"));
+        assert!(source.contains("pub total: rust_decimal::Decimal,"));
+        assert!(source.contains(
+            "    #[sqlx(rename = \"placedAt\")]\n    pub placed_at: Option<chrono::DateTime<chrono::Utc>>,"
+        ));
+        assert!(source.contains("    #[sqlx(rename = \"type\")]\n    pub type_: String,"));
+        assert!(source.contains("pub details: Option<serde_json::Value>,"));
+        assert_eq!(model.extra_dependencies().len(), 2);
+
+        assert!(!source.contains("#[validate("));
+        assert!(source.contains("pub async fn insert(&self) -> Result<Order, AppError>"));
+
+        let mut coded = orders_table(&DatabaseType::Sqlite);
+        coded
+            .columns
+            .push(column("code", "VARCHAR(12)", true, false));
+        let coded = TableModel::from_table("order", &coded, DatabaseType::Sqlite).unwrap();
+        let validations: Vec<Vec<String>> = coded.columns.iter().map(|c| c.validations()).collect();
+        assert_eq!(validations[0], Vec::<String>::new());
+        assert_eq!(validations[1], ["presence"]);
+        assert_eq!(validations[6], ["length(max = 12)"]);
+
+        let mut user = orders_table(&DatabaseType::Mysql);
+        user.name = "user".to_string();
+        let source = TableModel::from_table("user", &user, DatabaseType::Mysql)
+            .unwrap()
+            .render();
+        assert!(source.contains("SELECT * FROM `user` WHERE id = ?"));
+
+        let mut no_key = orders_table(&DatabaseType::Postgres);
+        no_key.columns[0].primary_key = false;
+        assert!(TableModel::from_table("order", &no_key, DatabaseType::Postgres).is_err());
+
+        let mut unsupported = orders_table(&DatabaseType::Postgres);
+        unsupported
+            .columns
+            .push(column("status", "USER-DEFINED", false, false));
+        let error = TableModel::from_table("order", &unsupported, DatabaseType::Postgres)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("status (USER-DEFINED)"));
+    }
+
+    #[test]
+    fn test_from_table_model_behaviours() {
+        let mut gadgets = TableInfo {
+            name: "gadgets".to_string(),
+            columns: vec![
+                column("id", "integer", false, true),
+                column("name", "text", false, false),
+                column("created_at", "timestamp", false, false),
+                column("updated_at", "timestamp", false, false),
+                column("deleted_at", "timestamp", true, false),
+                column("lock_version", "integer", false, false),
+            ],
+        };
+        let source = TableModel::from_table("gadget", &gadgets, DatabaseType::Postgres)
+            .unwrap()
+            .render();
+        assert!(syn::parse_file(&source).is_ok());
+        assert!(
+            source.contains("#[model(table = \"gadgets\", timestamps, soft_delete, lock_version)]")
+        );
+        assert!(source.contains("Ok(<Self as Model>::all(&conn).await?)"));
+        assert!(source.contains("match <Self as Model>::find(&conn, id).await {"));
+        assert!(source.contains("match Model::update(self, &Self::connection().await?).await {"));
+        assert!(source.contains("Ok(record) => Ok(Model::delete(&record, &conn).await?),"));
+        assert!(!source.contains("sqlx::query"));
+
+        // A NOT NULL `deleted_at` cannot be cleared by `restore`, so it is left alone
+        gadgets.columns[4].nullable = false;
+        gadgets.columns.remove(5);
+        let source = TableModel::from_table("gadget", &gadgets, DatabaseType::Postgres)
+            .unwrap()
+            .render();
+        assert!(source.contains("#[model(table = \"gadgets\", timestamps)]"));
+    }
+
+    fn blog_schema() -> SchemaInfo {
+        let fk = |table: &str, column: &str, referenced_table: &str| ForeignKeyInfo {
+            table: table.to_string(),
+            column: column.to_string(),
+            referenced_table: referenced_table.to_string(),
+            referenced_column: "id".to_string(),
+        };
+        let table = |name: &str, columns: Vec<ColumnInfo>| TableInfo {
+            name: name.to_string(),
+            columns,
+        };
+        SchemaInfo {
+            tables: vec![
+                table(
+                    "users",
+                    vec![
+                        column("id", "integer", false, true),
+                        column("name", "text", false, false),
+                    ],
+                ),
+                table(
+                    "posts",
+                    vec![
+                        column("id", "integer", false, true),
+                        column("author_id", "integer", false, false),
+                        column("editor_id", "integer", true, false),
+                        column("title", "text", false, false),
+                    ],
+                ),
+                table(
+                    "tags",
+                    vec![
+                        column("id", "bigint", false, true),
+                        column("label", "text", false, false),
+                    ],
+                ),
+                table(
+                    "post_tags",
+                    vec![
+                        column("post_id", "integer", false, false),
+                        column("tag_id", "bigint", false, false),
+                        column("created_at", "timestamp", false, false),
+                    ],
+                ),
+            ],
+            foreign_keys: vec![
+                fk("posts", "author_id", "users"),
+                fk("posts", "editor_id", "users"),
+                fk("post_tags", "post_id", "posts"),
+                fk("post_tags", "tag_id", "tags"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_associations_from_foreign_keys() {
+        let schema = blog_schema();
+        let model = |name: &str, table: &str| {
+            TableModel::from_table(name, schema.table(table).unwrap(), DatabaseType::Postgres)
+                .unwrap()
+                .with_associations(&schema)
+        };
+
+        let post = model("post", "posts");
+        let names: Vec<&str> = post.associations.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["author", "editor", "tags"]);
+        assert_eq!(
+            post.associations[2].kind,
+            AssociationKind::HasManyThrough {
+                through: "post_tags".to_string(),
+                source_key: "post_id".to_string(),
+                target_key: "tag_id".to_string(),
+            }
+        );
+        let source = post.render();
+        assert!(syn::parse_file(&source).is_ok());
+        assert!(source.contains(
+            "#[derive(Debug, Clone, Serialize, Deserialize, Model)]\n#[model(table = \"posts\")]"
+        ));
+        assert!(source.contains("use rustyroad::model::{associations, Model, ModelError};"));
+        assert!(source.contains("    #[validate(presence)]\n    pub title: String,"));
+        assert!(source.contains(
+            "pub async fn insert(&self) -> Result<Post, AppError> {\n        Ok(Model::insert(self, &Self::connection().await?).await?)"
+        ));
+        assert!(source
+            .contains("pub async fn author(&self) -> Result<crate::models::user::User, AppError>"));
+        assert!(source.contains(
+            "pub async fn editor(&self) -> Result<Option<crate::models::user::User>, AppError>"
+        ));
+        assert!(source.contains("pub async fn include_tags("));
+        assert!(source.contains("preload_has_many_through::<_, _, i32>("));
+
+        let user = model("user", "users");
+        let names: Vec<&str> = user.associations.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["posts_as_author", "posts_as_editor"]);
+        assert!(user
+            .render()
+            .contains("associations::has_many(&conn, \"author_id\", self.primary_key())"));
+
+        let tag = model("tag", "tags");
+        assert_eq!(tag.associations[0].name, "posts");
+        assert_eq!(tag.associations[0].model, "post");
+    }
+
+    #[test]
+    fn test_render_per_dialect() {
+        let pg = TableModel::from_table(
+            "order",
+            &orders_table(&DatabaseType::Postgres),
+            DatabaseType::Postgres,
+        )
+        .unwrap()
+        .render();
+        assert!(pg.contains(
+            r#"INSERT INTO orders (reference, total, \"placedAt\", type, details) VALUES ($1, $2, $3, $4, $5) RETURNING *"#
+        ));
+        assert!(pg.contains(r#"WHERE id = $6 RETURNING *"#));
+        assert!(pg.contains("PoolConnection::Pg(pool)"));
+
+        let mysql = TableModel::from_table(
+            "order",
+            &orders_table(&DatabaseType::Mysql),
+            DatabaseType::Mysql,
+        )
+        .unwrap()
+        .render();
+        assert!(syn::parse_file(&mysql).is_ok());
+        assert!(
+            mysql.contains("(reference, total, `placedAt`, type, details) VALUES (?, ?, ?, ?, ?)")
+        );
+        assert!(mysql.contains("Self::find(result.last_insert_id() as i32).await"));
+        assert!(mysql.contains("Self::find(self.id).await"));
+        assert!(!mysql.contains("RETURNING"));
+
+        let sqlite = TableModel::from_table(
+            "order",
+            &orders_table(&DatabaseType::Sqlite),
+            DatabaseType::Sqlite,
+        )
+        .unwrap()
+        .render();
+        assert!(syn::parse_file(&sqlite).is_ok());
+        assert!(sqlite.contains("pub total: f64,"));
+        assert!(sqlite.contains("Ok(<Self as Model>::all(&conn).await?)"));
+        assert!(sqlite.contains("match <Self as Model>::find(&conn, id).await {"));
+        assert!(sqlite.contains("Ok(record) => Ok(Model::delete(&record, &conn).await?),"));
+        assert!(!sqlite.contains("sqlx::query"));
+        assert!(!sqlite.contains("fn pool()"));
+    }
+}
@@ -14,26 +14,30 @@ use std::io::Error;
 ///
 /// * `Ok(())` if the content was successfully written to the file, or an Error if something went wrong.
 pub fn write_to_cargo_toml(project: &Project, database_data: &Database) -> Result<(), Error> {
+    // Every backend gets the sqlx rustyroad uses: generated models hand its pools and errors to
+    // the app's sqlx code, and a second libsqlite3-sys would conflict with rustyroad's
     let dependencies = match database_data.database_type {
         DatabaseType::Postgres => {
             r#"[dependencies.sqlx]
 features = ["postgres", "macros", "chrono", "json", "uuid", "runtime-tokio"]
-version = "0.7.2""#
+version = "0.8.2""#
         }
         DatabaseType::Mysql => {
             r#"[dependencies.sqlx]
 features = ["mysql", "macros", "chrono", "json", "uuid", "runtime-tokio"]
-version = "0.7.2""#
+version = "0.8.2""#
         }
         DatabaseType::Sqlite => {
-            r#"[dependencies.rusqlite]
-version = "0.28.0"
-features = ["bundled"]
-libsqlite3-sys = { version = "0.25.0", features = ["bundled"] }
-"#
+            r#"[dependencies.sqlx]
+features = ["sqlite", "macros", "chrono", "json", "uuid", "runtime-tokio"]
+version = "0.8.2""#
         }
         DatabaseType::Mongo => {
-            r#"[dependencies.mongodb]
+            r#"[dependencies.sqlx]
+features = ["macros", "chrono", "json", "uuid", "runtime-tokio"]
+version = "0.8.2"
+
+[dependencies.mongodb]
 version =  "2.4.0"
 default-features = false
 features = ["sync", "bson", "tls"]"#
@@ -61,7 +65,8 @@ local-ip-address = "0.5.0"
 futures = "0.3.23"
 tera = "1.17.1"
 reqwest = "0.11"
-rustyroad = "1.0.8"
+rustyroad = "{version}"
+rustyroad-derive = "{version}"
 rand = "0.8.5"
 chrono = {{ version = "0.4.24", features = ["serde"] }}
 base64 = "0.21.0"
@@ -71,7 +76,10 @@ color-eyre = "0.6.2"
 serde_derive = "1.0.189"
 {}
 "#,
-        &project.name, dependencies
+        &project.name,
+        dependencies,
+        // Generated code uses this version's `rustyroad::web`, `model` and `database` APIs
+        version = env!("CARGO_PKG_VERSION"),
     );
 
     fs::write(&project.cargo_toml, config.as_bytes())
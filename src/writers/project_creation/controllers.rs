@@ -7,14 +7,15 @@ use std::io::Error;
 // Write to index controller
 pub fn write_to_index_controller(project: &Project) -> Result<(), Error> {
     let contents = r#"use actix_web::{get, web, HttpResponse};
+use rustyroad::web::AppError;
 use tera::{Context, Tera};
 
 #[get("/")]
-async fn index(tmpl: web::Data<Tera>) -> HttpResponse {
+async fn index(tmpl: web::Data<Tera>) -> Result<HttpResponse, AppError> {
     let mut context = Context::new();
     context.insert("foo", "123");
-    let rendered = tmpl.render("pages/index.html.tera", &context).unwrap();
-    HttpResponse::Ok().body(rendered)
+    let rendered = tmpl.render("pages/index.html.tera", &context)?;
+    Ok(HttpResponse::Ok().body(rendered))
 }"#
     .to_string();
 
@@ -60,19 +61,16 @@ pub fn write_to_dashboard_controller(project: &Project) -> Result<(), Error> {
 pub async fn dashboard_controller(
     tmpl: Data<Tera>,
     user: Option<Identity>,
-) -> Result<HttpResponse, Error> {
-    if let Some(user) = user {
-        let mut context = Context::new();
-        context.insert("username", &user.id().unwrap());
-        let rendered = tmpl.render("pages/dashboard.html.tera", &context).unwrap();
-        Ok(HttpResponse::Ok().body(rendered))
-    } else {
-        let mut context = Context::new();
-        context.insert("error", "You must be logged in to view this page.");
-        Ok(HttpResponse::Found()
-            .append_header((LOCATION, "/login"))
-            .finish())
-    }
+) -> Result<HttpResponse, AppError> {
+    // Signed out visitors are redirected to /login by the ErrorPages middleware
+    let user = user.ok_or(AppError::Unauthorized)?;
+    let username = user
+        .id()
+        .map_err(|e| AppError::Internal(format!("Failed to read the session: {}", e)))?;
+    let mut context = Context::new();
+    context.insert("username", &username);
+    let rendered = tmpl.render("pages/dashboard.html.tera", &context)?;
+    Ok(HttpResponse::Ok().body(rendered))
 }
 "#
     .to_string();
@@ -84,8 +82,7 @@ pub async fn dashboard_controller(
     import_contents = add_or_update_import(&import_contents, "tera", "Context");
     import_contents = add_or_update_import(&import_contents, "tera", "Tera");
     import_contents = add_or_update_import(&import_contents, "actix_identity", "Identity");
-    import_contents = add_or_update_import(&import_contents, "actix_web", "Error");
-    import_contents = add_or_update_import(&import_contents, "actix_web", "http::header::LOCATION");
+    import_contents = add_or_update_import(&import_contents, "rustyroad::web", "AppError");
     import_contents = add_or_update_import(&import_contents, "actix_web", "web::Data");
 
     import_contents.push_str("\n\n");
@@ -29,6 +29,7 @@ pub fn create_files(project: &Project) -> Result<(), Error> {
         &project.not_found_controller,
         &project.not_found_html,
         &project.server_error_html,
+        &project.forbidden_html,
         &project.favicon_ico,
         &project.robots_txt,
         &project.login_page_html,
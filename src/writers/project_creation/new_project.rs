@@ -53,6 +53,7 @@ pub fn new(name: String) -> Project {
     let not_found_controller = format!("{}/not_found.rs", controllers);
     let not_found_html = format!("{}/404.html.tera", template_pages);
     let server_error_html = format!("{}/500.html.tera", template_pages);
+    let forbidden_html = format!("{}/403.html.tera", template_pages);
     let favicon_ico = format!("{}/favicon.ico", static_images);
     let robots_txt = format!("{}/robots.txt", static_dir);
     let login_page_html = format!("{}/login.html.tera", template_pages);
@@ -133,6 +134,7 @@ pub fn new(name: String) -> Project {
         not_found_controller,
         not_found_html,
         server_error_html,
+        forbidden_html,
         favicon_ico,
         robots_txt,
         login_page_html,
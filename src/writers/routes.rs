@@ -0,0 +1,945 @@
+use crate::writers::{app_chains, method_chain, path_ends_with, tokens};
+use color_eyre::eyre::Result;
+use eyre::{eyre, Error};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Expr, ExprMethodCall, FnArg, GenericArgument, Item, ItemFn, Lit, PathArguments, Stmt, Token,
+    Type, UseTree,
+};
+
+/// The Actix route macros and `web::<method>()` route builders.
+const METHODS: [&str; 9] = [
+    "get", "post", "put", "delete", "patch", "head", "options", "trace", "connect",
+];
+
+/// A route the app serves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Route {
+    /// `GET`, `POST`, ... or `*` for any method.
+    pub method: String,
+    /// The full path, including the prefixes of the scopes it is mounted in.
+    pub path: String,
+    /// The handler as `module::fn`, from the crate root.
+    pub handler: String,
+    /// Whether the handler takes an `Identity` or `Option<Identity>`, so only signed-in users
+    /// reach it.
+    pub authenticated: bool,
+    /// The guards of the route and of the scopes around it.
+    pub guards: Vec<String>,
+    pub file: String,
+    pub line: usize,
+}
+
+/// A handler that is never registered, or a registration whose handler doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteProblem {
+    pub handler: String,
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RouteTable {
+    /// In registration order, which is the order Actix matches them in.
+    pub routes: Vec<Route>,
+    /// Handlers under src/controllers with a route attribute that main.rs never reaches.
+    pub unregistered: Vec<RouteProblem>,
+    /// `.service(..)`, `.to(..)` and `.configure(..)` arguments naming a function under
+    /// `controllers` that doesn't exist.
+    pub missing: Vec<RouteProblem>,
+}
+
+impl RouteTable {
+    /// Keeps what belongs to the `name` controller: handlers in a `name` module or file.
+    pub fn retain_controller(&mut self, name: &str) {
+        let name = name.trim_end_matches(".rs");
+        let belongs = |handler: &str, file: &str| {
+            let segments: Vec<&str> = handler.split("::").collect();
+            segments[..segments.len() - 1].contains(&name)
+                || Path::new(file).file_stem().is_some_and(|stem| stem == name)
+        };
+        self.routes.retain(|r| belongs(&r.handler, &r.file));
+        self.unregistered.retain(|p| belongs(&p.handler, &p.file));
+        self.missing.retain(|p| belongs(&p.handler, &p.file));
+    }
+}
+
+/// `#[get("/path")]`, or `#[route("/path", method = "GET", method = "POST")]`.
+struct RouteAttribute {
+    methods: Vec<String>,
+    path: String,
+    guards: Vec<String>,
+}
+
+struct Function {
+    item: ItemFn,
+    file: String,
+    routes: Vec<RouteAttribute>,
+    authenticated: bool,
+}
+
+impl Function {
+    fn line(&self) -> usize {
+        self.item.sig.ident.span().start().line
+    }
+}
+
+#[derive(Default)]
+struct Module {
+    functions: HashMap<String, Function>,
+    /// The child modules re-exported with `pub use <child>::*;`.
+    globs: Vec<Vec<String>>,
+}
+
+fn string_literal(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(s) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether `item` has an actix route attribute such as `#[get("/posts")]`.
+pub(crate) fn is_route_handler(item: &ItemFn) -> bool {
+    !route_attributes(item).is_empty()
+}
+
+fn route_attributes(item: &ItemFn) -> Vec<RouteAttribute> {
+    item.attrs
+        .iter()
+        .filter_map(|attr| {
+            let name = attr.path().segments.last()?.ident.to_string();
+            if name != "route" && !METHODS.contains(&name.as_str()) {
+                return None;
+            }
+            let args = attr
+                .parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                .ok()?;
+            let mut args = args.into_iter();
+            let path = string_literal(&args.next()?)?;
+            let mut methods = Vec::new();
+            if name != "route" {
+                methods.push(name.to_uppercase());
+            }
+            let mut guards = Vec::new();
+            for arg in args {
+                let Expr::Assign(assign) = arg else {
+                    continue;
+                };
+                let Some(value) = string_literal(&assign.right) else {
+                    continue;
+                };
+                match tokens(&assign.left).as_str() {
+                    "method" => methods.push(value.to_uppercase()),
+                    "guard" => guards.push(value),
+                    _ => {}
+                }
+            }
+            if methods.is_empty() {
+                methods.push("*".to_string());
+            }
+            Some(RouteAttribute {
+                methods,
+                path,
+                guards,
+            })
+        })
+        .collect()
+}
+
+fn takes_identity(item: &ItemFn) -> bool {
+    item.sig.inputs.iter().any(|input| match input {
+        FnArg::Typed(arg) => is_identity(&arg.ty),
+        FnArg::Receiver(_) => false,
+    })
+}
+
+/// `Identity`, or `Option<Identity>` as generated handlers take it before redirecting to the
+/// login page.
+fn is_identity(ty: &Type) -> bool {
+    let Type::Path(ty) = ty else {
+        return false;
+    };
+    let Some(segment) = ty.path.segments.last() else {
+        return false;
+    };
+    if segment.ident == "Identity" {
+        return true;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if segment.ident == "Option" => {
+            args.args.iter().any(|arg| match arg {
+                GenericArgument::Type(ty) => is_identity(ty),
+                _ => false,
+            })
+        }
+        _ => false,
+    }
+}
+
+fn glob_children(tree: &UseTree, mut prefix: Vec<String>, out: &mut Vec<Vec<String>>) {
+    match tree {
+        UseTree::Path(path) => {
+            if path.ident != "self" {
+                prefix.push(path.ident.to_string());
+            }
+            glob_children(&path.tree, prefix, out);
+        }
+        UseTree::Glob(_)
+            if prefix
+                .first()
+                .is_some_and(|first| first != "super" && first != "crate") =>
+        {
+            out.push(prefix);
+        }
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                glob_children(tree, prefix.clone(), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_module(file: &str, parsed: &syn::File) -> Module {
+    let mut module = Module::default();
+    for item in &parsed.items {
+        match item {
+            Item::Fn(item) => {
+                module.functions.insert(
+                    item.sig.ident.to_string(),
+                    Function {
+                        routes: route_attributes(item),
+                        authenticated: takes_identity(item),
+                        file: file.to_string(),
+                        item: item.clone(),
+                    },
+                );
+            }
+            Item::Use(item) if matches!(item.vis, syn::Visibility::Public(_)) => {
+                glob_children(&item.tree, Vec::new(), &mut module.globs);
+            }
+            _ => {}
+        }
+    }
+    module
+}
+
+/// `src/controllers/post/mod.rs` is `controllers::post`, `src/controllers/post/post.rs` is
+/// `controllers::post::post`.
+fn module_path(file: &str) -> Vec<String> {
+    let relative = file.strip_prefix("src/").unwrap_or(file);
+    let mut segments: Vec<String> = relative
+        .trim_end_matches(".rs")
+        .split('/')
+        .map(str::to_string)
+        .collect();
+    if segments.last().is_some_and(|last| last == "mod") {
+        segments.pop();
+    }
+    segments
+}
+
+fn join(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    let joined = if path.is_empty() {
+        prefix.to_string()
+    } else if path.starts_with('/') {
+        format!("{}{}", prefix, path)
+    } else {
+        format!("{}/{}", prefix, path)
+    };
+    if joined.is_empty() {
+        "/".to_string()
+    } else {
+        joined
+    }
+}
+
+/// Where registrations are being read: the scope they are mounted in and the module
+/// their paths are relative to.
+#[derive(Clone)]
+struct Scope {
+    prefix: String,
+    guards: Vec<String>,
+    module: Vec<String>,
+    file: String,
+}
+
+struct Resolved {
+    /// The path as registered, from the crate root.
+    path: Vec<String>,
+    /// The module the function is defined in, after following glob re-exports.
+    module: Vec<String>,
+    name: String,
+}
+
+struct Walker<'p> {
+    modules: &'p HashMap<Vec<String>, Module>,
+    table: RouteTable,
+    registered: HashSet<(Vec<String>, String)>,
+    configuring: Vec<(Vec<String>, String)>,
+}
+
+impl<'p> Walker<'p> {
+    fn find(&self, module: &[String], name: &str, depth: usize) -> Option<Vec<String>> {
+        let found = self.modules.get(module)?;
+        if found.functions.contains_key(name) {
+            return Some(module.to_vec());
+        }
+        if depth > 16 {
+            return None;
+        }
+        found.globs.iter().find_map(|glob| {
+            let child = [module, glob.as_slice()].concat();
+            self.find(&child, name, depth + 1)
+        })
+    }
+
+    /// Resolves `path` relative to `module`, then from the crate root. On failure, returns
+    /// the path from the crate root for reporting.
+    fn resolve(&self, module: &[String], path: &syn::Path) -> Result<Resolved, Vec<String>> {
+        let mut segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+        let mut bases = Vec::new();
+        match segments.first().map(String::as_str) {
+            Some("crate") => {
+                segments.remove(0);
+                bases.push(Vec::new());
+            }
+            Some("self") | Some("super") => {
+                let mut base = module.to_vec();
+                while let Some(first) = segments.first() {
+                    match first.as_str() {
+                        "self" => {}
+                        "super" => {
+                            base.pop();
+                        }
+                        _ => break,
+                    }
+                    segments.remove(0);
+                }
+                bases.push(base);
+            }
+            _ => {
+                bases.push(module.to_vec());
+                if !module.is_empty() {
+                    bases.push(Vec::new());
+                }
+            }
+        }
+        for base in &bases {
+            let full = [base.as_slice(), segments.as_slice()].concat();
+            let Some((name, parent)) = full.split_last() else {
+                continue;
+            };
+            if let Some(defined) = self.find(parent, name, 0) {
+                return Ok(Resolved {
+                    path: full.clone(),
+                    module: defined,
+                    name: name.clone(),
+                });
+            }
+        }
+        Err([bases[0].as_slice(), segments.as_slice()].concat())
+    }
+
+    fn function(&self, resolved: &Resolved) -> &'p Function {
+        &self.modules[&resolved.module].functions[&resolved.name]
+    }
+
+    fn report_missing(&mut self, path: Vec<String>, scope: &Scope, at: &impl Spanned) {
+        // Only the controllers are read, so anything else may well exist
+        if path.first().is_some_and(|first| first == "controllers") {
+            self.table.missing.push(RouteProblem {
+                handler: path.join("::"),
+                file: scope.file.clone(),
+                line: at.span().start().line,
+            });
+        }
+    }
+
+    fn walk_calls(&mut self, calls: &[&ExprMethodCall], scope: &Scope) {
+        // A scope's guards apply to everything in it, wherever they appear in the chain
+        let mut scope = scope.clone();
+        scope.guards.extend(
+            calls
+                .iter()
+                .filter(|call| call.method == "guard")
+                .filter_map(|call| call.args.first())
+                .map(tokens),
+        );
+        for call in calls {
+            let mut args = call.args.iter();
+            match (call.method.to_string().as_str(), args.next(), args.next()) {
+                ("service", Some(service), _) => self.service(service, &scope),
+                ("configure", Some(Expr::Path(function)), _) => {
+                    self.configure(&function.path, &scope)
+                }
+                ("route", Some(path), Some(route)) => {
+                    if let Some(path) = string_literal(path) {
+                        let path = join(&scope.prefix, &path);
+                        self.route(&path, route, &scope.guards, &scope);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn service(&mut self, service: &Expr, scope: &Scope) {
+        let (root, calls) = match service {
+            Expr::Path(path) => return self.register(&path.path, scope),
+            Expr::MethodCall(call) => method_chain(call),
+            Expr::Call(call) => (Some(call), Vec::new()),
+            _ => return,
+        };
+        let Some(root) = root else {
+            return;
+        };
+        let Some(path) = root.args.first().and_then(string_literal) else {
+            return;
+        };
+        let path = join(&scope.prefix, &path);
+        if path_ends_with(&root.func, &["scope"]) {
+            let inner = Scope {
+                prefix: path,
+                ..scope.clone()
+            };
+            self.walk_calls(&calls, &inner);
+        } else if path_ends_with(&root.func, &["resource"]) {
+            let mut guards = scope.guards.clone();
+            guards.extend(
+                calls
+                    .iter()
+                    .filter(|call| call.method == "guard")
+                    .filter_map(|call| call.args.first())
+                    .map(tokens),
+            );
+            for call in &calls {
+                match (call.method.to_string().as_str(), call.args.first()) {
+                    ("route", Some(route)) => self.route(&path, route, &guards, scope),
+                    ("to", Some(handler)) => {
+                        self.handler_route(vec!["*".to_string()], &path, handler, &guards, scope)
+                    }
+                    _ => {}
+                }
+            }
+        } else if path_ends_with(&root.func, &["Files", "new"]) {
+            self.table.routes.push(Route {
+                method: "GET".to_string(),
+                path,
+                handler: tokens(service),
+                authenticated: false,
+                guards: scope.guards.clone(),
+                file: scope.file.clone(),
+                line: service.span().start().line,
+            });
+        }
+    }
+
+    /// `.service(handler)`: one route per method of each of the handler's route attributes.
+    fn register(&mut self, path: &syn::Path, scope: &Scope) {
+        let resolved = match self.resolve(&scope.module, path) {
+            Ok(resolved) => resolved,
+            Err(full) => return self.report_missing(full, scope, path),
+        };
+        let function = self.function(&resolved);
+        if function.routes.is_empty() {
+            return self.report_missing(resolved.path, scope, path);
+        }
+        for attribute in &function.routes {
+            let mut guards = scope.guards.clone();
+            guards.extend(attribute.guards.iter().cloned());
+            for method in &attribute.methods {
+                self.table.routes.push(Route {
+                    method: method.clone(),
+                    path: join(&scope.prefix, &attribute.path),
+                    handler: resolved.path.join("::"),
+                    authenticated: function.authenticated,
+                    guards: guards.clone(),
+                    file: function.file.clone(),
+                    line: function.line(),
+                });
+            }
+        }
+        self.registered.insert((resolved.module, resolved.name));
+    }
+
+    /// `web::get().to(handler)`, `web::route().guard(..).method(..).to(handler)`.
+    fn route(&mut self, path: &str, route: &Expr, guards: &[String], scope: &Scope) {
+        let Expr::MethodCall(call) = route else {
+            return;
+        };
+        let (root, calls) = method_chain(call);
+        let method_name = |expr: &Expr| {
+            let method = tokens(expr);
+            method
+                .rsplit("::")
+                .next()
+                .unwrap_or_default()
+                .to_uppercase()
+        };
+        let mut methods = Vec::new();
+        if let Some(Expr::Path(builder)) = root.map(|root| &*root.func) {
+            let name = builder
+                .path
+                .segments
+                .last()
+                .map(|s| s.ident.to_string())
+                .unwrap_or_default();
+            if METHODS.contains(&name.as_str()) {
+                methods.push(name.to_uppercase());
+            } else if name == "method" {
+                methods.extend(root.and_then(|root| root.args.first()).map(method_name));
+            }
+        }
+        let mut guards = guards.to_vec();
+        let mut handler = None;
+        for call in calls {
+            match (call.method.to_string().as_str(), call.args.first()) {
+                ("guard", Some(guard)) => guards.push(tokens(guard)),
+                ("method", Some(method)) => methods.push(method_name(method)),
+                ("to", Some(to)) => handler = Some(to),
+                _ => {}
+            }
+        }
+        if methods.is_empty() {
+            methods.push("*".to_string());
+        }
+        if let Some(handler) = handler {
+            self.handler_route(methods, path, handler, &guards, scope);
+        }
+    }
+
+    fn handler_route(
+        &mut self,
+        methods: Vec<String>,
+        path: &str,
+        handler: &Expr,
+        guards: &[String],
+        scope: &Scope,
+    ) {
+        let (name, authenticated, file, line) = match handler {
+            Expr::Path(handler_path) => match self.resolve(&scope.module, &handler_path.path) {
+                Ok(resolved) => {
+                    let function = self.function(&resolved);
+                    let found = (
+                        resolved.path.join("::"),
+                        function.authenticated,
+                        function.file.clone(),
+                        function.line(),
+                    );
+                    self.registered.insert((resolved.module, resolved.name));
+                    found
+                }
+                Err(full) => {
+                    let found = (
+                        full.join("::"),
+                        false,
+                        scope.file.clone(),
+                        handler.span().start().line,
+                    );
+                    self.report_missing(full, scope, handler);
+                    found
+                }
+            },
+            _ => (
+                "<closure>".to_string(),
+                false,
+                scope.file.clone(),
+                handler.span().start().line,
+            ),
+        };
+        for method in methods {
+            self.table.routes.push(Route {
+                method,
+                path: path.to_string(),
+                handler: name.clone(),
+                authenticated,
+                guards: guards.to_vec(),
+                file: file.clone(),
+                line,
+            });
+        }
+    }
+
+    /// `.configure(f)`: reads the `cfg.service(..)` calls in `f`'s body.
+    fn configure(&mut self, path: &syn::Path, scope: &Scope) {
+        let resolved = match self.resolve(&scope.module, path) {
+            Ok(resolved) => resolved,
+            Err(full) => return self.report_missing(full, scope, path),
+        };
+        let key = (resolved.module.clone(), resolved.name.clone());
+        if self.configuring.contains(&key) {
+            return;
+        }
+        let function = self.function(&resolved);
+        let inner = Scope {
+            module: resolved.module,
+            file: function.file.clone(),
+            ..scope.clone()
+        };
+        self.configuring.push(key);
+        for stmt in &function.item.block.stmts {
+            let Stmt::Expr(Expr::MethodCall(call), _) = stmt else {
+                continue;
+            };
+            // `cfg.service(..).route(..)`: calls on the ServiceConfig itself
+            if let (None, calls) = method_chain(call) {
+                self.walk_calls(&calls, &inner);
+            }
+        }
+        self.configuring.pop();
+    }
+}
+
+/// Builds the route table from main.rs and `(path, contents)` of the controller files,
+/// with paths relative to the project root.
+fn route_table(main_rs: &str, controllers: &[(String, String)]) -> Result<RouteTable, Error> {
+    let main = syn::parse_file(main_rs).map_err(|e| eyre!("Could not parse src/main.rs: {}", e))?;
+    let mut modules = HashMap::new();
+    modules.insert(Vec::new(), parse_module("src/main.rs", &main));
+    for (file, contents) in controllers {
+        let parsed =
+            syn::parse_file(contents).map_err(|e| eyre!("Could not parse {}: {}", file, e))?;
+        modules.insert(module_path(file), parse_module(file, &parsed));
+    }
+
+    let mut walker = Walker {
+        modules: &modules,
+        table: RouteTable::default(),
+        registered: HashSet::new(),
+        configuring: Vec::new(),
+    };
+    let root = Scope {
+        prefix: String::new(),
+        guards: Vec::new(),
+        module: Vec::new(),
+        file: "src/main.rs".to_string(),
+    };
+    for chain in app_chains(&main) {
+        walker.walk_calls(&chain.calls, &root);
+    }
+
+    let mut table = std::mem::take(&mut walker.table);
+    for (path, module) in &modules {
+        if path.first().map(String::as_str) != Some("controllers") {
+            continue;
+        }
+        for (name, function) in &module.functions {
+            if !function.routes.is_empty()
+                && !walker.registered.contains(&(path.clone(), name.clone()))
+            {
+                // `controllers/post/post.rs` is re-exported by `controllers/post/mod.rs`, and
+                // registered as `controllers::post::show_post`
+                let parent = &path[..path.len() - 1];
+                let module =
+                    if parent.len() > 1 && walker.find(parent, name, 0).as_ref() == Some(path) {
+                        parent
+                    } else {
+                        path.as_slice()
+                    };
+                table.unregistered.push(RouteProblem {
+                    handler: [module, std::slice::from_ref(name)].concat().join("::"),
+                    file: function.file.clone(),
+                    line: function.line(),
+                });
+            }
+        }
+    }
+    table
+        .unregistered
+        .sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+    Ok(table)
+}
+
+fn collect_rs_files(dir: &Path, files: &mut Vec<String>) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_rs_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+/// # Name: collect_routes
+/// ## Description
+/// Reads the routes of the project in `project_dir` by parsing `src/main.rs` and
+/// `src/controllers/**/*.rs`. Starting from the `App::new()` chain, it follows
+/// `.service(..)`, `.route(..)` and `.configure(..)` through `web::scope(..)` and
+/// `web::resource(..)`, joining scope prefixes and collecting their guards. Handlers are
+/// found through `pub use <module>::*;` re-exports, the way the generators register them.
+pub fn collect_routes(project_dir: &Path) -> Result<RouteTable, Error> {
+    let main_rs = project_dir.join("src/main.rs");
+    if !main_rs.exists() {
+        return Err(eyre!(
+            "Could not find src/main.rs. Run this command from your RustyRoad project root."
+        ));
+    }
+    let main_rs = fs::read_to_string(main_rs)?;
+
+    let mut files = Vec::new();
+    let controllers_dir = project_dir.join("src/controllers");
+    if controllers_dir.exists() {
+        collect_rs_files(&controllers_dir, &mut files)?;
+    }
+    files.sort();
+    let prefix = format!("{}/", project_dir.to_string_lossy().replace('\\', "/"));
+    let controllers = files
+        .into_iter()
+        .map(|file| {
+            let contents = fs::read_to_string(&file)?;
+            let relative = file.strip_prefix(&prefix).unwrap_or(&file).to_string();
+            Ok((relative, contents))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    route_table(&main_rs, &controllers)
+}
+
+/// Renders the route table as aligned columns, followed by the handlers that are never
+/// registered and the registrations whose handler is missing.
+pub fn render_routes(table: &RouteTable) -> String {
+    let mut out = String::new();
+    if table.routes.is_empty() {
+        out.push_str("No routes found.\n");
+    } else {
+        let rows: Vec<[String; 5]> = table
+            .routes
+            .iter()
+            .map(|route| {
+                [
+                    route.method.clone(),
+                    route.path.clone(),
+                    route.handler.clone(),
+                    if route.authenticated { "yes" } else { "no" }.to_string(),
+                    route.guards.join(", "),
+                ]
+            })
+            .collect();
+        let header = ["Method", "Path", "Handler", "Auth", "Guards"].map(str::to_string);
+        let mut widths = [0; 5];
+        for row in std::iter::once(&header).chain(&rows) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        for row in std::iter::once(&header).chain(&rows) {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+    }
+
+    let mut section = |title: &str, problems: &[RouteProblem]| {
+        if problems.is_empty() {
+            return;
+        }
+        out.push_str(&format!("\n{}:\n", title));
+        for problem in problems {
+            out.push_str(&format!(
+                "  {} ({}:{})\n",
+                problem.handler, problem.file, problem.line
+            ));
+        }
+    };
+    section(
+        "Handlers never registered in src/main.rs",
+        &table.unregistered,
+    );
+    section("Registrations of missing handlers", &table.missing);
+    out
+}
+
+/// # Name: print_routes
+/// ## Description
+/// Prints the routes of the project in the current directory as a table or, with
+/// `format` "json", as JSON. `controller` limits the output to one controller.
+pub fn print_routes(controller: Option<&str>, format: &str) -> Result<(), Error> {
+    let mut table = collect_routes(Path::new("."))?;
+    if let Some(controller) = controller {
+        table.retain_controller(controller);
+    }
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&table)?);
+    } else {
+        print!("{}", render_routes(&table));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN_RS: &str = r#"use actix_web::{guard, web, App, HttpServer};
+mod controllers;
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    HttpServer::new(move || {
+        App::new()
+            .service(controllers::index::index)
+            .service(controllers::post::show_post)
+            .service(
+                web::scope("/admin")
+                    .guard(guard::Host("admin.example.com"))
+                    .service(controllers::post::edit_post)
+                    .route("/health", web::get().to(controllers::admin::health)),
+            )
+            .service(web::scope("/api").configure(controllers::api::configure))
+            .service(web::resource("/ping").route(web::head().to(|| async { "pong" })))
+            .service(Files::new("/static", "./static"))
+    })
+    .run()
+    .await
+}
+"#;
+
+    fn controllers() -> Vec<(String, String)> {
+        [
+            ("src/controllers/mod.rs", "pub mod index;\npub mod post;\npub mod api;\n\npub use index::*;\npub use post::*;\n"),
+            ("src/controllers/index.rs", "#[get(\"/\")]\npub async fn index() -> HttpResponse { todo!() }\n"),
+            ("src/controllers/post/mod.rs", "pub mod post;\n\npub use post::*;\n"),
+            (
+                "src/controllers/post/post.rs",
+                "#[get(\"/posts/{id}\")]\npub async fn show_post(id: web::Path<i32>) -> HttpResponse { todo!() }\n\n#[route(\"/posts/{id}/edit\", method = \"GET\", method = \"POST\")]\npub async fn edit_post(user: Identity) -> HttpResponse { todo!() }\n\n#[post(\"/posts/{id}/delete\")]\npub async fn delete_post(user: Option<Identity>) -> HttpResponse { todo!() }\n",
+            ),
+            ("src/controllers/api/mod.rs", "pub mod post;\n\npub fn configure(cfg: &mut web::ServiceConfig) {\n    cfg.app_data(web::JsonConfig::default());\n    cfg.configure(post::configure);\n}\n"),
+            ("src/controllers/api/post.rs", "pub fn configure(cfg: &mut web::ServiceConfig) {\n    cfg.service(list_posts).service(show_post);\n}\n\n#[get(\"/posts\")]\npub async fn list_posts() -> HttpResponse { todo!() }\n"),
+        ]
+        .iter()
+        .map(|(file, contents)| (file.to_string(), contents.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn test_route_table_follows_scopes_and_configure() {
+        let table = route_table(MAIN_RS, &controllers()).unwrap();
+        let routes: Vec<(&str, &str, &str, bool)> = table
+            .routes
+            .iter()
+            .map(|r| {
+                (
+                    r.method.as_str(),
+                    r.path.as_str(),
+                    r.handler.as_str(),
+                    r.authenticated,
+                )
+            })
+            .collect();
+        assert_eq!(
+            routes,
+            [
+                ("GET", "/", "controllers::index::index", false),
+                ("GET", "/posts/{id}", "controllers::post::show_post", false),
+                (
+                    "GET",
+                    "/admin/posts/{id}/edit",
+                    "controllers::post::edit_post",
+                    true
+                ),
+                (
+                    "POST",
+                    "/admin/posts/{id}/edit",
+                    "controllers::post::edit_post",
+                    true
+                ),
+                ("GET", "/admin/health", "controllers::admin::health", false),
+                (
+                    "GET",
+                    "/api/posts",
+                    "controllers::api::post::list_posts",
+                    false
+                ),
+                ("HEAD", "/ping", "<closure>", false),
+                (
+                    "GET",
+                    "/static",
+                    "Files::new(\"/static\",\"./static\")",
+                    false
+                ),
+            ]
+        );
+        assert_eq!(
+            table.routes[2].guards,
+            ["guard::Host(\"admin.example.com\")"]
+        );
+        assert_eq!(table.routes[2].file, "src/controllers/post/post.rs");
+        assert_eq!(table.routes[2].line, 5);
+
+        // `show_post` isn't defined next to the API's `list_posts`, nor is the admin controller
+        let missing: Vec<&str> = table.missing.iter().map(|p| p.handler.as_str()).collect();
+        assert_eq!(
+            missing,
+            [
+                "controllers::admin::health",
+                "controllers::api::post::show_post"
+            ]
+        );
+        assert_eq!(table.missing[1].file, "src/controllers/api/post.rs");
+        assert_eq!(
+            table.unregistered,
+            [RouteProblem {
+                handler: "controllers::post::delete_post".to_string(),
+                file: "src/controllers/post/post.rs".to_string(),
+                line: 8,
+            }]
+        );
+
+        let mut filtered = table.clone();
+        filtered.retain_controller("post.rs");
+        assert_eq!(filtered.routes.len(), 4);
+        assert_eq!(filtered.missing.len(), 1);
+        assert_eq!(filtered.unregistered.len(), 1);
+    }
+
+    #[test]
+    fn test_takes_identity() {
+        let takes = |item: ItemFn| takes_identity(&item);
+        assert!(takes(syn::parse_quote! {
+            async fn edit(user: Identity) -> HttpResponse { todo!() }
+        }));
+        assert!(takes(syn::parse_quote! {
+            async fn edit(id: web::Path<i32>, user: Option<actix_identity::Identity>) -> HttpResponse { todo!() }
+        }));
+        assert!(!takes(syn::parse_quote! {
+            async fn show(id: web::Path<i32>, tmpl: web::Data<Tera>) -> HttpResponse { todo!() }
+        }));
+        assert!(!takes(syn::parse_quote! {
+            async fn show(ids: Vec<Identity>) -> HttpResponse { todo!() }
+        }));
+    }
+
+    #[test]
+    fn test_render_routes() {
+        let table = route_table(MAIN_RS, &controllers()).unwrap();
+        let rendered = render_routes(&table);
+        let mut lines = rendered.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "Method  Path                    Handler                             Auth  Guards"
+        );
+        assert!(rendered.contains(
+            "GET     /admin/posts/{id}/edit  controllers::post::edit_post        yes   guard::Host(\"admin.example.com\")\n"
+        ));
+        assert!(rendered.contains(
+            "\nHandlers never registered in src/main.rs:\n  controllers::post::delete_post (src/controllers/post/post.rs:8)\n"
+        ));
+        assert_eq!(render_routes(&RouteTable::default()), "No routes found.\n");
+    }
+}
@@ -0,0 +1,1606 @@
+use crate::database::{
+    find_create_table_migration, write_create_table_migration, Database, DatabaseType,
+};
+use crate::generators::{render_template, Generator};
+use crate::writers::{add_new_controller_to_main_rs, is_route_handler};
+use crate::CRUDType;
+use color_eyre::eyre::Result;
+use eyre::{eyre, Error};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use tera::Context;
+
+/// The layout every scaffolded page extends.
+const AUTHENTICATED_LAYOUT: &str = "layouts/authenticated_page/authenticated_page.html.tera";
+
+pub(crate) const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while",
+];
+
+/// # Name: FieldType
+/// ## Description
+/// The column types a scaffold can map to both SQL and a Rust field that sqlx decodes on every backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    String,
+    Text,
+    Integer,
+    BigInt,
+    Boolean,
+    Float,
+    Date,
+    DateTime,
+    Time,
+}
+
+impl FieldType {
+    /// Parses the type part of a `name:type` field definition.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "string" => Some(FieldType::String),
+            "text" => Some(FieldType::Text),
+            "integer" | "int" => Some(FieldType::Integer),
+            "biginteger" | "bigint" => Some(FieldType::BigInt),
+            "boolean" | "bool" => Some(FieldType::Boolean),
+            "float" | "double" => Some(FieldType::Float),
+            "date" => Some(FieldType::Date),
+            "datetime" | "timestamp" => Some(FieldType::DateTime),
+            "time" => Some(FieldType::Time),
+            _ => None,
+        }
+    }
+
+    /// The type's name in field definitions and templates, e.g. `datetime`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FieldType::String => "string",
+            FieldType::Text => "text",
+            FieldType::Integer => "integer",
+            FieldType::BigInt => "bigint",
+            FieldType::Boolean => "boolean",
+            FieldType::Float => "float",
+            FieldType::Date => "date",
+            FieldType::DateTime => "datetime",
+            FieldType::Time => "time",
+        }
+    }
+
+    /// The Rust type of a non-null column of this type.
+    pub fn rust_type(&self) -> &'static str {
+        match self {
+            FieldType::String | FieldType::Text => "String",
+            FieldType::Integer => "i32",
+            FieldType::BigInt => "i64",
+            FieldType::Boolean => "bool",
+            FieldType::Float => "f64",
+            FieldType::Date => "chrono::NaiveDate",
+            FieldType::DateTime => "chrono::NaiveDateTime",
+            FieldType::Time => "chrono::NaiveTime",
+        }
+    }
+
+    /// The `<input type>` used for this type in the generated form.
+    fn input_type(&self) -> &'static str {
+        match self {
+            FieldType::Integer | FieldType::BigInt | FieldType::Float => "number",
+            FieldType::Boolean => "checkbox",
+            FieldType::Date => "date",
+            FieldType::DateTime => "datetime-local",
+            FieldType::Time => "time",
+            FieldType::String | FieldType::Text => "text",
+        }
+    }
+
+    /// Whether the value renders as a string in Tera and therefore needs escaping.
+    fn is_textual(&self) -> bool {
+        !matches!(
+            self,
+            FieldType::Integer | FieldType::BigInt | FieldType::Boolean | FieldType::Float
+        )
+    }
+}
+
+/// # Name: ScaffoldField
+/// ## Description
+/// One `name:type[:constraints]` field of a scaffold.
+/// The original definition is kept so the migration goes through the same column parser as `migration generate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaffoldField {
+    pub name: String,
+    pub field_type: FieldType,
+    pub required: bool,
+    /// The column has a `unique` constraint, which the model validates before writing.
+    pub unique: bool,
+    /// The table a `references=table` field points to. The model gets a `belongs_to` method for it.
+    pub belongs_to: Option<String>,
+    pub definition: String,
+}
+
+impl ScaffoldField {
+    /// # Name: parse
+    /// ## Description
+    /// Parses a field definition such as `title:string:not_null`.
+    /// ## Example
+    /// ```
+    /// use rustyroad::writers::{FieldType, ScaffoldField};
+    ///
+    /// let field = ScaffoldField::parse("title:string:not_null").unwrap();
+    /// assert_eq!(field.field_type, FieldType::String);
+    /// assert!(field.required);
+    /// ```
+    pub fn parse(definition: &str) -> Result<Self, Error> {
+        let parts: Vec<&str> = definition.split(':').collect();
+        if parts.len() < 2 || parts[0].is_empty() {
+            return Err(eyre!(
+                "Invalid field '{}'. Fields use the format name:type[:constraints]",
+                definition
+            ));
+        }
+
+        let name = parts[0].to_string();
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            || name.starts_with(|c: char| c.is_ascii_digit())
+        {
+            return Err(eyre!(
+                "Invalid field name '{}'. Use snake_case names such as published_at",
+                name
+            ));
+        }
+        if RUST_KEYWORDS.contains(&name.as_str()) {
+            return Err(eyre!(
+                "Field name '{}' is a Rust keyword; pick another name",
+                name
+            ));
+        }
+        if name == "id" {
+            return Err(eyre!(
+                "The scaffold adds the 'id' primary key itself; remove the id field"
+            ));
+        }
+
+        let field_type = FieldType::parse(parts[1]).ok_or_else(|| {
+            eyre!(
+                "Unsupported type '{}' for field '{}'. Supported: string, text, integer, bigint, boolean, float, date, datetime, time",
+                parts[1],
+                name
+            )
+        })?;
+
+        let constraints: Vec<String> = parts
+            .get(2)
+            .map(|c| c.split(',').map(|c| c.to_lowercase()).collect())
+            .unwrap_or_default();
+        if constraints.iter().any(|c| c == "primary_key") {
+            return Err(eyre!(
+                "Field '{}' cannot be the primary key; the scaffold uses an 'id' column",
+                name
+            ));
+        }
+
+        // belongs_to loads by the `id` primary key, so `references=table(column)` only counts for `id`
+        let integer = matches!(field_type, FieldType::Integer | FieldType::BigInt);
+        let belongs_to = constraints.iter().filter(|_| integer).find_map(|c| {
+            let reference = c.strip_prefix("references=")?;
+            match reference.trim_end_matches(')').split_once('(') {
+                None => Some(reference.to_string()),
+                Some((table, "id")) => Some(table.to_string()),
+                Some(_) => None,
+            }
+        });
+
+        Ok(ScaffoldField {
+            name,
+            field_type,
+            required: constraints.iter().any(|c| c == "not_null"),
+            unique: constraints.iter().any(|c| c == "unique"),
+            belongs_to,
+            definition: definition.to_string(),
+        })
+    }
+
+    /// The Rust type of the model field, `Option<T>` when the column is nullable.
+    pub fn rust_type(&self) -> String {
+        if self.required {
+            self.field_type.rust_type().to_string()
+        } else {
+            format!("Option<{}>", self.field_type.rust_type())
+        }
+    }
+
+    /// The Rust type of the form field. Checkboxes are absent when unchecked, so they are plain `bool`s.
+    fn form_type(&self) -> String {
+        if self.field_type == FieldType::Boolean {
+            "bool".to_string()
+        } else {
+            self.rust_type()
+        }
+    }
+
+    /// The human readable label, e.g. `published_at` becomes `Published at`.
+    fn label(&self) -> String {
+        humanize(&self.name)
+    }
+
+    /// The model's `#[validate(..)]` rules that mirror the column definition: `presence` for
+    /// NOT NULL text, the 255 character limit of a `string`'s VARCHAR(255) and `unique`.
+    fn validations(&self) -> Vec<&'static str> {
+        let mut rules = Vec::new();
+        if self.required && matches!(self.field_type, FieldType::String | FieldType::Text) {
+            rules.push("presence");
+        }
+        if self.field_type == FieldType::String {
+            rules.push("length(max = 255)");
+        }
+        if self.unique {
+            rules.push("unique");
+        }
+        rules
+    }
+}
+
+/// One field in the context of the generator templates; see `model/model.rs.tera`.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FieldContext {
+    name: String,
+    label: String,
+    #[serde(rename = "type")]
+    field_type: &'static str,
+    required: bool,
+    rust_type: String,
+    form_type: String,
+    /// The arguments of the field's `#[validate(..)]` attribute, or an empty string.
+    validate: String,
+    input_type: &'static str,
+    step: &'static str,
+    value: String,
+    input_value: String,
+}
+
+/// A `belongs_to` association in the context of the model template.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct AssociationContext {
+    /// The method name, e.g. `author` for `author_id`.
+    name: String,
+    field: String,
+    table: String,
+    /// The associated model type, e.g. `crate::models::user::User`.
+    target: String,
+    /// The associated model in messages, e.g. `User`.
+    title: String,
+    required: bool,
+}
+
+/// A required `references=table` field in the context of the controller tests template; the
+/// records a test creates need a row for it.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ReferenceContext {
+    field: String,
+    /// The association name, e.g. `author` for `author_id`.
+    name: String,
+    table: String,
+    target: String,
+    bigint: bool,
+    /// Whether the target is a generated model, which a factory can create. The tests use the
+    /// row with id 1 of the table's fixtures otherwise.
+    modelled: bool,
+}
+
+/// # Name: Scaffold
+/// ## Description
+/// Everything `rustyroad generate scaffold` writes for one resource: a migration, an sqlx model,
+/// CRUD controllers and Tera views. The `render_*` methods are pure so they can be tested without a project.
+/// ## Example
+/// ```
+/// use rustyroad::database::DatabaseType;
+/// use rustyroad::writers::Scaffold;
+///
+/// let fields = vec!["title:string".to_string()];
+/// let scaffold = Scaffold::new("BlogPost", &fields, DatabaseType::Postgres).unwrap();
+/// assert_eq!(scaffold.name, "blog_post");
+/// assert_eq!(scaffold.struct_name, "BlogPost");
+/// assert_eq!(scaffold.table_name, "blog_posts");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scaffold {
+    pub name: String,
+    pub struct_name: String,
+    pub table_name: String,
+    pub fields: Vec<ScaffoldField>,
+    pub database_type: DatabaseType,
+}
+
+/// The CRUD actions a full scaffold generates.
+pub const CRUD_ACTIONS: [CRUDType; 4] = [
+    CRUDType::Read,
+    CRUDType::Create,
+    CRUDType::Update,
+    CRUDType::Delete,
+];
+
+impl Scaffold {
+    pub fn new(name: &str, fields: &[String], database_type: DatabaseType) -> Result<Self, Error> {
+        let name = to_snake_case(name);
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(eyre!("Invalid scaffold name '{}'", name));
+        }
+        if RUST_KEYWORDS.contains(&name.as_str()) {
+            return Err(eyre!("Scaffold name '{}' is a Rust keyword", name));
+        }
+        if fields.is_empty() {
+            return Err(eyre!(
+                "A scaffold needs at least one field, e.g. rustyroad generate scaffold post title:string"
+            ));
+        }
+        if database_type == DatabaseType::Mongo {
+            return Err(eyre!("Scaffolds are not supported for MongoDB projects"));
+        }
+
+        let fields = fields
+            .iter()
+            .map(|f| ScaffoldField::parse(f))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (i, field) in fields.iter().enumerate() {
+            if fields[..i].iter().any(|f| f.name == field.name) {
+                return Err(eyre!("Field '{}' is listed more than once", field.name));
+            }
+        }
+
+        Ok(Scaffold {
+            struct_name: to_pascal_case(&name),
+            table_name: pluralize(&name),
+            name,
+            fields,
+            database_type,
+        })
+    }
+
+    /// # Name: from_model_source
+    /// ## Description
+    /// Rebuilds a scaffold from an existing model file, so a controller can be generated for a
+    /// model that was written earlier. Reads the fields of the `pub struct <Name>` in `source`;
+    /// `Option<T>` fields become nullable columns and `id` is skipped. Fields a generated
+    /// `belongs_to` method loads become `references=table` fields.
+    pub fn from_model_source(
+        name: &str,
+        source: &str,
+        database_type: DatabaseType,
+    ) -> Result<Self, Error> {
+        let struct_name = to_pascal_case(&to_snake_case(name));
+        let file = syn::parse_file(source)
+            .map_err(|e| eyre!("Could not parse the {} model: {}", struct_name, e))?;
+        let item = file
+            .items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Struct(s) if s.ident == struct_name => Some(s),
+                _ => None,
+            })
+            .ok_or_else(|| eyre!("The model file does not define `struct {}`", struct_name))?;
+        let form_name = format!("{}Form", struct_name);
+        let has_form = file
+            .items
+            .iter()
+            .any(|item| matches!(item, syn::Item::Struct(s) if s.ident == form_name));
+        if !has_form {
+            return Err(eyre!(
+                "The model has no `{}` struct for the forms to submit. Controllers can be generated \
+                 for models created by `generate model <name> field:type...` or `generate scaffold`",
+                form_name
+            ));
+        }
+
+        let references =
+            belongs_to_references(&file, &struct_name, &pluralize(&to_snake_case(name)));
+        let mut fields = Vec::new();
+        for field in &item.fields {
+            let Some(ident) = &field.ident else {
+                return Err(eyre!("`{}` must have named fields", struct_name));
+            };
+            let field_name = ident.to_string();
+            if field_name == "id" {
+                continue;
+            }
+            let rust_type = quote::ToTokens::to_token_stream(&field.ty)
+                .to_string()
+                .replace(' ', "");
+            let (inner, nullable) = match rust_type
+                .strip_prefix("Option<")
+                .and_then(|t| t.strip_suffix('>'))
+            {
+                Some(inner) => (inner, true),
+                None => (rust_type.as_str(), false),
+            };
+            let field_type = match inner.rsplit("::").next().unwrap_or(inner) {
+                "String" => "string",
+                "i32" => "integer",
+                "i64" => "bigint",
+                "bool" => "boolean",
+                "f32" | "f64" => "float",
+                "NaiveDate" => "date",
+                "NaiveDateTime" => "datetime",
+                "NaiveTime" => "time",
+                _ => {
+                    return Err(eyre!(
+                        "Field '{}' has type `{}`, which generators cannot map to a form input",
+                        field_name,
+                        rust_type
+                    ))
+                }
+            };
+            let mut constraints: Vec<String> = Vec::new();
+            if !nullable {
+                constraints.push("not_null".to_string());
+            }
+            if let Some(table) = references
+                .iter()
+                .find_map(|(f, t)| (*f == field_name).then_some(t))
+            {
+                constraints.push(format!("references={}", table));
+            }
+            fields.push(if constraints.is_empty() {
+                format!("{}:{}", field_name, field_type)
+            } else {
+                format!("{}:{}:{}", field_name, field_type, constraints.join(","))
+            });
+        }
+
+        Self::new(name, &fields, database_type)
+    }
+
+    /// The column definitions handed to the migration writer.
+    pub fn column_definitions(&self) -> Vec<String> {
+        self.fields.iter().map(|f| f.definition.clone()).collect()
+    }
+
+    /// The URL prefix of the resource, e.g. `/blog_posts`.
+    fn path(&self) -> String {
+        format!("/{}", self.table_name)
+    }
+
+    /// The directory under `src/views/` holding the resource's templates.
+    fn template_dir(&self) -> String {
+        format!("pages/{}", self.name)
+    }
+
+    /// The fields as the generator templates see them, in column order.
+    pub(crate) fn field_contexts(&self) -> Vec<FieldContext> {
+        self.fields
+            .iter()
+            .map(|f| {
+                let textual = f.field_type.is_textual();
+                let value = if textual {
+                    format!(
+                        "{{{{ {}.{} | default(value=\"\") | escape }}}}",
+                        self.name, f.name
+                    )
+                } else {
+                    format!("{{{{ {}.{} }}}}", self.name, f.name)
+                };
+                FieldContext {
+                    name: f.name.clone(),
+                    label: f.label(),
+                    field_type: f.field_type.name(),
+                    required: f.required,
+                    rust_type: f.rust_type(),
+                    form_type: f.form_type(),
+                    validate: f.validations().join(", "),
+                    input_type: f.field_type.input_type(),
+                    step: match f.field_type {
+                        FieldType::Float => "any",
+                        FieldType::DateTime | FieldType::Time => "1",
+                        _ => "",
+                    },
+                    input_value: if textual {
+                        value.clone()
+                    } else {
+                        format!("{{{{ {}.{} | default(value=\"\") }}}}", self.name, f.name)
+                    },
+                    value,
+                }
+            })
+            .collect()
+    }
+
+    /// The `belongs_to` associations of the `references=table` fields.
+    pub(crate) fn association_contexts(&self) -> Vec<AssociationContext> {
+        self.fields
+            .iter()
+            .filter_map(|f| {
+                let table = f.belongs_to.as_ref()?;
+                let model = model_name_for_table(table);
+                let target = if *table == self.table_name {
+                    "Self".to_string()
+                } else {
+                    format!("crate::models::{}::{}", model, to_pascal_case(&model))
+                };
+                Some(AssociationContext {
+                    name: association_name(&f.name, table),
+                    field: f.name.clone(),
+                    table: table.clone(),
+                    target,
+                    title: humanize(&model),
+                    required: f.required,
+                })
+            })
+            .collect()
+    }
+
+    /// # Name: template_context
+    /// ## Description
+    /// The Tera context the model, controller and view templates are rendered with. Each template
+    /// under `src/generators/templates/` lists the keys it uses in its opening comment.
+    pub fn template_context(&self, actions: &[CRUDType]) -> Context {
+        let (pool_type, pool_variant) = pool_types(&self.database_type);
+        let mut context = Context::new();
+        context.insert("name", &self.name);
+        context.insert("struct_name", &self.struct_name);
+        context.insert("table_name", &self.table_name);
+        context.insert("title", &humanize(&self.name));
+        context.insert("plural_title", &humanize(&self.table_name));
+        context.insert("path", &self.path());
+        context.insert("template_dir", &self.template_dir());
+        context.insert("layout", AUTHENTICATED_LAYOUT);
+        context.insert("record_id", &format!("{{{{ {}.id }}}}", self.name));
+        context.insert(
+            "database",
+            match self.database_type {
+                DatabaseType::Mysql => "mysql",
+                DatabaseType::Sqlite => "sqlite",
+                _ => "postgres",
+            },
+        );
+        context.insert("pool_type", pool_type);
+        context.insert("pool_variant", pool_variant);
+        context.insert(
+            "optional_inputs",
+            &self
+                .fields
+                .iter()
+                .any(|f| !f.required && f.field_type != FieldType::Boolean),
+        );
+        context.insert(
+            "actions",
+            &serde_json::json!({
+                "read": actions.contains(&CRUDType::Read),
+                "create": actions.contains(&CRUDType::Create),
+                "update": actions.contains(&CRUDType::Update),
+                "delete": actions.contains(&CRUDType::Delete),
+            }),
+        );
+        context.insert("fields", &self.field_contexts());
+        context.insert("associations", &self.association_contexts());
+        context
+    }
+
+    /// Controller handler names for `actions` in registration order.
+    pub fn handler_names(&self, actions: &[CRUDType]) -> Result<Vec<String>, Error> {
+        route_handlers(&self.render_controller(actions)?)
+    }
+
+    /// # Name: render_model
+    /// ## Description
+    /// Renders `src/models/<name>.rs` from the `model/model.rs` template: a `#[derive(Model)]`
+    /// struct, the form struct, async CRUD methods that go through `Model`, and a `belongs_to`
+    /// method for each `references=table` field.
+    pub fn render_model(&self) -> Result<String, Error> {
+        render_template("model", "model.rs", &self.template_context(&CRUD_ACTIONS))
+    }
+
+    /// # Name: render_controller
+    /// ## Description
+    /// Renders `src/controllers/<name>/<name>.rs` from the `controller/controller.rs` template with
+    /// the handlers for `actions`: read (index, show), create (new, create), update (edit, update)
+    /// and delete. Every handler redirects to `/login` when nobody is signed in.
+    pub fn render_controller(&self, actions: &[CRUDType]) -> Result<String, Error> {
+        render_template(
+            "controller",
+            "controller.rs",
+            &self.template_context(actions),
+        )
+    }
+
+    /// # Name: render_views
+    /// ## Description
+    /// Renders the Tera templates for `actions` as `(file name, contents)` pairs for `src/views/pages/<name>/`.
+    /// The pages extend the authenticated layout; `new` and `edit` share `_form.html.tera`.
+    pub fn render_views(&self, actions: &[CRUDType]) -> Result<Vec<(String, String)>, Error> {
+        let mut files = Vec::new();
+        if actions.contains(&CRUDType::Read) {
+            files.extend(["index.html", "show.html"]);
+        }
+        if actions.contains(&CRUDType::Create) {
+            files.push("new.html");
+        }
+        if actions.contains(&CRUDType::Update) {
+            files.push("edit.html");
+        }
+        if actions.contains(&CRUDType::Create) || actions.contains(&CRUDType::Update) {
+            files.push("_form.html");
+        }
+        let context = self.template_context(actions);
+        files
+            .into_iter()
+            .map(|file| {
+                let contents = render_template("controller", file, &context)?;
+                Ok((format!("{}.tera", file), contents))
+            })
+            .collect()
+    }
+
+    /// # Name: render_tests
+    /// ## Description
+    /// Renders the `controller/tests.rs` template: `actix_web::test` tests of the handlers for
+    /// `actions` against a `TestDatabase`, signed in and signed out. The records are built with
+    /// `rustyroad::testing::factory`, and required `references=table` fields point to a row a
+    /// factory creates when `src/models/` has a generated model for the table.
+    pub fn render_tests(&self, actions: &[CRUDType]) -> Result<String, Error> {
+        let mut context = self.template_context(actions);
+        context.insert("handlers", &self.handler_names(actions)?);
+        let text_field = self
+            .fields
+            .iter()
+            .find(|f| f.required && matches!(f.field_type, FieldType::String | FieldType::Text));
+        context.insert("text_field", &text_field.map_or("", |f| f.name.as_str()));
+        let references: Vec<ReferenceContext> = self
+            .association_contexts()
+            .into_iter()
+            .filter(|association| association.required)
+            .map(|association| {
+                let modelled = association.target != "Self"
+                    && model_implements_model(&model_name_for_table(&association.table));
+                let bigint = self
+                    .fields
+                    .iter()
+                    .any(|f| f.name == association.field && f.field_type == FieldType::BigInt);
+                ReferenceContext {
+                    field: association.field,
+                    name: association.name,
+                    table: association.table,
+                    target: association.target,
+                    bigint,
+                    modelled,
+                }
+            })
+            .collect();
+        context.insert("references", &references);
+        render_template("controller", "tests.rs", &context)
+    }
+
+    /// The actions whose handlers are all in `handlers`, e.g. the route handlers of a controller
+    /// that was generated earlier and maybe changed since.
+    pub fn actions_with_handlers(&self, handlers: &[String]) -> Result<Vec<CRUDType>, Error> {
+        let mut actions = Vec::new();
+        for action in CRUD_ACTIONS {
+            let names = self.handler_names(&[action])?;
+            if names.iter().all(|name| handlers.contains(name)) {
+                actions.push(action);
+            }
+        }
+        Ok(actions)
+    }
+}
+
+/// The sqlx pool type and `PoolConnection` variant of a backend, e.g. `sqlx::PgPool` and `Pg`.
+pub(crate) fn pool_types(database_type: &DatabaseType) -> (&'static str, &'static str) {
+    match database_type {
+        DatabaseType::Mysql => ("sqlx::MySqlPool", "MySql"),
+        DatabaseType::Sqlite => ("sqlx::SqlitePool", "Sqlite"),
+        _ => ("sqlx::PgPool", "Pg"),
+    }
+}
+
+/// The private `pool()` helper of a generated model, which opens the pool configured in rustyroad.toml.
+pub(crate) fn render_pool_fn(database_type: &DatabaseType) -> String {
+    let (pool_type, pool_variant) = pool_types(database_type);
+    format!(
+        r#"    async fn pool() -> Result<{pool_type}, sqlx::Error> {{
+        let database = Database::get_database_from_rustyroad_toml()
+            .map_err(|e| sqlx::Error::Configuration(e.into()))?;
+        match Database::get_db_pool(database).await {{
+            Ok(PoolConnection::{pool_variant}(pool)) => Ok(pool),
+            Ok(_) => Err(sqlx::Error::Configuration(
+                "rustyroad.toml points to a different database backend".into(),
+            )),
+            Err(e) => Err(sqlx::Error::Configuration(e.to_string().into())),
+        }}
+    }}
+"#
+    )
+}
+
+/// The private `connection()` helper of a generated model with associations.
+pub(crate) const CONNECTION_FN: &str = r#"    async fn connection() -> Result<DatabaseConnection, sqlx::Error> {
+        let database = Database::get_database_from_rustyroad_toml()
+            .map_err(|e| sqlx::Error::Configuration(e.into()))?;
+        Database::get_db_pool(database)
+            .await
+            .map(DatabaseConnection::from)
+            .map_err(|e| sqlx::Error::Configuration(e.to_string().into()))
+    }
+"#;
+
+/// `BlogPost`, `blog-post` and `blog post` all become `blog_post`.
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut previous_lower = false;
+    for c in name.trim().chars() {
+        if c.is_ascii_uppercase() {
+            if previous_lower {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+            previous_lower = false;
+        } else if c == '-' || c == ' ' || c == '_' {
+            if !out.ends_with('_') && !out.is_empty() {
+                out.push('_');
+            }
+            previous_lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            out.push(c);
+            previous_lower = true;
+        }
+    }
+    out.trim_end_matches('_').to_string()
+}
+
+pub(crate) fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// English pluralization that covers the usual table names.
+pub(crate) fn pluralize(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix('y') {
+        if !stem.ends_with(['a', 'e', 'i', 'o', 'u']) {
+            return format!("{}ies", stem);
+        }
+    }
+    if name.ends_with('s')
+        || name.ends_with('x')
+        || name.ends_with('z')
+        || name.ends_with("ch")
+        || name.ends_with("sh")
+    {
+        return format!("{}es", name);
+    }
+    format!("{}s", name)
+}
+
+/// The inverse of `pluralize`, e.g. `categories` becomes `category`.
+pub(crate) fn singularize(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        return format!("{}y", stem);
+    }
+    for suffix in ["sses", "xes", "zes", "ches", "shes"] {
+        if name.ends_with(suffix) {
+            return name[..name.len() - 2].to_string();
+        }
+    }
+    match name.strip_suffix('s') {
+        Some(stem) if !stem.ends_with('s') => stem.to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// The module under `src/models/` the model of `table` is generated into, e.g. `blog_post`.
+pub(crate) fn model_name_for_table(table: &str) -> String {
+    singularize(&to_snake_case(table))
+}
+
+/// The method a `belongs_to` foreign key is loaded with: `author_id` becomes `author`, and a
+/// column without the `_id` suffix is named after the singular of the table it refers to.
+pub(crate) fn association_name(column: &str, table: &str) -> String {
+    let column = to_snake_case(column);
+    let mut name = match column.strip_suffix("_id") {
+        Some(stem) if !stem.is_empty() => stem.to_string(),
+        _ => model_name_for_table(table),
+    };
+    if RUST_KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    name
+}
+
+/// Whether `src/models/<model>.rs` exists and implements `rustyroad::model::Model`, so other
+/// generated models can load it through associations.
+pub(crate) fn model_implements_model(model: &str) -> bool {
+    let path = Path::new("src/models").join(format!("{}.rs", model));
+    Generator::read_to_string(&path)
+        .map(|source| source.contains("rustyroad::model::"))
+        .unwrap_or(false)
+}
+
+/// `published_at` becomes `Published at`.
+pub(crate) fn humanize(name: &str) -> String {
+    let words = name.replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// Writes generated `contents` to `path` through the generator runtime.
+pub(crate) fn write_new_file(path: &Path, contents: &str) -> Result<(), Error> {
+    Generator::write_file(path, contents)?;
+    Ok(())
+}
+
+/// Adds `pub mod <name>;` / `pub use <name>::*;` to a mod.rs file, creating it if it is missing.
+/// Each goes on its own line after the last declaration of its kind, and declarations run
+/// together on one line, like `pub use index::*;pub mod login;`, are split up first.
+pub(crate) fn register_module(name: &str, mod_rs: &Path) -> Result<(), Error> {
+    if !Generator::exists(mod_rs) {
+        write_new_file(mod_rs, "")?;
+    }
+    let contents = Generator::read_to_string(mod_rs)?;
+    if contents.contains(&format!("pub mod {};", name)) {
+        return Ok(());
+    }
+
+    let declaration = regex::Regex::new(r"pub (mod|use) [^;]+;").unwrap();
+    let mut lines: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        let declarations: Vec<&str> = declaration.find_iter(line).map(|m| m.as_str()).collect();
+        if declarations.len() > 1 && declaration.replace_all(line, "").trim().is_empty() {
+            lines.extend(declarations.into_iter().map(String::from));
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+
+    for (prefix, added) in [
+        ("pub mod ", format!("pub mod {};", name)),
+        ("pub use ", format!("pub use {}::*;", name)),
+    ] {
+        let at = lines
+            .iter()
+            .rposition(|line| line.trim_start().starts_with(prefix))
+            .map_or(lines.len(), |i| i + 1);
+        lines.insert(at, added);
+    }
+    Generator::update_file(mod_rs, &(lines.join("\n") + "\n"))?;
+    Ok(())
+}
+
+/// Writes `src/models/<name>.rs` and declares it in `src/models/mod.rs`.
+pub(crate) fn write_model(scaffold: &Scaffold) -> Result<(), Error> {
+    let mut scaffold = scaffold.clone();
+    for field in &mut scaffold.fields {
+        let Some(table) = field.belongs_to.clone() else {
+            continue;
+        };
+        let model = model_name_for_table(&table);
+        if table != scaffold.table_name && !model_implements_model(&model) {
+            Generator::say(format!(
+                "Skipped the `{}` association of '{}': src/models/{}.rs is not a generated model yet.",
+                association_name(&field.name, &table),
+                field.name,
+                model
+            ));
+            field.belongs_to = None;
+        }
+    }
+    let model_path = Path::new("src/models").join(format!("{}.rs", scaffold.name));
+    write_new_file(&model_path, &scaffold.render_model()?)?;
+    register_module(&scaffold.name, Path::new("src/models/mod.rs"))
+}
+
+/// The `(field, table)` of each `belongs_to` association the model template generated in the
+/// `impl <struct_name>` of a model file. An association of the model itself refers to `table`.
+fn belongs_to_references(
+    file: &syn::File,
+    struct_name: &str,
+    table: &str,
+) -> Vec<(String, String)> {
+    let loads =
+        regex::Regex::new(r"associations::belongs_to\(&conn,self\.(\w+)\.into\(\)\)").unwrap();
+    let mut references = Vec::new();
+    for item in &file.items {
+        let syn::Item::Impl(implementation) = item else {
+            continue;
+        };
+        let self_type = quote::ToTokens::to_token_stream(&implementation.self_ty).to_string();
+        if implementation.trait_.is_some() || self_type != struct_name {
+            continue;
+        }
+        for item in &implementation.items {
+            let syn::ImplItem::Fn(method) = item else {
+                continue;
+            };
+            let body = quote::ToTokens::to_token_stream(&method.block)
+                .to_string()
+                .replace(' ', "");
+            let Some(field) = loads.captures(&body).map(|c| c[1].to_string()) else {
+                continue;
+            };
+            let syn::ReturnType::Type(_, output) = &method.sig.output else {
+                continue;
+            };
+            // Result<crate::models::user::User, sqlx::Error> or Result<Option<Self>, ..>
+            let output = quote::ToTokens::to_token_stream(output)
+                .to_string()
+                .replace(' ', "");
+            let target = output
+                .trim_start_matches("Result<")
+                .trim_start_matches("Option<")
+                .split([',', '>'])
+                .next()
+                .unwrap_or_default();
+            let target_table = match target.rsplit("::").nth(1) {
+                _ if target == "Self" => table.to_string(),
+                Some(model) => pluralize(model),
+                None => continue,
+            };
+            if !references.iter().any(|(f, _)| *f == field) {
+                references.push((field, target_table));
+            }
+        }
+    }
+    references
+}
+
+fn parse_controller(source: &str) -> Result<syn::File, Error> {
+    syn::parse_file(source).map_err(|e| eyre!("Could not parse: {}", e))
+}
+
+/// The `use` items of a Rust file, one `(use line, name brought into scope)` per imported item,
+/// so `use a::{b, c};` becomes `use a::b;` and `use a::c;`. Glob imports have no name.
+fn use_items(file: &syn::File) -> Vec<(String, Option<String>)> {
+    fn collect(tree: &syn::UseTree, prefix: &str, items: &mut Vec<(String, Option<String>)>) {
+        let join = |last: &str| {
+            if prefix.is_empty() {
+                last.to_string()
+            } else {
+                format!("{}::{}", prefix, last)
+            }
+        };
+        match tree {
+            syn::UseTree::Path(path) => collect(&path.tree, &join(&path.ident.to_string()), items),
+            syn::UseTree::Name(name) => items.push((
+                format!("use {};\n", join(&name.ident.to_string())),
+                Some(name.ident.to_string()),
+            )),
+            syn::UseTree::Rename(rename) => items.push((
+                format!(
+                    "use {} as {};\n",
+                    join(&rename.ident.to_string()),
+                    rename.rename
+                ),
+                Some(rename.rename.to_string()),
+            )),
+            syn::UseTree::Group(group) => {
+                group.items.iter().for_each(|t| collect(t, prefix, items))
+            }
+            syn::UseTree::Glob(_) => items.push((format!("use {};\n", join("*")), None)),
+        }
+    }
+
+    let mut items = Vec::new();
+    for item in &file.items {
+        if let syn::Item::Use(item) = item {
+            collect(&item.tree, "", &mut items);
+        }
+    }
+    items
+}
+
+/// The names of the `pub` functions with a route attribute, in file order.
+fn route_handlers(source: &str) -> Result<Vec<String>, Error> {
+    Ok(parse_controller(source)?
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Fn(f)
+                if matches!(f.vis, syn::Visibility::Public(_)) && is_route_handler(f) =>
+            {
+                Some(f.sig.ident.to_string())
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+/// Adds the `use` items and functions of the freshly `rendered` controller that an existing
+/// controller is missing, so handlers written by hand are kept. Returns `None` when the
+/// controller already has everything.
+fn extend_controller(rendered: &str, source: &str) -> Result<Option<String>, Error> {
+    let imported: Vec<String> = use_items(&parse_controller(source)?)
+        .into_iter()
+        .filter_map(|(_, name)| name)
+        .collect();
+    let generated = parse_controller(rendered)
+        .map_err(|e| eyre!("The controller template did not render valid Rust. {}", e))?;
+    let missing_imports: Vec<String> = use_items(&generated)
+        .into_iter()
+        .filter(|(line, name)| match name {
+            Some(name) => !imported.contains(name),
+            None => !source.contains(line.trim_end()),
+        })
+        .map(|(line, _)| line)
+        .collect();
+    // Generated functions are copied line by line, together with their attributes and docs
+    let lines: Vec<&str> = rendered.lines().collect();
+    let missing_fns: Vec<String> = generated
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Fn(f) if !source.contains(&format!("fn {}(", f.sig.ident)) => {
+                let span = f.span();
+                Some(lines[span.start().line - 1..span.end().line].join("\n") + "\n")
+            }
+            _ => None,
+        })
+        .collect();
+    if missing_imports.is_empty() && missing_fns.is_empty() {
+        return Ok(None);
+    }
+
+    // Imports go after any inner attributes and module docs at the top of the file.
+    let mut insert_at = 0;
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("//!") || trimmed.starts_with("#![") {
+            insert_at += line.len();
+        } else {
+            break;
+        }
+    }
+    let mut updated = String::with_capacity(source.len());
+    updated.push_str(&source[..insert_at]);
+    missing_imports
+        .iter()
+        .for_each(|line| updated.push_str(line));
+    updated.push_str(&source[insert_at..]);
+    if !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    for block in &missing_fns {
+        updated.push('\n');
+        updated.push_str(block);
+    }
+    Ok(Some(updated))
+}
+
+/// # Name: write_controller
+/// ## Description
+/// Writes the controller, views and route registrations for `actions`. An existing controller
+/// (`src/controllers/<name>.rs` or `src/controllers/<name>/<name>.rs`) only gets the functions it
+/// is missing, and only missing `.service(..)` lines are added. Views that exist with different
+/// contents are conflicts for the generator runtime.
+pub(crate) fn write_controller(scaffold: &Scaffold, actions: &[CRUDType]) -> Result<(), Error> {
+    let controllers = Path::new("src/controllers");
+    let flat = controllers.join(format!("{}.rs", scaffold.name));
+    let nested = controllers
+        .join(&scaffold.name)
+        .join(format!("{}.rs", scaffold.name));
+    let rendered = scaffold.render_controller(actions)?;
+
+    let controller = match [&flat, &nested].into_iter().find(|p| Generator::exists(p)) {
+        Some(existing) => {
+            let source = Generator::read_to_string(existing)?;
+            let updated = extend_controller(&rendered, &source)
+                .map_err(|e| eyre!("{}: {}", existing.display(), e))?;
+            let controller = updated.unwrap_or(source);
+            // Unchanged contents are reported as identical
+            Generator::update_file(existing, &controller)?;
+            controller
+        }
+        None => {
+            write_new_file(&nested, &rendered)?;
+            write_new_file(
+                &controllers.join(&scaffold.name).join("mod.rs"),
+                &format!("pub mod {0};\n\npub use {0}::*;\n", scaffold.name),
+            )?;
+            register_module(&scaffold.name, &controllers.join("mod.rs"))?;
+            rendered.clone()
+        }
+    };
+
+    let views_dir = Path::new("src/views").join(scaffold.template_dir());
+    for (file_name, contents) in scaffold.render_views(actions)? {
+        write_new_file(&views_dir.join(file_name), &contents)?;
+    }
+
+    // Handlers that are already registered are left alone
+    for handler in route_handlers(&rendered)? {
+        add_new_controller_to_main_rs(None, Some(&scaffold.name), &handler)?;
+    }
+
+    // Tests that were written before, and maybe changed since, are `generate test`'s to replace
+    let (tests, _) = tests_paths(&scaffold.name);
+    if !Generator::exists(&tests) {
+        write_tests(scaffold, &controller)?;
+    }
+    Ok(())
+}
+
+/// Where the tests of a controller go, next to it, and the mod.rs declaring them:
+/// `src/controllers/<name>/<name>_tests.rs` for a controller in its own directory, and
+/// `src/controllers/<name>_tests.rs` for `src/controllers/<name>.rs`.
+pub(crate) fn tests_paths(name: &str) -> (PathBuf, PathBuf) {
+    let controllers = Path::new("src/controllers");
+    let file = format!("{}_tests.rs", name);
+    if Generator::exists(controllers.join(format!("{}.rs", name))) {
+        (controllers.join(file), controllers.join("mod.rs"))
+    } else {
+        let dir = controllers.join(name);
+        (dir.join(file), dir.join("mod.rs"))
+    }
+}
+
+/// Writes the tests of the actions whose handlers are all in `controller`, the controller's
+/// source, and declares them as a `#[cfg(test)]` module.
+fn write_tests(scaffold: &Scaffold, controller: &str) -> Result<(), Error> {
+    let actions = scaffold.actions_with_handlers(&route_handlers(controller)?)?;
+    if actions.is_empty() {
+        Generator::say(format!(
+            "Skipped the tests of '{}': the controller has none of the generated handlers.",
+            scaffold.name
+        ));
+        return Ok(());
+    }
+    let (tests, mod_rs) = tests_paths(&scaffold.name);
+    write_new_file(&tests, &scaffold.render_tests(&actions)?)?;
+
+    let module = format!("{}_tests", scaffold.name);
+    let contents = if Generator::exists(&mod_rs) {
+        Generator::read_to_string(&mod_rs)?
+    } else {
+        String::new()
+    };
+    if !contents.contains(&format!("mod {};", module)) {
+        let separator = if contents.is_empty() || contents.ends_with("\n\n") {
+            ""
+        } else if contents.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        };
+        Generator::update_file(
+            &mod_rs,
+            &format!("{}{}#[cfg(test)]\nmod {};\n", contents, separator, module),
+        )?;
+    }
+    Ok(())
+}
+
+/// # Name: generate_test
+/// ## Description
+/// Writes `actix_web::test` integration tests for the controller of model `name`: the pages
+/// render for a signed in user, forms save and validate, records are deleted, and every route
+/// redirects to `/login` when nobody is signed in. The tests cover the actions whose handlers
+/// the controller still has, and run on a `rustyroad::testing::TestDatabase` with the project's
+/// migrations and fixtures. An existing tests file is a conflict for the generator runtime.
+/// ## Example
+/// ```no_run
+/// use rustyroad::writers::generate_test;
+///
+/// // src/controllers/post/post_tests.rs
+/// generate_test("post").unwrap();
+/// ```
+pub fn generate_test(name: &str) -> Result<(), Error> {
+    if !Path::new("rustyroad.toml").exists() {
+        return Err(eyre!(
+            "Could not find rustyroad.toml. Run this command from your RustyRoad project root."
+        ));
+    }
+    let name = to_snake_case(name);
+    let controllers = Path::new("src/controllers");
+    let controller = [
+        controllers.join(format!("{}.rs", name)),
+        controllers.join(&name).join(format!("{}.rs", name)),
+    ]
+    .into_iter()
+    .find(|path| Generator::exists(path))
+    .ok_or_else(|| {
+        eyre!(
+            "No controller for '{0}'. Generate one first: rustyroad generate controller {0}",
+            name
+        )
+    })?;
+    let model_path = Path::new("src/models").join(format!("{}.rs", name));
+    let model = Generator::read_to_string(&model_path)
+        .map_err(|e| eyre!("Could not read {}: {}", model_path.display(), e))?;
+    let database = Database::get_database_from_rustyroad_toml()
+        .map_err(|e| eyre!("Could not read the database settings: {}", e))?;
+    let scaffold = Scaffold::from_model_source(&name, &model, database.database_type)?;
+    let source = Generator::read_to_string(&controller)?;
+    write_tests(&scaffold, &source).map_err(|e| eyre!("{}: {}", controller.display(), e))
+}
+
+/// # Name: generate_scaffold
+/// ## Description
+/// Generates a complete CRUD resource without prompts: the CREATE TABLE migration for the
+/// project's database, `src/models/<name>.rs`, `src/controllers/<name>/`, the views under
+/// `src/views/pages/<name>/`, and the `mod` and `.service(..)` registrations.
+/// Files that already exist are handled by the generator runtime (`--force`, `--skip` or a prompt),
+/// but a second migration for the same table is refused.
+/// ## Example
+/// ```no_run
+/// use rustyroad::writers::generate_scaffold;
+///
+/// let fields = vec!["title:string:not_null".to_string(), "published:boolean".to_string()];
+/// generate_scaffold("post", &fields).unwrap();
+/// ```
+pub fn generate_scaffold(name: &str, fields: &[String]) -> Result<(), Error> {
+    if !Path::new("rustyroad.toml").exists() {
+        return Err(eyre!(
+            "Could not find rustyroad.toml. Run this command from your RustyRoad project root."
+        ));
+    }
+    let database = Database::get_database_from_rustyroad_toml()
+        .map_err(|e| eyre!("Could not read the database settings: {}", e))?;
+    let scaffold = Scaffold::new(name, fields, database.database_type)?;
+
+    if let Some(existing) = find_create_table_migration(&scaffold.table_name) {
+        return Err(eyre!(
+            "{} already creates the '{}' table; remove it or pick another name",
+            existing,
+            scaffold.table_name
+        ));
+    }
+
+    write_create_table_migration(
+        &scaffold.table_name,
+        &scaffold.column_definitions(),
+        &scaffold.database_type,
+    )?;
+
+    write_model(&scaffold)?;
+    write_controller(&scaffold, &CRUD_ACTIONS)?;
+
+    Generator::say(format!(
+        "\nScaffold for '{}' created. Run the migration, then visit /{}:\n  rustyroad migration run create_{}",
+        scaffold.name, scaffold.table_name, scaffold.table_name
+    ));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::GeneratorOptions;
+    use tera::Tera;
+
+    #[test]
+    fn test_register_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let mod_rs = dir.path().join("mod.rs");
+        std::fs::write(
+            &mod_rs,
+            "pub mod index;\npub use index::*;pub mod login;\npub use login::*;\n\n#[cfg(test)]\nmod login_tests;\n",
+        )
+        .unwrap();
+        let new_mod_rs = dir.path().join("new").join("mod.rs");
+
+        Generator::run(GeneratorOptions::default(), || {
+            register_module("post", &mod_rs)?;
+            // Already registered
+            register_module("post", &mod_rs)?;
+            register_module("post", &new_mod_rs)?;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&mod_rs).unwrap(),
+            "pub mod index;\npub use index::*;\npub mod login;\npub mod post;\npub use login::*;\npub use post::*;\n\n#[cfg(test)]\nmod login_tests;\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&new_mod_rs).unwrap(),
+            "pub mod post;\npub use post::*;\n"
+        );
+    }
+
+    fn post_scaffold(database_type: DatabaseType) -> Scaffold {
+        let fields = vec![
+            "title:string:not_null".to_string(),
+            "body:text".to_string(),
+            "published:boolean".to_string(),
+            "rating:integer".to_string(),
+        ];
+        Scaffold::new("post", &fields, database_type).unwrap()
+    }
+
+    #[test]
+    fn test_names_and_field_parsing() {
+        assert_eq!(to_snake_case("BlogPost"), "blog_post");
+        assert_eq!(to_snake_case("blog-post"), "blog_post");
+        assert_eq!(pluralize("category"), "categories");
+        assert_eq!(pluralize("day"), "days");
+        assert_eq!(pluralize("address"), "addresses");
+        for singular in ["post", "category", "day", "address", "box", "branch"] {
+            assert_eq!(singularize(&pluralize(singular)), singular);
+        }
+        assert_eq!(model_name_for_table("BlogPosts"), "blog_post");
+        assert_eq!(association_name("author_id", "users"), "author");
+        assert_eq!(association_name("owner", "people_groups"), "people_group");
+        assert_eq!(association_name("type_id", "types"), "type_");
+        assert_eq!(humanize("published_at"), "Published at");
+
+        let scaffold = post_scaffold(DatabaseType::Postgres);
+        assert_eq!(scaffold.table_name, "posts");
+        assert_eq!(scaffold.fields[0].rust_type(), "String");
+        assert_eq!(scaffold.fields[1].rust_type(), "Option<String>");
+
+        assert!(Scaffold::new("post", &[], DatabaseType::Postgres).is_err());
+        assert!(ScaffoldField::parse("title").is_err());
+        assert!(ScaffoldField::parse("type:string").is_err());
+        assert!(ScaffoldField::parse("id:integer").is_err());
+        assert!(ScaffoldField::parse("data:json").is_err());
+    }
+
+    #[test]
+    fn test_render_model_validates_column_rules() {
+        let model = post_scaffold(DatabaseType::Postgres)
+            .render_model()
+            .unwrap();
+        assert!(syn::parse_file(&model).is_ok());
+        assert!(model.contains(
+            "#[derive(Debug, Clone, Serialize, Deserialize, Model)]\n#[model(table = \"posts\")]"
+        ));
+        assert!(model.contains("use rustyroad::model::{Model, ModelError};"));
+        assert!(model.contains(
+            "    #[validate(presence, length(max = 255))]\n    pub title: String,\n    pub body: Option<String>,"
+        ));
+        assert!(model.contains("pub rating: Option<i32>,"));
+        assert!(model.contains("            published: Some(form.published),"));
+        assert!(model.contains(
+            "pub async fn create(form: PostForm) -> Result<Post, AppError> {\n        let conn = Self::connection().await?;\n        Ok(Model::insert(&Self::from_form(0, form), &conn).await?)"
+        ));
+        assert!(model.contains(
+            "Err(ModelError::Database(sqlx::Error::RowNotFound)) => Err(Self::not_found()),"
+        ));
+        assert!(model.contains("AppError::NotFound(\"Post not found\".to_string())"));
+        assert!(model.contains("Ok(<Self as Model>::all(&conn).await?)"));
+        assert!(model.contains(
+            "match <Self as Model>::find(&conn, id).await {\n            Err(sqlx::Error::RowNotFound) => Err(Self::not_found()),"
+        ));
+        assert!(model.contains("Ok(record) => Ok(Model::delete(&record, &conn).await?),"));
+        assert!(!model.contains("sqlx::query"));
+
+        let fields = vec!["email:string:not_null,unique".to_string()];
+        let model = Scaffold::new("account", &fields, DatabaseType::Sqlite)
+            .unwrap()
+            .render_model()
+            .unwrap();
+        assert!(model.contains("#[validate(presence, length(max = 255), unique)]"));
+    }
+
+    #[test]
+    fn test_render_model_belongs_to_references() {
+        let fields = vec![
+            "title:string:not_null".to_string(),
+            "author_id:integer:not_null,references=users".to_string(),
+            "parent_id:integer:references=comments(id)".to_string(),
+            "post_uuid:string:references=posts(uuid)".to_string(),
+        ];
+        let scaffold = Scaffold::new("comment", &fields, DatabaseType::Sqlite).unwrap();
+        assert_eq!(scaffold.fields[1].belongs_to.as_deref(), Some("users"));
+        assert_eq!(scaffold.fields[3].belongs_to, None);
+
+        let model = scaffold.render_model().unwrap();
+        assert!(syn::parse_file(&model).is_ok());
+        assert!(model.contains("use rustyroad::model::{associations, Model, ModelError};"));
+        assert!(model.contains("async fn connection() -> Result<DatabaseConnection, sqlx::Error>"));
+        assert!(model
+            .contains("pub async fn author(&self) -> Result<crate::models::user::User, AppError>"));
+        assert!(model.contains("AppError::NotFound(\"User not found\".to_string())"));
+        assert!(model.contains("associations::preload_belongs_to(&conn, records, \"author_id\")"));
+        assert!(model.contains("pub async fn parent(&self) -> Result<Option<Self>, AppError>"));
+        assert!(!model.contains("fn post_uuid("));
+    }
+
+    #[test]
+    fn test_render_controller_routes() {
+        let scaffold = post_scaffold(DatabaseType::Postgres);
+        let controller = scaffold.render_controller(&CRUD_ACTIONS).unwrap();
+        for route in [
+            "#[get(\"/posts\")]",
+            "#[get(\"/posts/new\")]",
+            "#[get(\"/posts/{id}\")]",
+            "#[get(\"/posts/{id}/edit\")]",
+            "#[post(\"/posts\")]",
+            "#[post(\"/posts/{id}\")]",
+            "#[post(\"/posts/{id}/delete\")]",
+        ] {
+            assert!(controller.contains(route), "missing {}", route);
+        }
+        for handler in scaffold.handler_names(&CRUD_ACTIONS).unwrap() {
+            assert!(controller.contains(&format!("pub async fn {}(", handler)));
+        }
+        assert!(controller.contains("use crate::models::post::{Post, PostForm};"));
+        assert!(controller.contains("use rustyroad::web::AppError;"));
+        assert!(controller
+            .contains("render_invalid_form(&tmpl, \"pages/post/new.html.tera\", &mut context, e)"));
+        assert!(controller.contains("let post = Post::find(id.into_inner()).await?;"));
+        assert_eq!(
+            controller
+                .matches("user.ok_or(AppError::Unauthorized)?;")
+                .count(),
+            scaffold.handler_names(&CRUD_ACTIONS).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_from_model_source_round_trips() {
+        let scaffold = post_scaffold(DatabaseType::Sqlite);
+        let parsed = Scaffold::from_model_source(
+            "post",
+            &scaffold.render_model().unwrap(),
+            DatabaseType::Sqlite,
+        )
+        .unwrap();
+        // `text` and `string` columns are both `String`s, so only the Rust types survive.
+        let types = |s: &Scaffold| -> Vec<(String, String)> {
+            s.fields
+                .iter()
+                .map(|f| (f.name.clone(), f.rust_type()))
+                .collect()
+        };
+        assert_eq!(types(&parsed), types(&scaffold));
+
+        let fields = vec![
+            "author_id:integer:not_null,references=users".to_string(),
+            "parent_id:integer:references=comments".to_string(),
+        ];
+        let comment = Scaffold::new("comment", &fields, DatabaseType::Sqlite).unwrap();
+        let parsed = Scaffold::from_model_source(
+            "comment",
+            &comment.render_model().unwrap(),
+            DatabaseType::Sqlite,
+        )
+        .unwrap();
+        assert_eq!(parsed.fields, comment.fields);
+
+        let source = "pub struct Post { pub id: i32, pub tags: Vec<String> } pub struct PostForm;";
+        assert!(Scaffold::from_model_source("post", source, DatabaseType::Sqlite).is_err());
+        assert!(Scaffold::from_model_source("comment", source, DatabaseType::Sqlite).is_err());
+        let without_form = "pub struct Post { pub id: i32, pub title: String }";
+        assert!(Scaffold::from_model_source("post", without_form, DatabaseType::Sqlite).is_err());
+    }
+
+    #[test]
+    fn test_render_controller_for_some_actions() {
+        let scaffold = post_scaffold(DatabaseType::Postgres);
+        let actions = [CRUDType::Create, CRUDType::Delete];
+        assert_eq!(
+            scaffold.handler_names(&actions).unwrap(),
+            vec!["new_post", "create_post", "delete_post"]
+        );
+
+        let controller = scaffold.render_controller(&actions).unwrap();
+        assert!(syn::parse_file(&controller).is_ok());
+        assert!(controller.contains("use actix_web::{get, post, web, HttpResponse};"));
+        assert!(controller.contains("Ok(_) => Ok(redirect_to(\"/posts/new\")),"));
+        assert!(controller.contains("_ => Ok(redirect_to(\"/\")),"));
+        assert!(!controller.contains("fn index_posts("));
+
+        let views: Vec<String> = scaffold
+            .render_views(&actions)
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(views, vec!["new.html.tera", "_form.html.tera"]);
+    }
+
+    #[test]
+    fn test_render_tests_for_the_controller_actions() {
+        let mut fields = post_scaffold(DatabaseType::Sqlite).column_definitions();
+        fields.push("author_id:bigint:not_null,references=users".to_string());
+        fields.push("editor_id:integer:references=users".to_string());
+        let scaffold = Scaffold::new("post", &fields, DatabaseType::Sqlite).unwrap();
+
+        let tests = scaffold.render_tests(&CRUD_ACTIONS).unwrap();
+        assert!(syn::parse_file(&tests).is_ok());
+        for handler in scaffold.handler_names(&CRUD_ACTIONS).unwrap() {
+            assert!(tests.contains(&format!(".service({})", handler)));
+        }
+        assert!(tests.contains("invalid.title = String::new();"));
+        assert!(tests.contains("form.title = \"Updated\".to_string();"));
+        assert!(tests.contains("published: post.published.unwrap_or_default(),"));
+        // No users model in this tree, so the author is a fixture
+        assert!(tests.contains("// tests/fixtures/users.toml needs a row with `id = 1`"));
+        assert!(tests.contains("post.author_id = 1;"));
+        assert!(!tests.contains("editor_id ="));
+
+        let tests = scaffold.render_tests(&[CRUDType::Delete]).unwrap();
+        assert!(syn::parse_file(&tests).is_ok());
+        assert!(tests.contains("use crate::models::post::Post;"));
+        assert!(!tests.contains("fn form_from("));
+        assert!(!tests.contains("fn test_index_lists_posts("));
+
+        let controller = scaffold
+            .render_controller(&[CRUDType::Read, CRUDType::Delete])
+            .unwrap()
+            .replace("pub async fn show_post(", "pub async fn view_post(");
+        let handlers = route_handlers(&controller).unwrap();
+        assert_eq!(
+            scaffold.actions_with_handlers(&handlers).unwrap(),
+            vec![CRUDType::Delete]
+        );
+    }
+
+    #[test]
+    fn test_extend_controller_adds_only_missing_handlers() {
+        let scaffold = post_scaffold(DatabaseType::Postgres);
+        let existing = scaffold.render_controller(&[CRUDType::Read]).unwrap();
+        assert_eq!(extend_controller(&existing, &existing).unwrap(), None);
+
+        let rendered = scaffold
+            .render_controller(&[CRUDType::Read, CRUDType::Delete])
+            .unwrap();
+        let extended = extend_controller(&rendered, &existing).unwrap().unwrap();
+        assert!(syn::parse_file(&extended).is_ok());
+        assert!(extended.starts_with("use actix_web::post;\n"));
+        assert_eq!(extended.matches("fn index_posts(").count(), 1);
+        assert_eq!(extended.matches("fn redirect_to(").count(), 1);
+        assert!(extended.contains("pub async fn delete_post("));
+    }
+
+    #[test]
+    fn test_rendered_views_compile_and_render() {
+        let scaffold = post_scaffold(DatabaseType::Postgres);
+        let mut tera = Tera::default();
+        tera.add_raw_template(
+            AUTHENTICATED_LAYOUT,
+            "{% block title %}{% endblock title %}{% block authenticated_content %}{% endblock authenticated_content %}",
+        )
+        .unwrap();
+        for (file_name, contents) in scaffold.render_views(&CRUD_ACTIONS).unwrap() {
+            tera.add_raw_template(&format!("pages/post/{}", file_name), &contents)
+                .unwrap();
+        }
+
+        let post = serde_json::json!({
+            "id": 7, "title": "<b>Hi</b>", "body": null, "published": true, "rating": null
+        });
+
+        let mut context = Context::new();
+        context.insert("title", "Posts");
+        context.insert("posts", &vec![post.clone()]);
+        let index = tera.render("pages/post/index.html.tera", &context).unwrap();
+        assert!(index.contains("&lt;b&gt;Hi&lt;&#x2F;b&gt;"));
+        assert!(index.contains("href=\"/posts/7/edit\""));
+
+        let mut context = Context::new();
+        context.insert("title", "New post");
+        context.insert("form_action", "/posts");
+        let new = tera.render("pages/post/new.html.tera", &context).unwrap();
+        assert!(new.contains("action=\"/posts\""));
+        assert!(new.contains("name=\"title\" value=\"\""));
+        assert!(!new.contains("text-red-700"));
+
+        context.insert(
+            "errors",
+            &serde_json::json!({ "title": ["can't be blank"], "base": ["<b>No</b>"] }),
+        );
+        let invalid = tera.render("pages/post/new.html.tera", &context).unwrap();
+        assert!(invalid.contains("<p class=\"mt-1 text-sm text-red-700\">can&#x27;t be blank</p>"));
+        assert!(invalid.contains("&lt;b&gt;No&lt;&#x2F;b&gt;"));
+        assert_eq!(invalid.matches("<p class=\"mt-1").count(), 1);
+
+        let mut context = Context::new();
+        context.insert("title", "Edit post");
+        context.insert("form_action", "/posts/7");
+        context.insert("post", &post);
+        let edit = tera.render("pages/post/edit.html.tera", &context).unwrap();
+        assert!(edit.contains("value=\"true\" checked"));
+        assert!(edit.contains("<textarea id=\"body\" name=\"body\" rows=\"6\" class=\"w-full rounded border px-2 py-1\"></textarea>"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::generators::Generator;
 use crate::writers::write_to_file;
 use eyre::Error;
 
@@ -67,14 +68,8 @@ pub fn create_grapesjs_component() -> Result<(), Error> {
     //declare the file path.
     let path = "src/views/components/grapesjs.html.tera";
 
-    // create the file.
-    std::fs::File::create(path)?;
-
-    // ensure the file exists.
-    std::fs::metadata(path)?;
-
     //write the file.
-    write_to_file(path, html.as_bytes())?;
+    Generator::write_file(path, html)?;
 
     Ok(())
 }
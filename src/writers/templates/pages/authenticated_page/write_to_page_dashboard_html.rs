@@ -1,6 +1,5 @@
+use crate::generators::Generator;
 use eyre::Error;
-use std::fs::File;
-use std::io::Write;
 use std::path::Path;
 
 pub fn write_to_page_dashboard_html() -> Result<(), Error> {
@@ -54,8 +53,7 @@ pub fn write_to_page_dashboard_html() -> Result<(), Error> {
 {% endblock authenticated_content %}
     "#;
     let path = Path::new("src/writers/templates/pages/authenticated_page/page_dashboard.html.tera");
-    let mut file = File::create(path)?;
-    file.write_all(contents.as_bytes())?;
+    Generator::write_file(path, contents)?;
     println!("Finished creating the page_dashboard.html.tera file.");
     Ok(())
 }
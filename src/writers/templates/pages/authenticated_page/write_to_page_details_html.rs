@@ -1,7 +1,5 @@
-use crate::generators::create_file;
+use crate::generators::Generator;
 use color_eyre::eyre::{Error, Result};
-use std::fs::OpenOptions;
-use std::io::Write;
 use std::path::Path;
 
 pub fn write_to_page_details_html() -> Result<(), Error> {
@@ -632,17 +630,7 @@ pub fn write_to_page_details_html() -> Result<(), Error> {
 
     let path = Path::new("src/views/layouts/authenticated_page/page/page_details.html.tera");
 
-    // create the file
-    create_file(path.to_str().unwrap()).expect("Error creating the page_details.html.tera file");
-
     // write to the file
-    let mut file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open(path)
-        .unwrap();
-    // Write the updated contents to the file
-    writeln!(file, "{}", contents)?;
-    file.flush()?;
+    Generator::write_file(path, &format!("{}\n", contents))?;
     Ok(())
 }
@@ -1,3 +1,7 @@
+pub mod write_to_403_html;
 pub mod write_to_404_html;
+pub mod write_to_500_html;
 
+pub use write_to_403_html::*;
 pub use write_to_404_html::*;
+pub use write_to_500_html::*;